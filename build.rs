@@ -1,15 +1,36 @@
-#[cfg(not(feature = "bindings"))]
-fn main() {}
+fn main() {
+    #[cfg(feature = "napi")]
+    napi_build::setup();
+    #[cfg(feature = "bindings")]
+    bindings();
+}
 
+/// Features that change the layout of the exported types
 #[cfg(feature = "bindings")]
-fn main() {
+const FEATURES: &[&str] = &["decimal"];
+
+#[cfg(feature = "bindings")]
+fn bindings() {
     use std::env;
     extern crate cbindgen;
 
     let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
 
-    cbindgen::Builder::new()
+    /* Fields that depend on a feature are guarded by a define, which
+     * is set in the header if the feature is enabled in this build */
+    let mut defines = String::new();
+    let mut builder = cbindgen::Builder::new();
+    for feature in FEATURES {
+        let define = format!("JSON_FEATURE_{}", feature.to_uppercase());
+        if env::var(format!("CARGO_FEATURE_{}", feature.to_uppercase())).is_ok() {
+            defines += &format!("#define {define}\n");
+        }
+        builder = builder.with_define("feature", feature, &define);
+    }
+
+    builder
       .with_crate(crate_dir)
+      .with_after_include(defines)
       .with_language(cbindgen::Language::C)
       .generate()
       .map_or_else(
@@ -4,16 +4,30 @@
 
 void json_print(Json json);
 
+static bool print_element(uintptr_t i, const Json *val, void *user_data) {
+        (void) user_data;
+        if (i > 0)
+                printf(", ");
+        json_print(*val);
+        return true;
+}
+
+static bool print_member(const char *key, uintptr_t key_len, const Json *val, void *user_data) {
+        size_t *i = user_data;
+        (void) key_len;
+        if ((*i)++ > 0)
+                printf(", ");
+        printf("%s : ", key);
+        json_print(*val);
+        return true;
+}
+
 void json_print(Json json) {
+        size_t members = 0;
         switch (json.tag) {
         case Array:
                 printf("[");
-                for (unsigned long i = 0; i < json.array.len; i++) {
-                        Json e = json.array.elems[i];
-                        json_print(e);
-                        if (i < json.array.len - 1)
-                                printf(", ");
-                }
+                json_array_foreach(&json, print_element, NULL);
                 printf("]");
                 break;
         case Number:
@@ -21,13 +35,7 @@ void json_print(Json json) {
                 break;
         case Object:
                 printf("{");
-                for (unsigned long i = 0; i < json.object.len; i++) {
-                        struct JsonString s = json.object.elems[i].key;
-                        printf("%s : ", s.buf);
-                        json_print(*json.object.elems[i].val);
-                        if (i < json.object.len - 1)
-                                printf(", ");
-                }
+                json_object_foreach(&json, print_member, &members);
                 printf("}");
                 break;
         case String:
@@ -47,6 +55,12 @@ void json_print(Json json) {
         }
 }
 
+static bool count_number(double n, void *user_data) {
+        (void) n;
+        (*(size_t*) user_data)++;
+        return true;
+}
+
 int main() {
         const char *text =
         "{"
@@ -62,6 +76,28 @@ int main() {
         Json json = json_deserialize(text);
 
         json_print(json);
+        printf("\n");
+
+        SerializeConfig conf = {
+                .indent = "  ",
+                .non_finite = NonFiniteAsNull,
+                .escape_non_ascii = false,
+                .sort_keys = true,
+        };
+        char *pretty = json_serialize_with_config(&json, conf);
+        assert(pretty != NULL);
+        printf("%s\n", pretty);
+        json_free_string(pretty);
+
+        const Json *inner = json_get_pointer(&json, "/array/2/inner");
+        assert(inner != NULL && inner->tag == Number);
+        printf("/array/2/inner = %f\n", inner->number);
 
         json_free(json);
+
+        size_t numbers = 0;
+        JsonEventCallbacks callbacks = { .number = count_number };
+        bool ok = json_parse_events(text, &callbacks, &numbers);
+        assert(ok);
+        printf("%zu numbers\n", numbers);
 }
@@ -0,0 +1,22 @@
+/* Build with `cargo build --features napi`, and copy
+ * target/debug/libjson.so into target/debug/json.node */
+const assert = require("assert");
+const json = require("../target/debug/json.node");
+
+const doc = json.parse('{ "name" : "Jhon", "tags" : ["a", "b"] }');
+assert.deepStrictEqual(doc, { name: "Jhon", tags: ["a", "b"] });
+
+assert.strictEqual(json.pointer(doc, "/tags/1"), "b");
+assert.strictEqual(json.pointer(doc, "/tags/2"), undefined);
+
+const edited = json.patch(doc, [
+        { op: "replace", path: "/name", value: "Jhon Doe" },
+        { op: "remove", path: "/tags/0" },
+]);
+assert.deepStrictEqual(edited, { name: "Jhon Doe", tags: ["b"] });
+assert.deepStrictEqual(JSON.parse(json.stringify(edited, "  ")), edited);
+
+assert.throws(() => json.parse("[1, 2"));
+assert.throws(() => json.patch(doc, [{ op: "test", path: "/name", value: "x" }]));
+
+console.log(json.stringify(edited, "  "));
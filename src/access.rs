@@ -0,0 +1,215 @@
+//! Fallible navigation of [Json] values
+
+use crate::prelude::*;
+use crate::{Json, JsonType};
+
+/// Error of [`Json::try_get`] and [`Json::try_nth`]
+///
+/// Paths are formatted like in [`json_get`](crate::json_get):
+/// `servers[0].port`.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct AccessError {
+    /// Path that was attempted, up to the step that failed.
+    /// It's relative to the value the failing call was made on.
+    pub path: String,
+    /// Type of container the failed step needed
+    pub expected: JsonType,
+    /// Type of the value found instead. `None` if the container
+    /// was right, but the key or index doesn't exist
+    pub found: Option<JsonType>,
+}
+
+impl AccessError {
+    fn new(path: String, expected: JsonType, found: Option<JsonType>) -> Self {
+        Self { path, expected, found }
+    }
+    /// Prefixes the path of this error with the given key
+    ///
+    /// The paths of the errors are relative to the value the failing
+    /// call was made on. When navigating step by step, this extends
+    /// them with the steps already taken.
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, AccessError, Json};
+    ///
+    /// fn port(server: &Json) -> Result<&Json, AccessError> {
+    ///     let listen = server.try_get("listen")?;
+    ///     listen.try_get("port").map_err(|e| e.within_key("listen"))
+    /// }
+    ///
+    /// let j = json!({ "servers" : [{ "listen" : [] }] });
+    /// let server = j.try_get("servers").unwrap().try_nth(0).unwrap();
+    /// let err = port(server).map_err(|e| e.within_index(0).within_key("servers")).unwrap_err();
+    /// assert_eq!(err.to_string(), "servers[0].listen.port: expected object, found array");
+    /// ```
+    #[must_use]
+    pub fn within_key(self, key: &str) -> Self {
+        self.within(key)
+    }
+    /// Prefixes the path of this error with the given index.
+    /// See [`within_key`](Self::within_key)
+    #[must_use]
+    pub fn within_index(self, i: usize) -> Self {
+        self.within(&format!("[{i}]"))
+    }
+    fn within(mut self, prefix: &str) -> Self {
+        if prefix.is_empty() {
+            return self
+        }
+        if !self.path.is_empty() && !self.path.starts_with('[') {
+            self.path.insert(0, '.');
+        }
+        self.path.insert_str(0, prefix);
+        self
+    }
+}
+
+impl Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "<root>" } else { &self.path };
+        match (self.found, self.expected) {
+            (Some(found), expected) => write!(f, "{path}: expected {expected}, found {found}"),
+            (None, JsonType::Array) => write!(f, "{path}: index out of bounds"),
+            (None, _) => write!(f, "{path}: missing key"),
+        }
+    }
+}
+
+impl core::error::Error for AccessError { }
+
+impl From<AccessError> for crate::Error {
+    fn from(err: AccessError) -> Self {
+        err.to_string().into()
+    }
+}
+
+impl Json {
+    /// Gets the member `key` of this object
+    ///
+    /// Unlike [`get`](Self::get), the error tells whether
+    /// the key is missing or this is not an object.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "port" : 80 });
+    /// assert_eq!(j.try_get("port").unwrap(), &json!(80));
+    /// assert_eq!(j.try_get("host").unwrap_err().to_string(), "host: missing key");
+    /// assert_eq!(j.try_nth(0).unwrap_err().to_string(), "[0]: expected array, found object");
+    /// ```
+    pub fn try_get(&self, key: impl AsRef<str>) -> Result<&Json, AccessError> {
+        let key = key.as_ref();
+        match self {
+            Json::Object(obj) => obj.get(key).ok_or_else(|| {
+                AccessError::new(key.to_string(), JsonType::Object, None)
+            }),
+            _ => Err(AccessError::new(key.to_string(), JsonType::Object, Some(self.json_type()))),
+        }
+    }
+    /// Gets the element `i` of this array. See [`try_get`](Self::try_get)
+    pub fn try_nth(&self, i: usize) -> Result<&Json, AccessError> {
+        match self {
+            Json::Array(arr) => arr.get(i).ok_or_else(|| {
+                AccessError::new(format!("[{i}]"), JsonType::Array, None)
+            }),
+            _ => Err(AccessError::new(format!("[{i}]"), JsonType::Array, Some(self.json_type()))),
+        }
+    }
+}
+
+/// A step of an [`At`] navigation: a key of an object, or an index of an array
+pub trait Step {
+    /// Gets the value this step references inside `json`
+    fn step<'a>(&self, json: &'a Json) -> Result<&'a Json, AccessError>;
+    /// Appends this step to a path, formatted like in [`json_get`](crate::json_get)
+    fn push(&self, path: &mut String);
+}
+
+impl Step for &str {
+    fn step<'a>(&self, json: &'a Json) -> Result<&'a Json, AccessError> { json.try_get(self) }
+    fn push(&self, path: &mut String) {
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(self);
+    }
+}
+
+impl Step for &String {
+    fn step<'a>(&self, json: &'a Json) -> Result<&'a Json, AccessError> { self.as_str().step(json) }
+    fn push(&self, path: &mut String) { self.as_str().push(path); }
+}
+
+impl Step for String {
+    fn step<'a>(&self, json: &'a Json) -> Result<&'a Json, AccessError> { self.as_str().step(json) }
+    fn push(&self, path: &mut String) { self.as_str().push(path); }
+}
+
+impl Step for usize {
+    fn step<'a>(&self, json: &'a Json) -> Result<&'a Json, AccessError> { json.try_nth(*self) }
+    fn push(&self, path: &mut String) {
+        write!(path, "[{self}]").unwrap();
+    }
+}
+
+/// Navigates a [Json] value, step by step. Built with [`Json::at`]
+///
+/// If a step fails, because a key or index doesn't exist, or the
+/// value is not a container, the rest of the steps are skipped,
+/// and the accessors at the end return `None`. [`required`](At::required)
+/// returns the error of the step that failed, with its full path.
+///
+/// # Example
+/// ```
+/// use json::json;
+///
+/// let conf = json!({ "servers" : [ { "host" : "a", "port" : 80 } ] });
+/// assert_eq!(conf.at("servers").at(0).at("port").as_f64(), Some(80.0));
+/// assert_eq!(conf.at("servers").at(1).at("port").as_f64(), None);
+///
+/// let err = conf.at("servers").at(0).at("tls").required().unwrap_err();
+/// assert_eq!(err.to_string(), "servers[0].tls: missing key");
+/// ```
+#[derive(Debug,Clone)]
+pub struct At<'a> {
+    path: String,
+    value: Result<&'a Json, AccessError>,
+}
+
+impl<'a> At<'a> {
+    /// Takes another step
+    #[must_use]
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn at(mut self, step: impl Step) -> Self {
+        if let Ok(json) = self.value {
+            self.value = step.step(json).map_err(|err| err.within(&self.path));
+            step.push(&mut self.path);
+        }
+        self
+    }
+    /// Returns the value reached, if all the steps succeeded
+    pub fn get(&self) -> Option<&'a Json> { self.value.as_ref().ok().copied() }
+    /// Returns the value reached, or the error of the step that failed
+    pub fn required(self) -> Result<&'a Json, AccessError> { self.value }
+    /// Returns the raw string reached, without resolving its escape sequences.
+    /// See [`Json::string`]
+    pub fn as_str(&self) -> Option<&'a str> { self.get()?.string() }
+    /// Returns the number reached. See [`Json::number`]
+    pub fn as_f64(&self) -> Option<f64> { self.get()?.number() }
+    /// Returns the boolean reached. See [`Json::boolean`]
+    pub fn as_bool(&self) -> Option<bool> { self.get()?.boolean() }
+    /// Returns true if the value reached exists and is null
+    pub fn is_null(&self) -> bool { self.get().is_some_and(Json::is_null) }
+}
+
+impl Json {
+    /// Starts navigating this value. See [`At`]
+    pub fn at(&self, step: impl Step) -> At<'_> {
+        At {
+            path: String::new(),
+            value: Ok(self),
+        }.at(step)
+    }
+}
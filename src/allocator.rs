@@ -0,0 +1,193 @@
+//! Routing of allocations to a custom allocator
+//!
+//! The allocator API of the standard library is unstable, so [Json]
+//! can't be parameterized by an allocator. Instead, this module provides
+//! a global allocator, [Routed], that forwards the allocations made inside
+//! an [`in_allocator`] scope to the given [Allocator], like an arena or a pool.
+//! Outside of those scopes, it forwards them to a fallback allocator.
+//!
+//! Each block remembers the allocator that made it, so it's always returned
+//! to it, even if it's freed or grown outside of the scope, or inside the
+//! scope of another allocator. This costs a small header on each allocation.
+//!
+//! # Example
+//! ```
+//! use std::alloc::{GlobalAlloc, Layout, System};
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use json::allocator::{in_allocator, Allocator, Routed};
+//! use json::Json;
+//!
+//! #[global_allocator]
+//! static GLOBAL: Routed<System> = Routed::new(System);
+//!
+//! /// Allocates from System, but keeps track of the live bytes
+//! struct Counting(AtomicUsize);
+//!
+//! unsafe impl Allocator for Counting {
+//!     fn allocate(&self, layout: Layout) -> *mut u8 {
+//!         self.0.fetch_add(layout.size(), Ordering::Relaxed);
+//!         unsafe { System.alloc(layout) }
+//!     }
+//!     unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout) {
+//!         self.0.fetch_sub(layout.size(), Ordering::Relaxed);
+//!         unsafe { System.dealloc(ptr, layout) }
+//!     }
+//! }
+//!
+//! static POOL: Counting = Counting(AtomicUsize::new(0));
+//!
+//! let json = in_allocator(&POOL, || {
+//!     Json::deserialize(r#"{ "a" : [1, 2, "3"] }"#).unwrap()
+//! });
+//! assert!(POOL.0.load(Ordering::Relaxed) > 0);
+//! /* Returned to POOL, even outside of the scope */
+//! drop(json);
+//! assert_eq!(POOL.0.load(Ordering::Relaxed), 0);
+//! ```
+//!
+//! [Json]: crate::Json
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::Cell;
+use core::{mem, ptr};
+
+/// An allocator that [Routed] can forward allocations to
+///
+/// # Safety
+/// The implementor must follow the same contract as [`GlobalAlloc`]
+pub unsafe trait Allocator: Sync {
+    /// Allocates memory for `layout`. Returns null if the allocation fails.
+    fn allocate(&self, layout: Layout) -> *mut u8;
+    /// Deallocates `ptr`, which was allocated by this allocator with `layout`
+    ///
+    /// # Safety
+    /// Same as [`GlobalAlloc::dealloc`]
+    unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout);
+}
+
+/// Allocator that made a block, or None for the fallback
+type Owner = Option<&'static dyn Allocator>;
+
+std::thread_local! {
+    /// Allocator of the innermost [`in_allocator`] scope of the thread
+    static CURRENT: Cell<Owner> = const { Cell::new(None) };
+}
+
+fn current() -> Owner {
+    /* During the destruction of the thread locals, CURRENT is not accessible,
+     * but it's not inside any scope either */
+    CURRENT.try_with(Cell::get).ok().flatten()
+}
+
+/// Returns the layout of the whole block for `layout`, and the
+/// offset of the memory handed out, after the [Owner] header.
+fn with_header(layout: Layout) -> Option<(Layout, usize)> {
+    /* The header goes right before the returned pointer, so the offset
+     * must keep both the header and the returned pointer aligned */
+    let offset = layout.align().max(mem::size_of::<Owner>());
+    let align = layout.align().max(mem::align_of::<Owner>());
+    let size = offset.checked_add(layout.size())?;
+    Some((Layout::from_size_align(size, align).ok()?, offset))
+}
+
+/// Returns the header of the block of `ptr`, right before it
+#[allow(clippy::cast_ptr_alignment)]
+fn header(ptr: *mut u8) -> *mut Owner {
+    /* The offset given by with_header keeps it aligned */
+    ptr.cast::<Owner>().wrapping_sub(1)
+}
+
+/// Global allocator that forwards the allocations to the allocator of
+/// the current [`in_allocator`] scope, or to `A` outside of them.
+///
+/// See the [module level docs](self)
+#[derive(Debug,Default)]
+pub struct Routed<A> {
+    fallback: A,
+}
+
+impl<A: GlobalAlloc> Routed<A> {
+    /// Builds a router that uses `fallback` outside of the [`in_allocator`] scopes
+    pub const fn new(fallback: A) -> Self { Self { fallback } }
+
+    unsafe fn alloc_block(&self, owner: Owner, layout: Layout) -> *mut u8 {
+        let Some((block, offset)) = with_header(layout) else { return ptr::null_mut() };
+        let base = match owner {
+            Some(alloc) => alloc.allocate(block),
+            None => unsafe { self.fallback.alloc(block) },
+        };
+        if base.is_null() {
+            return base
+        }
+        unsafe {
+            let ptr = base.add(offset);
+            header(ptr).write(owner);
+            ptr
+        }
+    }
+
+    /// Returns the owner of `ptr`, and the start and layout of its block
+    unsafe fn block(ptr: *mut u8, layout: Layout) -> (Owner, *mut u8, Layout) {
+        /* with_header succeeded when ptr was allocated */
+        let (block, offset) = with_header(layout).unwrap_or_else(|| unreachable!());
+        unsafe {
+            let owner = header(ptr).read();
+            (owner, ptr.sub(offset), block)
+        }
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for Routed<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { self.alloc_block(current(), layout) }
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe {
+            match Self::block(ptr, layout) {
+                (Some(alloc), base, block) => alloc.deallocate(base, block),
+                (None, base, block) => self.fallback.dealloc(base, block),
+            }
+        }
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let Ok(new_layout) = Layout::from_size_align(new_size, layout.align()) else {
+            return ptr::null_mut()
+        };
+        unsafe {
+            /* The block keeps its owner when it grows */
+            let (owner, base, block) = Self::block(ptr, layout);
+            if owner.is_none() {
+                let Some((new_block, offset)) = with_header(new_layout) else { return ptr::null_mut() };
+                let base = self.fallback.realloc(base, block, new_block.size());
+                return if base.is_null() { base } else { base.add(offset) }
+            }
+            let new = self.alloc_block(owner, new_layout);
+            if !new.is_null() {
+                ptr::copy_nonoverlapping(ptr, new, layout.size().min(new_size));
+                self.dealloc(ptr, layout);
+            }
+            new
+        }
+    }
+}
+
+/// Restores the previous allocator of the thread, even if the scope panics
+struct Restore(Owner);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.set(self.0);
+    }
+}
+
+/// Runs `f`, with the allocations of this thread forwarded to `alloc`.
+/// It only has effect if [Routed] is the global allocator.
+///
+/// Scopes can be nested. The innermost one takes effect.
+/// The memory is always freed by the allocator that made it, so `alloc`
+/// must live as long as the program: the values allocated in the scope,
+/// or the ones cached by the standard library, may outlive it.
+pub fn in_allocator<R>(alloc: &'static dyn Allocator, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(CURRENT.replace(Some(alloc)));
+    f()
+}
@@ -0,0 +1,37 @@
+//! Approximate comparison
+
+use crate::prelude::to_f64;
+use crate::Json;
+
+impl Json {
+    /// Compares two documents structurally, like [`PartialEq`], but
+    /// considers two numbers equal if they differ by at most `epsilon`.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(not(feature = "compact_numbers"))] {
+    /// use json::json;
+    ///
+    /// let computed = json!({ "total" : 0.1 + 0.2, "items" : [1.0 / 3.0] });
+    /// let expected = json!({ "total" : 0.3, "items" : [0.333_333_333_333_333] });
+    ///
+    /// assert_ne!(computed, expected);
+    /// assert!(computed.approx_eq(&expected, 1e-12));
+    /// assert!(!computed.approx_eq(&expected, 1e-16));
+    /// # }
+    /// ```
+    pub fn approx_eq(&self, other: &Json, epsilon: f64) -> bool {
+        match (self, other) {
+            (Json::Number(a), Json::Number(b)) => a == b || to_f64(a - b).abs() <= epsilon,
+            (Json::Array(a), Json::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.approx_eq(b, epsilon))
+            },
+            (Json::Object(a), Json::Object(b)) => {
+                a.len() == b.len() && a.iter().all(|(k, va)| {
+                    b.get(k).is_some_and(|vb| va.approx_eq(vb, epsilon))
+                })
+            },
+            _ => self == other,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Checked arithmetic and operators on [Json] values
+
+use core::mem;
+use core::ops::{Add, Div, Mul, Sub};
+
+use crate::prelude::*;
+use crate::{Json, Result};
+
+/// Reads any numeric variant as a f64
+fn operand(json: &Json) -> Option<f64> {
+    match json {
+        Json::Number(n) => Some(to_f64(*n)),
+        #[cfg(feature = "bigint")]
+        Json::BigInt(n) => Some(big_to_f64(n)),
+        #[cfg(feature = "decimal")]
+        Json::Decimal(n) => Some(dec_to_f64(n)),
+        _ => None,
+    }
+}
+
+impl Json {
+    fn checked_op(&self, rhs: f64, op: char, f: impl FnOnce(f64, f64) -> f64) -> Result<Json> {
+        let Some(lhs) = operand(self) else {
+            return Err(format!("expected number, found {}", self.type_name()).into())
+        };
+        let n = f(lhs, rhs);
+        if !n.is_finite() {
+            return Err(format!("{lhs} {op} {rhs} is not a finite number").into())
+        }
+        Ok(Json::Number(from_f64(n)))
+    }
+    /// Adds `rhs` to this number
+    ///
+    /// Fails if this is not a number, or if the result is not
+    /// finite, since JSON can't represent NaN or infinities.
+    /// Big integers and decimals are rounded to a f64.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// assert_eq!(json!(1.5).checked_add(2).unwrap(), json!(3.5));
+    /// assert_eq!(json!(1).checked_div(0).unwrap_err().to_string(),
+    ///            "1 / 0 is not a finite number");
+    /// assert!(json!("1").checked_add(1).is_err());
+    /// assert!(json!(null).checked_mul(2).is_err());
+    /// ```
+    pub fn checked_add(&self, rhs: impl Into<f64>) -> Result<Json> {
+        self.checked_op(rhs.into(), '+', |a, b| a + b)
+    }
+    /// Subtracts `rhs` from this number. See [`checked_add`](Self::checked_add)
+    pub fn checked_sub(&self, rhs: impl Into<f64>) -> Result<Json> {
+        self.checked_op(rhs.into(), '-', |a, b| a - b)
+    }
+    /// Multiplies this number by `rhs`. See [`checked_add`](Self::checked_add)
+    pub fn checked_mul(&self, rhs: impl Into<f64>) -> Result<Json> {
+        self.checked_op(rhs.into(), '*', |a, b| a * b)
+    }
+    /// Divides this number by `rhs`. Fails when dividing by zero.
+    /// See [`checked_add`](Self::checked_add)
+    pub fn checked_div(&self, rhs: impl Into<f64>) -> Result<Json> {
+        self.checked_op(rhs.into(), '/', |a, b| a / b)
+    }
+}
+
+fn mismatch(op: char, lhs: &Json, rhs: &Json) -> crate::Error {
+    format!("Can't apply {op} to {} and {}", lhs.type_name(), rhs.type_name()).into()
+}
+
+/// Concatenates strings and arrays, merges objects, and adds numbers.
+///
+/// When merging objects, the members of the right
+/// side replace the ones with the same key.
+/// Any other combination of values is an error.
+///
+/// # Example
+/// ```
+/// use json::json;
+///
+/// assert_eq!((json!(1) + json!(2)).unwrap(), json!(3));
+/// assert_eq!((json!("ab") + json!("c")).unwrap(), json!("abc"));
+/// assert_eq!((json!([1]) + json!([2, 3])).unwrap(), json!([1, 2, 3]));
+/// assert_eq!((json!({ "a" : 1, "b" : 2 }) + json!({ "b" : 3 })).unwrap(),
+///            json!({ "a" : 1, "b" : 3 }));
+///
+/// let err = (json!([1]) + json!(1)).unwrap_err();
+/// assert_eq!(err.to_string(), "Can't apply + to array and number");
+/// ```
+impl Add for Json {
+    type Output = Result<Json>;
+
+    fn add(mut self, mut rhs: Json) -> Result<Json> {
+        match (&mut self, &mut rhs) {
+            (Json::String(a), Json::String(b)) => return Ok(Json::String(format!("{a}{b}").into())),
+            (Json::Array(a), Json::Array(b)) => {
+                let mut elems = mem::take(a).into_vec();
+                elems.extend(mem::take(b).into_vec());
+                return Ok(elems.into())
+            },
+            (Json::Object(a), Json::Object(b)) => a.extend(mem::take(b)),
+            _ => return match operand(&rhs) {
+                Some(n) if operand(&self).is_some() => self.checked_add(n),
+                _ => Err(mismatch('+', &self, &rhs)),
+            },
+        }
+        Ok(self)
+    }
+}
+
+/// Same as the [`Add`] impl for owned values, cloning them
+impl Add for &Json {
+    type Output = Result<Json>;
+
+    fn add(self, rhs: &Json) -> Result<Json> {
+        match (self, rhs) {
+            (Json::String(_) | Json::Array(_) | Json::Object(_), _) => self.clone() + rhs.clone(),
+            (a, b) => match operand(b) {
+                Some(n) if operand(a).is_some() => a.checked_add(n),
+                _ => Err(mismatch('+', a, b)),
+            },
+        }
+    }
+}
+
+macro_rules! numeric_op {
+    ( $( $trait:ident $fn:ident $checked:ident $op:literal ),* ) => {
+        $(
+            #[doc = concat!("Applies ", $op, " to two numbers. See [`Json::", stringify!($checked), "`]")]
+            impl $trait for &Json {
+                type Output = Result<Json>;
+
+                fn $fn(self, rhs: &Json) -> Result<Json> {
+                    match operand(rhs) {
+                        Some(n) if operand(self).is_some() => self.$checked(n),
+                        _ => Err(mismatch($op, self, rhs)),
+                    }
+                }
+            }
+
+            #[doc = concat!("Applies ", $op, " to two numbers. See [`Json::", stringify!($checked), "`]")]
+            impl $trait for Json {
+                type Output = Result<Json>;
+
+                fn $fn(self, rhs: Json) -> Result<Json> {
+                    (&self).$fn(&rhs)
+                }
+            }
+        )*
+    };
+}
+
+numeric_op! {
+    Sub sub checked_sub '-',
+    Mul mul checked_mul '*',
+    Div div checked_div '/'
+}
@@ -0,0 +1,60 @@
+//! Parallel deserialization
+
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::prelude::*;
+use crate::{Json, Result};
+
+/// Deserializes each of the inputs, in parallel, using as many
+/// threads as the available parallelism of the system.
+///
+/// Returns the results in the same order as the inputs.
+///
+/// # Example
+/// ```
+/// use json::json;
+///
+/// let results = json::deserialize_batch(&["[1, 2]", r#"{ "a" : null }"#, "{"]);
+/// assert_eq!(results[0].as_ref().unwrap(), &json!([1, 2]));
+/// assert_eq!(results[1].as_ref().unwrap(), &json!({ "a" : null }));
+/// assert!(results[2].is_err());
+/// ```
+pub fn deserialize_batch(inputs: &[&str]) -> Vec<Result<Json>> {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    deserialize_batch_with(inputs, threads)
+}
+
+/// Same as [`deserialize_batch`], but using at most `max_threads` threads.
+///
+/// If `max_threads` is 0 or 1, the inputs are deserialized on the current thread.
+pub fn deserialize_batch_with(inputs: &[&str], max_threads: usize) -> Vec<Result<Json>> {
+    let threads = max_threads.min(inputs.len());
+    if threads <= 1 {
+        return inputs.iter().map(Json::deserialize).collect()
+    }
+    /* Each thread takes the next pending input, so a few big
+     * inputs don't leave the rest of the threads idle */
+    let next = AtomicUsize::new(0);
+    let work = || {
+        let mut done = Vec::new();
+        loop {
+            let i = next.fetch_add(1, Ordering::Relaxed);
+            let Some(input) = inputs.get(i) else { return done };
+            done.push((i, Json::deserialize(input)));
+        }
+    };
+    let mut results: Vec<Option<Result<Json>>> = Vec::new();
+    results.resize_with(inputs.len(), || None);
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..threads).map(|_| s.spawn(work)).collect();
+        for h in handles {
+            let done = h.join().unwrap_or_else(|e| std::panic::resume_unwind(e));
+            for (i, res) in done {
+                results[i] = Some(res);
+            }
+        }
+    });
+    results.into_iter().flatten().collect()
+}
@@ -0,0 +1,75 @@
+//! Cancellation of deserializations
+
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use crate::{Json, JsonConfig, Result};
+
+/// Aborts a deserialization, when it's cancelled or its deadline passes.
+/// See [`Json::deserialize_cancellable`]
+#[derive(Debug,Default)]
+pub struct CancelToken {
+    cancelled: AtomicBool,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+}
+
+impl CancelToken {
+    /// Builds a token that is only cancelled by calling [cancel](Self::cancel)
+    pub fn new() -> Self { Self::default() }
+    /// Builds a token that is cancelled once `deadline` passes
+    #[cfg(feature = "std")]
+    pub fn with_deadline(deadline: Instant) -> Self {
+        Self { cancelled: AtomicBool::new(false), deadline: Some(deadline) }
+    }
+    /// Builds a token that is cancelled after `timeout`
+    #[cfg(feature = "std")]
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self::with_deadline(Instant::now() + timeout)
+    }
+    /// Cancels the deserializations that use this token.
+    /// This can be called from another thread.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+    /// Returns true if the token has been cancelled, or its deadline has passed
+    pub fn is_cancelled(&self) -> bool {
+        #[cfg(feature = "std")]
+        if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            return true
+        }
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl Json {
+    /// Same as [`deserialize_with_config`](Self::deserialize_with_config),
+    /// but fails as soon as possible once `cancel` is cancelled.
+    ///
+    /// The token is checked once every few KiB of input, so small
+    /// inputs may be fully deserialized even after a cancellation.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use json::{CancelToken, Json, JsonConfig};
+    ///
+    /// let text = format!("[{}]", vec!["[1, 2, 3]"; 100_000].join(","));
+    ///
+    /// let token = CancelToken::with_timeout(Duration::from_secs(60));
+    /// assert!(Json::deserialize_cancellable(&text, JsonConfig::default(), &token).is_ok());
+    ///
+    /// token.cancel();
+    /// assert!(Json::deserialize_cancellable(&text, JsonConfig::default(), &token).is_err());
+    /// ```
+    pub fn deserialize_cancellable(text: impl AsRef<str>, conf: JsonConfig, cancel: &CancelToken) -> Result<Json> {
+        Self::deserialize_with_hook(text.as_ref(), conf, &mut |_| {
+            if cancel.is_cancelled() {
+                Err("Deserialization cancelled".into())
+            } else {
+                Ok(())
+            }
+        })
+    }
+}
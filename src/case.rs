@@ -0,0 +1,114 @@
+//! Renaming of object keys
+
+use crate::escape::{escape, unescape};
+use crate::prelude::*;
+use crate::Json;
+
+/// Case conversions for [`Json::rename_keys`]
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Case {
+    /// `user_id` to `userId`. Leading underscores are kept
+    SnakeToCamel,
+    /// `userId` to `user_id`. Runs of capitals are kept
+    /// together, so `HTTPServer` becomes `http_server`
+    CamelToSnake,
+}
+
+fn snake_to_camel(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while chars.next_if_eq(&'_').is_some() {
+        out.push('_');
+    }
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(next) if c == '_' && next.is_alphanumeric() => {
+                let next = chars.next().unwrap_or(c);
+                out.extend(next.to_uppercase());
+            },
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn camel_to_snake(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 4);
+    let chars: Vec<char> = s.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower) {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+impl Case {
+    /// Converts the given key
+    pub fn convert(self, key: &str) -> String {
+        match self {
+            Case::SnakeToCamel => snake_to_camel(key),
+            Case::CamelToSnake => camel_to_snake(key),
+        }
+    }
+}
+
+fn rename(json: &mut Json, f: &mut dyn FnMut(&str) -> String) {
+    match json {
+        Json::Array(arr) => arr.iter_mut().for_each(|e| rename(e, f)),
+        Json::Object(obj) => {
+            *obj = core::mem::take(obj)
+                   .into_iter()
+                   .map(|(k, mut v)| {
+                       rename(&mut v, f);
+                       (escape(&f(&unescape(&k))).into(), v)
+                   })
+                   .collect();
+        },
+        _ => {},
+    }
+}
+
+impl Json {
+    /// Converts the keys of all the objects in this document to the given [Case]
+    ///
+    /// If two keys of an object become equal after
+    /// being renamed, only the last member is kept.
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, Case};
+    ///
+    /// let mut j = json!({ "user_id" : 1, "home_address" : [{ "zip_code" : "1234" }] });
+    /// j.rename_keys(Case::SnakeToCamel);
+    /// assert_eq!(j, json!({ "userId" : 1, "homeAddress" : [{ "zipCode" : "1234" }] }));
+    ///
+    /// j.rename_keys(Case::CamelToSnake);
+    /// assert_eq!(j, json!({ "user_id" : 1, "home_address" : [{ "zip_code" : "1234" }] }));
+    /// ```
+    pub fn rename_keys(&mut self, case: Case) {
+        self.rename_keys_with(|k| case.convert(k));
+    }
+    /// Renames the keys of all the objects in this document with `f`
+    ///
+    /// The function receives and returns the keys with their escape
+    /// sequences resolved. Like in [`rename_keys`](Self::rename_keys),
+    /// if two keys become equal, only the last member is kept.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut j = json!({ "id" : 1, "tags" : { "a" : null } });
+    /// j.rename_keys_with(|k| k.to_uppercase());
+    /// assert_eq!(j, json!({ "ID" : 1, "TAGS" : { "A" : null } }));
+    /// ```
+    pub fn rename_keys_with(&mut self, mut f: impl FnMut(&str) -> String) {
+        rename(self, &mut f);
+    }
+}
@@ -0,0 +1,70 @@
+//! Colored output for terminals
+
+use crate::prelude::*;
+use crate::{Json, SerializeConfig};
+
+/// Resets the colors after each token
+pub(crate) const RESET: &str = "\x1b[0m";
+
+/// ANSI escape sequences used to color each kind of token.
+/// Empty sequences leave the tokens uncolored.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct ColorScheme<'a> {
+    /// Keys of object members
+    pub key: &'a str,
+    pub string: &'a str,
+    pub number: &'a str,
+    /// `true`, `false` and `null`
+    pub literal: &'a str,
+}
+
+impl ColorScheme<'static> {
+    /// Blue keys, green strings, cyan numbers and yellow literals
+    pub const fn ansi() -> Self {
+        ColorScheme {
+            key: "\x1b[1;34m",
+            string: "\x1b[32m",
+            number: "\x1b[36m",
+            literal: "\x1b[33m",
+        }
+    }
+    /// No colors at all
+    pub const fn none() -> Self {
+        ColorScheme { key: "", string: "", number: "", literal: "" }
+    }
+}
+
+/// Returns [`ColorScheme::ansi`], or [`ColorScheme::none`] if the
+/// `NO_COLOR` environment variable is set to a non-empty value.
+/// See <https://no-color.org>
+impl Default for ColorScheme<'static> {
+    fn default() -> Self {
+        #[cfg(feature = "std")]
+        if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return Self::none()
+        }
+        Self::ansi()
+    }
+}
+
+impl Json {
+    /// Serializes the JSON object into a `fmt::Write`, like `{:#}`,
+    /// coloring its tokens with the given [`ColorScheme`]
+    ///
+    /// To configure other aspects of the output, set
+    /// [`SerializeConfig::colors`].
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, ColorScheme};
+    ///
+    /// let mut out = String::new();
+    /// json!({ "a" : [1, null] }).serialize_colored(&mut out, &ColorScheme::ansi()).unwrap();
+    /// assert_eq!(out, "{\n  \x1b[1;34m\"a\"\x1b[0m: [\n    \x1b[36m1\x1b[0m,\n    \x1b[33mnull\x1b[0m\n  ]\n}");
+    /// ```
+    pub fn serialize_colored(&self, out: &mut dyn Write, colors: &ColorScheme<'_>) -> fmt::Result {
+        let conf = SerializeConfig { indent: Some("  "), colors: Some(colors), ..Default::default() };
+        self.serialize_with_config(&conf, out)
+            .map_err(|_| fmt::Error)
+    }
+}
@@ -0,0 +1,115 @@
+//! Configuration file loading
+//!
+//! Parses a JSON document and substitutes `${VAR}` and `${VAR:-default}`
+//! placeholders inside string values with environment variables.
+//! A `$$` produces a literal `$`, so `$${VAR}` is left untouched as `${VAR}`.
+//!
+//! # Example
+//! ```
+//! use json::{config, json};
+//!
+//! let conf = config::from_str_with(r#"{
+//!     "host" : "${HOST:-localhost}",
+//!     "url" : "http://${HOST:-localhost}:${PORT}",
+//!     "literal" : "$${PORT}"
+//! }"#, |var| (var == "PORT").then(|| "8080".to_string())).unwrap();
+//!
+//! assert_eq!(conf, json!({
+//!     "host" : "localhost",
+//!     "url" : "http://localhost:8080",
+//!     "literal" : "${PORT}"
+//! }));
+//! ```
+
+use std::{env, fs, path::Path};
+
+use crate::escape::escape;
+use crate::prelude::*;
+use crate::{Json, Result};
+
+fn substitute_str(s: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<Option<String>> {
+    if !s.contains('$') {
+        return Ok(None)
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if let Some(r) = rest.strip_prefix("$$") {
+            out.push('$');
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("${") {
+            let end = r.find('}').ok_or_else(|| format!("Unclosed placeholder in \"{s}\""))?;
+            let inner = &r[..end];
+            let (var, default) = match inner.split_once(":-") {
+                Some((var, default)) => (var, Some(default)),
+                None => (inner, None),
+            };
+            if var.is_empty() {
+                return Err(format!("Empty placeholder in \"{s}\"").into());
+            }
+            match (lookup(var), default) {
+                (Some(val), _) => out.push_str(&escape(&val)),
+                /* The default comes from the document, so it's already escaped */
+                (None, Some(default)) => out.push_str(default),
+                (None, None) => return Err(format!("Environment variable '{var}' is not set").into()),
+            }
+            rest = &r[end + 1..];
+        } else {
+            out.push('$');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(Some(out))
+}
+
+/// Substitutes the placeholders inside all the string values of
+/// the given document, using `lookup` to get the variables' values
+pub fn substitute(json: &mut Json, lookup: &dyn Fn(&str) -> Option<String>) -> Result<()> {
+    match json {
+        Json::String(s) => {
+            if let Some(new) = substitute_str(s, lookup)? {
+                *s = new.into();
+            }
+        },
+        Json::Array(arr) => {
+            for e in arr.iter_mut() {
+                substitute(e, lookup)?;
+            }
+        },
+        Json::Object(obj) => {
+            for v in obj.values_mut() {
+                substitute(v, lookup)?;
+            }
+        },
+        _ => {},
+    }
+    Ok(())
+}
+
+fn env_var(var: &str) -> Option<String> {
+    env::var(var).ok()
+}
+
+/// Parses the given text, substituting placeholders using `lookup`
+pub fn from_str_with(text: impl AsRef<str>, lookup: impl Fn(&str) -> Option<String>) -> Result<Json> {
+    let mut json = Json::deserialize(text)?;
+    substitute(&mut json, &lookup)?;
+    Ok(json)
+}
+
+/// Parses the given text, substituting placeholders with environment variables
+pub fn from_str(text: impl AsRef<str>) -> Result<Json> {
+    from_str_with(text, env_var)
+}
+
+/// Reads and parses the given file, substituting
+/// placeholders with environment variables
+pub fn load(path: impl AsRef<Path>) -> Result<Json> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+                  .map_err(|e| format!("{}: {e}", path.display()))?;
+    from_str(text)
+}
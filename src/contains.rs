@@ -0,0 +1,64 @@
+//! Structural containment
+
+use crate::Json;
+
+/// How [`Json::contains_with`] compares arrays
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum ArrayPolicy {
+    /// Arrays must be equal
+    #[default]
+    Equal,
+    /// Each element of the contained array must be contained by some
+    /// element of the containing one, in any order. Elements of the
+    /// containing array can be matched more than once.
+    Elements,
+}
+
+fn contains(json: &Json, other: &Json, policy: ArrayPolicy) -> bool {
+    match (json, other) {
+        (Json::Object(obj), Json::Object(sub)) => {
+            sub.iter().all(|(k, v)| obj.get(&**k).is_some_and(|own| contains(own, v, policy)))
+        },
+        (Json::Array(arr), Json::Array(sub)) if policy == ArrayPolicy::Elements => {
+            sub.iter().all(|e| arr.iter().any(|own| contains(own, e, policy)))
+        },
+        _ => json == other,
+    }
+}
+
+impl Json {
+    /// Returns true if this value contains `other`: every member of the
+    /// objects in `other` is present in this value, with an equal value,
+    /// recursively. Arrays and the rest of values must be equal.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let response = json!({ "id" : 1, "user" : { "name" : "Jhon", "age" : 30 }, "tags" : ["a", "b"] });
+    /// assert!(response.contains(&json!({ "user" : { "name" : "Jhon" } })));
+    /// assert!(response.contains(&json!({ "tags" : ["a", "b"] })));
+    /// assert!(!response.contains(&json!({ "tags" : ["a"] })));
+    /// assert!(!response.contains(&json!({ "user" : { "email" : null } })));
+    /// ```
+    pub fn contains(&self, other: &Json) -> bool {
+        contains(self, other, ArrayPolicy::Equal)
+    }
+    /// Same as [contains](Self::contains), comparing arrays with `policy`
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, ArrayPolicy};
+    ///
+    /// let j = json!({ "users" : [{ "id" : 1, "admin" : true }, { "id" : 2 }] });
+    /// assert!(j.contains_with(&json!({ "users" : [{ "id" : 2 }] }), ArrayPolicy::Elements));
+    /// assert!(!j.contains_with(&json!({ "users" : [{ "id" : 3 }] }), ArrayPolicy::Elements));
+    /// ```
+    pub fn contains_with(&self, other: &Json, policy: ArrayPolicy) -> bool {
+        contains(self, other, policy)
+    }
+    /// Returns true if `other` [contains](Self::contains) this value
+    pub fn subset_of(&self, other: &Json) -> bool {
+        other.contains(self)
+    }
+}
@@ -0,0 +1,521 @@
+//! Conversions between [Json] and Rust types
+
+use alloc::collections::BTreeMap;
+
+use crate::escape::{escape, unescape};
+use crate::prelude::*;
+use crate::{Error, Json, JsonType, Result};
+
+fn mismatch(expected: &str, json: &Json) -> Error {
+    format!("expected {expected}, found {}", json.type_name()).into()
+}
+
+/// Same as [`Json::number`], but big integers are rounded too
+fn number(json: &Json) -> Option<f64> {
+    #[cfg(feature = "bigint")]
+    if let Json::BigInt(n) = json {
+        return Some(big_to_f64(n))
+    }
+    json.number()
+}
+
+macro_rules! try_from_int {
+    ( $( $nty:ty ),* ) => {
+        $(
+            impl TryFrom<&Json> for $nty {
+                type Error = Error;
+
+                #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss, clippy::cast_sign_loss, clippy::cast_lossless)]
+                fn try_from(value: &Json) -> Result<Self> {
+                    /* Big integers and decimals are converted exactly */
+                    #[cfg(feature = "bigint")]
+                    if let Json::BigInt(n) = value {
+                        return num_traits::ToPrimitive::to_i128(n)
+                                .and_then(|n| <$nty>::try_from(n).ok())
+                                .ok_or_else(|| format!("{n} is out of range for {}", stringify!($nty)).into())
+                    }
+                    #[cfg(feature = "decimal")]
+                    if let Json::Decimal(d) = value {
+                        return d.is_integer()
+                                .then(|| num_traits::ToPrimitive::to_i128(d)).flatten()
+                                .and_then(|n| <$nty>::try_from(n).ok())
+                                .ok_or_else(|| format!("{d} is out of range for {}", stringify!($nty)).into())
+                    }
+                    let n = value.number().ok_or_else(|| mismatch(stringify!($nty), value))?;
+                    /* MAX + 1 is a power of two, so it's exact as a f64, unlike
+                     * the MAX of the 64 bit types, which rounds up to it */
+                    if fract(n) != 0.0 || n < <$nty>::MIN as f64 || n >= <$nty>::MAX as f64 + 1.0 {
+                        return Err(format!("{n} is out of range for {}", stringify!($nty)).into())
+                    }
+                    Ok(n as $nty)
+                }
+            }
+        )*
+    };
+}
+
+try_from_int!(i8,i16,i32,i64,isize,u8,u16,u32,u64,usize);
+
+impl TryFrom<&Json> for f64 {
+    type Error = Error;
+
+    fn try_from(value: &Json) -> Result<Self> {
+        number(value).ok_or_else(|| mismatch("f64", value))
+    }
+}
+
+impl TryFrom<&Json> for f32 {
+    type Error = Error;
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn try_from(value: &Json) -> Result<Self> {
+        number(value).map(|n| n as f32).ok_or_else(|| mismatch("f32", value))
+    }
+}
+
+impl TryFrom<&Json> for bool {
+    type Error = Error;
+
+    fn try_from(value: &Json) -> Result<Self> {
+        value.boolean().ok_or_else(|| mismatch("boolean", value))
+    }
+}
+
+/// Returns the raw string, without resolving escape sequences
+impl<'a> TryFrom<&'a Json> for &'a str {
+    type Error = Error;
+
+    fn try_from(value: &'a Json) -> Result<Self> {
+        value.string().ok_or_else(|| mismatch("string", value))
+    }
+}
+
+/// Returns the string with all its escape sequences resolved
+impl TryFrom<&Json> for String {
+    type Error = Error;
+
+    fn try_from(value: &Json) -> Result<Self> {
+        let s = value.string().ok_or_else(|| mismatch("string", value))?;
+        Ok(unescape(s).into_owned())
+    }
+}
+
+/// Converts a value into [Json]
+///
+/// Strings are escaped, so they round trip through [`FromJson`].
+/// Tuples and fixed size arrays become JSON arrays, and maps
+/// with string keys become objects.
+///
+/// # Example
+/// ```
+/// use std::collections::BTreeMap;
+/// use json::{json, Json};
+///
+/// let mut map = BTreeMap::new();
+/// map.insert("a".to_string(), vec![(1, true)]);
+/// map.insert("b".to_string(), vec![]);
+/// assert_eq!(Json::from_value(&map), json!({ "a" : [[1, true]], "b" : [] }));
+/// ```
+pub trait ToJson {
+    /// Builds a [Json] with this value
+    fn to_json(&self) -> Json;
+}
+
+/// Builds a value from a [Json]
+///
+/// # Example
+/// ```
+/// use json::json;
+///
+/// let j = json!([[1, "a"], [2, "b"]]);
+/// let pairs = j.to_value::<Vec<(u8, String)>>().unwrap();
+/// assert_eq!(pairs, [(1, "a".to_string()), (2, "b".to_string())]);
+///
+/// let err = j.to_value::<Vec<(u8, u8)>>().unwrap_err();
+/// assert_eq!(err.to_string(), "[0][1]: expected u8, found string");
+/// ```
+pub trait FromJson: Sized {
+    /// Converts the [Json] into this type
+    fn from_json(json: &Json) -> Result<Self>;
+    /// Same as [`from_json`](FromJson::from_json), for a value found
+    /// at `path`. Containers override it to extend the path of the
+    /// errors of their elements.
+    #[doc(hidden)]
+    fn from_json_at(json: &Json, path: &mut String) -> core::result::Result<Self, TypeError> {
+        Self::from_json(json).map_err(|err| TypeError::new(path, json, err))
+    }
+}
+
+/// Error converting a [Json] into a Rust type
+///
+/// Returned by [`Json::get_as`] and [`Json::as_type`]
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct TypeError {
+    /// Path to the value that couldn't be converted, like `users[0].name`.
+    /// It's empty if the value is the root.
+    pub path: String,
+    /// Type of the value found at the path, or `None` if it's missing
+    pub found: Option<JsonType>,
+    /// Why the conversion failed
+    pub message: String,
+}
+
+impl TypeError {
+    fn new(path: &str, json: &Json, msg: impl Display) -> Self {
+        TypeError {
+            path: path.into(),
+            found: Some(json.json_type()),
+            message: msg.to_string(),
+        }
+    }
+}
+
+impl Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl core::error::Error for TypeError { }
+
+impl From<TypeError> for Error {
+    fn from(err: TypeError) -> Self {
+        err.to_string().into()
+    }
+}
+
+impl Json {
+    /// Converts a Rust value into [Json]. See [`ToJson`]
+    pub fn from_value<T: ToJson + ?Sized>(value: &T) -> Json {
+        value.to_json()
+    }
+    /// Converts this value into a `T`. See [`FromJson`]
+    ///
+    /// Errors reference the path of the value that failed to
+    /// convert, like `users[0].name: expected string, found number`.
+    pub fn to_value<T: FromJson>(&self) -> Result<T> {
+        T::from_json(self)
+    }
+    /// Converts this value into a `T`. Like [`to_value`](Self::to_value),
+    /// but the error holds the path of the value that failed to convert,
+    /// and its type
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, JsonType};
+    ///
+    /// let j = json!({ "ports" : [80, "443"] });
+    /// let err = j.as_type::<std::collections::BTreeMap<String, Vec<u16>>>().unwrap_err();
+    /// assert_eq!(err.path, "ports[1]");
+    /// assert_eq!(err.found, Some(JsonType::String));
+    /// assert_eq!(err.to_string(), "ports[1]: expected u16, found string");
+    /// ```
+    pub fn as_type<T: FromJson>(&self) -> core::result::Result<T, TypeError> {
+        T::from_json_at(self, &mut String::new())
+    }
+    /// Gets the member `key` of this object, and converts it into a `T`
+    ///
+    /// Fails if this is not an object, if it doesn't have
+    /// the key, or if its value can't be converted.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "name" : "db", "replicas" : [1, 2] });
+    /// assert_eq!(j.get_as::<String>("name").unwrap(), "db");
+    /// assert_eq!(j.get_as::<Vec<u8>>("replicas").unwrap(), [1, 2]);
+    ///
+    /// let err = j.get_as::<u8>("port").unwrap_err();
+    /// assert_eq!(err.to_string(), "port: missing key");
+    /// assert_eq!(err.found, None);
+    /// ```
+    pub fn get_as<T: FromJson>(&self, key: impl AsRef<str>) -> core::result::Result<T, TypeError> {
+        let key = key.as_ref();
+        let Json::Object(obj) = self else {
+            return Err(TypeError::new("", self, mismatch("object", self)))
+        };
+        let mut path = unescape(key).into_owned();
+        match obj.get(key) {
+            Some(val) => T::from_json_at(val, &mut path),
+            None => Err(TypeError { path, found: None, message: "missing key".into() }),
+        }
+    }
+}
+
+type TypeResult<T> = core::result::Result<T, TypeError>;
+
+/// Converts the element `i` of an array, at `path`.
+/// Paths are formatted like in [`json_get`](crate::json_get)
+fn element<T: FromJson>(elems: &[Json], i: usize, path: &mut String) -> TypeResult<T> {
+    let len = path.len();
+    write!(path, "[{i}]").unwrap();
+    let val = T::from_json_at(&elems[i], path)?;
+    path.truncate(len);
+    Ok(val)
+}
+
+fn elements<'a>(json: &'a Json, path: &str) -> TypeResult<&'a [Json]> {
+    json.array().ok_or_else(|| TypeError::new(path, json, mismatch("array", json)))
+}
+
+fn elements_exact<'a>(json: &'a Json, n: usize, path: &str) -> TypeResult<&'a [Json]> {
+    let elems = elements(json, path)?;
+    if elems.len() != n {
+        let msg = format_args!("expected an array of {n} elements, found {}", elems.len());
+        return Err(TypeError::new(path, json, msg))
+    }
+    Ok(elems)
+}
+
+fn members<T: FromJson, M: FromIterator<(String, T)>>(json: &Json, path: &mut String) -> TypeResult<M> {
+    let obj = json.object().ok_or_else(|| TypeError::new(path, json, mismatch("object", json)))?;
+    let len = path.len();
+    obj.iter().map(|(k, v)| {
+        let key = unescape(k).into_owned();
+        if len > 0 {
+            path.push('.');
+        }
+        path.push_str(&key);
+        let val = T::from_json_at(v, path)?;
+        path.truncate(len);
+        Ok((key, val))
+    }).collect()
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Json { (**self).to_json() }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self) -> Json { (**self).to_json() }
+}
+
+impl<T: FromJson> FromJson for Box<T> {
+    fn from_json(json: &Json) -> Result<Self> { T::from_json(json).map(Box::new) }
+    fn from_json_at(json: &Json, path: &mut String) -> TypeResult<Self> { T::from_json_at(json, path).map(Box::new) }
+}
+
+impl ToJson for Json {
+    fn to_json(&self) -> Json { self.clone() }
+}
+
+impl FromJson for Json {
+    fn from_json(json: &Json) -> Result<Self> { Ok(json.clone()) }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Json { Json::String(escape(self).into()) }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Json { self.as_str().to_json() }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Json { Json::from(*self) }
+}
+
+macro_rules! num_to_json {
+    ( $( $nty:ty ),* ) => {
+        $(
+            impl ToJson for $nty {
+                #[allow(clippy::cast_precision_loss, clippy::cast_lossless)]
+                fn to_json(&self) -> Json { Json::Number(from_f64(*self as f64)) }
+            }
+        )*
+    };
+}
+
+num_to_json!(i8,i16,i32,u8,u16,u32,f32,f64);
+
+/// 2^53. Integers above it may not be represented exactly as an f64
+#[cfg(feature = "bigint")]
+const MAX_SAFE_INTEGER: u128 = 1 << 53;
+
+macro_rules! wide_int_to_json {
+    ( $( $nty:ty ),* ) => {
+        $(
+            /// Integers above 2^53 in magnitude can't be represented exactly
+            /// as an f64. With the `bigint` feature, they're converted into a
+            /// [`Json::BigInt`]. Otherwise, they're rounded to the nearest f64.
+            impl ToJson for $nty {
+                #[allow(clippy::cast_precision_loss, clippy::cast_lossless)]
+                fn to_json(&self) -> Json {
+                    #[cfg(feature = "bigint")]
+                    if (*self as i128).unsigned_abs() > MAX_SAFE_INTEGER {
+                        return Json::BigInt((*self).into())
+                    }
+                    Json::Number(from_f64(*self as f64))
+                }
+            }
+        )*
+    };
+}
+
+wide_int_to_json!(i64,isize,u64,usize);
+
+/// Implements [`FromJson`] with the [`TryFrom<&Json>`] impls above
+macro_rules! from_json_try_from {
+    ( $( $ty:ty ),* ) => {
+        $(
+            impl FromJson for $ty {
+                fn from_json(json: &Json) -> Result<Self> { Self::try_from(json) }
+            }
+        )*
+    };
+}
+
+from_json_try_from!(i8,i16,i32,i64,isize,u8,u16,u32,u64,usize,f32,f64,bool,String);
+
+/// `None` becomes null
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Json {
+        self.as_ref().map_or(Json::Null, ToJson::to_json)
+    }
+}
+
+/// Null becomes `None`
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(json: &Json) -> Result<Self> { Ok(Self::from_json_at(json, &mut String::new())?) }
+    fn from_json_at(json: &Json, path: &mut String) -> TypeResult<Self> {
+        match json {
+            Json::Null => Ok(None),
+            json => T::from_json_at(json, path).map(Some),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Json {
+        self.iter().map(ToJson::to_json).collect::<Vec<_>>().into()
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Json { self.as_slice().to_json() }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(json: &Json) -> Result<Self> { Ok(Self::from_json_at(json, &mut String::new())?) }
+    fn from_json_at(json: &Json, path: &mut String) -> TypeResult<Self> {
+        let elems = elements(json, path)?;
+        (0..elems.len()).map(|i| element(elems, i, path)).collect()
+    }
+}
+
+impl<T: ToJson, const N: usize> ToJson for [T; N] {
+    fn to_json(&self) -> Json { self.as_slice().to_json() }
+}
+
+impl<T: FromJson, const N: usize> FromJson for [T; N] {
+    fn from_json(json: &Json) -> Result<Self> { Ok(Self::from_json_at(json, &mut String::new())?) }
+    fn from_json_at(json: &Json, path: &mut String) -> TypeResult<Self> {
+        elements_exact(json, N, path)?;
+        let vec = Vec::<T>::from_json_at(json, path)?;
+        Ok(vec.try_into().unwrap_or_else(|_| unreachable!("the length was checked")))
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    fn to_json(&self) -> Json {
+        Json::Object(self.iter().map(|(k, v)| (escape(k).into(), v.to_json())).collect())
+    }
+}
+
+impl<T: FromJson> FromJson for BTreeMap<String, T> {
+    fn from_json(json: &Json) -> Result<Self> { Ok(members(json, &mut String::new())?) }
+    fn from_json_at(json: &Json, path: &mut String) -> TypeResult<Self> { members(json, path) }
+}
+
+#[cfg(feature = "std")]
+impl<T: ToJson, S> ToJson for std::collections::HashMap<String, T, S> {
+    fn to_json(&self) -> Json {
+        Json::Object(self.iter().map(|(k, v)| (escape(k).into(), v.to_json())).collect())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: FromJson, S: core::hash::BuildHasher + Default> FromJson for std::collections::HashMap<String, T, S> {
+    fn from_json(json: &Json) -> Result<Self> { Ok(members(json, &mut String::new())?) }
+    fn from_json_at(json: &Json, path: &mut String) -> TypeResult<Self> { members(json, path) }
+}
+
+macro_rules! tuples {
+    ( $( ( $( $n:tt $t:ident ),+ ) )* ) => {
+        $(
+            impl<$( $t: ToJson ),+> ToJson for ( $( $t, )+ ) {
+                fn to_json(&self) -> Json {
+                    vec![ $( self.$n.to_json() ),+ ].into()
+                }
+            }
+
+            impl<$( $t: FromJson ),+> FromJson for ( $( $t, )+ ) {
+                fn from_json(json: &Json) -> Result<Self> { Ok(Self::from_json_at(json, &mut String::new())?) }
+                fn from_json_at(json: &Json, path: &mut String) -> TypeResult<Self> {
+                    let elems = elements_exact(json, [ $( stringify!($t) ),+ ].len(), path)?;
+                    Ok(( $( element::<$t>(elems, $n, path)?, )+ ))
+                }
+            }
+        )*
+    };
+}
+
+tuples! {
+    (0 A)
+    (0 A, 1 B)
+    (0 A, 1 B, 2 C)
+    (0 A, 1 B, 2 C, 3 D)
+    (0 A, 1 B, 2 C, 3 D, 4 E)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H)
+}
+
+/// Helpers for the [`json_get`](crate::json_get) macro
+#[doc(hidden)]
+pub mod get {
+    use super::{Error, Json, Result};
+    use crate::prelude::*;
+
+    pub use crate::prelude::String;
+
+    fn located(path: &str, msg: impl Display) -> Error {
+        if path.is_empty() {
+            format!("<root>: {msg}").into()
+        } else {
+            format!("{path}: {msg}").into()
+        }
+    }
+
+    pub fn key<'a>(json: &'a Json, key: &str, path: &mut String) -> Result<&'a Json> {
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(key);
+        match json {
+            Json::Object(obj) => obj.get(key).ok_or_else(|| located(path, "missing key")),
+            _ => Err(located(path, format_args!("expected object, found {}", json.type_name()))),
+        }
+    }
+
+    pub fn index<'a>(json: &'a Json, i: usize, path: &mut String) -> Result<&'a Json> {
+        write!(path, "[{i}]").unwrap();
+        match json {
+            Json::Array(arr) => arr.get(i).ok_or_else(|| located(path, "index out of bounds")),
+            _ => Err(located(path, format_args!("expected array, found {}", json.type_name()))),
+        }
+    }
+
+    pub fn convert<'a, T>(json: &'a Json, path: &str) -> Result<T>
+    where
+        T: TryFrom<&'a Json>,
+        T::Error: Display,
+    {
+        T::try_from(json).map_err(|e| located(path, e))
+    }
+}
@@ -0,0 +1,399 @@
+//! Lossless concrete syntax tree
+//!
+//! Parses a document into a tree that keeps everything in the source:
+//! whitespace, comments, the order of the object members (including
+//! duplicated keys), and the exact text of strings and numbers.
+//! Printing the tree with [Display](core::fmt::Display) gives back
+//! the original text, byte by byte. [`Cst::to_json5`] prints it as
+//! JSON5 instead, keeping the comments.
+//!
+//! # Example
+//! ```
+//! use json::{cst, json};
+//!
+//! let src = r#"{
+//!     // The name of the package
+//!     "name" : "json",
+//!     "version" : 1.0
+//! }"#;
+//! let doc = cst::parse(src).unwrap();
+//! assert_eq!(doc.to_string(), src);
+//! assert_eq!(doc.to_json().unwrap(), json!({ "name" : "json", "version" : 1.0 }));
+//!
+//! let cst::NodeKind::Object { members, .. } = &doc.value.kind else { panic!() };
+//! assert_eq!(&*members[0].key_before, "\n    // The name of the package\n    ");
+//! assert_eq!(&*members[1].value.kind.to_string(), "1.0");
+//! ```
+
+use core::fmt;
+
+use crate::lexer::{self, token::TokenKind};
+use crate::prelude::*;
+use crate::serialize::{is_identifier, write_string};
+use crate::spanned::{self, Spanned, SpannedJson};
+use crate::{Json, JsonConfig, Result, Syntax};
+
+/// A parsed document
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Cst {
+    pub value: Node,
+    /// Whitespace and comments after the value
+    pub after: Box<str>,
+}
+
+/// A value, together with the whitespace and comments before it
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Node {
+    /// Whitespace and comments before the value
+    pub before: Box<str>,
+    pub kind: NodeKind,
+}
+
+/// The kinds of [Node]
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum NodeKind {
+    Array {
+        elems: Vec<Element>,
+        /// Whitespace and comments before the `]`
+        end: Box<str>,
+    },
+    Object {
+        members: Vec<Member>,
+        /// Whitespace and comments before the `}`
+        end: Box<str>,
+    },
+    /// A string, without its quotes. The escape sequences are unresolved
+    String(Box<str>),
+    /// A number, exactly as written
+    Number(Box<str>),
+    True, False, Null,
+}
+
+/// An element of an array
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Element {
+    pub value: Node,
+    /// If the element is followed by a comma,
+    /// the whitespace and comments before it
+    pub comma: Option<Box<str>>,
+}
+
+/// A member of an object
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Member {
+    /// Whitespace and comments before the key
+    pub key_before: Box<str>,
+    /// The key, without its quotes. The escape sequences are unresolved
+    pub key: Box<str>,
+    /// Whitespace and comments before the `:`
+    pub colon_before: Box<str>,
+    pub value: Node,
+    /// If the member is followed by a comma,
+    /// the whitespace and comments before it
+    pub comma: Option<Box<str>>,
+}
+
+/// Parses the given text into a [Cst]
+pub fn parse(text: impl AsRef<str>) -> Result<Cst> {
+    parse_with_config(text, JsonConfig::default())
+}
+
+/// Same as [parse], but using the given [`JsonConfig`]
+pub fn parse_with_config(text: impl AsRef<str>, conf: JsonConfig) -> Result<Cst> {
+    let src = text.as_ref();
+    let root = spanned::parse_with_config(src, conf)?;
+    let end = root.span.offset + root.span.len;
+    Ok(Cst {
+        value: node(src, &src[..root.span.offset], &root),
+        after: src[end..].into(),
+    })
+}
+
+/// Splits the text between two tokens at the first `sep`
+/// that is not inside a comment
+fn split(gap: &str, sep: u8) -> Option<(&str, &str)> {
+    let bytes = gap.as_bytes();
+    let mut i = 0;
+    while let Some(&b) = bytes.get(i) {
+        i = match (b, bytes.get(i + 1)) {
+            _ if b == sep => return Some((&gap[..i], &gap[i + 1..])),
+            (b'/', Some(b'/')) => gap[i..].find('\n').map_or(gap.len(), |n| i + n),
+            (b'/', Some(b'*')) => gap[i + 2..].find("*/").map_or(gap.len(), |n| i + n + 4),
+            _ => i + 1,
+        };
+    }
+    None
+}
+
+/// Splits the text after an element or member at its comma, if any.
+/// Returns the comma of the previous element and the trivia after it.
+fn split_comma(gap: &str) -> (Option<Box<str>>, &str) {
+    match split(gap, b',') {
+        Some((comma, rest)) => (Some(comma.into()), rest),
+        None => (None, gap),
+    }
+}
+
+fn node(src: &str, before: &str, json: &Spanned<SpannedJson>) -> Node {
+    let start = json.span.offset;
+    let end = start + json.span.len;
+    let kind = match &json.value {
+        SpannedJson::Array(arr) => {
+            let mut elems: Vec<Element> = Vec::with_capacity(arr.len());
+            let mut pos = start + 1;
+            for e in arr {
+                let mut before = &src[pos..e.span.offset];
+                if let Some(prev) = elems.last_mut() {
+                    (prev.comma, before) = split_comma(before);
+                }
+                elems.push(Element { value: node(src, before, e), comma: None });
+                pos = e.span.offset + e.span.len;
+            }
+            let mut end = &src[pos..end - 1];
+            if let Some(last) = elems.last_mut() {
+                (last.comma, end) = split_comma(end);
+            }
+            NodeKind::Array { elems, end: end.into() }
+        },
+        SpannedJson::Object(obj) => {
+            let mut members: Vec<Member> = Vec::with_capacity(obj.len());
+            let mut pos = start + 1;
+            for (k, v) in obj {
+                let mut key_before = &src[pos..k.span.offset];
+                if let Some(prev) = members.last_mut() {
+                    (prev.comma, key_before) = split_comma(key_before);
+                }
+                let key_end = k.span.offset + k.span.len;
+                let gap = &src[key_end..v.span.offset];
+                let (colon_before, before) = split(gap, b':').unwrap_or(("", gap));
+                members.push(Member {
+                    key_before: key_before.into(),
+                    key: k.value.clone(),
+                    colon_before: colon_before.into(),
+                    value: node(src, before, v),
+                    comma: None,
+                });
+                pos = v.span.offset + v.span.len;
+            }
+            let mut end = &src[pos..end - 1];
+            if let Some(last) = members.last_mut() {
+                (last.comma, end) = split_comma(end);
+            }
+            NodeKind::Object { members, end: end.into() }
+        },
+        SpannedJson::String(s) => NodeKind::String(s.clone()),
+        SpannedJson::True => NodeKind::True,
+        SpannedJson::False => NodeKind::False,
+        SpannedJson::Null => NodeKind::Null,
+        #[cfg(feature = "bigint")]
+        SpannedJson::BigInt(_) => NodeKind::Number(src[start..end].into()),
+        #[cfg(feature = "decimal")]
+        SpannedJson::Decimal(_) => NodeKind::Number(src[start..end].into()),
+        SpannedJson::Number(_) => NodeKind::Number(src[start..end].into()),
+    };
+    Node { before: before.into(), kind }
+}
+
+/// Parses the text of a number, which must be a single number token
+fn number(n: &str) -> Result<Json> {
+    match &lexer::tokenize(n)?[..] {
+        [t] if t.get_type() == TokenKind::Number && t.span().len == n.len() => Json::deserialize(n),
+        _ => Err(format!("Invalid number: {n}").into()),
+    }
+}
+
+impl Cst {
+    /// Builds the semantic [Json] value of this document.
+    /// See [`Node::to_json`]
+    pub fn to_json(&self) -> Result<Json> { self.value.to_json() }
+    /// Prints the document as JSON5, keeping its whitespace and comments.
+    ///
+    /// Keys that are identifiers are unquoted, and strings use single
+    /// quotes. Arrays and objects whose closing bracket is on its own
+    /// line get a trailing comma.
+    ///
+    /// # Example
+    /// ```
+    /// use json::cst;
+    ///
+    /// let doc = cst::parse(r#"{
+    ///     // Comment
+    ///     "name" : "it's",
+    ///     "a-b" : [1, 2]
+    /// }"#).unwrap();
+    /// assert_eq!(doc.to_json5(), r#"{
+    ///     // Comment
+    ///     name : 'it\'s',
+    ///     'a-b' : [1, 2],
+    /// }"#);
+    /// ```
+    pub fn to_json5(&self) -> String {
+        let mut out = String::new();
+        json5(&self.value, &mut out).unwrap_or_default();
+        out.push_str(&self.after);
+        out
+    }
+}
+
+/// Writes the trailing comma of a container, if `missing`
+/// and its closing bracket is on its own line
+fn trailing_comma(missing: bool, end: &str, out: &mut dyn Write) -> fmt::Result {
+    if missing && end.contains('\n') {
+        out.write_char(',')?;
+    }
+    Ok(())
+}
+
+fn json5(node: &Node, out: &mut dyn Write) -> fmt::Result {
+    out.write_str(&node.before)?;
+    match &node.kind {
+        NodeKind::Array { elems, end } => {
+            out.write_char('[')?;
+            for e in elems {
+                json5(&e.value, out)?;
+                if let Some(before) = &e.comma {
+                    write!(out, "{before},")?;
+                }
+            }
+            trailing_comma(elems.last().is_some_and(|e| e.comma.is_none()), end, out)?;
+            write!(out, "{end}]")
+        },
+        NodeKind::Object { members, end } => {
+            out.write_char('{')?;
+            for m in members {
+                out.write_str(&m.key_before)?;
+                if is_identifier(&m.key) {
+                    out.write_str(&m.key)?;
+                } else {
+                    write_string(&m.key, Syntax::Json5, false, out)?;
+                }
+                write!(out, "{}:", m.colon_before)?;
+                json5(&m.value, out)?;
+                if let Some(before) = &m.comma {
+                    write!(out, "{before},")?;
+                }
+            }
+            trailing_comma(members.last().is_some_and(|m| m.comma.is_none()), end, out)?;
+            write!(out, "{end}}}")
+        },
+        NodeKind::String(s) => write_string(s, Syntax::Json5, false, out),
+        kind => write!(out, "{kind}"),
+    }
+}
+
+impl Node {
+    /// Builds the semantic [Json] value of this node, dropping the
+    /// whitespace and comments. If a key is duplicated, the last one wins.
+    ///
+    /// Fails if a number of the tree is not a valid number.
+    pub fn to_json(&self) -> Result<Json> {
+        Ok(match &self.kind {
+            NodeKind::Array { elems, .. } => {
+                elems.iter().map(|e| e.value.to_json()).collect::<Result<Vec<_>>>()?.into()
+            },
+            NodeKind::Object { members, .. } => {
+                let mut obj = Map::default();
+                for m in members {
+                    obj.insert((*m.key).into(), m.value.to_json()?);
+                }
+                Json::Object(obj)
+            },
+            NodeKind::String(s) => Json::String(s.clone()),
+            NodeKind::Number(n) => number(n)?,
+            NodeKind::True => Json::True,
+            NodeKind::False => Json::False,
+            NodeKind::Null => Json::Null,
+        })
+    }
+}
+
+/// Builds a compact node, without whitespace
+impl From<&Json> for Node {
+    fn from(json: &Json) -> Self {
+        let kind = match json {
+            Json::Array(arr) => {
+                let mut elems: Vec<Element> = arr.iter().map(|e| Element { value: e.into(), comma: Some("".into()) }).collect();
+                if let Some(last) = elems.last_mut() {
+                    last.comma = None;
+                }
+                NodeKind::Array { elems, end: "".into() }
+            },
+            Json::Object(obj) => {
+                let mut members: Vec<Member> = obj.iter().map(|(k, v)| Member {
+                    key_before: "".into(),
+                    key: (**k).into(),
+                    colon_before: "".into(),
+                    value: v.into(),
+                    comma: Some("".into()),
+                }).collect();
+                if let Some(last) = members.last_mut() {
+                    last.comma = None;
+                }
+                NodeKind::Object { members, end: "".into() }
+            },
+            Json::String(s) => NodeKind::String(s.clone()),
+            Json::True => NodeKind::True,
+            Json::False => NodeKind::False,
+            Json::Null => NodeKind::Null,
+            #[cfg(feature = "bigint")]
+            n @ Json::BigInt(_) => NodeKind::Number(n.to_string().into()),
+            #[cfg(feature = "decimal")]
+            n @ Json::Decimal(_) => NodeKind::Number(n.to_string().into()),
+            n @ Json::Number(_) => NodeKind::Number(n.to_string().into()),
+        };
+        Node { before: "".into(), kind }
+    }
+}
+
+impl From<&Json> for Cst {
+    fn from(json: &Json) -> Self {
+        Cst { value: json.into(), after: "".into() }
+    }
+}
+
+fn comma(f: &mut fmt::Formatter<'_>, comma: Option<&str>) -> fmt::Result {
+    match comma {
+        Some(before) => write!(f, "{before},"),
+        None => Ok(()),
+    }
+}
+
+impl fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeKind::Array { elems, end } => {
+                f.write_str("[")?;
+                for e in elems {
+                    write!(f, "{}", e.value)?;
+                    comma(f, e.comma.as_deref())?;
+                }
+                write!(f, "{end}]")
+            },
+            NodeKind::Object { members, end } => {
+                f.write_str("{")?;
+                for m in members {
+                    write!(f, "{}\"{}\"{}:{}", m.key_before, m.key, m.colon_before, m.value)?;
+                    comma(f, m.comma.as_deref())?;
+                }
+                write!(f, "{end}}}")
+            },
+            NodeKind::String(s) => write!(f, "\"{s}\""),
+            NodeKind::Number(n) => f.write_str(n),
+            NodeKind::True => f.write_str("true"),
+            NodeKind::False => f.write_str("false"),
+            NodeKind::Null => f.write_str("null"),
+        }
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.before, self.kind)
+    }
+}
+
+impl fmt::Display for Cst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.after)
+    }
+}
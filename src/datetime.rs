@@ -0,0 +1,49 @@
+//! Conversions between [Json] strings and [chrono] datetimes
+
+use chrono::{DateTime, FixedOffset, SecondsFormat, TimeZone, Utc};
+
+use crate::prelude::*;
+use crate::Json;
+
+/// Formats a datetime as an RFC 3339 string in UTC, like `2024-05-01T10:30:00Z`
+pub(crate) fn format<Tz: TimeZone>(dt: &DateTime<Tz>, precision: SecondsFormat) -> String {
+    dt.with_timezone(&Utc).to_rfc3339_opts(precision, true)
+}
+
+impl Json {
+    /// Parses the inner string as an RFC 3339 datetime, like
+    /// `2024-05-01T12:30:00+02:00`.
+    ///
+    /// Returns `None` if this is not a String variant,
+    /// or if it doesn't hold a valid datetime.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "created" : "2024-05-01T12:30:00+02:00", "name" : "x" });
+    /// let dt = j.get("created").unwrap().as_datetime().unwrap();
+    /// assert_eq!(dt.timestamp(), 1_714_559_400);
+    /// assert!(j.get("name").unwrap().as_datetime().is_none());
+    /// ```
+    pub fn as_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(self.string()?).ok()
+    }
+}
+
+/// Builds a String with the datetime in RFC 3339 format, converted to UTC.
+/// Fractional seconds are only written if they are not zero.
+///
+/// # Example
+/// ```
+/// use chrono::{FixedOffset, TimeZone};
+/// use json::Json;
+///
+/// let dt = FixedOffset::east_opt(7200).unwrap().with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap();
+/// assert_eq!(Json::from(dt).to_string(), r#""2024-05-01T10:30:00Z""#);
+/// ```
+impl<Tz: TimeZone> From<DateTime<Tz>> for Json {
+    fn from(value: DateTime<Tz>) -> Self {
+        Json::String(format(&value, SecondsFormat::AutoSi).into())
+    }
+}
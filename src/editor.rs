@@ -0,0 +1,238 @@
+//! Format-preserving edition of documents
+//!
+//! # Example
+//! ```
+//! use json::editor::DocumentEditor;
+//! use json::json;
+//!
+//! let src = r#"{
+//!     // Bumped by the release script
+//!     "version" : "1.0.0",
+//!     "dependencies" : [ "a" ]
+//! }"#;
+//!
+//! let mut editor = DocumentEditor::parse(src).unwrap();
+//! editor.set("/version", &json!("1.1.0")).unwrap();
+//! editor.insert("/dependencies/-", &json!("b")).unwrap();
+//! assert_eq!(editor.to_string(), r#"{
+//!     // Bumped by the release script
+//!     "version" : "1.1.0",
+//!     "dependencies" : [ "a", "b" ]
+//! }"#);
+//! ```
+
+use core::fmt;
+use core::mem;
+
+use crate::cst::{self, Cst, Element, Member, Node, NodeKind};
+use crate::pointer::parse_index;
+use crate::prelude::*;
+use crate::{Json, JsonPointer, Result};
+
+/// Edits a document, keeping its formatting and comments.
+///
+/// Only the edited values change when printing the document back.
+/// New values are written in compact form, and new elements and
+/// members are separated like the ones already in the container.
+///
+/// Like in [`Json::pointer`], if a key is duplicated,
+/// the pointers reference its last member.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct DocumentEditor {
+    doc: Cst,
+}
+
+fn missing(ptr: &JsonPointer) -> crate::Error {
+    format!("Path \"{ptr}\" doesn't exist").into()
+}
+
+fn child<'a>(node: &'a mut Node, token: &str) -> Option<&'a mut Node> {
+    match &mut node.kind {
+        NodeKind::Object { members, .. } => members.iter_mut().rev().find(|m| &*m.key == token).map(|m| &mut m.value),
+        NodeKind::Array { elems, .. } => elems.get_mut(parse_index(token)?).map(|e| &mut e.value),
+        _ => None,
+    }
+}
+
+fn resolve<'a>(node: &'a mut Node, ptr: &JsonPointer) -> Option<&'a mut Node> {
+    ptr.tokens().try_fold(node, child)
+}
+
+/// Splits the whitespace and comments between two values at its first
+/// newline. The first part trails the previous value, like a comment at
+/// the end of its line, and the rest leads the next one.
+fn split_line(trivia: &str) -> (&str, &str) {
+    trivia.find('\n').map_or(("", trivia), |i| trivia.split_at(i))
+}
+
+/// Returns the whitespace at the end of `trivia`, without comments
+fn whitespace(trivia: &str) -> Box<str> {
+    trivia[trivia.trim_end().len()..].into()
+}
+
+/// Returns the whitespace that separates the values of a container.
+/// That is the one before its second value, if there's one.
+fn separator<'a>(mut befores: impl Iterator<Item = &'a str>) -> Box<str> {
+    let first = befores.next().unwrap_or("");
+    whitespace(befores.next().unwrap_or(first))
+}
+
+/// Makes room for a value at the end of a container. Returns the text
+/// before the new value, and leaves the comment at the end of the line
+/// of the last value, if any, after its comma.
+fn append(end: &mut Box<str>, last_comma: Option<&mut Option<Box<str>>>, sep: &str) -> (Box<str>, Option<Box<str>>) {
+    let Some(last_comma) = last_comma else {
+        return ("".into(), None)
+    };
+    let (trailing, leading) = split_line(end);
+    let before = format!("{trailing}{sep}").into();
+    *end = leading.into();
+    (before, last_comma.replace("".into()))
+}
+
+/// After removing the last value of a container, moves its comma to the
+/// value before it. The comment at the end of the line of the removed
+/// value is dropped, and the one of the previous value is kept.
+fn remove_last(prev_comma: Option<&mut Option<Box<str>>>, comma: Option<Box<str>>, before: &str, end: &mut Box<str>) {
+    if let Some(prev_comma) = prev_comma {
+        *prev_comma = comma;
+    }
+    *end = format!("{}{}", split_line(before).0, split_line(end).1).into();
+}
+
+/// Joins the whitespace and comments around a removed value
+fn join(removed: &str, next: &str, first: bool) -> Box<str> {
+    if !next.contains('\n') {
+        return if first { removed.into() } else { next.into() }
+    }
+    format!("{}{}", split_line(removed).0, split_line(next).1).into()
+}
+
+fn insert_element(elems: &mut Vec<Element>, end: &mut Box<str>, i: usize, value: &Json) {
+    let sep = separator(elems.iter().map(|e| &*e.value.before));
+    let mut new = Element { value: value.into(), comma: Some("".into()) };
+    if let Some(next) = elems.get_mut(i) {
+        if next.value.before.contains('\n') {
+            let (trailing, leading) = split_line(&next.value.before);
+            new.value.before = format!("{trailing}{sep}").into();
+            next.value.before = leading.into();
+        } else {
+            new.value.before = mem::replace(&mut next.value.before, sep);
+        }
+    } else {
+        (new.value.before, new.comma) = append(end, elems.last_mut().map(|e| &mut e.comma), &sep);
+    }
+    elems.insert(i, new);
+}
+
+fn push_member(members: &mut Vec<Member>, end: &mut Box<str>, key: &str, value: &Json) {
+    let sep = separator(members.iter().map(|m| &*m.key_before));
+    let mut new = Member {
+        key_before: "".into(),
+        key: key.into(),
+        colon_before: "".into(),
+        value: value.into(),
+        comma: None,
+    };
+    if let Some(last) = members.last() {
+        new.colon_before = whitespace(&last.colon_before);
+        new.value.before = whitespace(&last.value.before);
+    }
+    (new.key_before, new.comma) = append(end, members.last_mut().map(|m| &mut m.comma), &sep);
+    members.push(new);
+}
+
+impl DocumentEditor {
+    /// Builds an editor for the given [Cst]
+    pub fn new(doc: Cst) -> Self { Self { doc } }
+    /// Parses the given text. See [`cst::parse`]
+    pub fn parse(text: impl AsRef<str>) -> Result<Self> {
+        cst::parse(text).map(Self::new)
+    }
+    /// Returns the edited document
+    pub fn cst(&self) -> &Cst { &self.doc }
+    /// Returns the edited document
+    pub fn into_cst(self) -> Cst { self.doc }
+    /// Splits the pointer into the container that holds
+    /// the value it references and the last token
+    fn parent<'a>(&mut self, ptr: &'a JsonPointer) -> Result<(&mut NodeKind, &'a str)> {
+        let (Some(parent), Some(last)) = (ptr.parent(), ptr.last()) else {
+            return Err("Expected a path to a member or element".into());
+        };
+        let parent = resolve(&mut self.doc.value, &parent).ok_or_else(|| missing(ptr))?;
+        Ok((&mut parent.kind, last))
+    }
+    /// Replaces the value referenced by `ptr`. If it references a
+    /// member that doesn't exist, adds it at the end of its object.
+    ///
+    /// The whitespace and comments around the replaced value are kept.
+    pub fn set(&mut self, ptr: impl AsRef<str>, value: &Json) -> Result<()> {
+        let ptr = JsonPointer::parse(ptr.as_ref())?;
+        if let Some(node) = resolve(&mut self.doc.value, &ptr) {
+            node.kind = Node::from(value).kind;
+            return Ok(())
+        }
+        match self.parent(&ptr)? {
+            (NodeKind::Object { members, end }, key) => push_member(members, end, key, value),
+            _ => return Err(missing(&ptr)),
+        }
+        Ok(())
+    }
+    /// Inserts a value. If `ptr` references an array element,
+    /// the value is inserted before it. The `-` token appends
+    /// the value to the array.
+    ///
+    /// If `ptr` references an object member, it's added at the
+    /// end of the object. Fails if the member already exists.
+    pub fn insert(&mut self, ptr: impl AsRef<str>, value: &Json) -> Result<()> {
+        let ptr = JsonPointer::parse(ptr.as_ref())?;
+        match self.parent(&ptr)? {
+            (NodeKind::Object { members, end }, key) => {
+                if members.iter().any(|m| &*m.key == key) {
+                    return Err(format!("Path \"{ptr}\" already exists").into())
+                }
+                push_member(members, end, key, value);
+            },
+            (NodeKind::Array { elems, end }, token) => {
+                let i = if token == "-" { Some(elems.len()) } else { parse_index(token) };
+                let Some(i) = i.filter(|i| *i <= elems.len()) else {
+                    return Err(format!("Invalid index in path \"{ptr}\"").into())
+                };
+                insert_element(elems, end, i, value);
+            },
+            _ => return Err(format!("Can't add a value at \"{ptr}\"").into()),
+        }
+        Ok(())
+    }
+    /// Removes the value referenced by `ptr`, together
+    /// with the whitespace and comments before it
+    pub fn remove(&mut self, ptr: impl AsRef<str>) -> Result<()> {
+        let ptr = JsonPointer::parse(ptr.as_ref())?;
+        match self.parent(&ptr)? {
+            (NodeKind::Object { members, end }, key) => {
+                let i = members.iter().rposition(|m| &*m.key == key).ok_or_else(|| missing(&ptr))?;
+                let removed = members.remove(i);
+                match members.get_mut(i) {
+                    Some(next) => next.key_before = join(&removed.key_before, &next.key_before, i == 0),
+                    None => remove_last(members.last_mut().map(|m| &mut m.comma), removed.comma, &removed.key_before, end),
+                }
+            },
+            (NodeKind::Array { elems, end }, token) => {
+                let i = parse_index(token).filter(|i| *i < elems.len()).ok_or_else(|| missing(&ptr))?;
+                let removed = elems.remove(i);
+                match elems.get_mut(i) {
+                    Some(next) => next.value.before = join(&removed.value.before, &next.value.before, i == 0),
+                    None => remove_last(elems.last_mut().map(|e| &mut e.comma), removed.comma, &removed.value.before, end),
+                }
+            },
+            _ => return Err(missing(&ptr)),
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DocumentEditor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.doc.fmt(f)
+    }
+}
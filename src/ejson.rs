@@ -0,0 +1,204 @@
+//! Extended JSON (v2), as used by `MongoDB`
+//!
+//! Extended JSON wrappers like `{"$numberLong": "12"}` are plain JSON objects,
+//! so they are kept as such when parsing, which makes documents round-trip
+//! untouched. This module adds typed accessors to interpret those wrappers,
+//! and conversions to build them.
+//!
+//! # Example
+//! ```
+//! use json::Json;
+//! use json::ejson::ExtendedValue;
+//!
+//! let j = Json::deserialize(r#"{
+//!     "_id" : { "$oid" : "5d505646cf6d4fe581014ab2" },
+//!     "count" : { "$numberLong" : "9007199254740993" }
+//! }"#).unwrap();
+//!
+//! assert_eq!(j.get("_id").unwrap().object_id(), Some("5d505646cf6d4fe581014ab2"));
+//! assert_eq!(j.get("count").unwrap().number_long(), Some(9_007_199_254_740_993));
+//!
+//! let long = Json::from(ExtendedValue::Int64(12));
+//! assert_eq!(long.to_string(), r#"{"$numberLong":"12"}"#);
+//! ```
+
+use crate::prelude::*;
+use crate::Json;
+
+/// A date, as found inside a `$date` wrapper
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum ExtendedDate<'a> {
+    /// Milliseconds since the Unix epoch (canonical mode)
+    Millis(i64),
+    /// An ISO-8601 string (relaxed mode)
+    Iso(&'a str),
+}
+
+/// The value represented by an Extended JSON wrapper
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum ExtendedValue<'a> {
+    /// `{"$oid": "<hex>"}`
+    ObjectId(&'a str),
+    /// `{"$numberInt": "<n>"}`
+    Int32(i32),
+    /// `{"$numberLong": "<n>"}`
+    Int64(i64),
+    /// `{"$numberDouble": "<n>"}`
+    Double(f64),
+    /// `{"$numberDecimal": "<n>"}`, kept as text to avoid precision loss
+    Decimal128(&'a str),
+    /// `{"$date": ...}`
+    Date(ExtendedDate<'a>),
+    /// `{"$binary": {"base64": "<payload>", "subType": "<hex>"}}`
+    Binary { base64: &'a str, subtype: &'a str },
+    /// `{"$regularExpression": {"pattern": "<re>", "options": "<flags>"}}`
+    Regex { pattern: &'a str, options: &'a str },
+    /// `{"$timestamp": {"t": <secs>, "i": <inc>}}`
+    Timestamp { t: u32, i: u32 },
+    /// `{"$symbol": "<text>"}`
+    Symbol(&'a str),
+    /// `{"$minKey": 1}`
+    MinKey,
+    /// `{"$maxKey": 1}`
+    MaxKey,
+    /// `{"$undefined": true}`
+    Undefined,
+}
+
+fn single(json: &Json) -> Option<(&str,&Json)> {
+    let obj = json.object()?;
+    if obj.len() != 1 { return None }
+    obj.iter().next().map(|(k,v)| (&**k, v))
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn as_u32(json: &Json) -> Option<u32> {
+    let n = json.number()?;
+    (n >= 0.0 && n <= f64::from(u32::MAX) && fract(n) == 0.0).then_some(n as u32)
+}
+
+impl Json {
+    /// Interprets this value as an Extended JSON wrapper.
+    ///
+    /// Returns None if the value is not an object with the
+    /// exact shape of one of the wrappers of [`ExtendedValue`]
+    pub fn extended(&self) -> Option<ExtendedValue<'_>> {
+        let (key, val) = single(self)?;
+        let value = match key {
+            "$oid" => ExtendedValue::ObjectId(val.string()?),
+            "$numberInt" => ExtendedValue::Int32(val.string()?.parse().ok()?),
+            "$numberLong" => ExtendedValue::Int64(val.string()?.parse().ok()?),
+            "$numberDouble" => {
+                let n = match val.string()? {
+                    "Infinity" => f64::INFINITY,
+                    "-Infinity" => f64::NEG_INFINITY,
+                    "NaN" => f64::NAN,
+                    n => n.parse().ok()?,
+                };
+                ExtendedValue::Double(n)
+            },
+            "$numberDecimal" => ExtendedValue::Decimal128(val.string()?),
+            "$date" => {
+                let date = match val {
+                    Json::String(s) => ExtendedDate::Iso(s),
+                    _ => match val.extended()? {
+                        ExtendedValue::Int64(n) => ExtendedDate::Millis(n),
+                        _ => return None,
+                    }
+                };
+                ExtendedValue::Date(date)
+            },
+            "$binary" => ExtendedValue::Binary {
+                base64: val.get("base64")?.string()?,
+                subtype: val.get("subType")?.string()?,
+            },
+            "$regularExpression" => ExtendedValue::Regex {
+                pattern: val.get("pattern")?.string()?,
+                options: val.get("options")?.string()?,
+            },
+            "$timestamp" => ExtendedValue::Timestamp {
+                t: as_u32(val.get("t")?)?,
+                i: as_u32(val.get("i")?)?,
+            },
+            "$symbol" => ExtendedValue::Symbol(val.string()?),
+            "$minKey" => ExtendedValue::MinKey,
+            "$maxKey" => ExtendedValue::MaxKey,
+            "$undefined" => ExtendedValue::Undefined,
+            _ => return None,
+        };
+        Some(value)
+    }
+    /// Gets the hex string of an `$oid` wrapper
+    pub fn object_id(&self) -> Option<&str> {
+        match self.extended()? {
+            ExtendedValue::ObjectId(oid) => Some(oid),
+            _ => None,
+        }
+    }
+    /// Gets the value of a `$numberLong` or `$numberInt` wrapper
+    pub fn number_long(&self) -> Option<i64> {
+        match self.extended()? {
+            ExtendedValue::Int64(n) => Some(n),
+            ExtendedValue::Int32(n) => Some(n.into()),
+            _ => None,
+        }
+    }
+    /// Gets the value of a `$date` wrapper
+    pub fn date(&self) -> Option<ExtendedDate<'_>> {
+        match self.extended()? {
+            ExtendedValue::Date(d) => Some(d),
+            _ => None,
+        }
+    }
+}
+
+fn wrap(key: &str, value: Json) -> Json {
+    let mut map = Map::default();
+    map.insert(key.into(), value);
+    Json::Object(map)
+}
+
+impl From<ExtendedValue<'_>> for Json {
+    fn from(value: ExtendedValue<'_>) -> Self {
+        match value {
+            ExtendedValue::ObjectId(oid) => wrap("$oid", oid.into()),
+            ExtendedValue::Int32(n) => wrap("$numberInt", n.to_string().as_str().into()),
+            ExtendedValue::Int64(n) => wrap("$numberLong", n.to_string().as_str().into()),
+            ExtendedValue::Double(n) => {
+                let s = if n.is_nan() {
+                    "NaN".to_string()
+                } else if n.is_infinite() {
+                    if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+                } else {
+                    format!("{n:?}")
+                };
+                wrap("$numberDouble", s.as_str().into())
+            },
+            ExtendedValue::Decimal128(n) => wrap("$numberDecimal", n.into()),
+            ExtendedValue::Date(ExtendedDate::Iso(s)) => wrap("$date", s.into()),
+            ExtendedValue::Date(ExtendedDate::Millis(n)) => wrap("$date", ExtendedValue::Int64(n).into()),
+            ExtendedValue::Binary { base64, subtype } => {
+                let mut map = Map::default();
+                map.insert("base64".into(), base64.into());
+                map.insert("subType".into(), subtype.into());
+                wrap("$binary", Json::Object(map))
+            },
+            ExtendedValue::Regex { pattern, options } => {
+                let mut map = Map::default();
+                map.insert("pattern".into(), pattern.into());
+                map.insert("options".into(), options.into());
+                wrap("$regularExpression", Json::Object(map))
+            },
+            ExtendedValue::Timestamp { t, i } => {
+                let mut map = Map::default();
+                map.insert("t".into(), Json::Number(from_f64(t.into())));
+                map.insert("i".into(), Json::Number(from_f64(i.into())));
+                wrap("$timestamp", Json::Object(map))
+            },
+            ExtendedValue::Symbol(s) => wrap("$symbol", s.into()),
+            ExtendedValue::MinKey => wrap("$minKey", 1_u8.into()),
+            ExtendedValue::MaxKey => wrap("$maxKey", 1_u8.into()),
+            ExtendedValue::Undefined => wrap("$undefined", Json::True),
+        }
+    }
+}
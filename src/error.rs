@@ -1,7 +1,7 @@
 use core::num::ParseFloatError;
 use core::{error, fmt};
 
-use crate::prelude::Cow;
+use crate::prelude::{Cow,String,ToString};
 
 #[derive(Debug)]
 pub struct Error(Cow<'static,str>);
@@ -26,6 +26,12 @@ impl From<ParseFloatError> for Error {
     }
 }
 
+impl From<fmt::Error> for Error {
+    fn from(value: fmt::Error) -> Self {
+        Error(value.to_string().into())
+    }
+}
+
 impl From<&'static str> for Error {
     fn from(value: &'static str) -> Self {
         Error(value.into())
@@ -0,0 +1,136 @@
+//! Helpers to move text in and out of the escaped
+//! representation stored inside [`Json::String`](crate::Json::String)
+//!
+//! The parser keeps strings exactly as they appear in the source
+//! (minus the quotes), so any text coming from somewhere else must
+//! be escaped before being stored, and unescaped before being handed
+//! to a format that doesn't understand JSON escapes.
+
+use crate::prelude::*;
+
+/// Escapes `s` so it can be stored in a [`Json::String`](crate::Json::String)
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => {
+                write!(out, "\\u{:04x}", c as u32).unwrap();
+            },
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Resolves the escape sequences of a raw JSON string.
+///
+/// Invalid sequences are kept verbatim, and lone
+/// surrogates are replaced with U+FFFD.
+pub fn unescape(s: &str) -> Cow<'_,str> {
+    if !s.contains('\\') {
+        return Cow::Borrowed(s);
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue
+        }
+        match chars.next() {
+            Some('"') => out.push('"'),
+            Some('\\') | None => out.push('\\'),
+            Some('/') => out.push('/'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some('b') => out.push('\u{8}'),
+            Some('f') => out.push('\u{c}'),
+            Some('u') => {
+                let mut look = chars.clone();
+                let Some(hi) = hex4(&mut look) else {
+                    out.push_str("\\u");
+                    continue
+                };
+                chars = look;
+                let code = if (0xD800..0xDC00).contains(&hi) {
+                    let mut look = chars.clone();
+                    let lo = if look.next() == Some('\\') && look.next() == Some('u') {
+                        hex4(&mut look).filter(|lo| (0xDC00..0xE000).contains(lo))
+                    } else { None };
+                    if let Some(lo) = lo {
+                        chars = look;
+                        0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00)
+                    } else {
+                        0xFFFD
+                    }
+                } else {
+                    hi
+                };
+                out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+            },
+            Some(c) => { out.push('\\'); out.push(c); },
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Returns the byte offsets of the `\\u` escapes of a
+/// raw JSON string that encode lone UTF-16 surrogates
+pub(crate) fn lone_surrogates(s: &str) -> impl Iterator<Item = usize> + '_ {
+    let bytes = s.as_bytes();
+    let code = |at: usize| s.get(at..).and_then(|h| hex4(&mut h.chars()));
+    let mut i = 0;
+    core::iter::from_fn(move || {
+        while i < bytes.len() {
+            if bytes[i] != b'\\' {
+                i += 1;
+                continue
+            }
+            let start = i;
+            if bytes.get(i + 1) != Some(&b'u') {
+                i += 2;
+                continue
+            }
+            i += 6;
+            match code(start + 2) {
+                Some(0xD800..0xDC00) if bytes.get(i..i + 2) == Some(b"\\u")
+                    && matches!(code(i + 2), Some(0xDC00..0xE000)) => i += 6,
+                Some(0xD800..0xE000) => return Some(start),
+                _ => {},
+            }
+        }
+        None
+    })
+}
+
+/// Replaces the escapes of lone surrogates with U+FFFD
+pub(crate) fn replace_lone_surrogates(s: &str) -> Cow<'_,str> {
+    let mut out = String::new();
+    let mut last = 0;
+    for i in lone_surrogates(s) {
+        out.push_str(&s[last..i]);
+        out.push('\u{FFFD}');
+        last = i + 6;
+    }
+    if last == 0 {
+        return Cow::Borrowed(s)
+    }
+    out.push_str(&s[last..]);
+    Cow::Owned(out)
+}
+
+fn hex4(chars: &mut (impl Iterator<Item = char> + Clone)) -> Option<u32> {
+    let mut n = 0;
+    for _ in 0..4 {
+        n = n * 16 + chars.next()?.to_digit(16)?;
+    }
+    Some(n)
+}
@@ -0,0 +1,337 @@
+//! Event parser that doesn't allocate
+//!
+//! [`EventParser`] reads a document in chunks of bytes of any size, and
+//! reports it as a sequence of [Event]s. It only uses the buffers given by
+//! the caller: a scratch buffer, which bounds the size of a single token, and
+//! a nesting buffer, which bounds the depth of the document. This makes it
+//! usable on targets where building a whole [Json](crate::Json) is too heavy.
+//!
+//! Like the [`Json`](crate::Json) parser, strings are reported exactly as
+//! they appear in the source, with their escape sequences unresolved.
+//!
+//! # Example
+//! ```
+//! use json::events::{Event, EventParser};
+//!
+//! let mut scratch = [0; 16];
+//! let mut nesting = [0; 4];
+//! let mut parser = EventParser::new(&mut scratch, &mut nesting);
+//!
+//! let mut events = 0;
+//! for mut chunk in [&b"{ \"ab"[..], b"c\" : [1", b"2, true] }"] {
+//!     while let Some(event) = parser.next_event(&mut chunk).unwrap() {
+//!         if events == 1 {
+//!             assert_eq!(event, Event::Key("abc"));
+//!         }
+//!         if events == 3 {
+//!             assert_eq!(event, Event::Number(12.0));
+//!         }
+//!         events += 1;
+//!     }
+//! }
+//! assert_eq!(parser.finish().unwrap(), None);
+//! assert_eq!(events, 7);
+//! ```
+
+use core::fmt;
+
+use crate::prelude::*;
+
+/// Max depth of the documents parsed by the crate with an [`EventParser`]
+pub(crate) const MAX_DEPTH: usize = 4096;
+
+/// An event reported by an [`EventParser`]
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum Event<'a> {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    /// Key of an object member. Its value comes next
+    Key(&'a str),
+    String(&'a str),
+    Number(f64),
+    True,
+    False,
+    Null,
+}
+
+/// Error returned by an [`EventParser`]
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct EventError {
+    /// Offset of the byte where the error was found
+    pub offset: usize,
+    pub msg: &'static str,
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[byte {}]: {}", self.offset, self.msg)
+    }
+}
+
+impl core::error::Error for EventError { }
+
+impl From<EventError> for crate::Error {
+    fn from(value: EventError) -> Self {
+        crate::prelude::ToString::to_string(&value).into()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    /// Before a value
+    Value,
+    /// After a '['
+    FirstElement,
+    /// After a '{'
+    FirstKey,
+    /// After a comma inside an object
+    Key,
+    /// After a key
+    Colon,
+    /// After a value inside an array or object
+    CommaOrEnd,
+    /// After the top-level value
+    Done,
+}
+
+/// Token being copied into the scratch buffer
+#[derive(Clone, Copy, PartialEq)]
+enum Token {
+    None,
+    String { key: bool, escaped: bool },
+    Number,
+    /// true, false or null
+    Literal,
+}
+
+/// Buffer where the tokens are copied
+enum Scratch<'s> {
+    /// Given by the caller, which bounds the length of the tokens
+    Fixed(&'s mut [u8]),
+    /// Grows as the tokens need it
+    Growing(Vec<u8>),
+}
+
+impl Scratch<'_> {
+    fn get(&self, len: usize) -> &[u8] {
+        match self {
+            Scratch::Fixed(buf) => &buf[..len],
+            Scratch::Growing(buf) => &buf[..len],
+        }
+    }
+    /// Stores `b` at `i`. Returns false if it doesn't fit
+    fn set(&mut self, i: usize, b: u8) -> bool {
+        match self {
+            Scratch::Fixed(buf) => buf.get_mut(i).map(|slot| *slot = b).is_some(),
+            Scratch::Growing(buf) => {
+                buf.truncate(i);
+                buf.push(b);
+                true
+            },
+        }
+    }
+}
+
+const ARRAY: u8 = b'[';
+const OBJECT: u8 = b'{';
+
+/// Parses a document incrementally, without allocating.
+/// See the [module documentation](self)
+pub struct EventParser<'s> {
+    scratch: Scratch<'s>,
+    /// Length of the token in the scratch buffer
+    len: usize,
+    nesting: &'s mut [u8],
+    depth: usize,
+    state: State,
+    token: Token,
+    /// Bytes consumed so far
+    offset: usize,
+    error: Option<EventError>,
+}
+
+impl<'s> EventParser<'s> {
+    /// Builds a parser. Tokens (strings, keys and numbers) can't be longer than
+    /// `scratch`, and arrays and objects can't be nested deeper than the length
+    /// of `nesting`. The initial contents of the buffers don't matter.
+    pub fn new(scratch: &'s mut [u8], nesting: &'s mut [u8]) -> Self {
+        Self::with_scratch(Scratch::Fixed(scratch), nesting)
+    }
+    /// Builds a parser whose scratch buffer grows as long as the longest token.
+    /// For inputs held in memory, where the tokens are bounded by their length.
+    pub(crate) fn growing(nesting: &'s mut [u8]) -> Self {
+        Self::with_scratch(Scratch::Growing(Vec::new()), nesting)
+    }
+    fn with_scratch(scratch: Scratch<'s>, nesting: &'s mut [u8]) -> Self {
+        Self {
+            scratch, len: 0,
+            nesting, depth: 0,
+            state: State::Value,
+            token: Token::None,
+            offset: 0,
+            error: None,
+        }
+    }
+    /// Returns the number of bytes consumed so far
+    pub fn offset(&self) -> usize { self.offset }
+    /// Returns the text of the last key or string reported
+    pub(crate) fn last_token(&self) -> &str {
+        core::str::from_utf8(self.scratch.get(self.len)).unwrap_or_default()
+    }
+    fn error<T>(&mut self, msg: &'static str) -> Result<T, EventError> {
+        let err = EventError { offset: self.offset, msg };
+        self.error = Some(err);
+        Err(err)
+    }
+    fn push(&mut self, b: u8) -> Result<(), EventError> {
+        if !self.scratch.set(self.len, b) {
+            return self.error("Token too long")
+        }
+        self.len += 1;
+        Ok(())
+    }
+    fn token(&mut self) -> Result<&str, EventError> {
+        let err = EventError { offset: self.offset, msg: "Invalid UTF-8" };
+        core::str::from_utf8(self.scratch.get(self.len)).map_err(|_| {
+            self.error = Some(err);
+            err
+        })
+    }
+    fn after_value(&mut self) {
+        self.state = if self.depth == 0 { State::Done } else { State::CommaOrEnd };
+    }
+    fn open(&mut self, kind: u8) -> Result<Event<'static>, EventError> {
+        let Some(slot) = self.nesting.get_mut(self.depth) else {
+            return self.error("Max depth reached")
+        };
+        *slot = kind;
+        self.depth += 1;
+        if kind == ARRAY {
+            self.state = State::FirstElement;
+            Ok(Event::StartArray)
+        } else {
+            self.state = State::FirstKey;
+            Ok(Event::StartObject)
+        }
+    }
+    fn close(&mut self, b: u8) -> Result<Event<'static>, EventError> {
+        let kind = self.nesting[self.depth - 1];
+        match (kind, b) {
+            (ARRAY, b']') | (OBJECT, b'}') => {},
+            (ARRAY, _) => return self.error("Unclosed '['"),
+            _ => return self.error("Unclosed '{'"),
+        }
+        self.depth -= 1;
+        self.after_value();
+        Ok(if kind == ARRAY { Event::EndArray } else { Event::EndObject })
+    }
+    /// Starts copying a token into the scratch buffer
+    fn start(&mut self, token: Token, first: Option<u8>) -> Result<Option<Event<'static>>, EventError> {
+        self.token = token;
+        self.len = 0;
+        if let Some(b) = first {
+            self.push(b)?;
+        }
+        Ok(None)
+    }
+    /// Ends the number or literal in the scratch buffer
+    fn scalar(&mut self) -> Result<Event<'static>, EventError> {
+        let token = self.token;
+        self.token = Token::None;
+        self.after_value();
+        let event: Result<Event<'static>, &'static str> = match (token, self.token()?) {
+            (Token::Literal, "true") => Ok(Event::True),
+            (Token::Literal, "false") => Ok(Event::False),
+            (Token::Literal, "null") => Ok(Event::Null),
+            (Token::Number, n) => n.parse().map(Event::Number).map_err(|_| "Invalid number"),
+            _ => Err("Unknown token"),
+        };
+        event.or_else(|msg| self.error(msg))
+    }
+    /// Handles a byte that is not part of a token
+    fn byte(&mut self, b: u8) -> Result<Option<Event<'static>>, EventError> {
+        match (self.state, b) {
+            (_, b' ' | b'\t' | b'\n' | b'\r') => Ok(None),
+            (State::Value | State::FirstElement, b'[') => self.open(ARRAY).map(Some),
+            (State::Value | State::FirstElement, b'{') => self.open(OBJECT).map(Some),
+            (State::Value | State::FirstElement, b'"') => self.start(Token::String { key: false, escaped: false }, None),
+            (State::Value | State::FirstElement, b'-' | b'0'..=b'9') => self.start(Token::Number, Some(b)),
+            (State::Value | State::FirstElement, b'a'..=b'z') => self.start(Token::Literal, Some(b)),
+            (State::FirstElement | State::FirstKey | State::CommaOrEnd, b']' | b'}') => self.close(b).map(Some),
+            (State::Value, b']') if self.depth > 0 => self.error("Trailing comma on list"),
+            (State::FirstKey | State::Key, b'"') => self.start(Token::String { key: true, escaped: false }, None),
+            (State::Key, b'}') => self.error("Trailing comma in object"),
+            (State::FirstKey | State::Key, _) => self.error("Expected STRING"),
+            (State::Colon, b':') => {
+                self.state = State::Value;
+                Ok(None)
+            },
+            (State::Colon, _) => self.error("Expected ':'"),
+            (State::CommaOrEnd, b',') => {
+                self.state = if self.nesting[self.depth - 1] == ARRAY { State::Value } else { State::Key };
+                Ok(None)
+            },
+            (State::CommaOrEnd, _) => self.error("Expected comma after element"),
+            (State::Done, _) => self.error("Unexpected data after the value"),
+            (State::Value | State::FirstElement, _) => self.error("Unknown token"),
+        }
+    }
+    /// Parses the next event from `input`, advancing it past the consumed bytes.
+    ///
+    /// Returns `None` once `input` is exhausted; then, call this function
+    /// again with the next chunk, or [finish](Self::finish) at the end of
+    /// the document. After an error, it returns the same error again.
+    pub fn next_event(&mut self, input: &mut &[u8]) -> Result<Option<Event<'_>>, EventError> {
+        if let Some(err) = self.error {
+            return Err(err)
+        }
+        while let Some((&b, rest)) = input.split_first() {
+            match self.token {
+                Token::String { key, escaped } => {
+                    if b == b'"' && !escaped {
+                        *input = rest;
+                        self.offset += 1;
+                        self.token = Token::None;
+                        if key {
+                            self.state = State::Colon;
+                            return self.token().map(|k| Some(Event::Key(k)))
+                        }
+                        self.after_value();
+                        return self.token().map(|s| Some(Event::String(s)))
+                    }
+                    self.push(b)?;
+                    self.token = Token::String { key, escaped: !escaped && b == b'\\' };
+                },
+                Token::Number if matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') => self.push(b)?,
+                Token::Literal if b.is_ascii_alphabetic() => self.push(b)?,
+                Token::Number | Token::Literal => return self.scalar().map(Some),
+                Token::None => {
+                    let event = self.byte(b)?;
+                    *input = rest;
+                    self.offset += 1;
+                    if event.is_some() {
+                        return Ok(event)
+                    }
+                    continue
+                },
+            }
+            *input = rest;
+            self.offset += 1;
+        }
+        Ok(None)
+    }
+    /// Ends the document. Returns the top-level number or literal, if the
+    /// document consists of one that hasn't been reported yet.
+    pub fn finish(&mut self) -> Result<Option<Event<'_>>, EventError> {
+        if let Some(err) = self.error {
+            return Err(err)
+        }
+        match self.token {
+            Token::Number | Token::Literal if self.depth == 0 => self.scalar().map(Some),
+            _ if self.state == State::Done && self.token == Token::None => Ok(None),
+            _ => self.error("Unexpected end of input"),
+        }
+    }
+}
@@ -1,8 +1,25 @@
 //! C bindings for the json crate
+//!
+//! No panic unwinds out of these functions. If one happens, the function
+//! returns as if it had failed: `Json::Error` for the parsing functions,
+//! NULL for the ones that return a pointer, and false for the rest.
 
-use std::{ffi::{c_char, CStr}, mem, ptr, slice};
+use std::{ffi::{c_char, c_void, CStr, CString}, mem, panic, ptr, slice};
+
+use crate::escape::unescape;
+use crate::events::{Event, EventError, EventParser, MAX_DEPTH};
+use crate::prelude::{from_f64, Key};
 
 type RustJson = crate::Json;
+type RustSerializeConfig<'a> = crate::SerializeConfig<'a>;
+
+/// Runs `f`, returning `on_panic` if it panics.
+///
+/// Unwinding into C is undefined behaviour, so every
+/// exported function runs its body through this.
+fn catch_panic<T>(on_panic: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(panic::AssertUnwindSafe(f)).unwrap_or(on_panic)
+}
 
 /// Json struct for C
 #[repr(C)]
@@ -38,6 +55,16 @@ impl JsonString {
     }
 }
 
+impl JsonString {
+    fn as_str(&self) -> Option<&str> {
+        if self.len == 0 {
+            return Some("")
+        }
+        let bytes = unsafe { slice::from_raw_parts(self.buf, self.len) };
+        std::str::from_utf8(bytes).ok()
+    }
+}
+
 impl Drop for JsonString {
     fn drop(&mut self) {
         let elems = ptr::slice_from_raw_parts_mut(self.buf, self.len);
@@ -52,37 +79,303 @@ pub struct Pair {
     val: *mut Json
 }
 
-impl Json {
-    fn from_json(json: RustJson) -> Self {
-        match json {
-            RustJson::Array(arr) => {
-                let elems = arr.into_vec().into_iter().map(Json::from_json).collect::<Vec<_>>();
-                let len = elems.len();
-                let elems = vec_2_ptr(elems);
-                Json::Array{ elems, len }
+/// An array or object being converted by [`Json::from_json`]
+enum Frame {
+    Array {
+        rest: std::vec::IntoIter<RustJson>,
+        done: Vec<Json>,
+    },
+    Object {
+        rest: <crate::Map<Key,RustJson> as IntoIterator>::IntoIter,
+        /// Key of the member being converted
+        key: Option<JsonString>,
+        done: Vec<Pair>,
+    },
+}
+
+impl Frame {
+    /// Adds a converted element or member
+    fn add(&mut self, converted: Json) {
+        match self {
+            Frame::Array { done, .. } => done.push(converted),
+            Frame::Object { key, done, .. } => {
+                if let Some(key) = key.take() {
+                    done.push(Pair { key, val: Box::into_raw(Box::new(converted)) });
+                }
             },
-            RustJson::Object(obj) => {
-                let elems = obj.into_iter().map(|(k,v)| {
-                    let string = JsonString::new(k.into_string());
-                    let v = Json::from_json(v);
-                    let v = Box::new(v);
-                    Pair { key : string, val: Box::into_raw(v) }
-                }).collect::<Vec<_>>();
-                let len = elems.len();
-                let elems = vec_2_ptr(elems);
-                Json::Object{ elems, len }
+        }
+    }
+    /// Returns the next element or member to convert
+    fn next(&mut self) -> Option<RustJson> {
+        match self {
+            Frame::Array { rest, .. } => rest.next(),
+            Frame::Object { rest, key, .. } => rest.next().map(|(k, v)| {
+                *key = Some(JsonString::new(String::from(&*k)));
+                v
+            }),
+        }
+    }
+    fn finish(self) -> Json {
+        match self {
+            Frame::Array { done, .. } => {
+                let len = done.len();
+                Json::Array { elems: vec_2_ptr(done), len }
             },
-            RustJson::String(s) => {
-                Json::String(JsonString::new(s.into_string()))
+            Frame::Object { done, .. } => {
+                let len = done.len();
+                Json::Object { elems: vec_2_ptr(done), len }
             },
-            RustJson::Number(n) => Json::Number(n),
-            RustJson::True => Json::True,
-            RustJson::False => Json::False,
-            RustJson::Null => Json::Null,
         }
     }
 }
 
+impl Json {
+    /// Converts a [`RustJson`]. Nested arrays and objects are kept on an
+    /// explicit stack, so deeply nested values can't overflow the call stack.
+    fn from_json(json: RustJson) -> Self {
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut next = json;
+        loop {
+            let mut converted = match &mut next {
+                RustJson::Array(arr) => {
+                    stack.push(Frame::Array { rest: mem::take(arr).into_vec().into_iter(), done: Vec::new() });
+                    None
+                },
+                RustJson::Object(obj) => {
+                    stack.push(Frame::Object { rest: mem::take(obj).into_iter(), key: None, done: Vec::new() });
+                    None
+                },
+                RustJson::String(s) => Some(Json::String(JsonString::new(mem::take(s).into_string()))),
+                RustJson::Number(n) => Some(Json::Number(crate::prelude::to_f64(*n))),
+                #[cfg(feature = "bigint")]
+                RustJson::BigInt(n) => Some(Json::Number(crate::prelude::big_to_f64(n))),
+                #[cfg(feature = "decimal")]
+                RustJson::Decimal(n) => Some(Json::Number(crate::prelude::dec_to_f64(n))),
+                RustJson::True => Some(Json::True),
+                RustJson::False => Some(Json::False),
+                RustJson::Null => Some(Json::Null),
+            };
+            /* Add the converted value to its parent, and close
+             * the containers that have no elements left */
+            next = loop {
+                let Some(frame) = stack.last_mut() else {
+                    return converted.unwrap_or(Json::Error)
+                };
+                if let Some(c) = converted.take() {
+                    frame.add(c);
+                }
+                if let Some(next) = frame.next() {
+                    break next
+                }
+                converted = stack.pop().map(Frame::finish);
+            };
+        }
+    }
+}
+
+/// An array or object being converted by [`Json::to_json`]
+enum BackFrame<'a> {
+    Array {
+        rest: slice::Iter<'a, Json>,
+        done: Vec<RustJson>,
+    },
+    Object {
+        rest: slice::Iter<'a, Pair>,
+        /// Key of the member being converted
+        key: Option<Key>,
+        done: crate::Map<Key,RustJson>,
+    },
+}
+
+impl<'a> BackFrame<'a> {
+    fn add(&mut self, converted: RustJson) {
+        match self {
+            BackFrame::Array { done, .. } => done.push(converted),
+            BackFrame::Object { key, done, .. } => {
+                if let Some(key) = key.take() {
+                    done.insert(key, converted);
+                }
+            },
+        }
+    }
+    /// Returns the next element or member to convert.
+    /// Fails if the member has an invalid key, or a NULL value.
+    fn next(&mut self) -> Option<Result<&'a Json, ()>> {
+        match self {
+            BackFrame::Array { rest, .. } => rest.next().map(Ok),
+            BackFrame::Object { rest, key, .. } => rest.next().map(|Pair { key: k, val }| {
+                *key = Some(k.as_str().ok_or(())?.into());
+                unsafe { val.as_ref() }.ok_or(())
+            }),
+        }
+    }
+    fn finish(self) -> RustJson {
+        match self {
+            BackFrame::Array { done, .. } => done.into(),
+            BackFrame::Object { done, .. } => RustJson::Object(done),
+        }
+    }
+}
+
+/// Builds a slice from the raw parts of an array or object
+fn parts<'a, T>(elems: *const T, len: usize) -> &'a [T] {
+    if len == 0 || elems.is_null() {
+        return &[]
+    }
+    unsafe { slice::from_raw_parts(elems, len) }
+}
+
+impl Json {
+    /// Converts back into a [`RustJson`]. Returns `None` if there's
+    /// an `Error` value, or a string that is not valid UTF-8.
+    fn to_json(&self) -> Option<RustJson> {
+        let mut stack: Vec<BackFrame<'_>> = Vec::new();
+        let mut next = self;
+        loop {
+            let mut converted = match next {
+                Json::Array { elems, len } => {
+                    stack.push(BackFrame::Array { rest: parts(*elems, *len).iter(), done: Vec::new() });
+                    None
+                },
+                Json::Object { elems, len } => {
+                    stack.push(BackFrame::Object { rest: parts(*elems, *len).iter(), key: None, done: crate::Map::default() });
+                    None
+                },
+                Json::String(s) => Some(RustJson::String(s.as_str()?.into())),
+                Json::Number(n) => Some(RustJson::Number(from_f64(*n))),
+                Json::True => Some(RustJson::True),
+                Json::False => Some(RustJson::False),
+                Json::Null => Some(RustJson::Null),
+                Json::Error => return None,
+            };
+            next = loop {
+                let Some(frame) = stack.last_mut() else {
+                    return converted
+                };
+                if let Some(c) = converted.take() {
+                    frame.add(c);
+                }
+                if let Some(next) = frame.next() {
+                    break next.ok()?
+                }
+                converted = stack.pop().map(BackFrame::finish);
+            };
+        }
+    }
+}
+
+/// What to do with numbers that can't be represented in JSON (NaN and ±Infinity)
+#[repr(C)]
+pub enum JsonNonFinite {
+    /// Fail the serialization
+    NonFiniteError,
+    /// Write them as `null`
+    NonFiniteAsNull,
+    /// Write them as the strings `"NaN"`, `"Infinity"` and `"-Infinity"`
+    NonFiniteAsString,
+}
+
+/// Configures the JSON serializer
+#[repr(C)]
+pub struct SerializeConfig {
+    /// If not NULL, each array element and object member is written
+    /// on its own line, indented by this string for each level of
+    /// nesting. Otherwise, the output is compact.
+    pub indent: *const c_char,
+    /// Policy for NaN and ±Infinity
+    pub non_finite: JsonNonFinite,
+    /// Write all non-ASCII characters as `\uXXXX` escapes
+    pub escape_non_ascii: bool,
+    /// Write the members of objects sorted by their key
+    pub sort_keys: bool,
+}
+
+/// Serializes the given Json struct into a NULL terminated string.
+///
+/// Returns NULL if the struct can't be serialized. For example,
+/// if it's an `Error`, or has a string that is not valid UTF-8.
+///
+/// The caller of this function must free the returned
+/// string by calling [`json_free_string`] afterwards.
+///
+/// # Safety
+/// The pointer must point to a valid Json struct
+#[no_mangle]
+pub unsafe extern "C"
+fn json_serialize(json: *const Json) -> *mut c_char {
+    let conf = SerializeConfig {
+        indent: ptr::null(),
+        non_finite: JsonNonFinite::NonFiniteAsNull,
+        escape_non_ascii: false,
+        sort_keys: false,
+    };
+    unsafe { json_serialize_with_config(json, conf) }
+}
+
+/// Serializes the given Json struct into a NULL terminated string,
+/// using the given `SerializeConfig` struct.
+///
+/// Returns NULL if the struct can't be serialized. For example,
+/// if it's an `Error`, or if it has a non finite number and the
+/// config doesn't allow them.
+///
+/// The caller of this function must free the returned
+/// string by calling [`json_free_string`] afterwards.
+///
+/// # Safety
+/// The pointer must point to a valid Json struct, and the
+/// indent must be NULL or a valid NULL terminated C string
+#[no_mangle]
+pub unsafe extern "C"
+fn json_serialize_with_config(json: *const Json, conf: SerializeConfig) -> *mut c_char {
+    catch_panic(ptr::null_mut(), || {
+        let Some(json) = (unsafe { json.as_ref() }).and_then(Json::to_json) else {
+            return ptr::null_mut()
+        };
+        let indent = if conf.indent.is_null() {
+            None
+        } else {
+            match unsafe { CStr::from_ptr(conf.indent) }.to_str() {
+                Ok(indent) => Some(indent),
+                Err(_) => return ptr::null_mut(),
+            }
+        };
+        let conf = RustSerializeConfig {
+            indent,
+            non_finite: match conf.non_finite {
+                JsonNonFinite::NonFiniteError => crate::NonFinite::Error,
+                JsonNonFinite::NonFiniteAsNull => crate::NonFinite::AsNull,
+                JsonNonFinite::NonFiniteAsString => crate::NonFinite::AsString,
+            },
+            escape_non_ascii: conf.escape_non_ascii,
+            sort_keys: conf.sort_keys,
+            ..Default::default()
+        };
+        let mut out = String::new();
+        let res = json.serialize_with_config(&conf, &mut out);
+        drop(json);
+        if res.is_err() {
+            return ptr::null_mut()
+        }
+        CString::new(out).map_or(ptr::null_mut(), CString::into_raw)
+    })
+}
+
+/// Frees a string returned by [`json_serialize`]
+///
+/// # Safety
+/// The pointer must be NULL, or a string returned by [`json_serialize`]
+/// or [`json_serialize_with_config`] that hasn't been freed yet
+#[no_mangle]
+pub unsafe extern "C"
+fn json_free_string(s: *mut c_char) {
+    catch_panic((), || {
+        if !s.is_null() {
+            mem::drop(unsafe { CString::from_raw(s) });
+        }
+    });
+}
+
 /// Deserializes the given string into a Json struct.
 /// If any error is encountered while parsing, the
 /// type of the Json struct is `Json::Error`.
@@ -95,13 +388,15 @@ impl Json {
 #[no_mangle]
 pub unsafe extern "C"
 fn json_deserialize(ptr: *const c_char) -> Json {
-    let cstr = unsafe { CStr::from_ptr(ptr) };
-    if let Ok(s) = cstr.to_str() {
-        if let Ok(json) = crate::Json::deserialize(s) {
-            return Json::from_json(json);
+    catch_panic(Json::Error, || {
+        let cstr = unsafe { CStr::from_ptr(ptr) };
+        if let Ok(s) = cstr.to_str() {
+            if let Ok(json) = crate::Json::deserialize(s) {
+                return Json::from_json(json);
+            }
         }
-    }
-    Json::Error
+        Json::Error
+    })
 }
 
 /// Deserializes the given string into a Json struct.
@@ -118,13 +413,52 @@ fn json_deserialize(ptr: *const c_char) -> Json {
 #[no_mangle]
 pub unsafe extern "C"
 fn json_deserialize_with_config(ptr: *const c_char, conf: crate::JsonConfig) -> Json {
-    let cstr = unsafe { CStr::from_ptr(ptr) };
-    if let Ok(s) = cstr.to_str() {
-        if let Ok(json) = crate::Json::deserialize_with_config(s, conf) {
-            return Json::from_json(json);
+    catch_panic(Json::Error, || {
+        let cstr = unsafe { CStr::from_ptr(ptr) };
+        if let Ok(s) = cstr.to_str() {
+            if let Ok(json) = crate::Json::deserialize_with_config(s, conf) {
+                return Json::from_json(json);
+            }
         }
-    }
-    Json::Error
+        Json::Error
+    })
+}
+
+/// Deserializes the first `len` bytes of the given buffer into a Json struct.
+/// The buffer doesn't need to be NULL terminated, and it's not copied.
+///
+/// If any error is encountered while parsing, the
+/// type of the Json struct is `Json::Error`.
+///
+/// The caller of this function must free the returned
+/// struct by calling [`json_free`] afterwards.
+///
+/// # Safety
+/// The pointer must be valid for reads of `len` bytes.
+/// It can only be NULL if `len` is 0
+#[no_mangle]
+pub unsafe extern "C"
+fn json_deserialize_n(ptr: *const c_char, len: usize) -> Json {
+    unsafe { json_deserialize_n_with_config(ptr, len, crate::JsonConfig::default()) }
+}
+
+/// Same as [`json_deserialize_n`], using the given `JsonConfig` struct
+///
+/// # Safety
+/// The pointer must be valid for reads of `len` bytes.
+/// It can only be NULL if `len` is 0
+#[no_mangle]
+pub unsafe extern "C"
+fn json_deserialize_n_with_config(ptr: *const c_char, len: usize, conf: crate::JsonConfig) -> Json {
+    catch_panic(Json::Error, || {
+        let bytes = parts(ptr.cast::<u8>(), len);
+        if let Ok(s) = std::str::from_utf8(bytes) {
+            if let Ok(json) = crate::Json::deserialize_with_config(s, conf) {
+                return Json::from_json(json);
+            }
+        }
+        Json::Error
+    })
 }
 
 fn ptr_2_vec<T>(ptr: *mut T, len: usize) -> Vec<T> {
@@ -146,26 +480,205 @@ fn vec_2_ptr<T>(vec: Vec<T>) -> *mut T {
 #[no_mangle]
 pub extern "C"
 fn json_free(json: Json) {
-    match json{
-        Json::Array{ elems, len } => {
-            let elems = ptr_2_vec(elems, len);
-            for e in elems {
-                json_free(e);
+    catch_panic((), || {
+        /* Free the nested values from an explicit stack, instead
+         * of recursing, so deep structures can't overflow the stack */
+        let mut stack = vec![json];
+        while let Some(json) = stack.pop() {
+            match json {
+                Json::Array{ elems, len } => {
+                    stack.extend(ptr_2_vec(elems, len));
+                },
+                Json::Object{ elems, len } => {
+                    let elems = ptr_2_vec(elems, len);
+                    for Pair { key, val } in elems {
+                        mem::drop( key );
+                        let val = unsafe { Box::from_raw(val) };
+                        stack.push(*val);
+                    }
+                },
+                Json::String(s) => {
+                    mem::drop(s);
+                },
+                Json::Number(_) |
+                Json::True | Json::False |
+                Json::Null | Json::Error => {},
             }
-        },
-        Json::Object{ elems, len } => {
-            let elems = ptr_2_vec(elems, len);
-            for Pair { key, val } in elems {
-                mem::drop( key );
-                let val = unsafe { Box::from_raw(val) };
-                json_free(*val);
+        }
+    });
+}
+
+/// Called by [`json_array_foreach`] with the index and value of each
+/// element, and the user data. Returning false stops the iteration.
+pub type JsonArrayCallback = unsafe extern "C" fn(index: usize, val: *const Json, user_data: *mut c_void) -> bool;
+
+/// Called by [`json_object_foreach`] with the key (a NULL terminated
+/// string, and its length) and value of each member, and the user
+/// data. Returning false stops the iteration.
+pub type JsonObjectCallback = unsafe extern "C" fn(key: *const c_char, key_len: usize, val: *const Json, user_data: *mut c_void) -> bool;
+
+/// Calls `callback` for each element of the given array, in order.
+///
+/// Returns false if the Json struct is not an `Array`.
+///
+/// # Safety
+/// The pointer must be NULL or point to a valid Json struct, which
+/// must not be modified or freed until this function returns
+#[no_mangle]
+pub unsafe extern "C"
+fn json_array_foreach(json: *const Json, callback: JsonArrayCallback, user_data: *mut c_void) -> bool {
+    catch_panic(false, || {
+        let Some(Json::Array { elems, len }) = (unsafe { json.as_ref() }) else {
+            return false
+        };
+        for (i, e) in parts(*elems, *len).iter().enumerate() {
+            if !unsafe { callback(i, e, user_data) } {
+                break
             }
-        },
-        Json::String(s) => {
-            mem::drop(s);
-        },
-        Json::Number(_) |
-        Json::True | Json::False |
-        Json::Null | Json::Error => {},
+        }
+        true
+    })
+}
+
+/// Calls `callback` for each member of the given object, in order.
+///
+/// Returns false if the Json struct is not an `Object`.
+///
+/// # Safety
+/// The pointer must be NULL or point to a valid Json struct, which
+/// must not be modified or freed until this function returns
+#[no_mangle]
+pub unsafe extern "C"
+fn json_object_foreach(json: *const Json, callback: JsonObjectCallback, user_data: *mut c_void) -> bool {
+    catch_panic(false, || {
+        let Some(Json::Object { elems, len }) = (unsafe { json.as_ref() }) else {
+            return false
+        };
+        for Pair { key, val } in parts(*elems, *len) {
+            if !unsafe { callback(key.buf.cast(), key.len, *val, user_data) } {
+                break
+            }
+        }
+        true
+    })
+}
+
+/// Gets the value referenced by the given JSON pointer (RFC 6901), like
+/// `/a/b/0`. The returned value is borrowed from `json`, so it must not
+/// be freed. It's valid as long as `json` is.
+///
+/// The tokens of the pointer are compared with the keys once their
+/// escape sequences are resolved, so `/a"b` matches the key `"a\"b"`.
+///
+/// Returns NULL if the pointer is malformed, or doesn't reference any value.
+///
+/// # Safety
+/// `json` must be NULL or point to a valid Json struct.
+/// `ptr` must be NULL or a valid NULL terminated C string
+#[no_mangle]
+pub unsafe extern "C"
+fn json_get_pointer(json: *const Json, ptr: *const c_char) -> *const Json {
+    catch_panic(ptr::null(), || {
+        if ptr.is_null() {
+            return ptr::null()
+        }
+        let Some(json) = (unsafe { json.as_ref() }) else { return ptr::null() };
+        let Ok(ptr) = unsafe { CStr::from_ptr(ptr) }.to_str() else { return ptr::null() };
+        let Ok(ptr) = crate::JsonPointer::parse(ptr) else { return ptr::null() };
+        let found = ptr.tokens().try_fold(json, |j, t| match j {
+            Json::Array { elems, len } => parts(*elems, *len).get(crate::pointer::parse_index(t)?),
+            Json::Object { elems, len } => {
+                let pair = parts(*elems, *len).iter()
+                    .find(|p| p.key.as_str().is_some_and(|k| unescape(k) == t))?;
+                unsafe { pair.val.as_ref() }
+            },
+            _ => None,
+        });
+        found.map_or(ptr::null(), ptr::from_ref)
+    })
+}
+
+/// Callbacks for [`json_parse_events`]. All of them receive the user data
+/// as their last argument, and can be NULL to ignore that kind of event.
+/// Returning false stops the parsing.
+///
+/// Keys and strings are not NULL terminated, and their escape sequences
+/// are unresolved. They're only valid until the callback returns.
+#[repr(C)]
+pub struct JsonEventCallbacks {
+    pub start_object: Option<unsafe extern "C" fn(user_data: *mut c_void) -> bool>,
+    pub end_object: Option<unsafe extern "C" fn(user_data: *mut c_void) -> bool>,
+    pub start_array: Option<unsafe extern "C" fn(user_data: *mut c_void) -> bool>,
+    pub end_array: Option<unsafe extern "C" fn(user_data: *mut c_void) -> bool>,
+    pub key: Option<unsafe extern "C" fn(key: *const c_char, len: usize, user_data: *mut c_void) -> bool>,
+    pub string: Option<unsafe extern "C" fn(s: *const c_char, len: usize, user_data: *mut c_void) -> bool>,
+    pub number: Option<unsafe extern "C" fn(n: f64, user_data: *mut c_void) -> bool>,
+    pub boolean: Option<unsafe extern "C" fn(b: bool, user_data: *mut c_void) -> bool>,
+    pub null: Option<unsafe extern "C" fn(user_data: *mut c_void) -> bool>,
+    /// Called with the offset and message of the error, if the document is not valid
+    pub error: Option<unsafe extern "C" fn(offset: usize, msg: *const c_char, user_data: *mut c_void)>,
+}
+
+impl JsonEventCallbacks {
+    /// Calls the callback for the given event. Returns false to stop
+    unsafe fn call(&self, event: Event<'_>, user_data: *mut c_void) -> bool {
+        let empty = |f: Option<unsafe extern "C" fn(*mut c_void) -> bool>| {
+            f.is_none_or(|f| unsafe { f(user_data) })
+        };
+        let text = |f: Option<unsafe extern "C" fn(*const c_char, usize, *mut c_void) -> bool>, s: &str| {
+            f.is_none_or(|f| unsafe { f(s.as_ptr().cast(), s.len(), user_data) })
+        };
+        match event {
+            Event::StartObject => empty(self.start_object),
+            Event::EndObject => empty(self.end_object),
+            Event::StartArray => empty(self.start_array),
+            Event::EndArray => empty(self.end_array),
+            Event::Key(k) => text(self.key, k),
+            Event::String(s) => text(self.string, s),
+            Event::Number(n) => self.number.is_none_or(|f| unsafe { f(n, user_data) }),
+            Event::True => self.boolean.is_none_or(|f| unsafe { f(true, user_data) }),
+            Event::False => self.boolean.is_none_or(|f| unsafe { f(false, user_data) }),
+            Event::Null => empty(self.null),
+        }
     }
 }
+
+/// Parses the given string, calling the matching callback for each value,
+/// without building a Json struct. See [`JsonEventCallbacks`].
+/// Arrays and objects can be nested up to 4096 levels deep.
+///
+/// Returns true if the whole document was parsed. If it's not valid, calls
+/// the `error` callback and returns false. It also returns false if a
+/// callback stopped the parsing.
+///
+/// # Safety
+/// `text` must be a valid NULL terminated C string, and `callbacks`
+/// must point to a valid `JsonEventCallbacks` struct
+#[no_mangle]
+pub unsafe extern "C"
+fn json_parse_events(text: *const c_char, callbacks: *const JsonEventCallbacks, user_data: *mut c_void) -> bool {
+    catch_panic(false, || {
+        let Some(callbacks) = (unsafe { callbacks.as_ref() }) else { return false };
+        let mut input = unsafe { CStr::from_ptr(text) }.to_bytes();
+        let mut nesting = [0; MAX_DEPTH];
+        let mut parser = EventParser::growing(&mut nesting);
+        let EventError { offset, msg } = loop {
+            let event = match parser.next_event(&mut input) {
+                Ok(Some(event)) => event,
+                Ok(None) => match parser.finish() {
+                    Ok(Some(event)) => return unsafe { callbacks.call(event, user_data) },
+                    Ok(None) => return true,
+                    Err(err) => break err,
+                },
+                Err(err) => break err,
+            };
+            if !unsafe { callbacks.call(event, user_data) } {
+                return false
+            }
+        };
+        if let (Some(error), Ok(msg)) = (callbacks.error, CString::new(msg)) {
+            unsafe { error(offset, msg.as_ptr(), user_data) };
+        }
+        false
+    })
+}
@@ -0,0 +1,105 @@
+//! Partial extraction of values
+
+use crate::escape::unescape;
+use crate::lexer::token::{Token, TokenKind};
+use crate::lexer::{self, Span};
+use crate::locate::{skip_comma, skip_value};
+use crate::prelude::*;
+use crate::{Json, JsonPointer, Result};
+
+struct Extractor<'a> {
+    src: &'a str,
+    tokens: &'a [Token],
+    targets: Vec<(&'a str, JsonPointer)>,
+    found: Map<Key,Json>,
+}
+
+impl Extractor<'_> {
+    fn token(&self, i: usize) -> Result<&Token> {
+        self.tokens.get(i).ok_or_else(|| "Unexpected end of input".into())
+    }
+    fn expect(&self, i: usize, kind: TokenKind) -> Result<&Token> {
+        let t = self.token(i)?;
+        if t.get_type() == kind {
+            Ok(t)
+        } else {
+            Err(format!("Expected {kind}, found {}", t.get_type()).into())
+        }
+    }
+    /// Visits the value starting at token `i`, and returns the index of the token after it
+    fn value(&mut self, i: usize, path: &mut JsonPointer) -> Result<usize> {
+        let end = skip_value(self.tokens, i).ok_or("Unexpected end of input")?;
+        if self.targets.iter().any(|(_, t)| t == path) {
+            let (start, last) = (self.token(i)?.span(), self.token(end - 1)?.span());
+            let span = Span { offset: start.offset, len: last.offset + last.len - start.offset };
+            let json = Json::deserialize(span.slice(self.src))?;
+            for (name, _) in self.targets.iter().filter(|(_, t)| t == path) {
+                self.found.insert((*name).into(), json.clone());
+            }
+        }
+        if !self.targets.iter().any(|(_, t)| t != path && path.is_prefix_of(t)) {
+            return Ok(end)
+        }
+        let mut i = i + 1;
+        match self.token(i - 1)?.get_type() {
+            TokenKind::LeftBrace => {
+                while self.token(i)?.get_type() != TokenKind::RightBrace {
+                    let key = self.expect(i, TokenKind::String)?.span().slice(self.src);
+                    self.expect(i + 1, TokenKind::Colon)?;
+                    path.push(unescape(&key[1..key.len() - 1]));
+                    i = self.value(i + 2, path)?;
+                    path.pop();
+                    i = skip_comma(self.tokens, i);
+                }
+            },
+            TokenKind::LSquareBracket => {
+                let mut n = 0_usize;
+                while self.token(i)?.get_type() != TokenKind::RSquareBracket {
+                    path.push(n.to_string());
+                    i = self.value(i, path)?;
+                    path.pop();
+                    i = skip_comma(self.tokens, i);
+                    n += 1;
+                }
+            },
+            _ => {},
+        }
+        Ok(end)
+    }
+}
+
+/// Extracts the values referenced by the given JSON pointers, in a single
+/// pass over the input. Only the requested values are built, the rest of
+/// the document is skipped.
+///
+/// The result maps each pointer to its value. Pointers that don't
+/// reference any value are not included.
+///
+/// Fails if a pointer is malformed, or if the input is not valid JSON.
+/// Parts of the document that are skipped are only validated loosely.
+///
+/// # Example
+/// ```
+/// use json::json;
+///
+/// let src = r#"{
+///     "user" : { "id" : 42, "name" : "Jhon" },
+///     "items" : [ { "price" : 9.5 }, { "price" : 3 } ]
+/// }"#;
+///
+/// let values = json::extract(src, &["/user/id", "/items/0/price", "/missing"]).unwrap();
+/// assert_eq!(values.len(), 2);
+/// assert_eq!(values.get("/user/id"), Some(&json!(42)));
+/// assert_eq!(values.get("/items/0/price"), Some(&json!(9.5)));
+/// ```
+pub fn extract(text: impl AsRef<str>, pointers: &[&str]) -> Result<Map<Key,Json>> {
+    let src = text.as_ref();
+    let targets = pointers.iter().map(|p| Ok((*p, JsonPointer::parse(p)?))).collect::<Result<_>>()?;
+    let tokens = lexer::tokenize(src)?;
+    let mut extractor = Extractor { src, tokens: &tokens, targets, found: Map::default() };
+    let end = extractor.value(0, &mut JsonPointer::root())?;
+    if end != tokens.len() {
+        return Err("Unexpected data after the document".into())
+    }
+    Ok(extractor.found)
+}
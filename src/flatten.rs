@@ -0,0 +1,210 @@
+//! Conversion between nested documents and flat objects
+//!
+//! # Example
+//! ```
+//! use json::json;
+//! use json::flatten::{ArrayStyle, FlattenConfig};
+//!
+//! let j = json!({ "a" : { "b" : [ { "c" : 1 }, 2 ] } });
+//!
+//! let flat = j.flatten();
+//! assert_eq!(flat, json!({ "a.b[0].c" : 1, "a.b[1]" : 2 }));
+//! assert_eq!(flat.unflatten().unwrap(), j);
+//!
+//! let conf = FlattenConfig { separator: "__", arrays: ArrayStyle::Separator };
+//! assert_eq!(j.flatten_with(&conf), json!({ "a__b__0__c" : 1, "a__b__1" : 2 }));
+//! ```
+
+use alloc::collections::BTreeMap;
+
+use crate::pointer::parse_index;
+use crate::prelude::*;
+use crate::{Json, Result};
+
+/// How array elements are represented in the flattened keys
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum ArrayStyle {
+    /// `a[0]`
+    Brackets,
+    /// `a.0`, using the configured separator.
+    ///
+    /// When unflattening, every numeric segment becomes an array index.
+    Separator,
+    /// Arrays are not flattened, and are kept as values
+    Leaf,
+}
+
+/// Configures [`Json::flatten_with`] and [`Json::unflatten_with`]
+#[derive(Debug,Clone)]
+pub struct FlattenConfig<'a> {
+    /// Separator between object keys
+    pub separator: &'a str,
+    /// How to handle arrays
+    pub arrays: ArrayStyle,
+}
+
+impl Default for FlattenConfig<'_> {
+    fn default() -> Self {
+        Self { separator: ".", arrays: ArrayStyle::Brackets }
+    }
+}
+
+fn flatten_into(json: &Json, prefix: &mut String, conf: &FlattenConfig<'_>, out: &mut Map<Key,Json>) {
+    let len = prefix.len();
+    match json {
+        Json::Object(obj) if !obj.is_empty() => {
+            for (k, v) in obj {
+                if !prefix.is_empty() {
+                    prefix.push_str(conf.separator);
+                }
+                prefix.push_str(k);
+                flatten_into(v, prefix, conf, out);
+                prefix.truncate(len);
+            }
+        },
+        Json::Array(arr) if !arr.is_empty() && conf.arrays != ArrayStyle::Leaf => {
+            for (i, e) in arr.iter().enumerate() {
+                if conf.arrays == ArrayStyle::Brackets {
+                    write!(prefix, "[{i}]").unwrap();
+                } else {
+                    if !prefix.is_empty() {
+                        prefix.push_str(conf.separator);
+                    }
+                    write!(prefix, "{i}").unwrap();
+                }
+                flatten_into(e, prefix, conf, out);
+                prefix.truncate(len);
+            }
+        },
+        _ => { out.insert(prefix.as_str().into(), json.clone()); },
+    }
+}
+
+#[derive(Debug)]
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+fn segments<'a>(key: &'a str, conf: &FlattenConfig<'_>) -> Result<Vec<Segment<'a>>> {
+    let mut segs = Vec::new();
+    for part in key.split(conf.separator) {
+        match conf.arrays {
+            ArrayStyle::Brackets => {
+                let (name, mut rest) = part.find('[').map_or((part, ""), |i| part.split_at(i));
+                if !name.is_empty() || segs.is_empty() && rest.is_empty() {
+                    segs.push(Segment::Key(name));
+                }
+                while !rest.is_empty() {
+                    let index = rest.strip_prefix('[')
+                                    .and_then(|r| r.split_once(']'))
+                                    .and_then(|(i, r)| Some((parse_index(i)?, r)));
+                    let Some((index, r)) = index else {
+                        return Err(format!("Invalid array index in key \"{key}\"").into());
+                    };
+                    segs.push(Segment::Index(index));
+                    rest = r;
+                }
+            },
+            ArrayStyle::Separator => {
+                segs.push(parse_index(part).map_or(Segment::Key(part), Segment::Index));
+            },
+            ArrayStyle::Leaf => segs.push(Segment::Key(part)),
+        }
+    }
+    Ok(segs)
+}
+
+enum Tree {
+    Leaf(Json),
+    Object(Map<Key,Tree>),
+    Array(BTreeMap<usize,Tree>),
+}
+
+impl Tree {
+    fn insert(&mut self, segs: &[Segment<'_>], value: Json, key: &str) -> Result<()> {
+        let conflict = || format!("Conflicting key \"{key}\"").into();
+        let Some((first, rest)) = segs.split_first() else {
+            return if matches!(self, Tree::Object(o) if o.is_empty()) {
+                *self = Tree::Leaf(value);
+                Ok(())
+            } else {
+                Err(conflict())
+            }
+        };
+        let empty = || if let Some(Segment::Index(_)) = rest.first() {
+            Tree::Array(BTreeMap::new())
+        } else {
+            Tree::Object(Map::default())
+        };
+        let child = match (self, first) {
+            (Tree::Object(o), Segment::Key(k)) => o.entry((*k).into()).or_insert_with(empty),
+            (Tree::Array(a), Segment::Index(i)) => a.entry(*i).or_insert_with(empty),
+            _ => return Err(conflict()),
+        };
+        child.insert(rest, value, key)
+    }
+    fn into_json(self) -> Json {
+        match self {
+            Tree::Leaf(j) => j,
+            Tree::Object(o) => Json::Object(o.into_iter().map(|(k, v)| (k, v.into_json())).collect()),
+            Tree::Array(a) => {
+                let len = a.keys().next_back().map_or(0, |i| i + 1);
+                let mut arr = vec![Json::Null; len];
+                for (i, e) in a {
+                    arr[i] = e.into_json();
+                }
+                arr.into()
+            },
+        }
+    }
+}
+
+impl Json {
+    /// Flattens this document into a single level object,
+    /// with the [default](FlattenConfig::default) configuration.
+    ///
+    /// Empty objects and arrays are kept as values.
+    /// Scalars are returned as an object with a single `""` key.
+    #[must_use]
+    pub fn flatten(&self) -> Json {
+        self.flatten_with(&FlattenConfig::default())
+    }
+    /// Flattens this document into a single level object
+    #[must_use]
+    pub fn flatten_with(&self, conf: &FlattenConfig<'_>) -> Json {
+        let mut out = Map::default();
+        flatten_into(self, &mut String::new(), conf, &mut out);
+        Json::Object(out)
+    }
+    /// Reverses [flatten](Self::flatten)
+    pub fn unflatten(&self) -> Result<Json> {
+        self.unflatten_with(&FlattenConfig::default())
+    }
+    /// Reverses [`flatten_with`](Self::flatten_with)
+    ///
+    /// Fails if this is not an object, or if two keys collide
+    /// (like `a` and `a.b`)
+    pub fn unflatten_with(&self, conf: &FlattenConfig<'_>) -> Result<Json> {
+        let Json::Object(obj) = self else {
+            return Err("Only objects can be unflattened".into());
+        };
+        if let Some(v) = obj.get("") {
+            if obj.len() == 1 {
+                return Ok(v.clone());
+            }
+        }
+        let mut tree = None;
+        for (k, v) in obj {
+            let segs = segments(k, conf)?;
+            let tree = tree.get_or_insert_with(|| if let Some(Segment::Index(_)) = segs.first() {
+                Tree::Array(BTreeMap::new())
+            } else {
+                Tree::Object(Map::default())
+            });
+            tree.insert(&segs, v.clone(), k)?;
+        }
+        let tree = tree.unwrap_or_else(|| Tree::Object(Map::default()));
+        Ok(tree.into_json())
+    }
+}
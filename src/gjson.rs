@@ -0,0 +1,352 @@
+//! GJSON-like path queries
+
+use core::cmp::Ordering;
+
+use crate::escape::unescape;
+use crate::prelude::*;
+use crate::{Json, Number};
+
+#[derive(Debug)]
+enum Op { Eq, Ne, Lt, Le, Gt, Ge }
+
+#[derive(Debug)]
+struct Condition<'a> {
+    path: &'a str,
+    op: Op,
+    value: Json,
+}
+
+#[derive(Debug)]
+enum Component<'a> {
+    Key(Cow<'a,str>),
+    /// `#`: the length of an array, or each of its elements
+    Hash,
+    /// `#(cond)` if `all` is false, `#(cond)#` otherwise
+    Filter { cond: Condition<'a>, all: bool },
+}
+
+/// Splits the path in its dot separated parts, honoring
+/// `\` escapes and dots inside parentheses
+fn split(path: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0_usize;
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in path.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '.' if depth == 0 => {
+                parts.push(&path[start..i]);
+                start = i + 1;
+            },
+            _ => {},
+        }
+    }
+    parts.push(&path[start..]);
+    parts
+}
+
+fn literal(s: &str) -> Option<Json> {
+    let s = s.trim();
+    if let Some(inner) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        return Some(Json::String(inner.into()));
+    }
+    match s {
+        "true" => Some(Json::True),
+        "false" => Some(Json::False),
+        "null" => Some(Json::Null),
+        n => n.parse().ok().map(Json::Number),
+    }
+}
+
+fn condition(s: &str) -> Option<Condition<'_>> {
+    const OPS: [(&str, Op); 7] = [
+        ("==", Op::Eq), ("!=", Op::Ne), ("<=", Op::Le), (">=", Op::Ge),
+        ("<", Op::Lt), (">", Op::Gt), ("=", Op::Eq),
+    ];
+    let (i, len, op) = OPS.into_iter()
+                          .filter_map(|(sym, op)| s.find(sym).map(|i| (i, sym.len(), op)))
+                          .min_by_key(|(i, len, _)| (*i, usize::MAX - len))?;
+    Some(Condition {
+        path: s[..i].trim(),
+        op,
+        value: literal(&s[i + len..])?,
+    })
+}
+
+fn component(part: &str) -> Option<Component<'_>> {
+    if part == "#" {
+        return Some(Component::Hash);
+    }
+    if let Some(rest) = part.strip_prefix("#(") {
+        let (inner, all) = match rest.strip_suffix(")#") {
+            Some(inner) => (inner, true),
+            None => (rest.strip_suffix(')')?, false),
+        };
+        return Some(Component::Filter { cond: condition(inner)?, all });
+    }
+    let key = if part.contains('\\') {
+        let mut key = String::with_capacity(part.len());
+        let mut chars = part.chars();
+        while let Some(c) = chars.next() {
+            key.push(if c == '\\' { chars.next().unwrap_or('\\') } else { c });
+        }
+        Cow::Owned(key)
+    } else {
+        Cow::Borrowed(part)
+    };
+    Some(Component::Key(key))
+}
+
+/// Matches `s` against a pattern where `*` matches any
+/// sequence of characters, and `?` a single character
+fn glob(pattern: &str, s: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let s: Vec<char> = s.chars().collect();
+    let (mut pi, mut si) = (0, 0);
+    let mut backtrack = None;
+    while si < s.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == s[si]) {
+            pi += 1;
+            si += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            backtrack = Some((pi, si));
+            pi += 1;
+        } else if let Some((bp, bs)) = backtrack {
+            pi = bp + 1;
+            si = bs + 1;
+            backtrack = Some((bp, bs + 1));
+        } else {
+            return false
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+fn compare(a: &Json, b: &Json) -> Option<Ordering> {
+    match (a, b) {
+        (Json::Number(a), Json::Number(b)) => a.partial_cmp(b),
+        (Json::String(a), Json::String(b)) => Some(unescape(a).cmp(&unescape(b))),
+        _ => (a == b).then_some(Ordering::Equal),
+    }
+}
+
+impl Condition<'_> {
+    fn eval(&self, json: &Json) -> bool {
+        let target = if self.path.is_empty() {
+            Some(json.clone())
+        } else {
+            json.query(self.path)
+        };
+        let Some(target) = target else { return false };
+        let ord = compare(&target, &self.value);
+        match self.op {
+            Op::Eq => ord == Some(Ordering::Equal),
+            Op::Ne => ord != Some(Ordering::Equal),
+            Op::Lt => ord == Some(Ordering::Less),
+            Op::Le => matches!(ord, Some(Ordering::Less | Ordering::Equal)),
+            Op::Gt => ord == Some(Ordering::Greater),
+            Op::Ge => matches!(ord, Some(Ordering::Greater | Ordering::Equal)),
+        }
+    }
+}
+
+fn eval(json: &Json, path: &[Component<'_>]) -> Option<Json> {
+    let Some((first, rest)) = path.split_first() else {
+        return Some(json.clone());
+    };
+    match (first, json) {
+        (Component::Key(k), Json::Object(obj)) => {
+            if k.contains(['*', '?']) {
+                obj.iter()
+                   .find(|(key, _)| glob(k, key))
+                   .and_then(|(_, v)| eval(v, rest))
+            } else {
+                eval(obj.get(&**k)?, rest)
+            }
+        },
+        (Component::Key(k), Json::Array(arr)) => eval(arr.get(k.parse::<usize>().ok()?)?, rest),
+        #[allow(clippy::cast_precision_loss)]
+        (Component::Hash, Json::Array(arr)) => {
+            if rest.is_empty() {
+                Some(Json::Number(arr.len() as Number))
+            } else {
+                Some(arr.iter().filter_map(|e| eval(e, rest)).collect::<Vec<_>>().into())
+            }
+        },
+        (Component::Filter { cond, all: false }, Json::Array(arr)) => {
+            eval(arr.iter().find(|e| cond.eval(e))?, rest)
+        },
+        (Component::Filter { cond, all: true }, Json::Array(arr)) => {
+            Some(arr.iter()
+                    .filter(|e| cond.eval(e))
+                    .filter_map(|e| eval(e, rest))
+                    .collect::<Vec<_>>()
+                    .into())
+        },
+        _ => None,
+    }
+}
+
+/// Collects every value that matches `path` into `out`
+fn select<'a>(json: &'a Json, path: &[Component<'_>], out: &mut Vec<&'a Json>) {
+    let Some((first, rest)) = path.split_first() else {
+        out.push(json);
+        return
+    };
+    match (first, json) {
+        (Component::Key(k), Json::Object(obj)) => {
+            for (_, v) in obj.iter().filter(|(key, _)| glob(k, key)) {
+                select(v, rest, out);
+            }
+        },
+        (Component::Key(k), Json::Array(arr)) if &**k == "*" => {
+            arr.iter().for_each(|e| select(e, rest, out));
+        },
+        (Component::Key(k), Json::Array(arr)) => {
+            if let Some(e) = k.parse::<usize>().ok().and_then(|i| arr.get(i)) {
+                select(e, rest, out);
+            }
+        },
+        (Component::Hash, Json::Array(arr)) => arr.iter().for_each(|e| select(e, rest, out)),
+        (Component::Filter { cond, all }, Json::Array(arr)) => {
+            let matches = arr.iter().filter(|e| cond.eval(e));
+            for e in matches.take(if *all { usize::MAX } else { 1 }) {
+                select(e, rest, out);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Same as [select], but calls `f` on each match
+fn select_mut(json: &mut Json, path: &[Component<'_>], f: &mut dyn FnMut(&mut Json)) -> usize {
+    let Some((first, rest)) = path.split_first() else {
+        f(json);
+        return 1
+    };
+    match (first, json) {
+        (Component::Key(k), Json::Object(obj)) => {
+            obj.iter_mut()
+               .filter(|(key, _)| glob(k, key))
+               .map(|(_, v)| select_mut(v, rest, f))
+               .sum()
+        },
+        (Component::Key(k), Json::Array(arr)) if &**k == "*" => {
+            arr.iter_mut().map(|e| select_mut(e, rest, f)).sum()
+        },
+        (Component::Key(k), Json::Array(arr)) => {
+            match k.parse::<usize>().ok().and_then(|i| arr.get_mut(i)) {
+                Some(e) => select_mut(e, rest, f),
+                None => 0,
+            }
+        },
+        (Component::Hash, Json::Array(arr)) => arr.iter_mut().map(|e| select_mut(e, rest, f)).sum(),
+        (Component::Filter { cond, all }, Json::Array(arr)) => {
+            arr.iter_mut()
+               .filter(|e| cond.eval(e))
+               .take(if *all { usize::MAX } else { 1 })
+               .map(|e| select_mut(e, rest, f))
+               .sum()
+        },
+        _ => 0,
+    }
+}
+
+fn components(path: &str) -> Option<Vec<Component<'_>>> {
+    if path.is_empty() {
+        return None
+    }
+    split(path).into_iter().map(component).collect()
+}
+
+impl Json {
+    /// Returns all the values that match a [query](Self::query) path
+    ///
+    /// Unlike [`query`](Self::query), which returns the first match of
+    /// a wildcard, this returns every match: `*` matches all the members
+    /// of an object and all the elements of an array, and `#` all the
+    /// elements of an array.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({
+    ///     "items" : [
+    ///         { "name" : "a", "price" : 1.25 },
+    ///         { "name" : "b", "price" : 3.5 }
+    ///     ]
+    /// });
+    /// assert_eq!(j.select_all("items.*.price"), [&json!(1.25), &json!(3.5)]);
+    /// assert_eq!(j.select_all("items.#(price>2)#.name"), [&json!("b")]);
+    /// assert!(j.select_all("items.*.stock").is_empty());
+    /// ```
+    pub fn select_all(&self, path: impl AsRef<str>) -> Vec<&Json> {
+        let mut out = Vec::new();
+        if let Some(components) = components(path.as_ref()) {
+            select(self, &components, &mut out);
+        }
+        out
+    }
+    /// Calls `f` on each value that matches the path, in place.
+    /// Returns the number of matches. See [`select_all`](Self::select_all)
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut j = json!({ "items" : [{ "price" : 1.25 }, { "price" : 3.5 }] });
+    /// let n = j.set_all("items.*.price", |price| {
+    ///     *price = json!(price.number().unwrap().round());
+    /// });
+    /// assert_eq!(n, 2);
+    /// assert_eq!(j, json!({ "items" : [{ "price" : 1 }, { "price" : 4 }] }));
+    /// ```
+    pub fn set_all(&mut self, path: impl AsRef<str>, mut f: impl FnMut(&mut Json)) -> usize {
+        match components(path.as_ref()) {
+            Some(components) => select_mut(self, &components, &mut f),
+            None => 0,
+        }
+    }
+    /// Queries this document with a [GJSON]-like path
+    ///
+    /// The supported syntax is:
+    /// - `a.b.c`: members of objects. Numbers index arrays (`a.0`).
+    ///   Keys can use the `*` and `?` wildcards, and `\` to escape special characters.
+    /// - `a.#`: the length of the array `a`.
+    /// - `a.#.b`: an array with the member `b` of each element of `a`.
+    /// - `a.#(b>10)`: the first element of `a` matching the condition.
+    ///   `a.#(b>10)#` returns all the matching elements.
+    ///   Conditions support `==`, `!=`, `<`, `<=`, `>` and `>=`.
+    ///
+    /// Returns None if nothing matches the path.
+    ///
+    /// [GJSON]: https://github.com/tidwall/gjson/blob/master/SYNTAX.md
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({
+    ///     "friends" : [
+    ///         { "first" : "Dale", "age" : 44 },
+    ///         { "first" : "Roger", "age" : 68 },
+    ///         { "first" : "Jane", "age" : 47 }
+    ///     ]
+    /// });
+    ///
+    /// assert_eq!(j.query("friends.#"), Some(json!(3)));
+    /// assert_eq!(j.query("friends.1.first"), Some(json!("Roger")));
+    /// assert_eq!(j.query("friends.#.first"), Some(json!(["Dale", "Roger", "Jane"])));
+    /// assert_eq!(j.query(r#"friends.#(first=="Jane").age"#), Some(json!(47)));
+    /// assert_eq!(j.query("friends.#(age>45)#.first"), Some(json!(["Roger", "Jane"])));
+    /// ```
+    pub fn query(&self, path: impl AsRef<str>) -> Option<Json> {
+        let components = components(path.as_ref())?;
+        eval(self, &components)
+    }
+}
@@ -0,0 +1,105 @@
+//! Content hashing
+
+use core::hash::Hasher;
+
+use crate::escape::unescape;
+use crate::prelude::*;
+use crate::Json;
+
+/// 64-bit FNV-1a. Unlike std's `DefaultHasher`, its output is
+/// stable across runs, platforms and versions.
+pub(crate) struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self { Fnv(0xcbf2_9ce4_8422_2325) }
+}
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 { self.0 }
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u64::from(*b);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/* Hasher::write_u64 uses the native endianness, which would make the hash platform dependent */
+fn hash_u64<H: Hasher>(n: u64, state: &mut H) {
+    state.write(&n.to_le_bytes());
+}
+
+/// Hashes an already unescaped string
+fn hash_str<H: Hasher>(s: &str, state: &mut H) {
+    hash_u64(s.len() as u64, state);
+    state.write(s.as_bytes());
+}
+
+impl Json {
+    /// Feeds the canonical form of this document into the given [Hasher].
+    ///
+    /// Documents that are equal, except for the order of their object
+    /// members, the escaping of their strings, or the sign of zero,
+    /// produce the same hash.
+    pub fn canonical_hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Json::Array(arr) => {
+                state.write_u8(b'[');
+                hash_u64(arr.len() as u64, state);
+                arr.iter().for_each(|e| e.canonical_hash(state));
+            },
+            Json::Object(obj) => {
+                state.write_u8(b'{');
+                hash_u64(obj.len() as u64, state);
+                let mut members: Vec<_> = obj.iter().map(|(k, v)| (unescape(k), v)).collect();
+                members.sort_unstable_by(|(k1, _), (k2, _)| k1.cmp(k2));
+                for (k, v) in members {
+                    hash_str(&k, state);
+                    v.canonical_hash(state);
+                }
+            },
+            Json::String(s) => {
+                state.write_u8(b'"');
+                hash_str(&unescape(s), state);
+            },
+            Json::Number(n) => {
+                state.write_u8(b'n');
+                let n = if *n == 0.0 { 0.0 } else if n.is_nan() { f64::NAN } else { to_f64(*n) };
+                hash_u64(n.to_bits(), state);
+            },
+            #[cfg(feature = "bigint")]
+            Json::BigInt(n) => {
+                state.write_u8(b'b');
+                hash_str(&n.to_string(), state);
+            },
+            #[cfg(feature = "decimal")]
+            Json::Decimal(n) => {
+                /* Equal decimals can have different scales, like 1.5 and 1.50 */
+                state.write_u8(b'd');
+                hash_str(&n.normalize().to_string(), state);
+            },
+            Json::True => state.write_u8(b't'),
+            Json::False => state.write_u8(b'f'),
+            Json::Null => state.write_u8(b'0'),
+        }
+    }
+    /// Returns a stable 64-bit hash of the canonical form of
+    /// this document. See [`canonical_hash`](Self::canonical_hash)
+    ///
+    /// # Example
+    /// ```
+    /// use json::Json;
+    ///
+    /// let a = Json::deserialize(r#"{ "a" : 1, "b" : ["x"] }"#).unwrap();
+    /// let b = Json::deserialize(r#"{ "b" : ["x"], "a" : 1.0 }"#).unwrap();
+    /// assert_eq!(a.content_hash(), b.content_hash());
+    ///
+    /// let c = Json::deserialize(r#"{ "b" : ["x"], "a" : 2 }"#).unwrap();
+    /// assert_ne!(a.content_hash(), c.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = Fnv::default();
+        self.canonical_hash(&mut hasher);
+        hasher.finish()
+    }
+}
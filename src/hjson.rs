@@ -0,0 +1,341 @@
+//! Hjson reader
+//!
+//! [Hjson](https://hjson.github.io) is a relaxed syntax for human-edited
+//! config files. Compared to JSON, it allows:
+//! - Comments starting with `#`, `//`, or enclosed in `/* */`
+//! - Omitting the braces of the root object
+//! - Omitting the commas between values, if they are on separate lines,
+//!   and trailing commas
+//! - Unquoted keys, made of any characters other than whitespace and `,:[]{}`
+//! - Single quoted strings
+//! - Quoteless strings, which span until the end of the line
+//! - Multiline strings, between `'''`. Their indentation is
+//!   removed up to the column of the opening quotes.
+//!
+//! # Example
+//! ```
+//! use json::{json, Json};
+//!
+//! let j = Json::from_hjson(r#"
+//!     ## Database settings
+//!     host: db.example.com
+//!     port: 5432
+//!     users: [
+//!         admin
+//!         'guest'
+//!     ]
+//!     motd:
+//!         '''
+//!         Welcome!
+//!           Be nice
+//!         '''
+//! "#).unwrap();
+//!
+//! assert_eq!(j, json!({
+//!     "host" : "db.example.com",
+//!     "port" : 5432,
+//!     "users" : ["admin", "guest"],
+//!     "motd" : r"Welcome!\n  Be nice",
+//! }));
+//! ```
+
+use crate::escape::escape;
+use crate::prelude::*;
+use crate::{Json, Number, Result};
+
+struct HjsonParser<'a> {
+    src: &'a str,
+    curr: usize,
+}
+
+/// Returns true if `s` is a valid JSON number
+fn is_number(s: &str) -> bool {
+    let s = s.strip_prefix('-').unwrap_or(s);
+    let digits = |s: &str| s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let int = digits(s);
+    if int == 0 || (int > 1 && s.starts_with('0')) {
+        return false
+    }
+    let mut rest = &s[int..];
+    if let Some(frac) = rest.strip_prefix('.') {
+        let n = digits(frac);
+        if n == 0 {
+            return false
+        }
+        rest = &frac[n..];
+    }
+    if let Some(exp) = rest.strip_prefix(['e', 'E']) {
+        let exp = exp.strip_prefix(['+', '-']).unwrap_or(exp);
+        let n = digits(exp);
+        if n == 0 {
+            return false
+        }
+        rest = &exp[n..];
+    }
+    rest.is_empty()
+}
+
+/// Removes up to `indent` whitespace characters from the start of `line`
+fn unindent(line: &str, indent: usize) -> &str {
+    let n = line.chars()
+                .take(indent)
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .count();
+    &line[n..]
+}
+
+impl HjsonParser<'_> {
+    fn error<T>(&self, msg: &str) -> Result<T> {
+        Err(format!("[{}]: {msg}", self.curr).into())
+    }
+    fn rest(&self) -> &str {
+        &self.src[self.curr..]
+    }
+    fn starts_with(&self, s: &str) -> bool {
+        self.rest().starts_with(s)
+    }
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+    fn is_finished(&self) -> bool {
+        self.curr >= self.src.len()
+    }
+    fn skip_line(&mut self) {
+        self.curr += self.rest().find('\n').unwrap_or(self.rest().len());
+    }
+    /// Skips whitespace, including newlines, and comments
+    fn skip_whitespace(&mut self) -> Result<()> {
+        loop {
+            let rest = self.rest();
+            self.curr += rest.len() - rest.trim_start().len();
+            if self.starts_with("#") || self.starts_with("//") {
+                self.skip_line();
+            } else if self.starts_with("/*") {
+                match self.rest().find("*/") {
+                    Some(i) => self.curr += i + 2,
+                    None => return self.error("Non terminated comment block"),
+                }
+            } else {
+                return Ok(())
+            }
+        }
+    }
+    /// Skips the whitespace and comments after a value, and its comma, if any
+    fn separator(&mut self) -> Result<()> {
+        self.skip_whitespace()?;
+        if self.starts_with(",") {
+            self.curr += 1;
+        }
+        Ok(())
+    }
+    fn document(&mut self) -> Result<Json> {
+        self.skip_whitespace()?;
+        if self.starts_with("{") || self.starts_with("[") {
+            let value = self.value()?;
+            self.skip_whitespace()?;
+            if !self.is_finished() {
+                return self.error("Unexpected content after the value");
+            }
+            return Ok(value)
+        }
+        /* Try an object without braces first, and then a single value */
+        let start = self.curr;
+        let err = match self.members(None) {
+            Ok(obj) => return Ok(obj),
+            Err(err) => err,
+        };
+        self.curr = start;
+        let value = self.value().ok().filter(|_| {
+            self.skip_whitespace().is_ok() && self.is_finished()
+        });
+        value.ok_or(err)
+    }
+    fn value(&mut self) -> Result<Json> {
+        match self.peek() {
+            Some('{') => {
+                self.curr += 1;
+                self.members(Some('}'))
+            },
+            Some('[') => self.array(),
+            Some('"') => self.quoted('"'),
+            Some('\'') if self.starts_with("'''") => self.multiline(),
+            Some('\'') => self.quoted('\''),
+            Some(',' | ':' | ']' | '}') | None => self.error("Expected a value"),
+            Some(_) => self.quoteless(),
+        }
+    }
+    /// Parses the members of an object, until `end`, or the end of the text
+    fn members(&mut self, end: Option<char>) -> Result<Json> {
+        let mut map = Map::default();
+        loop {
+            self.skip_whitespace()?;
+            match (self.peek(), end) {
+                (None, None) => break,
+                (None, Some(_)) => return self.error("Unclosed '{'"),
+                (Some(c), Some(end)) if c == end => {
+                    self.curr += 1;
+                    break
+                },
+                _ => {},
+            }
+            let key = self.key()?;
+            self.skip_whitespace()?;
+            if !self.starts_with(":") {
+                return self.error("Expected ':'");
+            }
+            self.curr += 1;
+            self.skip_whitespace()?;
+            let value = self.value()?;
+            map.insert(key, value);
+            self.separator()?;
+        }
+        Ok(Json::Object(map))
+    }
+    fn array(&mut self) -> Result<Json> {
+        self.curr += 1;
+        let mut elems = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            match self.peek() {
+                None => return self.error("Unclosed '['"),
+                Some(']') => {
+                    self.curr += 1;
+                    break
+                },
+                _ => {},
+            }
+            elems.push(self.value()?);
+            self.separator()?;
+        }
+        Ok(elems.into())
+    }
+    fn key(&mut self) -> Result<Key> {
+        if let Some(q @ ('"' | '\'')) = self.peek() {
+            let Ok(s) = self.quoted(q)?.into_string() else {
+                unreachable!("quoted always returns a string")
+            };
+            return Ok(to_key(s))
+        }
+        let len = self.rest()
+                      .find(|c: char| c.is_whitespace() || matches!(c, ',' | ':' | '[' | ']' | '{' | '}'))
+                      .unwrap_or(self.rest().len());
+        if len == 0 {
+            return self.error("Expected a key");
+        }
+        let key = &self.src[self.curr..self.curr + len];
+        self.curr += len;
+        Ok(escape(key).into())
+    }
+    /// Parses a string between `quote`s, with JSON escape
+    /// sequences, plus `\'`. The result is kept escaped.
+    fn quoted(&mut self, quote: char) -> Result<Json> {
+        self.curr += 1;
+        let mut out = String::new();
+        let mut chars = self.rest().char_indices();
+        loop {
+            let Some((i, c)) = chars.next() else {
+                return self.error("Unterminated string");
+            };
+            match c {
+                _ if c == quote => {
+                    self.curr += i + 1;
+                    break
+                },
+                '\n' | '\r' => {
+                    self.curr += i;
+                    return self.error("Unterminated string");
+                },
+                '"' => out.push_str("\\\""),
+                '\\' => match chars.next() {
+                    Some((_, '\'')) => out.push('\''),
+                    Some((_, c @ ('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't'))) => {
+                        out.push('\\');
+                        out.push(c);
+                    },
+                    Some((_, 'u')) => {
+                        out.push_str("\\u");
+                        for _ in 0..4 {
+                            match chars.next() {
+                                Some((_, c)) if c.is_ascii_hexdigit() => out.push(c),
+                                _ => {
+                                    self.curr += i;
+                                    return self.error("Truncated \\u escape sequence");
+                                },
+                            }
+                        }
+                    },
+                    _ => {
+                        self.curr += i;
+                        return self.error("Invalid escape sequence");
+                    },
+                },
+                c if (c as u32) < 0x20 => out.push_str(&escape(c.encode_utf8(&mut [0; 4]))),
+                c => out.push(c),
+            }
+        }
+        Ok(Json::String(out.into()))
+    }
+    fn multiline(&mut self) -> Result<Json> {
+        let line_start = self.src[..self.curr].rfind('\n').map_or(0, |i| i + 1);
+        let indent = self.src[line_start..self.curr].chars().count();
+        self.curr += 3;
+        let Some(len) = self.rest().find("'''") else {
+            return self.error("Unterminated multiline string");
+        };
+        let text = &self.src[self.curr..self.curr + len];
+        self.curr += len + 3;
+
+        /* Skip the rest of the opening line, if it's blank,
+         * and the last line, if only holds the indentation */
+        let text = match text.split_once('\n') {
+            Some((first, rest)) if first.trim().is_empty() => rest,
+            _ => text,
+        };
+        let text = match text.rsplit_once('\n') {
+            Some((init, last)) if last.trim().is_empty() => init,
+            _ => text,
+        };
+        let text = text.lines()
+                       .map(|l| unindent(l, indent))
+                       .collect::<Vec<_>>()
+                       .join("\n");
+        Ok(Json::String(escape(&text).into()))
+    }
+    /// Parses a quoteless string, a number, or a literal
+    fn quoteless(&mut self) -> Result<Json> {
+        let rest = &self.src[self.curr..];
+        let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+        let token_len = line.find(|c: char| c.is_whitespace() || matches!(c, ',' | ']' | '}' | '#'))
+                            .unwrap_or(line.len());
+        let token = &line[..token_len];
+        let token = &token[..token.find("//").or_else(|| token.find("/*")).unwrap_or(token.len())];
+        let after = line[token.len()..].trim_start_matches([' ', '\t', '\r']);
+        let ends = after.is_empty() || after.starts_with([',', ']', '}', '#']) || after.starts_with("//") || after.starts_with("/*");
+        if ends {
+            let value = match token {
+                "true" => Some(Json::True),
+                "false" => Some(Json::False),
+                "null" => Some(Json::Null),
+                n if is_number(n) => Some(Json::Number(n.parse::<Number>()?)),
+                _ => None,
+            };
+            if let Some(value) = value {
+                self.curr += token.len();
+                return Ok(value)
+            }
+        }
+        self.curr += line.len();
+        Ok(Json::String(escape(line.trim_end()).into()))
+    }
+}
+
+impl Json {
+    /// Parses the given Hjson document into a [Json] object.
+    /// See the [module documentation](crate::hjson)
+    pub fn from_hjson(text: impl AsRef<str>) -> Result<Json> {
+        HjsonParser {
+            src: text.as_ref(),
+            curr: 0,
+        }.document()
+    }
+}
@@ -0,0 +1,111 @@
+//! HTML rendering
+//!
+//! Renders a [Json] as a collapsible tree, meant to be embedded in
+//! reports and dashboards. Arrays and objects become `<details>`
+//! elements, whose summary is their number of elements, holding a
+//! `<ul>` with an `<li>` for each element or member.
+//!
+//! Every value is tagged with a CSS class for its type: `json-array`,
+//! `json-object`, `json-string`, `json-number`, `json-boolean` and
+//! `json-null`. Keys have the `json-key` class, and the whole tree is
+//! wrapped in a `<div class="json">`. [STYLE] has a basic stylesheet.
+//!
+//! Strings and keys are written like in JSON, between quotes, and
+//! with the characters that are special in HTML encoded as entities.
+//!
+//! # Example
+//! ```
+//! use json::json;
+//!
+//! let mut out = String::new();
+//! json!({ "a" : ["<b>", null] }).to_html(&mut out).unwrap();
+//! assert_eq!(out, concat!(
+//!     r#"<div class="json"><details open class="json-object"><summary>{1}</summary><ul>"#,
+//!     r#"<li><span class="json-key">&quot;a&quot;</span>: "#,
+//!     r#"<details open class="json-array"><summary>[2]</summary><ul>"#,
+//!     r#"<li><span class="json-string">&quot;&lt;b&gt;&quot;</span></li>"#,
+//!     r#"<li><span class="json-null">null</span></li>"#,
+//!     r#"</ul></details></li></ul></details></div>"#,
+//! ));
+//! ```
+
+use crate::prelude::*;
+use crate::Json;
+
+/// Basic stylesheet for the classes of the rendered tree
+pub const STYLE: &str = "\
+.json { font-family: monospace; }
+.json ul { list-style: none; margin: 0; padding-left: 1.5em; }
+.json summary { cursor: pointer; color: #888; }
+.json-key { color: #0451a5; }
+.json-string { color: #a31515; }
+.json-number { color: #098658; }
+.json-boolean, .json-null { color: #0000ff; }
+";
+
+/// Writes `s`, encoding the characters that are special in HTML
+fn encode_entities(s: &str, out: &mut dyn Write) -> fmt::Result {
+    for c in s.chars() {
+        match c {
+            '<' => out.write_str("&lt;")?,
+            '>' => out.write_str("&gt;")?,
+            '&' => out.write_str("&amp;")?,
+            '"' => out.write_str("&quot;")?,
+            '\'' => out.write_str("&#39;")?,
+            c => out.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Writes a string, which is already escaped, between quotes
+fn write_string(s: &str, class: &str, out: &mut dyn Write) -> fmt::Result {
+    write!(out, "<span class=\"{class}\">&quot;")?;
+    encode_entities(s, out)?;
+    out.write_str("&quot;</span>")
+}
+
+fn write_value(json: &Json, out: &mut dyn Write) -> fmt::Result {
+    match json {
+        Json::Array(elems) if elems.is_empty() => out.write_str(r#"<span class="json-array">[]</span>"#),
+        Json::Object(obj) if obj.is_empty() => out.write_str(r#"<span class="json-object">{}</span>"#),
+        Json::Array(elems) => {
+            write!(out, r#"<details open class="json-array"><summary>[{}]</summary><ul>"#, elems.len())?;
+            for e in elems {
+                out.write_str("<li>")?;
+                write_value(e, out)?;
+                out.write_str("</li>")?;
+            }
+            out.write_str("</ul></details>")
+        },
+        Json::Object(obj) => {
+            write!(out, r#"<details open class="json-object"><summary>{{{}}}</summary><ul>"#, obj.len())?;
+            for (k, v) in obj {
+                out.write_str("<li>")?;
+                write_string(k, "json-key", out)?;
+                out.write_str(": ")?;
+                write_value(v, out)?;
+                out.write_str("</li>")?;
+            }
+            out.write_str("</ul></details>")
+        },
+        Json::String(s) => write_string(s, "json-string", out),
+        Json::True | Json::False => write!(out, r#"<span class="json-boolean">{json}</span>"#),
+        Json::Null => out.write_str(r#"<span class="json-null">null</span>"#),
+        n @ Json::Number(_) => write!(out, r#"<span class="json-number">{n}</span>"#),
+        #[cfg(feature = "bigint")]
+        n @ Json::BigInt(_) => write!(out, r#"<span class="json-number">{n}</span>"#),
+        #[cfg(feature = "decimal")]
+        n @ Json::Decimal(_) => write!(out, r#"<span class="json-number">{n}</span>"#),
+    }
+}
+
+impl Json {
+    /// Renders the JSON object as an HTML tree into a `fmt::Write`.
+    /// See the [module documentation](crate::html)
+    pub fn to_html(&self, out: &mut dyn Write) -> fmt::Result {
+        out.write_str(r#"<div class="json">"#)?;
+        write_value(self, out)?;
+        out.write_str("</div>")
+    }
+}
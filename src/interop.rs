@@ -0,0 +1,102 @@
+//! Conversions between [Json] and [`serde_json::Value`]
+//!
+//! With the `preserve_order` feature, `serde_json/preserve_order` is
+//! enabled too, so objects keep the order of their members both ways.
+//!
+//! Integers are converted exactly when they fit in a i64 or u64, as long
+//! as the [Json] can hold them: numbers above 2^53 in magnitude need the
+//! `bigint` feature, or they are rounded to the nearest f64. Other numbers,
+//! like big integers that don't fit in 64 bits, go through a f64.
+
+use core::mem;
+
+use serde_json::Value;
+
+use crate::escape::{escape, unescape};
+use crate::prelude::*;
+use crate::Json;
+
+impl From<Value> for Json {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => Json::Null,
+            Value::Bool(b) => b.into(),
+            Value::Number(n) => number(&n),
+            Value::String(s) => Json::String(escape(&s).into()),
+            Value::Array(arr) => {
+                arr.into_iter().map(Json::from).collect::<Vec<_>>().into()
+            },
+            Value::Object(obj) => {
+                let map = obj.into_iter()
+                             .map(|(k,v)| (escape(&k).into(), Json::from(v)))
+                             .collect::<Map<_,_>>();
+                Json::Object(map)
+            },
+        }
+    }
+}
+
+/// Converts a [`serde_json::Number`], exactly if it's a big integer
+fn number(n: &serde_json::Number) -> Json {
+    #[cfg(feature = "bigint")]
+    {
+        let big = n.as_i64().map(num_bigint::BigInt::from)
+                   .or_else(|| n.as_u64().map(num_bigint::BigInt::from));
+        if let Some(big) = big {
+            if big.magnitude().bits() > 53 {
+                return Json::BigInt(big)
+            }
+        }
+    }
+    Json::Number(from_f64(n.as_f64().unwrap_or(f64::NAN)))
+}
+
+/// Returns the given integer as a [`serde_json::Number`], if it fits in a i64 or u64
+#[cfg(any(feature = "bigint", feature = "decimal"))]
+fn integer(n: &impl num_traits::ToPrimitive) -> Option<Value> {
+    n.to_i64().map(Into::into).or_else(|| n.to_u64().map(Into::into)).map(Value::Number)
+}
+
+/// Converts a f64, or [`Value::Null`] if it's not finite
+fn float(n: f64) -> Value {
+    serde_json::Number::from_f64(n).map_or(Value::Null, Value::Number)
+}
+
+/// Non finite numbers can't be represented by [`serde_json::Number`],
+/// so they are converted into [`Value::Null`]
+impl From<Json> for Value {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn from(mut value: Json) -> Self {
+        match &mut value {
+            Json::Null => Value::Null,
+            Json::True => Value::Bool(true),
+            Json::False => Value::Bool(false),
+            Json::Number(n) => {
+                let n = to_f64(*n);
+                /* Keep integers as integers, so they serialize without a fraction.
+                 * 2^63 and 2^64 are exact as f64, and the first values out of range */
+                if n.fract() == 0.0 && n >= -(2_f64.powi(63)) && n < 2_f64.powi(63) {
+                    Value::Number((n as i64).into())
+                } else if n.fract() == 0.0 && n >= 0.0 && n < 2_f64.powi(64) {
+                    Value::Number((n as u64).into())
+                } else {
+                    float(n)
+                }
+            },
+            #[cfg(feature = "bigint")]
+            Json::BigInt(n) => integer(n).unwrap_or_else(|| float(big_to_f64(n))),
+            #[cfg(feature = "decimal")]
+            Json::Decimal(n) => n.is_integer().then(|| integer(n)).flatten().unwrap_or_else(|| float(dec_to_f64(n))),
+            Json::String(s) => Value::String(unescape(s).into_owned()),
+            Json::Array(arr) => {
+                Value::Array(mem::take(arr).into_vec().into_iter().map(Value::from).collect())
+            },
+            Json::Object(obj) => {
+                let map = mem::take(obj).into_iter()
+                             .map(|(k,v)| (unescape(&k).into_owned(), Value::from(v)))
+                             .collect();
+                Value::Object(map)
+            },
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Iterators over the members of a [Json]
+//!
+//! Iterating over an array yields its elements, with a `None` key.
+//! Iterating over an object yields its members, with their key.
+//! Any other value yields nothing.
+//!
+//! # Example
+//! ```
+//! use json::json;
+//!
+//! let arr = json!([1, 2]);
+//! let sum: f64 = (&arr).into_iter().filter_map(|(_, v)| v.number()).sum();
+//! assert_eq!(sum, 3.0);
+//!
+//! for (key, value) in json!({ "a" : true }) {
+//!     assert_eq!(key.as_deref(), Some("a"));
+//!     assert_eq!(value, json!(true));
+//! }
+//! ```
+
+use core::mem;
+
+use crate::prelude::*;
+use crate::Json;
+
+type MapIntoIter = <Map<Key,Json> as IntoIterator>::IntoIter;
+type MapIter<'a> = <&'a Map<Key,Json> as IntoIterator>::IntoIter;
+
+/// Owning iterator over the members of a [Json]
+pub enum IntoIter {
+    #[doc(hidden)]
+    Array(alloc::vec::IntoIter<Json>),
+    #[doc(hidden)]
+    Object(MapIntoIter),
+    #[doc(hidden)]
+    Empty,
+}
+
+impl Iterator for IntoIter {
+    type Item = (Option<Key>, Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            IntoIter::Array(it) => it.next().map(|v| (None, v)),
+            IntoIter::Object(it) => it.next().map(|(k, v)| (Some(k), v)),
+            IntoIter::Empty => None,
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            IntoIter::Array(it) => it.size_hint(),
+            IntoIter::Object(it) => it.size_hint(),
+            IntoIter::Empty => (0, Some(0)),
+        }
+    }
+}
+
+/// Borrowing iterator over the members of a [Json]
+pub enum Iter<'a> {
+    #[doc(hidden)]
+    Array(core::slice::Iter<'a, Json>),
+    #[doc(hidden)]
+    Object(MapIter<'a>),
+    #[doc(hidden)]
+    Empty,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (Option<&'a str>, &'a Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Iter::Array(it) => it.next().map(|v| (None, v)),
+            Iter::Object(it) => it.next().map(|(k, v)| (Some(&**k), v)),
+            Iter::Empty => None,
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Iter::Array(it) => it.size_hint(),
+            Iter::Object(it) => it.size_hint(),
+            Iter::Empty => (0, Some(0)),
+        }
+    }
+}
+
+impl IntoIterator for Json {
+    type Item = (Option<Key>, Json);
+    type IntoIter = IntoIter;
+
+    fn into_iter(mut self) -> IntoIter {
+        match &mut self {
+            Json::Array(arr) => IntoIter::Array(mem::take(arr).into_vec().into_iter()),
+            Json::Object(obj) => IntoIter::Object(mem::take(obj).into_iter()),
+            _ => IntoIter::Empty,
+        }
+    }
+}
+
+impl Json {
+    /// Returns an iterator over the members of this value.
+    /// See the [module documentation](self) for details.
+    pub fn iter(&self) -> Iter<'_> {
+        self.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Json {
+    type Item = (Option<&'a str>, &'a Json);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        match self {
+            Json::Array(arr) => Iter::Array(arr.iter()),
+            Json::Object(obj) => Iter::Object(obj.iter()),
+            _ => Iter::Empty,
+        }
+    }
+}
@@ -0,0 +1,545 @@
+//! A small jq-like filter language
+//!
+//! Supported syntax:
+//! - Paths: `.`, `.a`, `."a b"`, `.a.b`, `.[0]`, `.[-1]`, `.[]`, `.a[]`
+//! - Pipes and commas: `.a | .b`, `.a, .b`
+//! - Literals: numbers, strings, `true`, `false`, `null`
+//! - Constructors: `[.a, .b]`, `{a: .x, "b": 1, c}`
+//! - Operators: `+ - * /`, `== != < <= > >=`, `and`, `or`
+//! - Functions: `select(f)`, `map(f)`, `length`, `keys`, `not`, `type`, `add`, `has(k)`, `empty`
+//!
+//! # Example
+//! ```
+//! use json::{json, jq};
+//!
+//! let doc = json!({
+//!     "items" : [
+//!         { "id" : 1, "name" : "a", "x" : 5 },
+//!         { "id" : 2, "name" : "b", "x" : 1 }
+//!     ]
+//! });
+//!
+//! let out = jq::eval(".items[] | select(.x > 3) | {id, name}", &doc).unwrap();
+//! assert_eq!(out, vec![json!({ "id" : 1, "name" : "a" })]);
+//! ```
+
+use crate::escape::unescape;
+use crate::sort::compare;
+use crate::prelude::*;
+use crate::{Json, Number, Result};
+
+#[derive(Debug,Clone,PartialEq)]
+enum Token {
+    Dot,
+    Ident(Box<str>),
+    /// Raw (still escaped) string
+    Str(Box<str>),
+    Num(f64),
+    Punct(&'static str),
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>> {
+    const PUNCT: [&str; 20] = [
+        "==", "!=", "<=", ">=", "<", ">", "|", ",", ";", "(", ")",
+        "[", "]", "{", "}", ":", "+", "-", "*", "/",
+    ];
+    let mut tokens = Vec::new();
+    let mut rest = src.trim_start();
+    while !rest.is_empty() {
+        let c = rest.chars().next().unwrap();
+        if c == '.' {
+            tokens.push(Token::Dot);
+            rest = &rest[1..];
+        } else if c == '"' {
+            let mut escaped = false;
+            let end = rest[1..].char_indices().find(|(_, c)| {
+                let end = *c == '"' && !escaped;
+                escaped = *c == '\\' && !escaped;
+                end
+            }).map(|(i, _)| i + 1).ok_or("Unterminated string")?;
+            tokens.push(Token::Str(rest[1..end].into()));
+            rest = &rest[end + 1..];
+        } else if c.is_ascii_digit() {
+            let end = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+            let n = rest[..end].parse().map_err(|_| format!("Invalid number '{}'", &rest[..end]))?;
+            tokens.push(Token::Num(n));
+            rest = &rest[end..];
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let end = rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..end].into()));
+            rest = &rest[end..];
+        } else if let Some(p) = PUNCT.iter().find(|p| rest.starts_with(**p)) {
+            tokens.push(Token::Punct(p));
+            rest = &rest[p.len()..];
+        } else {
+            return Err(format!("Unexpected character '{c}'").into());
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug,Clone,Copy)]
+enum BinOp { Add, Sub, Mul, Div, Eq, Ne, Lt, Le, Gt, Ge, And, Or }
+
+#[derive(Debug)]
+enum Expr {
+    Identity,
+    Field(Box<Expr>, Box<str>),
+    Index(Box<Expr>, Box<Expr>),
+    Iterate(Box<Expr>),
+    Pipe(Box<Expr>, Box<Expr>),
+    Comma(Box<Expr>, Box<Expr>),
+    Literal(Json),
+    Array(Option<Box<Expr>>),
+    Object(Vec<(Key, Expr)>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Call(Box<str>, Vec<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    curr: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.curr)
+    }
+    fn advance(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.curr).cloned();
+        self.curr += 1;
+        t
+    }
+    fn match_punct(&mut self, p: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Punct(q)) if *q == p) {
+            self.curr += 1;
+            true
+        } else {
+            false
+        }
+    }
+    fn expect(&mut self, p: &str) -> Result<()> {
+        if self.match_punct(p) { Ok(()) } else { Err(format!("Expected '{p}'").into()) }
+    }
+    fn pipe(&mut self) -> Result<Expr> {
+        let mut e = self.comma()?;
+        while self.match_punct("|") {
+            e = Expr::Pipe(Box::new(e), Box::new(self.comma()?));
+        }
+        Ok(e)
+    }
+    fn comma(&mut self) -> Result<Expr> {
+        let mut e = self.or()?;
+        while self.match_punct(",") {
+            e = Expr::Comma(Box::new(e), Box::new(self.or()?));
+        }
+        Ok(e)
+    }
+    fn keyword(&mut self, kw: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(i)) if &**i == kw) {
+            self.curr += 1;
+            true
+        } else {
+            false
+        }
+    }
+    fn or(&mut self) -> Result<Expr> {
+        let mut e = self.and()?;
+        while self.keyword("or") {
+            e = Expr::Binary(BinOp::Or, Box::new(e), Box::new(self.and()?));
+        }
+        Ok(e)
+    }
+    fn and(&mut self) -> Result<Expr> {
+        let mut e = self.comparison()?;
+        while self.keyword("and") {
+            e = Expr::Binary(BinOp::And, Box::new(e), Box::new(self.comparison()?));
+        }
+        Ok(e)
+    }
+    fn comparison(&mut self) -> Result<Expr> {
+        let e = self.additive()?;
+        let op = match self.peek() {
+            Some(Token::Punct("==")) => BinOp::Eq,
+            Some(Token::Punct("!=")) => BinOp::Ne,
+            Some(Token::Punct("<")) => BinOp::Lt,
+            Some(Token::Punct("<=")) => BinOp::Le,
+            Some(Token::Punct(">")) => BinOp::Gt,
+            Some(Token::Punct(">=")) => BinOp::Ge,
+            _ => return Ok(e),
+        };
+        self.curr += 1;
+        Ok(Expr::Binary(op, Box::new(e), Box::new(self.additive()?)))
+    }
+    fn additive(&mut self) -> Result<Expr> {
+        let mut e = self.multiplicative()?;
+        loop {
+            let op = if self.match_punct("+") {
+                BinOp::Add
+            } else if self.match_punct("-") {
+                BinOp::Sub
+            } else {
+                return Ok(e)
+            };
+            e = Expr::Binary(op, Box::new(e), Box::new(self.multiplicative()?));
+        }
+    }
+    fn multiplicative(&mut self) -> Result<Expr> {
+        let mut e = self.postfix()?;
+        loop {
+            let op = if self.match_punct("*") {
+                BinOp::Mul
+            } else if self.match_punct("/") {
+                BinOp::Div
+            } else {
+                return Ok(e)
+            };
+            e = Expr::Binary(op, Box::new(e), Box::new(self.postfix()?));
+        }
+    }
+    /// Parses the `.a` / `[i]` / `[]` suffixes after a term
+    fn suffixes(&mut self, mut e: Expr) -> Result<Expr> {
+        loop {
+            if self.peek() == Some(&Token::Dot) {
+                match self.tokens.get(self.curr + 1) {
+                    Some(Token::Ident(name) | Token::Str(name)) => {
+                        let name = name.clone();
+                        self.curr += 2;
+                        e = Expr::Field(Box::new(e), name);
+                    },
+                    Some(Token::Punct("[")) => { self.curr += 1; },
+                    _ => return Ok(e),
+                }
+            } else if self.match_punct("[") {
+                if self.match_punct("]") {
+                    e = Expr::Iterate(Box::new(e));
+                } else {
+                    let index = self.pipe()?;
+                    self.expect("]")?;
+                    e = Expr::Index(Box::new(e), Box::new(index));
+                }
+            } else {
+                return Ok(e)
+            }
+        }
+    }
+    fn postfix(&mut self) -> Result<Expr> {
+        if self.match_punct("-") {
+            let e = self.postfix()?;
+            return Ok(Expr::Binary(BinOp::Sub, Box::new(Expr::Literal(Json::Number(0.0))), Box::new(e)));
+        }
+        let term = match self.advance().ok_or("Unexpected end of filter")? {
+            Token::Dot => {
+                match self.peek() {
+                    Some(Token::Ident(name) | Token::Str(name)) => {
+                        let name = name.clone();
+                        self.curr += 1;
+                        Expr::Field(Box::new(Expr::Identity), name)
+                    },
+                    _ => Expr::Identity,
+                }
+            },
+            Token::Num(n) => Expr::Literal(Json::Number(from_f64(n))),
+            Token::Str(s) => Expr::Literal(Json::String(s)),
+            Token::Ident(i) => match &*i {
+                "true" => Expr::Literal(Json::True),
+                "false" => Expr::Literal(Json::False),
+                "null" => Expr::Literal(Json::Null),
+                _ => {
+                    let mut args = Vec::new();
+                    if self.match_punct("(") {
+                        loop {
+                            args.push(self.pipe()?);
+                            if !self.match_punct(";") { break }
+                        }
+                        self.expect(")")?;
+                    }
+                    Expr::Call(i, args)
+                }
+            },
+            Token::Punct("(") => {
+                let e = self.pipe()?;
+                self.expect(")")?;
+                e
+            },
+            Token::Punct("[") => {
+                if self.match_punct("]") {
+                    Expr::Array(None)
+                } else {
+                    let e = self.pipe()?;
+                    self.expect("]")?;
+                    Expr::Array(Some(Box::new(e)))
+                }
+            },
+            Token::Punct("{") => self.object()?,
+            t @ Token::Punct(_) => return Err(format!("Unexpected token {t:?}").into()),
+        };
+        self.suffixes(term)
+    }
+    fn object(&mut self) -> Result<Expr> {
+        let mut entries = Vec::new();
+        if self.match_punct("}") {
+            return Ok(Expr::Object(entries));
+        }
+        loop {
+            let Some(Token::Ident(key) | Token::Str(key)) = self.advance() else {
+                return Err("Expected object key".into());
+            };
+            let value = if self.match_punct(":") {
+                self.or()?
+            } else {
+                Expr::Field(Box::new(Expr::Identity), key.clone())
+            };
+            entries.push((to_key(key), value));
+            if self.match_punct("}") { break }
+            self.expect(",")?;
+        }
+        Ok(Expr::Object(entries))
+    }
+}
+
+fn truthy(json: &Json) -> bool {
+    !matches!(json, Json::False | Json::Null)
+}
+
+fn binary(op: BinOp, a: &Json, b: &Json) -> Result<Json> {
+    Ok(match (op, a, b) {
+        (BinOp::Add, Json::Null, x) | (BinOp::Add, x, Json::Null) => x.clone(),
+        (BinOp::Add, Json::Number(a), Json::Number(b)) => Json::Number(a + b),
+        (BinOp::Add, Json::String(a), Json::String(b)) => format!("{a}{b}").into(),
+        (BinOp::Add, Json::Array(a), Json::Array(b)) => {
+            a.iter().chain(b.iter()).cloned().collect::<Vec<_>>().into()
+        },
+        (BinOp::Add, Json::Object(a), Json::Object(b)) => {
+            let mut map = a.clone();
+            map.extend(b.iter().map(|(k,v)| (k.clone(), v.clone())));
+            Json::Object(map)
+        },
+        (BinOp::Sub, Json::Number(a), Json::Number(b)) => Json::Number(a - b),
+        (BinOp::Sub, Json::Array(a), Json::Array(b)) => {
+            a.iter().filter(|e| !b.contains(e)).cloned().collect::<Vec<_>>().into()
+        },
+        (BinOp::Mul, Json::Number(a), Json::Number(b)) => Json::Number(a * b),
+        (BinOp::Div, Json::Number(_), Json::Number(b)) if *b == 0.0 => {
+            return Err("Division by zero".into())
+        },
+        (BinOp::Div, Json::Number(a), Json::Number(b)) => Json::Number(a / b),
+        (BinOp::Eq, a, b) => (a == b).into(),
+        (BinOp::Ne, a, b) => (a != b).into(),
+        (BinOp::Lt, a, b) => compare(a, b).is_lt().into(),
+        (BinOp::Le, a, b) => compare(a, b).is_le().into(),
+        (BinOp::Gt, a, b) => compare(a, b).is_gt().into(),
+        (BinOp::Ge, a, b) => compare(a, b).is_ge().into(),
+        (BinOp::And, a, b) => (truthy(a) && truthy(b)).into(),
+        (BinOp::Or, a, b) => (truthy(a) || truthy(b)).into(),
+        (op, a, b) => return Err(format!("Can't apply {op:?} to {a} and {b}").into()),
+    })
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn call(name: &str, args: &[Expr], input: &Json) -> Result<Vec<Json>> {
+    let arity = |n: usize| if args.len() == n {
+        Ok(())
+    } else {
+        Err(crate::Error::from(format!("{name}/{} is not defined", args.len())))
+    };
+    Ok(match name {
+        "select" => {
+            arity(1)?;
+            let keep = evaluate(&args[0], input)?.iter().any(truthy);
+            if keep { vec![input.clone()] } else { Vec::new() }
+        },
+        "map" => {
+            arity(1)?;
+            let Json::Array(arr) = input else {
+                return Err(format!("Can't iterate over {}", input.type_name()).into());
+            };
+            let mut out = Vec::new();
+            for e in arr {
+                out.extend(evaluate(&args[0], e)?);
+            }
+            vec![out.into()]
+        },
+        "has" => {
+            arity(1)?;
+            let mut out = Vec::new();
+            for key in evaluate(&args[0], input)? {
+                let has = match (input, &key) {
+                    (Json::Object(o), Json::String(k)) => o.contains_key(&**k),
+                    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+                    (Json::Array(a), Json::Number(n)) => *n >= 0.0 && (*n as usize) < a.len(),
+                    _ => return Err(format!("Can't check if {} has a key", input.type_name()).into()),
+                };
+                out.push(has.into());
+            }
+            out
+        },
+        "length" => {
+            arity(0)?;
+            let len = match input {
+                Json::Null => 0.0,
+                Json::Number(n) => to_f64(*n).abs(),
+                Json::String(s) => unescape(s).chars().count() as f64,
+                Json::Array(a) => a.len() as f64,
+                Json::Object(o) => o.len() as f64,
+                _ => return Err("boolean has no length".into()),
+            };
+            vec![Json::Number(from_f64(len))]
+        },
+        "keys" => {
+            arity(0)?;
+            let o = match input {
+                Json::Object(o) => o,
+                Json::Array(a) => {
+                    return Ok(vec![(0..a.len()).map(|i| Json::Number(i as Number)).collect::<Vec<_>>().into()]);
+                },
+                _ => return Err(format!("{} has no keys", input.type_name()).into()),
+            };
+            let mut keys: Vec<_> = o.keys().collect();
+            keys.sort_by(|a, b| unescape(a).cmp(&unescape(b)));
+            vec![keys.into_iter().map(|k| Json::String((**k).into())).collect::<Vec<_>>().into()]
+        },
+        "not" => {
+            arity(0)?;
+            vec![(!truthy(input)).into()]
+        },
+        "type" => {
+            arity(0)?;
+            vec![input.type_name().into()]
+        },
+        "add" => {
+            arity(0)?;
+            let Json::Array(arr) = input else {
+                return Err(format!("Can't iterate over {}", input.type_name()).into());
+            };
+            let mut acc = Json::Null;
+            for e in arr {
+                acc = binary(BinOp::Add, &acc, e)?;
+            }
+            vec![acc]
+        },
+        "empty" => {
+            arity(0)?;
+            Vec::new()
+        },
+        _ => return Err(format!("{name}/{} is not defined", args.len()).into()),
+    })
+}
+
+fn evaluate(expr: &Expr, input: &Json) -> Result<Vec<Json>> {
+    Ok(match expr {
+        Expr::Identity => vec![input.clone()],
+        Expr::Literal(j) => vec![j.clone()],
+        Expr::Field(e, name) => {
+            let mut out = Vec::new();
+            for j in evaluate(e, input)? {
+                out.push(match &j {
+                    Json::Object(o) => o.get(&**name).cloned().unwrap_or(Json::Null),
+                    Json::Null => Json::Null,
+                    _ => return Err(format!("Can't index {} with \"{name}\"", j.type_name()).into()),
+                });
+            }
+            out
+        },
+        Expr::Index(e, index) => {
+            let mut out = Vec::new();
+            for j in evaluate(e, input)? {
+                for i in evaluate(index, input)? {
+                    out.push(match (&j, &i) {
+                        (Json::Array(a), Json::Number(n)) => {
+                            #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+                            let n = if *n < 0.0 { a.len() as f64 + to_f64(*n) } else { to_f64(*n) };
+                            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                            let elem = if n < 0.0 { None } else { a.get(n as usize) };
+                            elem.cloned().unwrap_or(Json::Null)
+                        },
+                        (Json::Object(o), Json::String(k)) => o.get(&**k).cloned().unwrap_or(Json::Null),
+                        (Json::Null, _) => Json::Null,
+                        _ => return Err(format!("Can't index {} with {}", j.type_name(), i.type_name()).into()),
+                    });
+                }
+            }
+            out
+        },
+        Expr::Iterate(e) => {
+            let mut out = Vec::new();
+            for mut j in evaluate(e, input)? {
+                if !(j.is_array() || j.is_object()) {
+                    return Err(format!("Can't iterate over {}", j.type_name()).into())
+                }
+                j.drain_into(&mut out);
+            }
+            out
+        },
+        Expr::Pipe(a, b) => {
+            let mut out = Vec::new();
+            for j in evaluate(a, input)? {
+                out.extend(evaluate(b, &j)?);
+            }
+            out
+        },
+        Expr::Comma(a, b) => {
+            let mut out = evaluate(a, input)?;
+            out.extend(evaluate(b, input)?);
+            out
+        },
+        Expr::Array(None) => vec![Json::Array(Box::new([]))],
+        Expr::Array(Some(e)) => vec![evaluate(e, input)?.into()],
+        Expr::Object(entries) => {
+            /* Each entry may produce several values, so build the cartesian product */
+            let mut objects = vec![Map::default()];
+            for (k, e) in entries {
+                let values = evaluate(e, input)?;
+                let mut next = Vec::with_capacity(objects.len() * values.len());
+                for obj in &objects {
+                    for v in &values {
+                        let mut obj = obj.clone();
+                        obj.insert(k.clone(), v.clone());
+                        next.push(obj);
+                    }
+                }
+                objects = next;
+            }
+            objects.into_iter().map(Json::Object).collect()
+        },
+        Expr::Binary(op, a, b) => {
+            let mut out = Vec::new();
+            for r in evaluate(b, input)? {
+                for l in evaluate(a, input)? {
+                    out.push(binary(*op, &l, &r)?);
+                }
+            }
+            out
+        },
+        Expr::Call(name, args) => call(name, args, input)?,
+    })
+}
+
+/// A compiled jq filter
+#[derive(Debug)]
+pub struct Filter(Expr);
+
+impl Filter {
+    /// Compiles the given filter
+    pub fn compile(src: &str) -> Result<Filter> {
+        let mut parser = Parser {
+            tokens: tokenize(src)?,
+            curr: 0,
+        };
+        let expr = parser.pipe()?;
+        if let Some(t) = parser.peek() {
+            return Err(format!("Unexpected token {t:?}").into());
+        }
+        Ok(Filter(expr))
+    }
+    /// Runs this filter on the given input, returning all the outputs
+    pub fn run(&self, input: &Json) -> Result<Vec<Json>> {
+        evaluate(&self.0, input)
+    }
+}
+
+/// Compiles and runs the given filter
+pub fn eval(filter: &str, input: &Json) -> Result<Vec<Json>> {
+    Filter::compile(filter)?.run(input)
+}
@@ -0,0 +1,47 @@
+//! Reusable parser
+
+use crate::lexer::{self, token::Token};
+use crate::parser::{self, Frame, JsonBuilder};
+use crate::prelude::*;
+use crate::{Json, JsonConfig, Result};
+
+/// A parser that keeps its buffers between calls to [parse](Self::parse).
+///
+/// [`Json::deserialize`] allocates a new buffer of tokens, and a new stack
+/// of open arrays and objects, each time it's called. When parsing lots of
+/// small messages, reusing a `JsonParser` avoids those allocations.
+///
+/// # Example
+/// ```
+/// use json::{json, JsonParser};
+///
+/// let mut parser = JsonParser::new();
+/// for (id, msg) in [(0, r#"{ "id" : 0 }"#), (1, r#"{ "id" : 1 }"#)] {
+///     assert_eq!(parser.parse(msg).unwrap(), json!({ "id" : id }));
+/// }
+/// ```
+pub struct JsonParser {
+    conf: JsonConfig,
+    tokens: Vec<Token>,
+    stack: Vec<Frame<JsonBuilder>>,
+}
+
+impl JsonParser {
+    /// Builds a parser with the default [`JsonConfig`]
+    pub fn new() -> Self { Self::with_config(JsonConfig::default()) }
+    /// Builds a parser with the given [`JsonConfig`]
+    pub fn with_config(conf: JsonConfig) -> Self {
+        Self { conf, tokens: Vec::new(), stack: Vec::new() }
+    }
+    /// Deserializes the given string into a [Json] object.
+    /// Same as [`Json::deserialize_with_config`], with the config of this parser.
+    pub fn parse(&mut self, text: impl AsRef<str>) -> Result<Json> {
+        let text = text.as_ref();
+        lexer::tokenize_into(text, self.conf.max_bytes, None, &mut self.tokens)?;
+        parser::parse_reusing(text, &self.tokens, self.conf, None, &mut self.stack)
+    }
+}
+
+impl Default for JsonParser {
+    fn default() -> Self { Self::new() }
+}
@@ -0,0 +1,118 @@
+//! JSON Lines
+//!
+//! A [JSON Lines](https://jsonlines.org) file holds a document per line,
+//! in compact form. It's a common format for logs, since new documents
+//! can be appended to the end of the file.
+//!
+//! # Example
+//! ```
+//! use json::jsonl::JsonLinesWriter;
+//! use json::json;
+//!
+//! let mut writer = JsonLinesWriter::new(Vec::new());
+//! writer.write(&json!({ "level" : "info" })).unwrap();
+//! writer.write(&json!(["started", 1])).unwrap();
+//! writer.flush().unwrap();
+//! assert_eq!(writer.lines(), 2);
+//!
+//! let out = writer.into_inner();
+//! assert_eq!(out, b"{\"level\":\"info\"}\n[\"started\",1]\n");
+//! ```
+
+use std::io::Write;
+
+use crate::prelude::*;
+use crate::{Json, Result};
+
+fn io_error(err: &std::io::Error) -> crate::Error {
+    err.to_string().into()
+}
+
+/// Writes documents as JSON Lines
+///
+/// The lines are written to the underlying writer as they come, but it's
+/// only flushed when [`flush`](Self::flush) is called, or every `n` lines if
+/// [`flush_every`](Self::flush_every) is set. Wrap unbuffered writers, like
+/// files, in a [`BufWriter`](std::io::BufWriter), so that flushing controls
+/// when the lines reach them. Before rotating a log file, flush the writer
+/// and replace it with [`into_inner`](Self::into_inner) and [`new`](Self::new).
+pub struct JsonLinesWriter<W: Write> {
+    writer: W,
+    buf: String,
+    lines: usize,
+    flush_every: Option<usize>,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    /// Builds a writer of lines into `writer`
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buf: String::new(),
+            lines: 0,
+            flush_every: None,
+        }
+    }
+    /// Flushes the writer after every `n` lines.
+    /// `None` only flushes when [`flush`](Self::flush) is called
+    #[must_use]
+    pub fn flush_every(mut self, n: Option<usize>) -> Self {
+        self.flush_every = n.filter(|n| *n > 0);
+        self
+    }
+    /// Writes `json` in compact form, followed by a line feed
+    pub fn write(&mut self, json: &Json) -> Result<()> {
+        self.buf.clear();
+        json.serialize(&mut self.buf)?;
+        self.buf.push('\n');
+        self.writer.write_all(self.buf.as_bytes()).map_err(|e| io_error(&e))?;
+        self.lines += 1;
+        if self.flush_every.is_some_and(|n| self.lines.is_multiple_of(n)) {
+            self.flush()?;
+        }
+        Ok(())
+    }
+    /// Flushes the underlying writer, so all the lines written so far reach it
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush().map_err(|e| io_error(&e))
+    }
+    /// Returns the number of lines written
+    pub fn lines(&self) -> usize { self.lines }
+    /// Returns a reference to the underlying writer
+    pub fn get_ref(&self) -> &W { &self.writer }
+    /// Returns a mutable reference to the underlying writer
+    pub fn get_mut(&mut self) -> &mut W { &mut self.writer }
+    /// Returns the underlying writer, without flushing it
+    pub fn into_inner(self) -> W { self.writer }
+}
+
+#[cfg(feature = "gzip")]
+impl<W: Write> JsonLinesWriter<flate2::write::GzEncoder<W>> {
+    /// Builds a writer of gzip compressed lines into `writer`
+    ///
+    /// Flushing it completes the compressed block, so everything written
+    /// before can be decompressed, even if the stream is cut afterwards.
+    /// Call [`finish`](Self::finish) to write the end of the gzip stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Read;
+    /// use json::jsonl::JsonLinesWriter;
+    /// use json::json;
+    ///
+    /// let mut writer = JsonLinesWriter::gzip(Vec::new());
+    /// writer.write(&json!([1, 2])).unwrap();
+    /// let compressed = writer.finish().unwrap();
+    ///
+    /// let mut text = String::new();
+    /// flate2::read::GzDecoder::new(&compressed[..]).read_to_string(&mut text).unwrap();
+    /// assert_eq!(text, "[1,2]\n");
+    /// ```
+    pub fn gzip(writer: W) -> Self {
+        Self::new(flate2::write::GzEncoder::new(writer, flate2::Compression::default()))
+    }
+    /// Ends the gzip stream, and returns the underlying writer
+    pub fn finish(self) -> Result<W> {
+        self.writer.finish().map_err(|e| io_error(&e))
+    }
+}
@@ -1,37 +1,111 @@
+//! Tokenizer
+//!
+//! Splits a document into [Token]s, the same way the parser does, so
+//! external tools like syntax highlighters can reuse it. Each token
+//! has a [`TokenKind`] and the [Span] it covers in the source.
+//!
+//! Whitespace and comments (`// ...` and `/* ... */`) don't produce
+//! tokens. Neither do identifiers other than `true`, `false` and
+//! `null`, which the parser ignores too.
+//!
+//! This API follows the semver guarantees of the crate. New kinds
+//! of tokens may be added in minor versions, so [`TokenKind`]
+//! is `#[non_exhaustive]`.
+//!
+//! # Example
+//! ```
+//! use json::lexer::{self, TokenKind};
+//!
+//! let src = r#"{ "a" : [1, true] } // Comment"#;
+//! let tokens = lexer::tokenize(src).unwrap();
+//!
+//! let kinds: Vec<_> = tokens.iter().map(|t| t.get_type()).collect();
+//! assert_eq!(kinds, [
+//!     TokenKind::LeftBrace, TokenKind::String, TokenKind::Colon,
+//!     TokenKind::LSquareBracket, TokenKind::Number, TokenKind::Comma,
+//!     TokenKind::True, TokenKind::RSquareBracket, TokenKind::RightBrace,
+//! ]);
+//! assert_eq!(tokens[1].span().slice(src), r#""a""#);
+//! ```
+
 mod cursor;
-pub mod span;
+pub(crate) mod span;
 
 use cursor::Cursor;
-use span::FilePosition;
-pub use span::Span;
+pub use span::{FilePosition, LineIndex, Span};
+
+use core::mem::size_of;
 
 use crate::prelude::*;
 
 use crate::Result;
 
-pub mod token;
-use token::{Token,TokenKind};
+pub(crate) mod token;
+pub use token::{Token, TokenKind};
+
+/// Called periodically while deserializing, with the amount of work done
+/// so far. Tokenizing the input counts as `0..len`, and parsing it as
+/// `len..2*len`. Returning an error aborts the deserialization.
+pub(crate) type Hook<'a> = &'a mut dyn FnMut(usize) -> Result<()>;
 
-struct Lexer<'a> {
+/// Number of bytes processed between two calls to a [Hook]
+pub(crate) const HOOK_INTERVAL: usize = 64 * 1024;
+
+/// Error returned when a [`JsonConfig::max_bytes`](crate::JsonConfig::max_bytes) budget is exceeded
+pub(crate) const BUDGET_EXCEEDED: &str = "Memory budget exceeded";
+
+struct Lexer<'a, 'h> {
     c: Cursor<'a>,
+    max_bytes: usize,
+    hook: Option<Hook<'h>>,
 }
 
+/// Splits the text into tokens.
+///
+/// Fails on characters that can't start a token, and on
+/// unterminated strings and comments.
 pub fn tokenize(text: &str) -> Result<Vec<Token>> {
+    tokenize_with(text, usize::MAX, None)
+}
+
+/// Tokenizes the text, failing if the tokens take more than `max_bytes`
+pub(crate) fn tokenize_with(text: &str, max_bytes: usize, hook: Option<Hook<'_>>) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    tokenize_into(text, max_bytes, hook, &mut tokens)?;
+    Ok(tokens)
+}
+
+/// Same as [`tokenize_with`], but writes the tokens into the given buffer,
+/// replacing its contents. This allows reusing the buffer between calls.
+pub(crate) fn tokenize_into(text: &str, max_bytes: usize, hook: Option<Hook<'_>>, tokens: &mut Vec<Token>) -> Result<()> {
+    tokens.clear();
     Lexer {
         c: Cursor::new(text),
-    }.tokenize()
+        max_bytes,
+        hook,
+    }.tokenize(tokens)
 }
 
-impl Lexer<'_> {
-    fn tokenize(&mut self) -> Result<Vec<Token>> {
-        let mut tokens:Vec<Token> = Vec::new();
+impl Lexer<'_, '_> {
+    fn tokenize(&mut self, tokens: &mut Vec<Token>) -> Result<()> {
+        let mut next_check = HOOK_INTERVAL;
         while !self.c.is_finished() {
             self.c.step();
             if let Some(t) = self.scan_token()? {
+                if (tokens.len() + 1) * size_of::<Token>() > self.max_bytes {
+                    return Err(BUDGET_EXCEEDED.into())
+                }
                 tokens.push(t);
             }
+            if let Some(hook) = &mut self.hook {
+                let offset = self.c.get_span().offset;
+                if offset >= next_check {
+                    hook(offset)?;
+                    next_check = offset + HOOK_INTERVAL;
+                }
+            }
         }
-        Ok(tokens)
+        Ok(())
     }
     #[allow(clippy::unnecessary_wraps)]
     fn add_token(&self, token_type: TokenKind) -> Result<Option<Token>> {
@@ -46,7 +120,12 @@ impl Lexer<'_> {
             ']' => self.add_token(TokenKind::RSquareBracket),
             ',' => self.add_token(TokenKind::Comma),
             '.' => self.add_token(TokenKind::Dot),
-            '-' => self.add_token(TokenKind::Minus),
+            '-' =>
+                if self.c.peek().is_ascii_digit() {
+                    self.number()
+                } else {
+                    self.add_token(TokenKind::Minus)
+                },
             '+' => self.add_token(TokenKind::Plus),
             ':' => self.add_token(TokenKind::Colon),
             '/' =>
@@ -88,21 +167,35 @@ impl Lexer<'_> {
         Ok(None)
     }
     fn string(&mut self) -> Result<Option<Token>> {
-        let mut scaping = false;
         loop {
-            let c = self.c.peek();
-            if c == '"' && !scaping  {
-                break
-            }
-            scaping = c ==  '\\';
-
-            self.c.advance();
-
             if self.c.is_finished() { return self.error("Unterminated string"); }
+            match self.c.advance() {
+                '"' => break,
+                '\\' => self.escape()?,
+                _ => {},
+            }
         }
-        self.c.advance();
         self.add_token(TokenKind::String)
     }
+    /// Validates the escape sequence after a '\\'
+    fn escape(&mut self) -> Result<()> {
+        let FilePosition { end_line, end_col, .. } = self.c.file_pos();
+        let error = |msg: String| Err(format!("[{end_line}:{}] {msg}", end_col - 1).into());
+        match self.c.advance() {
+            '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' => Ok(()),
+            'u' => {
+                for _ in 0..4 {
+                    if !self.c.peek().is_ascii_hexdigit() {
+                        return error("Truncated \\u escape sequence".to_string())
+                    }
+                    self.c.advance();
+                }
+                Ok(())
+            },
+            _ if self.c.is_finished() => error("Unterminated string".to_string()),
+            c => error(format!("Invalid escape sequence [\\{c}]")),
+        }
+    }
     fn number(&mut self) -> Result<Option<Token>> {
         self.c.advance_while(char::is_ascii_digit);
         if self.c.peek() == '.' && self.c.peek_next().is_ascii_digit() {
@@ -1,9 +1,11 @@
 //! Utilities to represent spans inside a file
 
-use std::{fmt, str};
+use core::fmt;
+
+use crate::prelude::*;
 
 /// Represents a span in a buffer, bounded by an offset and a len
-#[derive(Clone,Copy,Debug)]
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
 pub struct Span {
     /// Offset of the span inside the buffer
     pub offset: usize,
@@ -13,7 +15,7 @@ pub struct Span {
 
 /// Represents a [`Span`] in a file, bounded by
 /// it's start line and col, plus it's end line and col
-#[derive(Debug,Clone,Copy,Default)]
+#[derive(Debug,Clone,Copy,Default,PartialEq,Eq,Hash)]
 pub struct FilePosition {
     pub start_line: usize,
     pub start_col: usize,
@@ -70,6 +72,64 @@ impl Span {
     }
 }
 
+/// Index of the lines of a string, to get the [file position] of many
+/// spans in it quickly.
+///
+/// [`Span::file_position`] scans the string from its beginning each time.
+/// A `LineIndex` scans it once, and then finds the line of each span with
+/// a binary search. Only the line of the span is scanned, to get its column.
+///
+/// # Example
+/// ```
+/// use json::LineIndex;
+///
+/// let src = "{\n  \"a\" : 1,\n  \"b\" : [1, \"2\"]\n}";
+/// let index = LineIndex::new(src);
+/// for ptr in ["/a", "/b/0", "/b/1"] {
+///     let pos = index.locate(ptr).unwrap();
+///     assert_eq!(pos, json::locate(src, ptr).unwrap());
+/// }
+/// assert_eq!(index.locate("/b/1").unwrap().start_line, 2);
+/// ```
+///
+/// [file position]: FilePosition
+#[derive(Debug,Clone)]
+pub struct LineIndex<'a> {
+    src: &'a str,
+    /// Offsets of the newlines of `src`
+    newlines: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Builds the index of the given string
+    pub fn new(src: &'a str) -> Self {
+        let newlines = src.bytes().enumerate()
+                          .filter_map(|(i, b)| (b == b'\n').then_some(i))
+                          .collect();
+        Self { src, newlines }
+    }
+    /// Returns the indexed string
+    pub(crate) fn src(&self) -> &'a str { self.src }
+    /// Returns the line and column of the given offset
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.newlines.partition_point(|&nl| nl < offset);
+        /* Like in Span::file_position, the newline that starts
+         * a line counts as its first column */
+        let start = if line == 0 { 0 } else { self.newlines[line - 1] };
+        (line, self.src[start..offset].chars().count())
+    }
+    /// Gets the [file position] of the given span.
+    /// Same as [`Span::file_position`], but faster.
+    ///
+    /// [file position]: FilePosition
+    #[must_use]
+    pub fn file_position(&self, span: Span) -> FilePosition {
+        let (start_line, start_col) = self.position(span.offset);
+        let (end_line, end_col) = self.position(span.offset + span.len);
+        FilePosition { start_line, start_col, end_line, end_col }
+    }
+}
+
 impl fmt::Display for Span {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[{}:{}]", self.offset, self.offset + self.len)
@@ -2,16 +2,23 @@ use core::fmt;
 
 use crate::lexer::span::Span;
 
-#[derive(Clone,Copy,Debug,PartialEq)]
+/// The kind of a [Token]
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+#[non_exhaustive]
 pub enum TokenKind {
     /* Single-character tokens. */
     LSquareBracket, RSquareBracket, LeftBrace, RightBrace,
     Comma, Dot, Minus, Plus,
-    Colon, String, Number,
+    Colon,
+    /// A string, including its quotes
+    String,
+    /// A number, including its sign
+    Number,
     False, True, Null
 }
 
-#[derive(Debug)]
+/// A token of a document. See the [module documentation](super)
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
 pub struct Token {
     kind: TokenKind,
     span: Span,
@@ -27,6 +34,8 @@ impl Token {
     pub fn new(kind: TokenKind, span: Span) -> Self {
         Self{ kind, span }
     }
+    /// Returns the kind of this token
     pub fn get_type(&self) -> TokenKind { self.kind }
+    /// Returns the location of this token in the source
     pub fn span(&self) -> Span { self.span }
 }
@@ -12,7 +12,7 @@
 //!     }
 //! }"#).unwrap();
 //!
-//! let Json::Object(map) = j else { panic!() };
+//! let Json::Object(map) = &j else { panic!() };
 //! assert!(
 //!     matches!(
 //!         map.get("true"),
@@ -31,42 +31,335 @@
 extern crate alloc;
 
 mod prelude {
-    pub use alloc::string::ToString;
+    pub use alloc::string::{String,ToString};
     pub use core::fmt::{self,Display,Write};
     pub use alloc::vec::Vec;
     pub use alloc::borrow::Cow;
     pub use alloc::boxed::Box;
 
-    #[cfg(feature = "std")]
-    pub type Map<K,V> = std::collections::HashMap<K,V>;
+    /* The map of the objects is chosen by features, in this order:
+     *  - preserve_order: IndexMap, which keeps the insertion order
+     *  - btree_map: BTreeMap, which iterates sorted by key
+     *  - hashbrown: hashbrown's HashMap, also available without std
+     *  - std's HashMap, or a BTreeMap without std */
 
-    #[cfg(not(feature = "std"))]
+    #[cfg(feature = "preserve_order")]
+    pub type Map<K,V> = indexmap::IndexMap<K,V,Hasher>;
+
+    #[cfg(all(feature = "btree_map", not(feature = "preserve_order")))]
     pub type Map<K,V> = alloc::collections::BTreeMap<K,V>;
+
+    #[cfg(all(feature = "hashbrown", not(any(feature = "preserve_order", feature = "btree_map"))))]
+    pub type Map<K,V> = hashbrown::HashMap<K,V,Hasher>;
+
+    #[cfg(all(feature = "std", not(any(feature = "preserve_order", feature = "btree_map", feature = "hashbrown"))))]
+    pub type Map<K,V> = std::collections::HashMap<K,V,Hasher>;
+
+    /// Key of the members of objects. With the `arc_keys` feature, keys
+    /// are reference counted, so cloning a [Json](crate::Json) doesn't
+    /// allocate a copy of each key.
+    #[cfg(not(feature = "arc_keys"))]
+    pub type Key = Box<str>;
+
+    #[cfg(feature = "arc_keys")]
+    pub type Key = alloc::sync::Arc<str>;
+
+    /// Converts a boxed string into a [Key], without copying it if they're the same type
+    #[allow(clippy::useless_conversion)]
+    pub fn to_key(key: Box<str>) -> Key { key.into() }
+
+    /* The hash maps use FxHash with the fxhash feature. It's much faster
+     * for short keys, but unlike SipHash, it isn't resistant to HashDoS.
+     * Maps with a custom hasher must be built with Map::default() */
+
+    #[cfg(feature = "fxhash")]
+    pub type Hasher = rustc_hash::FxBuildHasher;
+
+    #[cfg(all(feature = "std", not(feature = "fxhash")))]
+    pub type Hasher = std::hash::RandomState;
+
+    #[cfg(all(feature = "hashbrown", not(any(feature = "std", feature = "fxhash"))))]
+    pub type Hasher = hashbrown::DefaultHashBuilder;
+
+    #[cfg(not(any(feature = "std", feature = "btree_map", feature = "hashbrown")))]
+    pub type Map<K,V> = alloc::collections::BTreeMap<K,V>;
+
+    /// Removes a member from the map, keeping the
+    /// order of the others if it's preserved
+    pub fn remove_key<V>(map: &mut Map<Key,V>, key: &str) -> Option<V> {
+        #[cfg(feature = "preserve_order")]
+        return map.shift_remove(key);
+        #[cfg(not(feature = "preserve_order"))]
+        return map.remove(key);
+    }
+
+    /// Widens a stored [`Number`](crate::Number) into a f64
+    #[allow(clippy::useless_conversion)]
+    pub fn to_f64(n: crate::Number) -> f64 { n.into() }
+
+    /// Converts a big integer into a f64, rounding it
+    #[cfg(feature = "bigint")]
+    pub fn big_to_f64(n: &num_bigint::BigInt) -> f64 {
+        num_traits::ToPrimitive::to_f64(n).unwrap_or(f64::NAN)
+    }
+
+    /// Converts a decimal into a f64, rounding it
+    #[cfg(feature = "decimal")]
+    pub fn dec_to_f64(n: &rust_decimal::Decimal) -> f64 {
+        num_traits::ToPrimitive::to_f64(n).unwrap_or(f64::NAN)
+    }
+
+    /// Converts a f64 into a [`Number`](crate::Number). This loses
+    /// precision if the `compact_numbers` feature is enabled
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn from_f64(n: f64) -> crate::Number { n as crate::Number }
+
+    /* f64's rounding methods are only available with std. Without it,
+     * they're computed from trunc, which casts to an integer.
+     * That's exact, since every f64 at or above 2^52 is already an integer */
+
+    /// Integer part of `n`
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn trunc(n: f64) -> f64 {
+        #[cfg(feature = "std")]
+        return n.trunc();
+        #[cfg(not(feature = "std"))]
+        return if n.abs() < 4_503_599_627_370_496.0 { n as i64 as f64 } else { n };
+    }
+
+    /// Fractional part of `n`. It's NaN for infinities
+    pub fn fract(n: f64) -> f64 { n - trunc(n) }
+
+    /// Largest integer less than or equal to `n`
+    pub fn floor(n: f64) -> f64 {
+        #[cfg(feature = "std")]
+        return n.floor();
+        #[cfg(not(feature = "std"))]
+        return match trunc(n) { t if t > n => t - 1.0, t => t };
+    }
+
+    /// Smallest integer greater than or equal to `n`
+    pub fn ceil(n: f64) -> f64 {
+        #[cfg(feature = "std")]
+        return n.ceil();
+        #[cfg(not(feature = "std"))]
+        return match trunc(n) { t if t < n => t + 1.0, t => t };
+    }
+
+    /// Nearest integer to `n`, rounding half-way cases away from zero
+    pub fn round(n: f64) -> f64 {
+        #[cfg(feature = "std")]
+        return n.round();
+        #[cfg(not(feature = "std"))]
+        return match trunc(n) { t if (n - t).abs() >= 0.5 => t + n.signum(), t => t };
+    }
 }
 
 use prelude::*;
+use core::mem;
 
-mod lexer;
+pub mod lexer;
 mod parser;
 
 #[cfg(feature = "bindings")]
 pub mod export;
 
+#[cfg(feature = "napi")]
+pub mod napi;
+
+#[cfg(feature = "xml")]
+pub mod xml;
+
+#[cfg(feature = "html")]
+pub mod html;
+
+#[cfg(feature = "ejson")]
+pub mod ejson;
+
+#[cfg(feature = "hjson")]
+pub mod hjson;
+
+#[cfg(feature = "jq")]
+pub mod jq;
+
+#[cfg(feature = "std")]
+pub mod config;
+
+#[cfg(feature = "std")]
+pub mod allocator;
+
+#[cfg(feature = "std")]
+pub mod jsonl;
+
+#[cfg(feature = "std")]
+pub mod seq;
+
+#[cfg(feature = "std")]
+pub mod stream;
+
+pub mod cst;
+pub mod editor;
+pub mod events;
+pub mod flatten;
+pub mod iter;
+pub mod lint;
+pub mod merge;
+pub use merge::{merge3, MergeStrategy};
+pub mod merkle;
+pub mod patch;
+pub mod pattern;
+pub mod pointer;
+pub use pointer::JsonPointer;
+pub mod random;
+pub use random::generate;
+pub mod reference;
+pub mod rpc;
+pub mod schema;
+pub mod shared;
+pub use shared::SharedJson;
+pub mod spanned;
+pub mod typed;
+
+#[cfg(feature = "serde_json")]
+mod interop;
+
+#[cfg(feature = "serde")]
+pub mod transcode;
+
+#[cfg(feature = "unicode-normalization")]
+mod normalize;
+
+mod access;
+mod approx;
+mod arith;
+#[cfg(feature = "std")]
+mod batch;
+mod cancel;
+mod case;
+mod color;
+mod contains;
+mod convert;
+#[cfg(feature = "chrono")]
+mod datetime;
 mod error;
+mod escape;
+mod extract;
+mod gjson;
+mod hash;
+mod json_parser;
+mod locate;
+mod query;
+#[cfg(feature = "std")]
+mod reader;
+mod redact;
+mod serialize;
+mod set;
+mod size;
+mod sort;
+mod table;
+mod template;
+#[cfg(feature = "uuid")]
+mod uuid;
+mod walk;
+
+#[cfg(feature = "std")]
+pub use batch::{deserialize_batch, deserialize_batch_with};
+pub use access::{AccessError, At, Step};
+pub use cancel::CancelToken;
+pub use case::Case;
+pub use color::ColorScheme;
+pub use contains::ArrayPolicy;
+pub use convert::{FromJson, ToJson, TypeError};
+pub use error::Error;
+pub use extract::extract;
+pub use json_parser::JsonParser;
+pub use lexer::span::{FilePosition, LineIndex, Span};
+pub use locate::locate;
+pub use redact::Redaction;
+pub use serialize::{NonFinite, SerializeConfig, Syntax, Truncated};
+pub use walk::Leaves;
 
-type Result<T> = core::result::Result<T,error::Error>;
+type Result<T> = core::result::Result<T,Error>;
+
+/// Storage of [`Json::Number`] values
+#[cfg(not(feature = "compact_numbers"))]
+pub type Number = f64;
+
+/// Storage of [`Json::Number`] values.
+///
+/// With the `compact_numbers` feature, numbers are stored with
+/// single precision, which makes [Json] values smaller.
+#[cfg(feature = "compact_numbers")]
+pub type Number = f32;
 
 /// Represents a JSON object
-#[derive(Debug,PartialEq)]
+#[derive(Debug,Clone,PartialEq,Default)]
 pub enum Json {
     Array(Box<[Json]>),
-    Object(Map<Box<str>,Json>),
+    Object(Map<Key,Json>),
     String(Box<str>),
-    Number(f64),
-    True, False, Null,
+    Number(Number),
+    /// Integer too big for a 64-bit integer
+    #[cfg(feature = "bigint")]
+    BigInt(num_bigint::BigInt),
+    /// Exact decimal number. See [`JsonConfig::decimals`]
+    #[cfg(feature = "decimal")]
+    Decimal(rust_decimal::Decimal),
+    True, False,
+    #[default]
+    Null,
+}
+
+/// Values are dropped without recursing into their nested arrays and
+/// objects, so dropping a deeply nested value can't overflow the stack.
+impl Drop for Json {
+    fn drop(&mut self) {
+        let nested = match self {
+            Json::Array(arr) => arr.iter().any(|e| e.is_array() || e.is_object()),
+            Json::Object(obj) => obj.values().any(|v| v.is_array() || v.is_object()),
+            _ => false,
+        };
+        if !nested {
+            return
+        }
+        /* The values are emptied before being dropped, so
+         * dropping them doesn't recurse any further */
+        let mut stack = Vec::new();
+        self.drain_into(&mut stack);
+        while let Some(mut json) = stack.pop() {
+            json.drain_into(&mut stack);
+        }
+    }
+}
+
+/// The type of a [Json] value
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum JsonType {
+    Array, Object, String, Number, Bool, Null,
+}
+
+impl JsonType {
+    /// Returns the name of this type, as used in error messages
+    pub fn name(self) -> &'static str {
+        match self {
+            JsonType::Array => "array",
+            JsonType::Object => "object",
+            JsonType::String => "string",
+            JsonType::Number => "number",
+            JsonType::Bool => "boolean",
+            JsonType::Null => "null",
+        }
+    }
+}
+
+impl Display for JsonType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
 /// Configures the JSON parser
+#[derive(Clone,Copy)]
 #[repr(C)]
 pub struct JsonConfig {
     /// Max depth for nested objects
@@ -76,12 +369,41 @@ pub struct JsonConfig {
     /// are not allowed, but this flag makes
     /// the parser skip them.
     pub recover_from_errors: bool,
+    /// Max number of bytes to allocate while parsing.
+    /// This is an estimate, which counts the strings, the
+    /// elements of arrays and objects, and the temporary
+    /// buffer of tokens used by the parser.
+    pub max_bytes: usize,
+    /// What to do with `\\u` escapes that encode lone UTF-16 surrogates
+    pub lone_surrogates: LoneSurrogates,
+    /// Parse numbers as exact [decimals](Json::Decimal), instead of f64.
+    /// Numbers that don't fit in a decimal are parsed as usual.
+    #[cfg(feature = "decimal")]
+    pub decimals: bool,
+}
+
+/// Policy for lone UTF-16 surrogates in strings, like `"\\uD800"`.
+/// They can't be converted to valid UTF-8.
+///
+/// cbindgen:prefix-with-name
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+#[repr(C)]
+pub enum LoneSurrogates {
+    /// Replace them with U+FFFD, the replacement character
+    #[default]
+    Replace,
+    /// Fail to parse the document
+    Error,
 }
 
 /// Default config used by [`Json::deserialize`]
 const DEFAULT_CONFIG: JsonConfig = JsonConfig {
     max_depth: u32::MAX,
     recover_from_errors: false,
+    max_bytes: usize::MAX,
+    lone_surrogates: LoneSurrogates::Replace,
+    #[cfg(feature = "decimal")]
+    decimals: false,
 };
 
 impl Default for JsonConfig {
@@ -92,7 +414,7 @@ macro_rules! deserialize {
     ($text:ident, $conf:ident) => {
         {
             let txt = $text.as_ref();
-            let mut tokens = lexer::tokenize(txt)?;
+            let mut tokens = lexer::tokenize_with(txt, $conf.max_bytes, None)?;
             parser::parse(txt, &mut tokens, $conf)
         }
     };
@@ -105,6 +427,10 @@ impl Json {
     /// [`max_depth`](JsonConfig::max_depth) = [`u32::MAX`]
     ///
     /// [`recover_from_errors`](JsonConfig::recover_from_errors) = false
+    ///
+    /// [`max_bytes`](JsonConfig::max_bytes) = [`usize::MAX`]
+    ///
+    /// [`lone_surrogates`](JsonConfig::lone_surrogates) = [`LoneSurrogates::Replace`]
     pub fn deserialize(text: impl AsRef<str>) -> Result<Json> {
         deserialize!(text, DEFAULT_CONFIG)
     }
@@ -113,39 +439,46 @@ impl Json {
     pub fn deserialize_with_config(text: impl AsRef<str>, conf: JsonConfig) -> Result<Json> {
         deserialize!(text, conf)
     }
-    /// Serializes the JSON object into a `fmt::Write`
-    pub fn serialize(&self, out: &mut dyn Write) -> core::fmt::Result {
-        match self {
-            Json::Array(elements) => {
-                out.write_char('[')?;
-                for i in 0..elements.len() {
-                    elements[i].serialize(out)?;
-                    if i < elements.len() -1 {
-                        out.write_char(',')?;
-                    }
-                }
-                out.write_char(']')?;
-            },
-            Json::Object(obj) => {
-                out.write_char('{')?;
-                let mut first = true;
-                for (k,v) in obj {
-                    if !first {
-                        out.write_char(',')?;
-                    }
-                    first = false;
-                    write!(out, "\"{k}\":")?;
-                    v.serialize(out)?;
-                }
-                out.write_char('}')?;
-            },
-            Json::String(s) => { write!(out, "\"{s}\"")?; },
-            Json::Number(n) => { write!(out, "{n}")?; },
-            Json::True => { out.write_str("true")? },
-            Json::False => { out.write_str("false")? },
-            Json::Null => { out.write_str("null")? },
-        }
-        Ok(())
+    /// Deserializes the given string, using the given [`JsonConfig`].
+    /// Calls `hook` periodically, with the amount of work done.
+    fn deserialize_with_hook(text: &str, conf: JsonConfig, hook: lexer::Hook<'_>) -> Result<Json> {
+        let tokens = lexer::tokenize_with(text, conf.max_bytes, Some(&mut *hook))?;
+        parser::parse_with_hook::<parser::JsonBuilder>(text, &tokens, conf, Some(hook))
+    }
+    /// Same as [`deserialize_with_config`](Self::deserialize_with_config),
+    /// but reports the progress of the deserialization to `progress`.
+    ///
+    /// The callback receives the number of bytes processed so far and the
+    /// total. The input is processed in two passes (it's first tokenized,
+    /// and then parsed), each of which accounts for half of the total.
+    /// The callback is only called once every few KiB, and a last time
+    /// when the deserialization succeeds.
+    ///
+    /// # Example
+    /// ```
+    /// use json::{Json, JsonConfig};
+    ///
+    /// let text = format!("[{}]", vec!["{ \"a\" : [1, 2, 3] }"; 100_000].join(","));
+    /// let mut last = 0;
+    /// let json = Json::deserialize_with_progress(&text, JsonConfig::default(), |done, total| {
+    ///     assert!(done >= last && done <= total);
+    ///     last = done;
+    /// }).unwrap();
+    /// assert_eq!(last, text.len());
+    /// ```
+    pub fn deserialize_with_progress(
+        text: impl AsRef<str>,
+        conf: JsonConfig,
+        mut progress: impl FnMut(usize, usize)
+    ) -> Result<Json> {
+        let text = text.as_ref();
+        let total = text.len();
+        let json = Self::deserialize_with_hook(text, conf, &mut |work| {
+            progress(work / 2, total);
+            Ok(())
+        })?;
+        progress(total, total);
+        Ok(json)
     }
     /// Attempts to get a value of the given json object.
     /// If the json enum is not an Object variant, or if
@@ -184,9 +517,31 @@ impl Json {
         }
     }
     /// Attempts to get the inner f64 of the json object, if
-    /// it is a Number variant
+    /// it is a Number variant.
+    ///
+    /// [Decimal](Json::Decimal) numbers are rounded to the nearest f64.
+    /// [Big integers](Json::BigInt) return None, see [bigint](Self::bigint).
     pub fn number(&self) -> Option<f64> {
-        if let Json::Number(n) = self {
+        match self {
+            Json::Number(n) => Some(to_f64(*n)),
+            #[cfg(feature = "decimal")]
+            Json::Decimal(n) => Some(dec_to_f64(n)),
+            _ => None,
+        }
+    }
+    /// Attempts to get the inner big integer of the json object,
+    /// if it is a `BigInt` variant
+    #[cfg(feature = "bigint")]
+    pub fn bigint(&self) -> Option<&num_bigint::BigInt> {
+        if let Json::BigInt(n) = self {
+            Some(n)
+        } else { None }
+    }
+    /// Attempts to get the inner decimal of the json object,
+    /// if it is a `Decimal` variant
+    #[cfg(feature = "decimal")]
+    pub fn decimal(&self) -> Option<rust_decimal::Decimal> {
+        if let Json::Decimal(n) = self {
             Some(*n)
         } else { None }
     }
@@ -199,7 +554,7 @@ impl Json {
     }
     /// Attempts to get the inner Object of the json object, if
     /// it is an Object variant
-    pub fn object(&self) -> Option<&Map<Box<str>,Json>> {
+    pub fn object(&self) -> Option<&Map<Key,Json>> {
         if let Json::Object(o) = self {
             Some(o)
         } else { None }
@@ -220,15 +575,157 @@ impl Json {
             Some(false)
         } else { None }
     }
+    /// Takes the inner Object of the json, if it is an
+    /// Object variant. Otherwise, gives the json back.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let obj = json!({ "a" : 1 }).into_object().unwrap();
+    /// assert_eq!(obj.get("a"), Some(&json!(1)));
+    ///
+    /// assert_eq!(json!([1]).into_object(), Err(json!([1])));
+    /// ```
+    pub fn into_object(mut self) -> core::result::Result<Map<Key,Json>,Json> {
+        if let Json::Object(o) = &mut self {
+            Ok(mem::take(o))
+        } else { Err(self) }
+    }
+    /// Takes the inner Array of the json, if it is an
+    /// Array variant. Otherwise, gives the json back.
+    pub fn into_array(mut self) -> core::result::Result<Vec<Json>,Json> {
+        if let Json::Array(arr) = &mut self {
+            Ok(mem::take(arr).into_vec())
+        } else { Err(self) }
+    }
+    /// Takes the inner String of the json, if it is a
+    /// String variant. Otherwise, gives the json back.
+    ///
+    /// Like [string](Self::string), the string is returned as
+    /// it is stored, without decoding its escape sequences.
+    pub fn into_string(mut self) -> core::result::Result<Box<str>,Json> {
+        if let Json::String(s) = &mut self {
+            Ok(mem::take(s))
+        } else { Err(self) }
+    }
+    /// Gets the inner f64 of the json, if it is a
+    /// Number variant. Otherwise, gives the json back.
+    pub fn into_number(self) -> core::result::Result<f64,Json> {
+        if let Json::Number(n) = self {
+            Ok(to_f64(n))
+        } else { Err(self) }
+    }
+    /// Moves the elements of this array, or the member
+    /// values of this object, to the end of `out`
+    fn drain_into(&mut self, out: &mut Vec<Json>) {
+        match self {
+            Json::Array(arr) => out.extend(mem::take(arr).into_vec()),
+            Json::Object(obj) => out.extend(mem::take(obj).into_values()),
+            _ => {},
+        }
+    }
     /// Returns true if the json is a Nil variant
     pub fn is_null(&self) -> bool {
         matches!(self,Json::Null)
     }
+    /// Returns true if the json is an Object variant
+    pub fn is_object(&self) -> bool {
+        matches!(self,Json::Object(_))
+    }
+    /// Returns true if the json is an Array variant
+    pub fn is_array(&self) -> bool {
+        matches!(self,Json::Array(_))
+    }
+    /// Returns true if the json is a String variant
+    pub fn is_string(&self) -> bool {
+        matches!(self,Json::String(_))
+    }
+    /// Returns true if the json is a Number variant
+    pub fn is_number(&self) -> bool {
+        #[cfg(feature = "bigint")]
+        if let Json::BigInt(_) = self {
+            return true
+        }
+        #[cfg(feature = "decimal")]
+        if let Json::Decimal(_) = self {
+            return true
+        }
+        matches!(self,Json::Number(_))
+    }
+    /// Returns true if the json is a True or False variant
+    pub fn is_boolean(&self) -> bool {
+        matches!(self,Json::True | Json::False)
+    }
+    /// Returns true if the json is a True variant
+    pub fn is_true(&self) -> bool {
+        matches!(self,Json::True)
+    }
+    /// Returns true if the json is a False variant
+    pub fn is_false(&self) -> bool {
+        matches!(self,Json::False)
+    }
+    /// Returns the type of this value
+    pub fn json_type(&self) -> JsonType {
+        match self {
+            Json::Array(_) => JsonType::Array,
+            Json::Object(_) => JsonType::Object,
+            Json::String(_) => JsonType::String,
+            Json::Number(_) => JsonType::Number,
+            #[cfg(feature = "bigint")]
+            Json::BigInt(_) => JsonType::Number,
+            #[cfg(feature = "decimal")]
+            Json::Decimal(_) => JsonType::Number,
+            Json::True | Json::False => JsonType::Bool,
+            Json::Null => JsonType::Null,
+        }
+    }
+    /// Returns the name of this value's type, like "object" or "boolean"
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "a" : { "b" : "1" } });
+    /// let b = j.pointer("/a/b").unwrap();
+    /// assert_eq!(format!("expected number, found {} at /a/b", b.type_name()),
+    ///            "expected number, found string at /a/b");
+    /// ```
+    pub fn type_name(&self) -> &'static str {
+        self.json_type().name()
+    }
+    /// Returns the number of elements of an array, members
+    /// of an object, or characters of a string.
+    /// For any other variant, returns None
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Json::Array(arr) => Some(arr.len()),
+            Json::Object(obj) => Some(obj.len()),
+            Json::String(s) => Some(escape::unescape(s).chars().count()),
+            _ => None,
+        }
+    }
+    /// Returns true if the json is an empty array, object or string
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+}
+
+impl core::str::FromStr for Json {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Json::deserialize(s)
+    }
 }
 
 impl Display for Json {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.serialize(f)
+        if f.alternate() {
+            self.serialize_pretty("  ", f)
+        } else {
+            self.serialize(f)
+        }
     }
 }
 
@@ -237,7 +734,7 @@ macro_rules! from_num {
         $(
             impl From<$nty> for Json {
                 fn from(value: $nty) -> Self {
-                    Self::Number(value.into())
+                    Self::Number(from_f64(value.into()))
                 }
             }
         )*
@@ -259,18 +756,44 @@ impl<'a> From<&'a str> for Json {
     }
 }
 
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Self::String(value.into())
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
 impl From<Vec<Json>> for Json {
     fn from(value: Vec<Json>) -> Self {
         Self::Array(value.into())
     }
 }
 
-impl From<Map<Box<str>,Json>> for Json {
-    fn from(value: Map<Box<str>,Json>) -> Self {
+impl From<Map<Key,Json>> for Json {
+    fn from(value: Map<Key,Json>) -> Self {
         Self::Object(value)
     }
 }
 
+#[cfg(feature = "bigint")]
+impl From<num_bigint::BigInt> for Json {
+    fn from(value: num_bigint::BigInt) -> Self {
+        Self::BigInt(value)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl From<rust_decimal::Decimal> for Json {
+    fn from(value: rust_decimal::Decimal) -> Self {
+        Self::Decimal(value)
+    }
+}
+
 impl From<bool> for Json {
     fn from(value: bool) -> Self {
         if value { Json::True } else { Json::False }
@@ -278,10 +801,28 @@ impl From<bool> for Json {
 }
 
 #[doc(hidden)]
-pub use prelude::Map;
+pub use prelude::{Key, Map};
+
+#[doc(hidden)]
+#[cfg(any(feature = "std", feature = "hashbrown", feature = "fxhash"))]
+pub use prelude::Hasher;
+
+#[doc(hidden)]
+pub use convert::get as __get;
 
 /// Builds a [Json] object
 ///
+/// Values can be literals, nested arrays and objects, `null`, or any
+/// expression convertible into [Json], which allows splicing existing
+/// values and [Option]s (`None` becomes `null`).
+///
+/// Arrays can spread any iterable of values convertible into
+/// [Json] with `..iterable`. Trailing commas are allowed.
+///
+/// Keys can be string literals, variables, or any expression
+/// inside brackets (`[expr]`). A key followed by `?` is only
+/// inserted if its value, which must be an [Option], is `Some`.
+///
 /// # Example
 /// ```
 /// use json::json;
@@ -296,29 +837,167 @@ pub use prelude::Map;
 ///         false
 ///     ]
 /// });
+///
+/// let key = "dynamic";
+/// let inner = json!([1, 2]);
+/// let missing: Option<f64> = None;
+/// let j = json!({
+///     key : inner,
+///     [format!("{key}_2")] : (1 + 2),
+///     "nothing" ? : missing,
+///     "null" : missing,
+///     "spread" : [0, ..[1, 2], ..(3..5).map(f64::from)],
+/// });
+/// assert_eq!(j, json!({
+///     "dynamic" : [1, 2],
+///     "dynamic_2" : 3,
+///     "null" : null,
+///     "spread" : [0, 1, 2, 3, 4]
+/// }));
 /// ```
 #[macro_export]
 macro_rules! json {
-    ( $lit:literal ) => {
-        $crate::Json::from( $lit )
+    /* Arrays: push the elements one by one into $vec */
+    (@array $vec:ident) => {};
+    (@array $vec:ident , $($rest:tt)*) => {
+        $crate::json!(@array $vec $($rest)*);
     };
-    ( [ $( $e:tt ),* ] ) => {
-        $crate::Json::from(
-            vec![
-                $(
-                    json!($e)
-                ),*
-            ]
-        )
+    (@array $vec:ident .. $iter:expr $(, $($rest:tt)*)?) => {
+        $vec.extend(::core::iter::IntoIterator::into_iter($iter).map($crate::Json::from));
+        $crate::json!(@array $vec $($($rest)*)?);
     };
-    ( { $( $key:literal : $val:tt ),*  } ) => {
-        {
-            let mut map = $crate::Map::new();
-            $( map.insert($key .into(), json!($val) );  )*
-            $crate::Json::from ( map )
+    (@array $vec:ident null $(, $($rest:tt)*)?) => {
+        $vec.push($crate::Json::Null);
+        $crate::json!(@array $vec $($($rest)*)?);
+    };
+    (@array $vec:ident [$($arr:tt)*] $(, $($rest:tt)*)?) => {
+        $vec.push($crate::json!([$($arr)*]));
+        $crate::json!(@array $vec $($($rest)*)?);
+    };
+    (@array $vec:ident {$($obj:tt)*} $(, $($rest:tt)*)?) => {
+        $vec.push($crate::json!({$($obj)*}));
+        $crate::json!(@array $vec $($($rest)*)?);
+    };
+    (@array $vec:ident $val:expr $(, $($rest:tt)*)?) => {
+        $vec.push($crate::Json::from($val));
+        $crate::json!(@array $vec $($($rest)*)?);
+    };
+
+    /* Objects: insert the members one by one into $map */
+    (@key [$key:expr]) => { ($key).into() };
+    (@key $key:tt) => { $key.into() };
+
+    (@object $map:ident) => {};
+    (@object $map:ident , $($rest:tt)*) => {
+        $crate::json!(@object $map $($rest)*);
+    };
+    (@object $map:ident $key:tt ? : $val:expr $(, $($rest:tt)*)?) => {
+        if let Some(val) = $val {
+            $map.insert($crate::json!(@key $key), $crate::Json::from(val));
         }
+        $crate::json!(@object $map $($($rest)*)?);
+    };
+    (@object $map:ident $key:tt : null $(, $($rest:tt)*)?) => {
+        $map.insert($crate::json!(@key $key), $crate::Json::Null);
+        $crate::json!(@object $map $($($rest)*)?);
+    };
+    (@object $map:ident $key:tt : [$($arr:tt)*] $(, $($rest:tt)*)?) => {
+        $map.insert($crate::json!(@key $key), $crate::json!([$($arr)*]));
+        $crate::json!(@object $map $($($rest)*)?);
     };
+    (@object $map:ident $key:tt : {$($obj:tt)*} $(, $($rest:tt)*)?) => {
+        $map.insert($crate::json!(@key $key), $crate::json!({$($obj)*}));
+        $crate::json!(@object $map $($($rest)*)?);
+    };
+    (@object $map:ident $key:tt : $val:expr $(, $($rest:tt)*)?) => {
+        $map.insert($crate::json!(@key $key), $crate::Json::from($val));
+        $crate::json!(@object $map $($($rest)*)?);
+    };
+
+    /* Entry points */
     ( null ) => {
         $crate::Json::Null
-    }
+    };
+    ( [ $($tt:tt)* ] ) => {
+        {
+            #[allow(unused_mut)]
+            let mut vec = vec![];
+            $crate::json!(@array vec $($tt)*);
+            $crate::Json::from ( vec )
+        }
+    };
+    ( { $($tt:tt)* } ) => {
+        {
+            let mut map = $crate::Map::default();
+            $crate::json!(@object map $($tt)*);
+            $crate::Json::from ( map )
+        }
+    };
+    ( $other:expr ) => {
+        $crate::Json::from( $other )
+    };
+}
+
+/// Navigates a [Json] object and converts the value found
+///
+/// The path is a sequence of keys (`"key"`, or `.key` / `."key"` after
+/// the first step) and array indices (`[expr]`). It can end with
+/// `as Type`, where `Type` implements `TryFrom<&Json>`.
+///
+/// Returns a [`Result`](core::result::Result) whose error
+/// contains the path where navigation or conversion failed.
+///
+/// # Example
+/// ```
+/// use json::{json, json_get};
+///
+/// let doc = json!({
+///     "users" : [
+///         { "id" : 12, "name" : "Jhon" }
+///     ]
+/// });
+///
+/// let id = json_get!(doc, "users"[0]."id" as u64).unwrap();
+/// assert_eq!(id, 12);
+///
+/// let user = json_get!(doc, "users"[0]).unwrap();
+/// assert_eq!(json_get!(user, "name" as &str).unwrap(), "Jhon");
+///
+/// let err = json_get!(doc, "users"[0].name as u64).unwrap_err();
+/// assert_eq!(err.to_string(), "users[0].name: expected u64, found string");
+/// ```
+#[macro_export]
+macro_rules! json_get {
+    (@step $cur:ident $path:ident) => {
+        ::core::result::Result::<_,$crate::Error>::Ok($cur)
+    };
+    (@step $cur:ident $path:ident as $ty:ty) => {
+        $crate::__get::convert::<$ty>($cur, &$path)
+    };
+    (@step $cur:ident $path:ident [$i:expr] $($rest:tt)*) => {
+        match $crate::__get::index($cur, $i, &mut $path) {
+            ::core::result::Result::Ok($cur) => $crate::json_get!(@step $cur $path $($rest)*),
+            ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
+        }
+    };
+    (@step $cur:ident $path:ident . $key:ident $($rest:tt)*) => {
+        $crate::json_get!(@step $cur $path . (stringify!($key)) $($rest)*)
+    };
+    (@step $cur:ident $path:ident . $key:tt $($rest:tt)*) => {
+        $crate::json_get!(@step $cur $path $key $($rest)*)
+    };
+    (@step $cur:ident $path:ident $key:tt $($rest:tt)*) => {
+        match $crate::__get::key($cur, $key, &mut $path) {
+            ::core::result::Result::Ok($cur) => $crate::json_get!(@step $cur $path $($rest)*),
+            ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
+        }
+    };
+    ($json:expr, $($path:tt)*) => {
+        {
+            let cur: &$crate::Json = &$json;
+            #[allow(unused_mut)]
+            let mut path = $crate::__get::String::new();
+            $crate::json_get!(@step cur path $($path)*)
+        }
+    };
 }
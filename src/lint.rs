@@ -0,0 +1,198 @@
+//! Linting of JSON documents
+//!
+//! Reports constructs that are valid JSON, but likely to cause problems.
+//!
+//! # Example
+//! ```
+//! use json::lint::{lint, Lint, LintConfig, Severity};
+//!
+//! let src = r#"{ "id" : 1, "id" : 2, "list" : [1, "2"] }"#;
+//! let diags = lint(src, &LintConfig::default());
+//!
+//! assert_eq!(diags.len(), 2);
+//! assert_eq!(diags[0].lint, Lint::DuplicateKey);
+//! assert_eq!(diags[0].severity, Severity::Error);
+//! assert_eq!(diags[0].span.slice(src), r#""id""#);
+//! assert_eq!(diags[0].position.start_col, 12);
+//! assert_eq!(diags[1].lint, Lint::MixedArray);
+//! ```
+
+use crate::prelude::*;
+use crate::spanned::{self, Spanned, SpannedJson};
+use crate::{FilePosition, JsonType, LineIndex, Span};
+
+/// How serious a [Diagnostic] is
+#[derive(Debug,Clone,Copy,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// The kind of problem reported by a [Diagnostic]
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Lint {
+    /// The document couldn't be parsed
+    Syntax,
+    /// An object has the same key more than once
+    DuplicateKey,
+    /// An integer too big to be represented exactly as an f64
+    LargeInteger,
+    /// Values nested deeper than [`LintConfig::max_depth`]
+    Depth,
+    /// An array with elements of different types
+    MixedArray,
+    /// An object member with an empty key
+    EmptyKey,
+}
+
+/// A problem found in a document
+#[derive(Debug,Clone,PartialEq)]
+pub struct Diagnostic {
+    pub lint: Lint,
+    pub severity: Severity,
+    /// Location of the problem in the source
+    pub span: Span,
+    /// Line and column of [span](Self::span)
+    pub position: FilePosition,
+    pub message: Box<str>,
+}
+
+/// Configures [lint]
+///
+/// Each lint can be disabled by setting its severity to None
+#[derive(Debug,Clone)]
+pub struct LintConfig {
+    pub duplicate_keys: Option<Severity>,
+    pub large_integers: Option<Severity>,
+    pub depth: Option<Severity>,
+    pub mixed_arrays: Option<Severity>,
+    pub empty_keys: Option<Severity>,
+    /// Max nesting depth allowed by the [`Lint::Depth`] lint
+    pub max_depth: usize,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            duplicate_keys: Some(Severity::Error),
+            large_integers: Some(Severity::Warning),
+            depth: Some(Severity::Warning),
+            mixed_arrays: Some(Severity::Warning),
+            empty_keys: Some(Severity::Warning),
+            max_depth: 64,
+        }
+    }
+}
+
+/// 2^53. Integers from here on may not be represented exactly as an f64
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+struct Linter<'a> {
+    src: &'a str,
+    conf: &'a LintConfig,
+    diags: Vec<Diagnostic>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, severity: Option<Severity>, lint: Lint, span: Span, message: String) {
+        if let Some(severity) = severity {
+            self.diags.push(Diagnostic {
+                lint, severity, span,
+                position: FilePosition::default(),
+                message: message.into(),
+            });
+        }
+    }
+    fn value(&mut self, json: &Spanned<SpannedJson>, depth: usize) {
+        if depth == self.conf.max_depth + 1 {
+            self.report(self.conf.depth, Lint::Depth, json.span,
+                        format!("Value nested more than {} levels deep", self.conf.max_depth));
+        }
+        match &json.value {
+            SpannedJson::Array(arr) => {
+                let mut types = arr.iter().map(|e| json_type(&e.value));
+                if let Some(first) = types.next() {
+                    if let Some(other) = types.find(|t| *t != first) {
+                        self.report(self.conf.mixed_arrays, Lint::MixedArray, json.span,
+                                    format!("Array mixes {first} and {other} elements"));
+                    }
+                }
+                for e in arr {
+                    self.value(e, depth + 1);
+                }
+            },
+            SpannedJson::Object(obj) => {
+                for (i, (k, v)) in obj.iter().enumerate() {
+                    if k.value.is_empty() {
+                        self.report(self.conf.empty_keys, Lint::EmptyKey, k.span, "Empty key".into());
+                    }
+                    if obj[..i].iter().any(|(prev, _)| prev.value == k.value) {
+                        self.report(self.conf.duplicate_keys, Lint::DuplicateKey, k.span,
+                                    format!("Duplicate key \"{}\"", k.value));
+                    }
+                    self.value(v, depth + 1);
+                }
+            },
+            SpannedJson::Number(n) => {
+                let text = json.span.slice(self.src);
+                if to_f64(*n).abs() >= MAX_SAFE_INTEGER && !text.contains('.') {
+                    self.report(self.conf.large_integers, Lint::LargeInteger, json.span,
+                                format!("{text} can't be represented exactly as a 64-bit float"));
+                }
+            },
+            #[cfg(feature = "bigint")]
+            SpannedJson::BigInt(_) => {
+                let text = json.span.slice(self.src);
+                self.report(self.conf.large_integers, Lint::LargeInteger, json.span,
+                            format!("{text} can't be represented exactly as a 64-bit float"));
+            },
+            _ => {},
+        }
+    }
+}
+
+fn json_type(json: &SpannedJson) -> JsonType {
+    match json {
+        SpannedJson::Array(_) => JsonType::Array,
+        SpannedJson::Object(_) => JsonType::Object,
+        SpannedJson::String(_) => JsonType::String,
+        SpannedJson::Number(_) => JsonType::Number,
+        #[cfg(feature = "bigint")]
+        SpannedJson::BigInt(_) => JsonType::Number,
+        #[cfg(feature = "decimal")]
+        SpannedJson::Decimal(_) => JsonType::Number,
+        SpannedJson::True | SpannedJson::False => JsonType::Bool,
+        SpannedJson::Null => JsonType::Null,
+    }
+}
+
+/// Lints the given text.
+///
+/// If the text is not valid JSON, returns a single [`Lint::Syntax`] diagnostic.
+/// Otherwise, the diagnostics are sorted by their position in the text.
+pub fn lint(text: impl AsRef<str>, conf: &LintConfig) -> Vec<Diagnostic> {
+    let src = text.as_ref();
+    let lines = LineIndex::new(src);
+    let doc = match spanned::parse(src) {
+        Ok(doc) => doc,
+        Err(err) => {
+            let span = Span { offset: 0, len: src.len() };
+            return vec![Diagnostic {
+                lint: Lint::Syntax,
+                severity: Severity::Error,
+                span,
+                position: lines.file_position(span),
+                message: err.to_string().into(),
+            }]
+        },
+    };
+    let mut linter = Linter { src, conf, diags: Vec::new() };
+    linter.value(&doc, 0);
+    let mut diags = linter.diags;
+    diags.sort_by_key(|d| d.span.offset);
+    for d in &mut diags {
+        d.position = lines.file_position(d.span);
+    }
+    diags
+}
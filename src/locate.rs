@@ -0,0 +1,108 @@
+//! Mapping of JSON pointers to source locations
+
+use crate::escape::unescape;
+use crate::lexer::token::{Token, TokenKind};
+use crate::lexer::span::{FilePosition, LineIndex};
+use crate::lexer::{self, Span};
+use crate::pointer::parse_index;
+use crate::JsonPointer;
+
+/// Returns the index of the token after the value starting at `i`
+pub(crate) fn skip_value(tokens: &[Token], mut i: usize) -> Option<usize> {
+    let mut depth = 0_usize;
+    loop {
+        match tokens.get(i)?.get_type() {
+            TokenKind::LeftBrace | TokenKind::LSquareBracket => depth += 1,
+            TokenKind::RightBrace | TokenKind::RSquareBracket => depth = depth.checked_sub(1)?,
+            _ => {},
+        }
+        i += 1;
+        if depth == 0 {
+            return Some(i)
+        }
+    }
+}
+
+/// Skips the comma after a value, if present
+pub(crate) fn skip_comma(tokens: &[Token], i: usize) -> usize {
+    match tokens.get(i) {
+        Some(t) if t.get_type() == TokenKind::Comma => i + 1,
+        _ => i,
+    }
+}
+
+/// Returns the index of the member `key` in the object starting at `i`
+fn member(src: &str, tokens: &[Token], mut i: usize, key: &str) -> Option<usize> {
+    i += 1;
+    let mut found = None;
+    while tokens.get(i)?.get_type() != TokenKind::RightBrace {
+        let k = tokens.get(i).filter(|t| t.get_type() == TokenKind::String)?;
+        let k = k.span().slice(src);
+        let k = &k[1..k.len() - 1];
+        i += 2;
+        if unescape(k) == key {
+            /* Like the parser, the last duplicated key wins */
+            found = Some(i);
+        }
+        i = skip_comma(tokens, skip_value(tokens, i)?);
+    }
+    found
+}
+
+/// Returns the index of the `n`th element of the array starting at `i`
+fn element(tokens: &[Token], mut i: usize, n: usize) -> Option<usize> {
+    i += 1;
+    for _ in 0..n {
+        if tokens.get(i)?.get_type() == TokenKind::RSquareBracket {
+            return None
+        }
+        i = skip_comma(tokens, skip_value(tokens, i)?);
+    }
+    (tokens.get(i)?.get_type() != TokenKind::RSquareBracket).then_some(i)
+}
+
+/// Finds the position in `src` of the value referenced by the JSON pointer `ptr`,
+/// by scanning the tokens of the document, without building it.
+///
+/// Returns None if the pointer is malformed, or doesn't reference any value.
+/// The document is not validated, so the result is unspecified for invalid JSON.
+///
+/// To locate many pointers in the same document, use [`LineIndex::locate`].
+///
+/// # Example
+/// ```
+/// let src = r#"{
+///     "server" : {
+///         "port" : "80"
+///     }
+/// }"#;
+///
+/// let pos = json::locate(src, "/server/port").unwrap();
+/// assert_eq!(pos.start_line, 2);
+/// assert_eq!(format!("server.port must be a number (line {}, col {})", pos.start_line + 1, pos.start_col),
+///            "server.port must be a number (line 3, col 18)");
+/// ```
+pub fn locate(src: &str, ptr: &str) -> Option<FilePosition> {
+    LineIndex::new(src).locate(ptr)
+}
+
+impl LineIndex<'_> {
+    /// Same as [`locate`], but reuses this index to get the position of the value
+    pub fn locate(&self, ptr: &str) -> Option<FilePosition> {
+        let src = self.src();
+        let ptr = JsonPointer::parse(ptr).ok()?;
+        let tokens = lexer::tokenize(src).ok()?;
+        let mut i = 0;
+        for t in ptr.tokens() {
+            i = match tokens.get(i)?.get_type() {
+                TokenKind::LeftBrace => member(src, &tokens, i, t)?,
+                TokenKind::LSquareBracket => element(&tokens, i, parse_index(t)?)?,
+                _ => return None,
+            };
+        }
+        let start = tokens.get(i)?.span();
+        let end = tokens.get(skip_value(&tokens, i)? - 1)?.span();
+        let span = Span { offset: start.offset, len: end.offset + end.len - start.offset };
+        Some(self.file_position(span))
+    }
+}
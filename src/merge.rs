@@ -0,0 +1,293 @@
+//! Merging documents
+//!
+//! [merge3] merges the changes made to a document by two sides, and
+//! [`Json::merge_from_str`] merges a text into an existing document.
+//!
+//! # Example
+//! ```
+//! use json::json;
+//!
+//! let base = json!({ "title" : "Draft", "tags" : ["a"], "views" : 1 });
+//! let ours = json!({ "title" : "Final", "tags" : ["a"], "views" : 1 });
+//! let theirs = json!({ "title" : "Draft", "tags" : ["a"], "views" : 2, "author" : "Jhon" });
+//!
+//! let merged = json::merge3(&base, &ours, &theirs).unwrap();
+//! assert_eq!(merged, json!({ "title" : "Final", "tags" : ["a"], "views" : 2, "author" : "Jhon" }));
+//!
+//! let theirs = json!({ "title" : "Published", "tags" : ["a"], "views" : 1 });
+//! let conflicts = json::merge3(&base, &ours, &theirs).unwrap_err();
+//! assert_eq!(conflicts[0].path.to_string(), "/title");
+//! ```
+
+use crate::events::{EventParser, MAX_DEPTH};
+use crate::prelude::*;
+use crate::typed::Tok;
+use crate::{Json, JsonPointer, Result};
+
+/// A value changed in different ways by both sides of a [merge3]
+#[derive(Debug,Clone,PartialEq)]
+pub struct Conflict {
+    /// Location of the value
+    pub path: JsonPointer,
+    /// The value in the base document, or None if it didn't exist
+    pub base: Option<Json>,
+    /// Our version of the value, or None if we removed it
+    pub ours: Option<Json>,
+    /// Their version of the value, or None if they removed it
+    pub theirs: Option<Json>,
+}
+
+struct Merger {
+    path: JsonPointer,
+    conflicts: Vec<Conflict>,
+}
+
+impl Merger {
+    fn merge(&mut self, base: Option<&Json>, ours: Option<&Json>, theirs: Option<&Json>) -> Option<Json> {
+        if ours == theirs || theirs == base {
+            return ours.cloned()
+        }
+        if ours == base {
+            return theirs.cloned()
+        }
+        match (base, ours, theirs) {
+            (None | Some(Json::Object(_)), Some(Json::Object(o)), Some(Json::Object(t))) => {
+                let b = match base {
+                    Some(Json::Object(b)) => Some(b),
+                    _ => None,
+                };
+                let keys = o.keys().chain(t.keys().filter(|k| !o.contains_key(*k)));
+                let mut merged = Map::default();
+                for k in keys {
+                    self.path.push(&**k);
+                    if let Some(v) = self.merge(b.and_then(|b| b.get(k)), o.get(k), t.get(k)) {
+                        merged.insert(k.clone(), v);
+                    }
+                    self.path.pop();
+                }
+                Some(Json::Object(merged))
+            },
+            (Some(Json::Array(b)), Some(Json::Array(o)), Some(Json::Array(t)))
+                if b.len() == o.len() && b.len() == t.len() => {
+                let mut merged = Vec::with_capacity(b.len());
+                for (i, ((b, o), t)) in b.iter().zip(o).zip(t).enumerate() {
+                    self.path.push(i.to_string());
+                    merged.extend(self.merge(Some(b), Some(o), Some(t)));
+                    self.path.pop();
+                }
+                Some(Json::Array(merged.into()))
+            },
+            _ => {
+                self.conflicts.push(Conflict {
+                    path: self.path.clone(),
+                    base: base.cloned(),
+                    ours: ours.cloned(),
+                    theirs: theirs.cloned(),
+                });
+                ours.cloned()
+            },
+        }
+    }
+}
+
+/// Merges the changes made by `ours` and `theirs` to `base`.
+///
+/// Changes made by only one side are applied. If both sides made the same
+/// change, it's applied once. Objects are merged member by member, and arrays
+/// element by element, as long as neither side changed their length.
+/// Otherwise, each value changed by both sides in different ways is reported
+/// as a [Conflict].
+///
+/// # Errors
+/// If there are conflicts, returns all of them.
+pub fn merge3(base: &Json, ours: &Json, theirs: &Json) -> core::result::Result<Json, Vec<Conflict>> {
+    let mut merger = Merger { path: JsonPointer::root(), conflicts: Vec::new() };
+    let result = merger.merge(Some(base), Some(ours), Some(theirs));
+    if merger.conflicts.is_empty() {
+        Ok(result.unwrap_or_default())
+    } else {
+        Err(merger.conflicts)
+    }
+}
+
+/// How [`Json::merge_from_str`] combines the parsed values with the existing ones
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum MergeStrategy {
+    /// Objects are merged member by member, recursively.
+    /// Any other value replaces the existing one.
+    #[default]
+    Deep,
+    /// Like [Deep](Self::Deep), but arrays are appended
+    /// to the end of the existing arrays.
+    Append,
+    /// JSON Merge Patch ([RFC 7396](https://www.rfc-editor.org/rfc/rfc7396)).
+    /// Like [Deep](Self::Deep), but members with a null value remove
+    /// the existing member instead.
+    Patch,
+}
+
+/// An array or object whose elements are being parsed
+enum Frame {
+    Array(Vec<Json>),
+    /// An object, and the key of the member being parsed. The members of
+    /// merged objects are merged into the existing ones, while the rest of
+    /// objects, like the ones inside arrays, are built as they're parsed.
+    Object { obj: Map<Key,Json>, key: Key, merged: bool },
+}
+
+/// Merges the values of an [`EventParser`] into a document, as they're parsed
+///
+/// The arrays and objects being parsed are kept in a stack. The existing
+/// values they're merged into are taken out of the document, and put
+/// back once they end.
+struct TextMerger<'a> {
+    events: EventParser<'a>,
+    input: &'a [u8],
+    strategy: MergeStrategy,
+    stack: Vec<Frame>,
+    root: Json,
+}
+
+impl TextMerger<'_> {
+    fn next(&mut self) -> Result<Tok> {
+        let event = match self.events.next_event(&mut self.input)? {
+            Some(event) => Some(event),
+            None => self.events.finish()?,
+        };
+        match event {
+            Some(event) => Ok(Tok::from(event)),
+            None => Err("Unexpected end of input".into()),
+        }
+    }
+    /// Returns true if the next value is merged into an existing one
+    fn merging(&self) -> bool {
+        matches!(self.stack.last(), None | Some(Frame::Object { merged: true, .. }))
+    }
+    /// Returns the existing value the next one is merged into
+    fn target(&mut self) -> Option<&mut Json> {
+        match self.stack.last_mut() {
+            None => Some(&mut self.root),
+            Some(Frame::Object { obj, key, merged: true }) => obj.get_mut(&**key),
+            Some(_) => None,
+        }
+    }
+    /// Puts a parsed value in its place
+    fn value(&mut self, json: Json) {
+        match self.stack.last_mut() {
+            Some(Frame::Array(elems)) => elems.push(json),
+            Some(Frame::Object { obj, key, .. }) => drop(obj.insert(core::mem::take(key), json)),
+            None => self.root = json,
+        }
+    }
+    /// Ends the array or object on top of the stack
+    fn end(&mut self) {
+        let json = match self.stack.pop() {
+            Some(Frame::Array(elems)) => elems.into(),
+            Some(Frame::Object { obj, .. }) => Json::Object(obj),
+            None => unreachable!("the event parser checks the nesting"),
+        };
+        self.value(json);
+    }
+    /// Merges a whole value
+    fn merge(&mut self) -> Result<()> {
+        loop {
+            match self.next()? {
+                Tok::StartObject => {
+                    let merged = self.merging();
+                    let obj = match self.target() {
+                        Some(Json::Object(obj)) => core::mem::take(obj),
+                        _ => Map::default(),
+                    };
+                    self.stack.push(Frame::Object { obj, key: Key::default(), merged });
+                },
+                Tok::StartArray => {
+                    let append = self.strategy == MergeStrategy::Append;
+                    let elems = match self.target() {
+                        Some(Json::Array(arr)) if append => core::mem::take(arr).into_vec(),
+                        _ => Vec::new(),
+                    };
+                    self.stack.push(Frame::Array(elems));
+                },
+                Tok::Key => {
+                    if let Some(Frame::Object { key, .. }) = self.stack.last_mut() {
+                        *key = self.events.last_token().into();
+                    }
+                },
+                Tok::EndArray | Tok::EndObject => self.end(),
+                Tok::Null if self.strategy == MergeStrategy::Patch && !self.stack.is_empty() && self.merging() => {
+                    if let Some(Frame::Object { obj, key, .. }) = self.stack.last_mut() {
+                        drop(remove_key(obj, key));
+                    }
+                },
+                Tok::String => {
+                    let s = self.events.last_token().into();
+                    self.value(Json::String(s));
+                },
+                Tok::Number(n) => self.value(Json::Number(from_f64(n))),
+                Tok::True => self.value(Json::True),
+                Tok::False => self.value(Json::False),
+                Tok::Null => self.value(Json::Null),
+            }
+            if self.stack.is_empty() {
+                return Ok(())
+            }
+        }
+    }
+    /// Puts the arrays and objects still in the stack back
+    /// in their place, and returns the merged document
+    fn finish(mut self) -> Json {
+        while !self.stack.is_empty() {
+            self.end();
+        }
+        self.root
+    }
+}
+
+impl Json {
+    /// Parses `text` and merges it into this document, following `strategy`.
+    ///
+    /// The values are merged as they're parsed, so the parsed document is
+    /// never built whole. This is useful to load configuration in layers,
+    /// where each layer overrides some values of the previous ones.
+    /// Like with [`from_reader`](Self::from_reader), numbers are always
+    /// parsed as f64, and arrays and objects can be nested up to 4096
+    /// levels deep.
+    ///
+    /// # Errors
+    /// If `text` is not valid JSON, or it's nested too deep. In that case,
+    /// the values parsed before the error are left merged into the document.
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, Json, MergeStrategy};
+    ///
+    /// let mut conf = json!({ "server" : { "host" : "localhost", "port" : 80 }, "plugins" : ["a"] });
+    /// conf.merge_from_str(r#"{ "server" : { "port" : 8080 }, "plugins" : ["b"] }"#, MergeStrategy::Deep).unwrap();
+    /// assert_eq!(conf, json!({ "server" : { "host" : "localhost", "port" : 8080 }, "plugins" : ["b"] }));
+    ///
+    /// conf.merge_from_str(r#"{ "plugins" : ["c"] }"#, MergeStrategy::Append).unwrap();
+    /// assert_eq!(conf.get("plugins"), Some(&json!(["b", "c"])));
+    ///
+    /// conf.merge_from_str(r#"{ "server" : { "host" : null } }"#, MergeStrategy::Patch).unwrap();
+    /// assert_eq!(conf.get("server"), Some(&json!({ "port" : 8080 })));
+    /// ```
+    pub fn merge_from_str(&mut self, text: impl AsRef<str>, strategy: MergeStrategy) -> Result<()> {
+        let mut nesting = [0; MAX_DEPTH];
+        let mut merger = TextMerger {
+            events: EventParser::growing(&mut nesting),
+            input: text.as_ref().as_bytes(),
+            strategy,
+            stack: Vec::new(),
+            root: core::mem::take(self),
+        };
+        let result = merger.merge().and_then(|()| {
+            if let Some(event) = merger.events.next_event(&mut merger.input)? {
+                return Err(format!("Unexpected {event:?} after the value").into())
+            }
+            merger.events.finish()?;
+            Ok(())
+        });
+        *self = merger.finish();
+        result
+    }
+}
@@ -0,0 +1,123 @@
+//! Per-subtree hashing for change detection
+//!
+//! A [`MerkleTree`] stores the hash of every value in a document, where the hash
+//! of an array or object is computed from the hashes of its children. Comparing
+//! the trees of two versions of a document only descends into the subtrees
+//! whose hashes differ.
+//!
+//! # Example
+//! ```
+//! use json::json;
+//! use json::merkle::MerkleTree;
+//!
+//! let old = json!({ "users" : [ { "name" : "a" }, { "name" : "b" } ], "version" : 1 });
+//! let new = json!({ "users" : [ { "name" : "a" }, { "name" : "c" } ], "version" : 1 });
+//!
+//! let old = MerkleTree::new(&old);
+//! let new = MerkleTree::new(&new);
+//!
+//! let changed: Vec<String> = old.changes(&new).iter().map(|p| p.to_string()).collect();
+//! assert_eq!(changed, vec!["/users/1/name"]);
+//! assert_eq!(old.pointer("/version").unwrap().hash(), new.pointer("/version").unwrap().hash());
+//! ```
+
+use core::hash::Hasher;
+
+use crate::hash::Fnv;
+use crate::pointer::parse_index;
+use crate::prelude::*;
+use crate::{Json, JsonPointer};
+
+#[derive(Debug,Clone,PartialEq)]
+enum Children {
+    Leaf,
+    Array(Vec<MerkleTree>),
+    Object(Map<Key,MerkleTree>),
+}
+
+/// The hashes of all the values in a document
+#[derive(Debug,Clone,PartialEq)]
+pub struct MerkleTree {
+    hash: u64,
+    children: Children,
+}
+
+impl MerkleTree {
+    /// Computes the hashes of all the values in the given document
+    pub fn new(json: &Json) -> Self {
+        match json {
+            Json::Array(arr) => {
+                let children: Vec<_> = arr.iter().map(MerkleTree::new).collect();
+                let mut hasher = Fnv::default();
+                hasher.write_u8(b'[');
+                for c in &children {
+                    hasher.write(&c.hash.to_le_bytes());
+                }
+                Self { hash: hasher.finish(), children: Children::Array(children) }
+            },
+            Json::Object(obj) => {
+                let children: Map<_,_> = obj.iter().map(|(k, v)| (k.clone(), MerkleTree::new(v))).collect();
+                /* Combine the members in key order, so the hash doesn't depend on the map's order */
+                let mut members: Vec<_> = children.iter().collect();
+                members.sort_unstable_by_key(|(k, _)| *k);
+                let mut hasher = Fnv::default();
+                hasher.write_u8(b'{');
+                for (k, c) in members {
+                    hasher.write(&(k.len() as u64).to_le_bytes());
+                    hasher.write(k.as_bytes());
+                    hasher.write(&c.hash.to_le_bytes());
+                }
+                Self { hash: hasher.finish(), children: Children::Object(children) }
+            },
+            _ => Self { hash: json.content_hash(), children: Children::Leaf },
+        }
+    }
+    /// Returns the hash of the value
+    pub fn hash(&self) -> u64 { self.hash }
+    /// Returns the subtree referenced by the given JSON pointer
+    pub fn pointer(&self, ptr: impl AsRef<str>) -> Option<&MerkleTree> {
+        JsonPointer::parse(ptr.as_ref()).ok()?.tokens().try_fold(self, |t, token| match &t.children {
+            Children::Array(arr) => arr.get(parse_index(token)?),
+            Children::Object(obj) => obj.get(token),
+            Children::Leaf => None,
+        })
+    }
+    /// Returns the pointers to the values that differ between `self` and `other`.
+    ///
+    /// Only the outermost changes are reported: if an array or object has a
+    /// different type or length in each version, it's reported as a whole.
+    /// Members added to or removed from an object are reported individually.
+    pub fn changes(&self, other: &MerkleTree) -> Vec<JsonPointer> {
+        let mut changes = Vec::new();
+        self.diff(other, &mut JsonPointer::root(), &mut changes);
+        changes
+    }
+    fn diff(&self, other: &MerkleTree, path: &mut JsonPointer, changes: &mut Vec<JsonPointer>) {
+        if self.hash == other.hash {
+            return
+        }
+        match (&self.children, &other.children) {
+            (Children::Array(a), Children::Array(b)) if a.len() == b.len() => {
+                for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                    path.push(i.to_string());
+                    a.diff(b, path, changes);
+                    path.pop();
+                }
+            },
+            (Children::Object(a), Children::Object(b)) => {
+                for (k, va) in a {
+                    path.push(&**k);
+                    match b.get(k) {
+                        Some(vb) => va.diff(vb, path, changes),
+                        None => changes.push(path.clone()),
+                    }
+                    path.pop();
+                }
+                for k in b.keys().filter(|k| !a.contains_key(*k)) {
+                    changes.push(path.join(&**k));
+                }
+            },
+            _ => changes.push(path.clone()),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Node.js bindings for the json crate
+//!
+//! Built with the `napi` feature. The cdylib can be loaded as a native
+//! addon, renaming it to `json.node`.
+//!
+//! ```js
+//! const json = require("./json.node");
+//!
+//! const doc = json.parse('{ "a" : [1, 2] }');
+//! json.pointer(doc, "/a/1");               // 2
+//! json.patch(doc, [{ op: "remove", path: "/a/0" }]);  // { a: [2] }
+//! json.stringify(doc, "  ");
+//! ```
+
+use napi::{Env, JsObject, JsUnknown, ValueType};
+use napi_derive::napi;
+
+use crate::escape::{escape, unescape};
+use crate::patch::Patch;
+use crate::prelude::*;
+use crate::{Json, SerializeConfig};
+
+impl From<crate::Error> for napi::Error {
+    fn from(err: crate::Error) -> Self {
+        napi::Error::from_reason(err.to_string())
+    }
+}
+
+/// Converts a [Json] into a JS value
+fn to_js(env: Env, json: &Json) -> napi::Result<JsUnknown> {
+    Ok(match json {
+        Json::Array(elems) => {
+            let mut arr = env.create_array_with_length(elems.len())?;
+            for (i, e) in (0..).zip(elems.iter()) {
+                arr.set_element(i, to_js(env, e)?)?;
+            }
+            arr.into_unknown()
+        },
+        Json::Object(obj) => {
+            let mut o = env.create_object()?;
+            for (k, v) in obj {
+                o.set_named_property(&unescape(k), to_js(env, v)?)?;
+            }
+            o.into_unknown()
+        },
+        Json::String(s) => env.create_string(&unescape(s))?.into_unknown(),
+        Json::Number(n) => env.create_double(to_f64(*n))?.into_unknown(),
+        #[cfg(feature = "bigint")]
+        Json::BigInt(n) => env.create_double(big_to_f64(n))?.into_unknown(),
+        #[cfg(feature = "decimal")]
+        Json::Decimal(n) => env.create_double(dec_to_f64(n))?.into_unknown(),
+        Json::True => env.get_boolean(true)?.into_unknown(),
+        Json::False => env.get_boolean(false)?.into_unknown(),
+        Json::Null => env.get_null()?.into_unknown(),
+    })
+}
+
+/// Converts a JS value into a [Json]. `undefined` becomes null,
+/// and functions, symbols and bigints can't be converted.
+fn from_js(value: JsUnknown) -> napi::Result<Json> {
+    Ok(match value.get_type()? {
+        ValueType::Null | ValueType::Undefined => Json::Null,
+        ValueType::Boolean => value.coerce_to_bool()?.get_value()?.into(),
+        ValueType::Number => Json::Number(from_f64(value.coerce_to_number()?.get_double()?)),
+        ValueType::String => {
+            let s = value.coerce_to_string()?.into_utf8()?;
+            Json::String(escape(s.as_str()?).into())
+        },
+        ValueType::Object if value.is_array()? => {
+            let arr: JsObject = unsafe { value.cast() };
+            let len = arr.get_array_length()?;
+            (0..len).map(|i| from_js(arr.get_element(i)?))
+                    .collect::<napi::Result<Vec<_>>>()?
+                    .into()
+        },
+        ValueType::Object => {
+            let obj: JsObject = unsafe { value.cast() };
+            let keys = obj.get_property_names()?;
+            let mut map = Map::default();
+            for i in 0..keys.get_array_length()? {
+                let key = keys.get_element::<JsUnknown>(i)?.coerce_to_string()?.into_utf8()?;
+                let key = key.as_str()?;
+                let val = from_js(obj.get_named_property(key)?)?;
+                map.insert(escape(key).into(), val);
+            }
+            Json::Object(map)
+        },
+        t => return Err(napi::Error::from_reason(format!("Can't convert a {t} to JSON"))),
+    })
+}
+
+/// Parses the given text
+#[napi]
+pub fn parse(env: Env, text: String) -> napi::Result<JsUnknown> {
+    let json = Json::deserialize(text)?;
+    to_js(env, &json)
+}
+
+/// Serializes the given value, indenting it with `indent`, if given
+#[napi]
+#[allow(clippy::needless_pass_by_value)]
+pub fn stringify(value: JsUnknown, indent: Option<String>) -> napi::Result<String> {
+    let json = from_js(value)?;
+    let conf = SerializeConfig { indent: indent.as_deref(), ..Default::default() };
+    let mut out = String::new();
+    json.serialize_with_config(&conf, &mut out)?;
+    Ok(out)
+}
+
+/// Gets the value referenced by the given JSON pointer,
+/// or `undefined` if there isn't any.
+#[napi]
+pub fn pointer(env: Env, value: JsUnknown, ptr: String) -> napi::Result<JsUnknown> {
+    match from_js(value)?.pointer(ptr) {
+        Some(json) => to_js(env, json),
+        None => Ok(env.get_undefined()?.into_unknown()),
+    }
+}
+
+/// Applies a JSON Patch (RFC 6902) to `doc`, returning the patched copy.
+/// Throws if the patch is invalid or can't be applied.
+#[napi]
+pub fn patch(env: Env, doc: JsUnknown, patch: JsUnknown) -> napi::Result<JsUnknown> {
+    let mut doc = from_js(doc)?;
+    let patch = Patch::try_from(from_js(patch)?)?;
+    patch.apply(&mut doc)?;
+    to_js(env, &doc)
+}
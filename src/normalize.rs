@@ -0,0 +1,58 @@
+//! Unicode normalization
+
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+use crate::escape::{escape, unescape};
+use crate::prelude::*;
+use crate::Json;
+
+/// Returns the NFC form of a raw string, if it's not already normalized
+fn nfc(s: &str) -> Option<Box<str>> {
+    let text = unescape(s);
+    if is_nfc_quick(text.chars()) == IsNormalized::Yes {
+        return None
+    }
+    let normalized: String = text.nfc().collect();
+    (normalized != *text).then(|| escape(&normalized).into())
+}
+
+impl Json {
+    /// Converts all the strings in this document, keys
+    /// included, to Unicode Normalization Form C (NFC).
+    ///
+    /// If two keys of an object become equal after being
+    /// normalized, only one of the members is kept.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut composed = json!({ "caf\u{e9}" : "\u{e9}" });
+    /// let mut decomposed = json!({ "cafe\u{301}" : "e\u{301}" });
+    /// assert_ne!(composed, decomposed);
+    ///
+    /// composed.normalize_unicode();
+    /// decomposed.normalize_unicode();
+    /// assert_eq!(composed, decomposed);
+    /// ```
+    pub fn normalize_unicode(&mut self) {
+        match self {
+            Json::String(s) => {
+                if let Some(n) = nfc(s) {
+                    *s = n;
+                }
+            },
+            Json::Array(arr) => arr.iter_mut().for_each(Json::normalize_unicode),
+            Json::Object(obj) => {
+                obj.values_mut().for_each(Json::normalize_unicode);
+                if obj.keys().any(|k| nfc(k).is_some()) {
+                    *obj = core::mem::take(obj)
+                           .into_iter()
+                           .map(|(k, v)| (nfc(&k).unwrap_or(k), v))
+                           .collect();
+                }
+            },
+            _ => {},
+        }
+    }
+}
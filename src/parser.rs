@@ -1,128 +1,288 @@
-use crate::lexer::span::FilePosition;
-use crate::lexer::Span;
+use core::cell::OnceCell;
+use core::marker::PhantomData;
+use core::mem::{size_of, size_of_val};
+
+use crate::lexer::span::{FilePosition, LineIndex};
+use crate::lexer::{Hook, Span, BUDGET_EXCEEDED, HOOK_INTERVAL};
 use crate::prelude::*;
 
 use crate::lexer::token::Token;
 use crate::lexer::token::TokenKind;
-use crate::Json;
-use crate::JsonConfig;
+use crate::{Json, Number};
+use crate::escape::{lone_surrogates, replace_lone_surrogates};
+use crate::{JsonConfig, LoneSurrogates};
 use crate::Result;
 
-struct Parser<'a> {
+/// Builds the values produced by the [Parser]
+pub trait Build {
+    type Value;
+    type Object;
+
+    fn object() -> Self::Object;
+    fn insert(obj: &mut Self::Object, key: Box<str>, key_span: Span, value: Self::Value);
+    fn finish_object(obj: Self::Object, span: Span) -> Self::Value;
+    fn array(elems: Vec<Self::Value>, span: Span) -> Self::Value;
+    fn scalar(json: Json, span: Span) -> Self::Value;
+}
+
+/// Builds plain [Json] values
+pub struct JsonBuilder;
+
+impl Build for JsonBuilder {
+    type Value = Json;
+    type Object = Map<Key,Json>;
+
+    fn object() -> Self::Object { Map::default() }
+    fn insert(obj: &mut Self::Object, key: Box<str>, _key_span: Span, value: Json) {
+        obj.insert(to_key(key), value);
+    }
+    fn finish_object(obj: Self::Object, _span: Span) -> Json { Json::Object(obj) }
+    fn array(elems: Vec<Json>, _span: Span) -> Json { elems.into() }
+    fn scalar(json: Json, _span: Span) -> Json { json }
+}
+
+/// An array or object being parsed
+pub(crate) enum Frame<B: Build> {
+    Array {
+        start: Span,
+        elems: Vec<B::Value>,
+    },
+    Object {
+        start: Span,
+        obj: B::Object,
+        /// Key of the member being parsed
+        key: Option<(Box<str>, Span)>,
+        empty: bool,
+    },
+}
+
+impl<B: Build> Frame<B> {
+    /// Adds a parsed element or member
+    fn add(&mut self, value: B::Value) {
+        match self {
+            Frame::Array { elems, .. } => elems.push(value),
+            Frame::Object { obj, key, empty, .. } => {
+                if let Some((key, key_span)) = key.take() {
+                    B::insert(obj, key, key_span, value);
+                }
+                *empty = false;
+            },
+        }
+    }
+}
+
+/// The start of a value: either a scalar, or a container
+enum Start<B: Build> {
+    Value(B::Value),
+    Frame(Frame<B>),
+}
+
+struct Parser<'a, 'h, B> {
     tokens: &'a [Token],
     src: &'a str,
     curr: usize,
     conf: JsonConfig,
-    depth: u32,
+    hook: Option<Hook<'h>>,
+    next_check: usize,
+    /// Estimated bytes allocated so far, including the tokens
+    used: usize,
+    /// Index of the lines of `src`, built with the first error
+    lines: OnceCell<LineIndex<'a>>,
+    builder: PhantomData<B>,
 }
 
-impl Parser<'_> {
-    fn parse(&mut self) -> Result<Json> {
-        self.value()
+impl<B: Build> Parser<'_, '_, B> {
+    /// Parses a value. Instead of recursing for each nested array and object,
+    /// the containers being parsed are kept on an explicit stack, so deeply
+    /// nested inputs can't overflow the call stack.
+    ///
+    /// `stack` must be empty. When this function succeeds, it's left empty.
+    fn parse(&mut self, stack: &mut Vec<Frame<B>>) -> Result<B::Value> {
+        let mut next = self.value(stack)?;
+        loop {
+            match next {
+                Start::Frame(frame) => stack.push(frame),
+                Start::Value(value) => {
+                    let Some(top) = stack.last_mut() else {
+                        return Ok(value)
+                    };
+                    top.add(value);
+                },
+            }
+            let Some(top) = stack.last_mut() else {
+                unreachable!("A frame was just pushed or added to")
+            };
+            next = if self.resume(top)? {
+                self.value(stack)?
+            } else {
+                let Some(frame) = stack.pop() else {
+                    unreachable!("The stack is not empty")
+                };
+                Start::Value(self.close(frame)?)
+            };
+        }
+    }
+    /// Span from the start of the given token to the end of the previous one
+    fn span_from(&mut self, start: Span) -> Result<Span> {
+        let end = self.previous()?.span();
+        Ok(Span { offset: start.offset, len: end.offset + end.len - start.offset })
+    }
+    /// Accounts for `bytes` more of memory, failing if
+    /// the [`max_bytes`](JsonConfig::max_bytes) budget is exceeded
+    fn alloc(&mut self, bytes: usize) -> Result<()> {
+        self.used = self.used.saturating_add(bytes);
+        if self.used > self.conf.max_bytes {
+            return Err(BUDGET_EXCEEDED.into())
+        }
+        Ok(())
     }
     fn is_finished(&self) -> bool {
         self.curr >= self.tokens.len()
     }
     fn error<T>(&mut self, msg: impl Into<Cow<'static,str>>) -> Result<T> {
-        let FilePosition { start_line, start_col, .. } = self.previous()?.span().file_position(self.src);
+        let span = self.previous()?.span();
+        self.error_at(span, msg)
+    }
+    fn error_at<T>(&self, span: Span, msg: impl Into<Cow<'static,str>>) -> Result<T> {
+        let lines = self.lines.get_or_init(|| LineIndex::new(self.src));
+        let FilePosition { start_line, start_col, .. } = lines.file_position(span);
         let msg = format!("[{start_line}:{start_col}]: {}", msg.into());
         Err(msg.into())
     }
-    fn value(&mut self) -> Result<Json> {
-        if self.depth > self.conf.max_depth {
+    /// Starts parsing a value, at the given level of nesting.
+    /// If it's an array or object, returns its [Frame].
+    fn value(&mut self, stack: &[Frame<B>]) -> Result<Start<B>> {
+        if stack.len() > self.conf.max_depth as usize {
             return self.error("Max depth reached")
         }
-        macro_rules! enter {
-            ($c:expr) => {
-                {
-                    self.depth += 1;
-                    let j = $c;
-                    self.depth -= 1;
-                    j
-                }
-            };
+        if let Some(hook) = &mut self.hook {
+            let offset = self.tokens.get(self.curr).map_or(self.src.len(), |t| t.span().offset);
+            if offset >= self.next_check {
+                hook(self.src.len() + offset)?;
+                self.next_check = offset + HOOK_INTERVAL;
+            }
         }
-        if self.match_type(TokenKind::LSquareBracket) {
-            enter!( self.array() )
+        let value = if self.match_type(TokenKind::LSquareBracket) {
+            let start = self.previous()?.span();
+            return Ok( Start::Frame(Frame::Array { start, elems: Vec::new() }) )
         } else if self.match_type(TokenKind::LeftBrace) {
-            enter!( self.object() )
+            let start = self.previous()?.span();
+            return Ok( Start::Frame(Frame::Object { start, obj: B::object(), key: None, empty: true }) )
         } else if self.match_type(TokenKind::Number) {
-            self.number()
+            self.number()?
         } else if self.match_type(TokenKind::String) {
-            self.string()
+            self.string()?
         } else if self.match_type(TokenKind::True) {
-            Ok( Json::True )
+            B::scalar(Json::True, self.previous()?.span())
         } else if self.match_type(TokenKind::False) {
-            Ok( Json::False )
+            B::scalar(Json::False, self.previous()?.span())
         } else if self.match_type(TokenKind::Null) {
-            Ok( Json::Null )
+            B::scalar(Json::Null, self.previous()?.span())
         } else {
-           self.error("Unknown token")
-        }
+           return self.error("Unknown token")
+        };
+        Ok( Start::Value(value) )
     }
-    fn array(&mut self) -> Result<Json> {
-        let mut elems = Vec::new();
-        while !self.check(TokenKind::RSquareBracket) {
-            if self.is_finished() { break }
-            if !elems.is_empty() {
-                self.consume(TokenKind::Comma, "Expected comma after element")?;
-            }
-            if self.peek()?.get_type() == TokenKind::RSquareBracket {
-                if self.conf.recover_from_errors {
-                    continue
+    /// Continues parsing the given array or object.
+    /// Returns true if an element follows, or false if the container is closed.
+    fn resume(&mut self, frame: &mut Frame<B>) -> Result<bool> {
+        match frame {
+            Frame::Array { elems, .. } => loop {
+                if self.check(TokenKind::RSquareBracket) || self.is_finished() {
+                    return Ok(false)
+                }
+                if !elems.is_empty() {
+                    self.consume(TokenKind::Comma, "Expected comma after element")?;
+                }
+                if self.peek()?.get_type() == TokenKind::RSquareBracket {
+                    if self.conf.recover_from_errors {
+                        continue
+                    }
+                    return self.error("Trailing comma on list");
+                }
+                self.alloc(size_of::<Json>())?;
+                return Ok(true)
+            },
+            Frame::Object { key, empty, .. } => loop {
+                if self.check(TokenKind::RightBrace) || self.is_finished() {
+                    return Ok(false)
+                }
+                if !*empty {
+                    self.consume(TokenKind::Comma, "Expected comma after element")?;
                 }
-                return self.error("Trailing comma on list");
-            }
-            let json = self.value()?;
-            elems.push(json);
-        }
-        self.consume(TokenKind::RSquareBracket, "Unclosed '['")?;
-        Ok( elems.into() )
-    }
-    fn object(&mut self) -> Result<Json> {
-        let mut elems = Map::new();
-        while !self.check(TokenKind::RightBrace) {
-            if self.is_finished() { break }
-            if !elems.is_empty() {
-                self.consume(TokenKind::Comma, "Expected comma after element")?;
-            }
 
-            if ! self.check(TokenKind::String) {
-                let msg = match self.previous().unwrap().get_type() {
-                    TokenKind::Comma => {
-                        if self.conf.recover_from_errors {
-                            continue
-                        }
-                        "Trailing comma in object"
-                    },
-                    _ => "Expected STRING",
-                };
-                return self.error(msg);
-            }
-            let span = self.advance()?.span();
-            let key = self.owned_lexem_strip(span);
+                if ! self.check(TokenKind::String) {
+                    let msg = match self.previous()?.get_type() {
+                        TokenKind::Comma => {
+                            if self.conf.recover_from_errors {
+                                continue
+                            }
+                            "Trailing comma in object"
+                        },
+                        _ => "Expected STRING",
+                    };
+                    return self.error(msg);
+                }
+                let key_span = self.advance()?.span();
+                self.alloc(size_of::<(Box<str>,Json)>() + key_span.len)?;
+                *key = Some((self.owned_lexem_strip(key_span)?, key_span));
 
-            self.consume(TokenKind::Colon, "Expected ':'")?;
-            let json = self.value()?;
-            elems.insert(key,json);
+                self.consume(TokenKind::Colon, "Expected ':'")?;
+                return Ok(true)
+            },
         }
-        self.consume(TokenKind::RightBrace, "Unclosed '{'")?;
-        Ok( Json::Object(elems) )
     }
-    fn owned_lexem_strip(&self, span: Span) -> Box<str> {
+    /// Consumes the closing token of the array or object
+    fn close(&mut self, frame: Frame<B>) -> Result<B::Value> {
+        match frame {
+            Frame::Array { start, elems } => {
+                self.consume(TokenKind::RSquareBracket, "Unclosed '['")?;
+                let span = self.span_from(start)?;
+                Ok( B::array(elems, span) )
+            },
+            Frame::Object { start, obj, .. } => {
+                self.consume(TokenKind::RightBrace, "Unclosed '{'")?;
+                let span = self.span_from(start)?;
+                Ok( B::finish_object(obj, span) )
+            },
+        }
+    }
+    /// Returns the contents of the string token, applying
+    /// the [`LoneSurrogates`](crate::LoneSurrogates) policy
+    fn owned_lexem_strip(&self, span: Span) -> Result<Box<str>> {
         let slice = span.slice(self.src);
         let slice = slice.strip_prefix("\"").unwrap_or(slice);
         let slice = slice.strip_suffix("\"").unwrap_or(slice);
-        Box::from(slice)
+        match self.conf.lone_surrogates {
+            LoneSurrogates::Replace => Ok(replace_lone_surrogates(slice).into()),
+            LoneSurrogates::Error => match lone_surrogates(slice).next() {
+                Some(i) => self.error_at(Span { offset: span.offset + 1 + i, len: 6 }, "Lone surrogate in string"),
+                None => Ok(Box::from(slice)),
+            },
+        }
     }
-    fn number(&mut self) -> Result<Json> {
-        let n: f64 = self.previous()?.span().slice(self.src).parse()?;
-        Ok( Json::Number(n) )
+    fn number(&mut self) -> Result<B::Value> {
+        let span = self.previous()?.span();
+        let text = span.slice(self.src);
+        #[cfg(feature = "decimal")]
+        if self.conf.decimals {
+            if let Ok(n) = rust_decimal::Decimal::from_str_exact(text) {
+                return Ok( B::scalar(Json::Decimal(n), span) )
+            }
+        }
+        #[cfg(feature = "bigint")]
+        if is_big_integer(text) {
+            let n = text.parse().map_err(|e: num_bigint::ParseBigIntError| e.to_string())?;
+            return Ok( B::scalar(Json::BigInt(n), span) )
+        }
+        let n: Number = text.parse()?;
+        Ok( B::scalar(Json::Number(n), span) )
     }
-    fn string(&mut self) -> Result<Json> {
-        let s = self.previous()?.span();
-        let s = self.owned_lexem_strip(s);
-        Ok( Json::String(s) )
+    fn string(&mut self) -> Result<B::Value> {
+        let span = self.previous()?.span();
+        self.alloc(span.len)?;
+        let s = self.owned_lexem_strip(span)?;
+        Ok( B::scalar(Json::String(s), span) )
     }
     fn consume(&mut self, t: TokenKind, msg: &'static str) -> Result<&Token> {
         if self.check(t) { return self.advance(); }
@@ -150,17 +310,51 @@ impl Parser<'_> {
                    .ok_or_else(|| "Index should be valid when calling peek".into())
     }
     fn previous(&mut self) -> Result<&Token> {
-        self.tokens.get(self.curr - 1)
-                   .ok_or_else(|| "Index should be valid when calling peek".into())
+        self.curr.checked_sub(1)
+                 .and_then(|i| self.tokens.get(i))
+                 .ok_or_else(|| "Unexpected end of input".into())
     }
 }
 
-pub fn parse(src: &str, tokens: &[Token], conf: JsonConfig) -> Result<Json> {
-    Parser {
+/// Returns true if `text` is an integer that doesn't fit in a i64 or u64
+#[cfg(feature = "bigint")]
+fn is_big_integer(text: &str) -> bool {
+    !text.contains(['.', 'e', 'E'])
+        && text.parse::<i64>().is_err()
+        && text.parse::<u64>().is_err()
+}
+
+pub fn parse_with<B: Build>(src: &str, tokens: &[Token], conf: JsonConfig) -> Result<B::Value> {
+    parse_with_hook::<B>(src, tokens, conf, None)
+}
+
+pub fn parse_with_hook<B: Build>(src: &str, tokens: &[Token], conf: JsonConfig, hook: Option<Hook<'_>>) -> Result<B::Value> {
+    parse_reusing::<B>(src, tokens, conf, hook, &mut Vec::new())
+}
+
+/// Parses the tokens, using `stack` as the stack of open arrays and objects.
+/// This allows reusing its allocation between calls.
+pub(crate) fn parse_reusing<B: Build>(
+    src: &str,
+    tokens: &[Token],
+    conf: JsonConfig,
+    hook: Option<Hook<'_>>,
+    stack: &mut Vec<Frame<B>>,
+) -> Result<B::Value> {
+    stack.clear();
+    Parser::<B> {
         tokens,
         src,
         curr: 0,
-        depth: 0,
         conf,
-    }.parse()
+        hook,
+        next_check: HOOK_INTERVAL,
+        used: size_of_val(tokens),
+        lines: OnceCell::new(),
+        builder: PhantomData,
+    }.parse(stack)
+}
+
+pub fn parse(src: &str, tokens: &[Token], conf: JsonConfig) -> Result<Json> {
+    parse_with::<JsonBuilder>(src, tokens, conf)
 }
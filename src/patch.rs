@@ -0,0 +1,324 @@
+//! JSON Patch (RFC 6902)
+//!
+//! # Example
+//! ```
+//! use json::json;
+//! use json::patch::Patch;
+//!
+//! let original = json!({ "name" : "Jhon", "tags" : ["a", "b"] });
+//! let edited = json!({ "name" : "Jhon Doe", "tags" : ["a"], "age" : 30 });
+//!
+//! let patch = Patch::diff(&original, &edited);
+//! let undo = patch.invert(&original).unwrap();
+//!
+//! let mut doc = original.clone();
+//! patch.apply(&mut doc).unwrap();
+//! assert_eq!(doc, edited);
+//!
+//! undo.apply(&mut doc).unwrap();
+//! assert_eq!(doc, original);
+//! ```
+
+use crate::escape::{escape, unescape};
+use crate::pointer::parse_index;
+use crate::prelude::*;
+use crate::{Json, JsonPointer, Result};
+
+/// A single operation of a [Patch]
+#[derive(Debug,Clone,PartialEq)]
+pub enum Operation {
+    Add { path: JsonPointer, value: Json },
+    Remove { path: JsonPointer },
+    Replace { path: JsonPointer, value: Json },
+    Move { from: JsonPointer, path: JsonPointer },
+    Copy { from: JsonPointer, path: JsonPointer },
+    Test { path: JsonPointer, value: Json },
+}
+
+/// A sequence of [operations](Operation), applied in order
+#[derive(Debug,Clone,Default,PartialEq)]
+pub struct Patch(pub Vec<Operation>);
+
+fn get<'a>(doc: &'a Json, path: &JsonPointer) -> Result<&'a Json> {
+    path.resolve(doc).ok_or_else(|| format!("Path \"{path}\" doesn't exist").into())
+}
+
+fn get_mut<'a>(doc: &'a mut Json, path: &JsonPointer) -> Result<&'a mut Json> {
+    path.resolve_mut(doc).ok_or_else(|| format!("Path \"{path}\" doesn't exist").into())
+}
+
+/// Splits `path` into the container that holds the target and the last token
+fn parent<'a, 'b>(doc: &'a mut Json, path: &'b JsonPointer) -> Result<(&'a mut Json, &'b str)> {
+    let (Some(parent), Some(last)) = (path.parent(), path.last()) else {
+        return Err("Expected a path to a member or element".into());
+    };
+    Ok((get_mut(doc, &parent)?, last))
+}
+
+/// Replaces a trailing "-" token, which references the end
+/// of an array, with the index of the array's end
+fn concrete(doc: &Json, path: &JsonPointer) -> JsonPointer {
+    if path.last() == Some("-") {
+        if let Some(Json::Array(arr)) = path.parent().and_then(|p| p.resolve(doc)) {
+            let mut path = path.clone();
+            path.pop();
+            path.push(arr.len().to_string());
+            return path
+        }
+    }
+    path.clone()
+}
+
+/// Returns the value that adding something at `path` would overwrite
+fn overwritten(doc: &Json, path: &JsonPointer) -> Option<Json> {
+    let Some(parent) = path.parent() else {
+        return Some(doc.clone())
+    };
+    match parent.resolve(doc)? {
+        Json::Object(obj) => obj.get(path.last()?).cloned(),
+        _ => None,
+    }
+}
+
+fn add(doc: &mut Json, path: &JsonPointer, value: Json) -> Result<()> {
+    if path.is_root() {
+        *doc = value;
+        return Ok(())
+    }
+    match parent(doc, path)? {
+        (Json::Object(obj), key) => { obj.insert(key.into(), value); },
+        (Json::Array(arr), token) => {
+            let i = if token == "-" { Some(arr.len()) } else { parse_index(token) };
+            let Some(i) = i.filter(|i| *i <= arr.len()) else {
+                return Err(format!("Invalid index in path \"{path}\"").into())
+            };
+            let mut elems = core::mem::take(arr).into_vec();
+            elems.insert(i, value);
+            *arr = elems.into();
+        },
+        _ => return Err(format!("Can't add a value at \"{path}\"").into()),
+    }
+    Ok(())
+}
+
+fn remove(doc: &mut Json, path: &JsonPointer) -> Result<Json> {
+    parent(doc, path)?;
+    path.remove(doc).ok_or_else(|| format!("Path \"{path}\" doesn't exist").into())
+}
+
+impl Operation {
+    /// Applies this operation to `doc`. On error, `doc` may be partially modified
+    fn apply(&self, doc: &mut Json) -> Result<()> {
+        match self {
+            Operation::Add { path, value } => add(doc, path, value.clone()),
+            Operation::Remove { path } => remove(doc, path).map(|_| ()),
+            Operation::Replace { path, value } => {
+                *get_mut(doc, path)? = value.clone();
+                Ok(())
+            },
+            Operation::Move { from, path } => {
+                if from != path && from.is_prefix_of(path) {
+                    return Err(format!("Can't move \"{from}\" into one of its children").into())
+                }
+                let value = remove(doc, from)?;
+                add(doc, path, value)
+            },
+            Operation::Copy { from, path } => {
+                let value = get(doc, from)?.clone();
+                add(doc, path, value)
+            },
+            Operation::Test { path, value } => {
+                if get(doc, path)? == value {
+                    Ok(())
+                } else {
+                    Err(format!("Test failed for path \"{path}\"").into())
+                }
+            },
+        }
+    }
+    /// Applies this operation to `doc`, and returns the
+    /// operations that revert it, in the order they must run
+    fn apply_inverted(&self, doc: &mut Json) -> Result<Vec<Operation>> {
+        let undo_add = |doc: &Json, path: JsonPointer| match overwritten(doc, &path) {
+            Some(value) => Operation::Replace { path, value },
+            None => Operation::Remove { path },
+        };
+        let undo = match self {
+            Operation::Add { path, .. } | Operation::Copy { path, .. } => {
+                vec![undo_add(doc, concrete(doc, path))]
+            },
+            Operation::Remove { path } => {
+                vec![Operation::Add { path: path.clone(), value: get(doc, path)?.clone() }]
+            },
+            Operation::Replace { path, .. } => {
+                vec![Operation::Replace { path: path.clone(), value: get(doc, path)?.clone() }]
+            },
+            Operation::Move { from, path } => {
+                if path.is_root() {
+                    vec![Operation::Replace { path: path.clone(), value: doc.clone() }]
+                } else {
+                    /* The target path must be resolved after removing the value */
+                    let mut after = doc.clone();
+                    remove(&mut after, from)?;
+                    let path = concrete(&after, path);
+                    let mut undo = vec![Operation::Move { from: path.clone(), path: from.clone() }];
+                    if let Some(value) = overwritten(&after, &path) {
+                        undo.push(Operation::Add { path, value });
+                    }
+                    undo
+                }
+            },
+            Operation::Test { .. } => Vec::new(),
+        };
+        self.apply(doc)?;
+        Ok(undo)
+    }
+}
+
+fn diff(path: &mut JsonPointer, a: &Json, b: &Json, ops: &mut Vec<Operation>) {
+    match (a, b) {
+        _ if a == b => {},
+        (Json::Object(a), Json::Object(b)) => {
+            for (k, va) in a {
+                path.push(&**k);
+                match b.get(k) {
+                    Some(vb) => diff(path, va, vb, ops),
+                    None => ops.push(Operation::Remove { path: path.clone() }),
+                }
+                path.pop();
+            }
+            for (k, vb) in b.iter().filter(|(k, _)| !a.contains_key(*k)) {
+                ops.push(Operation::Add { path: path.join(&**k), value: vb.clone() });
+            }
+        },
+        (Json::Array(a), Json::Array(b)) => {
+            for (i, (ea, eb)) in a.iter().zip(b.iter()).enumerate() {
+                path.push(i.to_string());
+                diff(path, ea, eb, ops);
+                path.pop();
+            }
+            for i in (b.len()..a.len()).rev() {
+                ops.push(Operation::Remove { path: path.join(i.to_string()) });
+            }
+            for (i, eb) in b.iter().enumerate().skip(a.len()) {
+                ops.push(Operation::Add { path: path.join(i.to_string()), value: eb.clone() });
+            }
+        },
+        _ => ops.push(Operation::Replace { path: path.clone(), value: b.clone() }),
+    }
+}
+
+impl Patch {
+    /// Builds a patch that turns `from` into `to`
+    pub fn diff(from: &Json, to: &Json) -> Self {
+        let mut ops = Vec::new();
+        diff(&mut JsonPointer::root(), from, to, &mut ops);
+        Self(ops)
+    }
+    /// Applies this patch to `doc`.
+    ///
+    /// The patch is applied atomically: if any operation fails,
+    /// `doc` is left unmodified.
+    pub fn apply(&self, doc: &mut Json) -> Result<()> {
+        let mut result = doc.clone();
+        for op in &self.0 {
+            op.apply(&mut result)?;
+        }
+        *doc = result;
+        Ok(())
+    }
+    /// Builds the inverse of this patch, which reverts the
+    /// changes it makes when applied to `original`.
+    ///
+    /// The inverse captures the values removed or replaced by
+    /// this patch, which makes it useful to implement undo.
+    ///
+    /// Fails if this patch can't be applied to `original`.
+    pub fn invert(&self, original: &Json) -> Result<Patch> {
+        let mut doc = original.clone();
+        let mut undo = Vec::with_capacity(self.0.len());
+        for op in &self.0 {
+            undo.push(op.apply_inverted(&mut doc)?);
+        }
+        Ok(Patch(undo.into_iter().rev().flatten().collect()))
+    }
+}
+
+fn string(s: &str) -> Json {
+    Json::String(escape(s).into())
+}
+
+impl From<Operation> for Json {
+    fn from(value: Operation) -> Self {
+        let mut map = Map::default();
+        let (op, path, from, value) = match value {
+            Operation::Add { path, value } => ("add", path, None, Some(value)),
+            Operation::Remove { path } => ("remove", path, None, None),
+            Operation::Replace { path, value } => ("replace", path, None, Some(value)),
+            Operation::Move { from, path } => ("move", path, Some(from), None),
+            Operation::Copy { from, path } => ("copy", path, Some(from), None),
+            Operation::Test { path, value } => ("test", path, None, Some(value)),
+        };
+        map.insert("op".into(), op.into());
+        map.insert("path".into(), string(&path.to_string()));
+        if let Some(from) = from {
+            map.insert("from".into(), string(&from.to_string()));
+        }
+        if let Some(value) = value {
+            map.insert("value".into(), value);
+        }
+        Json::Object(map)
+    }
+}
+
+impl From<Patch> for Json {
+    fn from(value: Patch) -> Self {
+        let ops: Vec<Json> = value.0.into_iter().map(Json::from).collect();
+        ops.into()
+    }
+}
+
+fn take_pointer(obj: &mut Map<Key,Json>, key: &str) -> Result<JsonPointer> {
+    match remove_key(obj, key).map(Json::into_string) {
+        Some(Ok(s)) => JsonPointer::parse(&unescape(&s)),
+        Some(Err(_)) => Err(format!("\"{key}\" must be a string").into()),
+        None => Err(format!("Missing \"{key}\"").into()),
+    }
+}
+
+impl TryFrom<Json> for Operation {
+    type Error = crate::Error;
+
+    fn try_from(json: Json) -> Result<Self> {
+        let Ok(mut obj) = json.into_object() else {
+            return Err("Expected an object".into())
+        };
+        let Some(Ok(op)) = remove_key(&mut obj, "op").map(Json::into_string) else {
+            return Err("Missing \"op\"".into())
+        };
+        let path = take_pointer(&mut obj, "path")?;
+        let mut value = || remove_key(&mut obj, "value").ok_or("Missing \"value\"");
+        Ok(match &*op {
+            "add" => Operation::Add { path, value: value()? },
+            "remove" => Operation::Remove { path },
+            "replace" => Operation::Replace { path, value: value()? },
+            "test" => Operation::Test { path, value: value()? },
+            "move" => Operation::Move { from: take_pointer(&mut obj, "from")?, path },
+            "copy" => Operation::Copy { from: take_pointer(&mut obj, "from")?, path },
+            op => return Err(format!("Unknown operation \"{op}\"").into()),
+        })
+    }
+}
+
+impl TryFrom<Json> for Patch {
+    type Error = crate::Error;
+
+    /// Parses a patch from its JSON representation:
+    /// an array of operation objects
+    fn try_from(json: Json) -> Result<Self> {
+        let Ok(ops) = json.into_array() else {
+            return Err("Expected an array of operations".into())
+        };
+        ops.into_iter().map(Operation::try_from).collect::<Result<_>>().map(Patch)
+    }
+}
@@ -0,0 +1,135 @@
+//! Partial matching of [Json] values against patterns
+//!
+//! A [Pattern] can be built by hand, or from a [Json] template, where
+//! some strings have a special meaning:
+//!
+//! | Template               | Matches                                  |
+//! |------------------------|------------------------------------------|
+//! | `"<any>"`              | any value                                |
+//! | `"<string>"`           | any string                               |
+//! | `"<number>"`           | any number                               |
+//! | `"<boolean>"`          | `true` or `false`                        |
+//! | `"<array>"`            | any array                                |
+//! | `"<object>"`           | any object                               |
+//! | `[a, b, "..."]`        | arrays starting with elements `a` and `b`|
+//! | `{"...": null, "k": a}`| objects with a member `k`, and any other |
+//!
+//! # Example
+//! ```
+//! use json::{json, Json};
+//! use json::pattern::Pattern;
+//!
+//! let response = json!({
+//!     "id" : 12,
+//!     "name" : "Jhon",
+//!     "roles" : ["admin", "dev"],
+//!     "created_at" : 1700000000
+//! });
+//!
+//! let pattern = Pattern::from(json!({
+//!     "id" : "<number>",
+//!     "name" : "<string>",
+//!     "roles" : ["admin", "..."],
+//!     "..." : null
+//! }));
+//! assert!(response.matches(&pattern));
+//! ```
+
+use core::mem;
+
+use crate::prelude::*;
+use crate::Json;
+
+/// Template string matching any value
+pub const ANY: &str = "<any>";
+/// Template string matching any string
+pub const ANY_STRING: &str = "<string>";
+/// Template string matching any number
+pub const ANY_NUMBER: &str = "<number>";
+/// Template string matching any boolean
+pub const ANY_BOOLEAN: &str = "<boolean>";
+/// Template string matching any array
+pub const ANY_ARRAY: &str = "<array>";
+/// Template string matching any object
+pub const ANY_OBJECT: &str = "<object>";
+/// Allows extra elements when found at the end of an array,
+/// and extra members when used as a key in an object.
+pub const ELLIPSIS: &str = "...";
+
+/// A pattern to [match](Json::matches) [Json] values against
+#[derive(Debug,PartialEq)]
+pub enum Pattern {
+    Any,
+    AnyString,
+    AnyNumber,
+    AnyBoolean,
+    AnyArray,
+    AnyObject,
+    /// Matches a value equal to the given one
+    Exact(Json),
+    /// Matches an array whose elements match the given patterns.
+    /// If `rest` is true, extra elements at the end are allowed.
+    Array { elems: Vec<Pattern>, rest: bool },
+    /// Matches an object whose members match the given patterns.
+    /// If `rest` is true, extra members are allowed.
+    Object { members: Map<Key,Pattern>, rest: bool },
+}
+
+impl From<Json> for Pattern {
+    fn from(mut value: Json) -> Self {
+        match &mut value {
+            Json::String(s) => match &**s {
+                ANY => Pattern::Any,
+                ANY_STRING => Pattern::AnyString,
+                ANY_NUMBER => Pattern::AnyNumber,
+                ANY_BOOLEAN => Pattern::AnyBoolean,
+                ANY_ARRAY => Pattern::AnyArray,
+                ANY_OBJECT => Pattern::AnyObject,
+                _ => Pattern::Exact(value),
+            },
+            Json::Array(arr) => {
+                let mut elems = mem::take(arr).into_vec();
+                let rest = matches!(elems.last(), Some(Json::String(s)) if &**s == ELLIPSIS);
+                if rest {
+                    elems.pop();
+                }
+                let elems = elems.into_iter().map(Pattern::from).collect();
+                Pattern::Array { elems, rest }
+            },
+            Json::Object(obj) => {
+                let rest = remove_key(obj, ELLIPSIS).is_some();
+                let members = mem::take(obj).into_iter().map(|(k,v)| (k, Pattern::from(v))).collect();
+                Pattern::Object { members, rest }
+            },
+            _ => Pattern::Exact(value),
+        }
+    }
+}
+
+impl Json {
+    /// Returns true if this value matches the given [Pattern]
+    pub fn matches(&self, pattern: &Pattern) -> bool {
+        match (pattern, self) {
+            (Pattern::Any, _) |
+            (Pattern::AnyString, Json::String(_)) |
+            (Pattern::AnyBoolean, Json::True | Json::False) |
+            (Pattern::AnyArray, Json::Array(_)) |
+            (Pattern::AnyObject, Json::Object(_)) => true,
+            (Pattern::AnyNumber, _) => self.is_number(),
+            (Pattern::Exact(j), _) => j == self,
+            (Pattern::Array { elems, rest }, Json::Array(arr)) => {
+                if arr.len() < elems.len() || (!rest && arr.len() > elems.len()) {
+                    return false
+                }
+                elems.iter().zip(arr.iter()).all(|(p,j)| j.matches(p))
+            },
+            (Pattern::Object { members, rest }, Json::Object(obj)) => {
+                if !rest && obj.len() != members.len() {
+                    return false
+                }
+                members.iter().all(|(k,p)| obj.get(k).is_some_and(|j| j.matches(p)))
+            },
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! JSON Pointers (RFC 6901)
+//!
+//! # Example
+//! ```
+//! use json::{json, JsonPointer};
+//!
+//! let j = json!({ "a/b" : [ { "c" : 1 } ] });
+//! assert_eq!(j.pointer("/a~1b/0/c"), Some(&json!(1)));
+//!
+//! let mut ptr = JsonPointer::root();
+//! ptr.push("a/b");
+//! ptr.push("0");
+//! assert_eq!(ptr.to_string(), "/a~1b/0");
+//! ```
+
+use core::str::FromStr;
+
+use crate::prelude::*;
+use crate::{Json, Result};
+
+/// A parsed JSON Pointer.
+///
+/// The reference tokens are stored unescaped.
+#[derive(Debug,Clone,Default,PartialEq,Eq,Hash,PartialOrd,Ord)]
+pub struct JsonPointer(Vec<Box<str>>);
+
+fn unescape_token(token: &str) -> Box<str> {
+    token.replace("~1", "/").replace("~0", "~").into()
+}
+
+/// Parses an array index token. Leading zeros are not allowed.
+pub(crate) fn parse_index(token: &str) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0'))
+       || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None
+    }
+    token.parse().ok()
+}
+
+impl JsonPointer {
+    /// The empty pointer, which references the whole document
+    pub fn root() -> Self { Self(Vec::new()) }
+    /// Parses a JSON pointer, like `/a/b/0`
+    pub fn parse(s: &str) -> Result<Self> {
+        if s.is_empty() {
+            return Ok(Self::root())
+        }
+        let Some(s) = s.strip_prefix('/') else {
+            return Err(format!("JSON pointer '{s}' must start with '/'").into());
+        };
+        if s.split('~').skip(1).any(|t| !t.starts_with(['0', '1'])) {
+            return Err(format!("Invalid escape sequence in JSON pointer '/{s}'").into());
+        }
+        Ok(Self(s.split('/').map(unescape_token).collect()))
+    }
+    /// Returns the reference tokens of this pointer
+    pub fn tokens(&self) -> impl DoubleEndedIterator<Item = &str> + ExactSizeIterator {
+        self.0.iter().map(|t| &**t)
+    }
+    /// Returns true if this is the [root](Self::root) pointer
+    pub fn is_root(&self) -> bool { self.0.is_empty() }
+    /// Appends a reference token
+    pub fn push(&mut self, token: impl Into<Box<str>>) {
+        self.0.push(token.into());
+    }
+    /// Removes the last reference token
+    pub fn pop(&mut self) -> Option<Box<str>> {
+        self.0.pop()
+    }
+    /// Returns the last reference token
+    pub fn last(&self) -> Option<&str> {
+        self.0.last().map(|t| &**t)
+    }
+    /// Returns the pointer to the parent of the referenced value
+    pub fn parent(&self) -> Option<JsonPointer> {
+        let (_, parent) = self.0.split_last()?;
+        Some(Self(parent.to_vec()))
+    }
+    /// Returns a new pointer with the given token appended
+    #[must_use]
+    pub fn join(&self, token: impl Into<Box<str>>) -> JsonPointer {
+        let mut ptr = self.clone();
+        ptr.push(token);
+        ptr
+    }
+    /// Returns true if `self` is a prefix of `other`
+    pub fn is_prefix_of(&self, other: &JsonPointer) -> bool {
+        other.0.starts_with(&self.0)
+    }
+    /// Gets the value referenced by this pointer
+    pub fn resolve<'a>(&self, json: &'a Json) -> Option<&'a Json> {
+        self.tokens().try_fold(json, |j, t| match j {
+            Json::Object(o) => o.get(t),
+            Json::Array(a) => a.get(parse_index(t)?),
+            _ => None,
+        })
+    }
+    /// Same as [resolve](Self::resolve), but with a mutable reference
+    pub fn resolve_mut<'a>(&self, json: &'a mut Json) -> Option<&'a mut Json> {
+        self.tokens().try_fold(json, |j, t| match j {
+            Json::Object(o) => o.get_mut(t),
+            Json::Array(a) => a.get_mut(parse_index(t)?),
+            _ => None,
+        })
+    }
+    /// Removes the value referenced by this pointer, and returns it.
+    /// The elements after a removed array element are shifted down.
+    ///
+    /// Returns None if the pointer doesn't reference any
+    /// value, or if it's the root, which can't be removed.
+    pub fn remove(&self, json: &mut Json) -> Option<Json> {
+        let parent = self.parent()?.resolve_mut(json)?;
+        let last = self.last()?;
+        match parent {
+            Json::Object(obj) => remove_key(obj, last),
+            Json::Array(arr) => {
+                let i = parse_index(last).filter(|i| *i < arr.len())?;
+                let mut elems = core::mem::take(arr).into_vec();
+                let removed = elems.remove(i);
+                *arr = elems.into();
+                Some(removed)
+            },
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for JsonPointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for t in &self.0 {
+            f.write_char('/')?;
+            for c in t.chars() {
+                match c {
+                    '~' => f.write_str("~0")?,
+                    '/' => f.write_str("~1")?,
+                    c => f.write_char(c)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for JsonPointer {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl<T: Into<Box<str>>> FromIterator<T> for JsonPointer {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl Json {
+    /// Gets the value referenced by the given JSON pointer.
+    ///
+    /// Returns None if the pointer is malformed,
+    /// or doesn't reference any value
+    pub fn pointer(&self, ptr: impl AsRef<str>) -> Option<&Json> {
+        JsonPointer::parse(ptr.as_ref()).ok()?.resolve(self)
+    }
+    /// Same as [pointer](Self::pointer), but with a mutable reference
+    pub fn pointer_mut(&mut self, ptr: impl AsRef<str>) -> Option<&mut Json> {
+        JsonPointer::parse(ptr.as_ref()).ok()?.resolve_mut(self)
+    }
+    /// Removes the value referenced by the given JSON pointer, and returns it.
+    /// See [`JsonPointer::remove`]
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut j = json!({ "a" : { "b" : [1, 2, 3] } });
+    /// assert_eq!(j.remove_pointer("/a/b/0"), Some(json!(1)));
+    /// assert_eq!(j, json!({ "a" : { "b" : [2, 3] } }));
+    /// assert_eq!(j.remove_pointer("/a/c"), None);
+    /// ```
+    pub fn remove_pointer(&mut self, ptr: impl AsRef<str>) -> Option<Json> {
+        JsonPointer::parse(ptr.as_ref()).ok()?.remove(self)
+    }
+}
@@ -0,0 +1,198 @@
+//! URL-encoded query string conversion
+//!
+//! Nesting is expressed with brackets: `a[b]=1` sets the member
+//! `b` of the object `a`, and `a[]=1` appends to the array `a`.
+
+use crate::escape::{escape, unescape};
+use crate::prelude::*;
+use crate::{Json, Result};
+
+fn percent_decode(s: &str) -> Result<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = [iter.next(), iter.next()];
+                let digit = |b: Option<u8>| (b.unwrap_or(0) as char).to_digit(16);
+                match (digit(hex[0]), digit(hex[1])) {
+                    #[allow(clippy::cast_possible_truncation)]
+                    (Some(hi), Some(lo)) => bytes.push((hi * 16 + lo) as u8),
+                    _ => return Err(format!("Invalid percent encoding in '{s}'").into()),
+                }
+            },
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| "Percent encoded text is not valid UTF-8".into())
+}
+
+fn percent_encode(s: &str, out: &mut dyn Write) -> fmt::Result {
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' |
+            b'-' | b'_' | b'.' | b'~' => out.write_char(b as char)?,
+            b' ' => out.write_char('+')?,
+            b => write!(out, "%{b:02X}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Splits `a[b][]` into `a` and the list of bracketed segments
+fn split_key(key: &str) -> Result<(&str, Vec<&str>)> {
+    let Some(open) = key.find('[') else {
+        return Ok((key, Vec::new()));
+    };
+    let (name, mut rest) = key.split_at(open);
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        let Some(inner) = rest.strip_prefix('[') else {
+            return Err(format!("Malformed key '{key}'").into());
+        };
+        let Some(close) = inner.find(']') else {
+            return Err(format!("Unclosed '[' in key '{key}'").into());
+        };
+        segments.push(&inner[..close]);
+        rest = &inner[close + 1..];
+    }
+    Ok((name, segments))
+}
+
+fn insert(target: &mut Json, segments: &[&str], value: Json) -> Result<()> {
+    let Some((seg, rest)) = segments.split_first() else {
+        *target = value;
+        return Ok(())
+    };
+    if seg.is_empty() {
+        if target.is_null() {
+            *target = Json::Array(Box::new([]));
+        }
+        let Json::Array(arr) = target else {
+            return Err("Mixed array and non-array values for the same key".into());
+        };
+        let mut elems = core::mem::take(arr).into_vec();
+        let mut elem = Json::Null;
+        insert(&mut elem, rest, value)?;
+        elems.push(elem);
+        *arr = elems.into();
+    } else {
+        if target.is_null() {
+            *target = Json::Object(Map::default());
+        }
+        let Json::Object(obj) = target else {
+            return Err(format!("Mixed object and non-object values for key '{seg}'").into());
+        };
+        let member = obj.entry(escape(seg).into()).or_insert(Json::Null);
+        insert(member, rest, value)?;
+    }
+    Ok(())
+}
+
+fn write_pairs(prefix: &mut String, json: &Json, first: &mut bool, out: &mut dyn Write) -> fmt::Result {
+    let len = prefix.len();
+    match json {
+        /* A query string can't tell these apart from "" */
+        Json::Null => return Err(fmt::Error),
+        Json::Array(arr) if arr.is_empty() => return Err(fmt::Error),
+        Json::Object(obj) if obj.is_empty() => return Err(fmt::Error),
+        Json::Object(obj) => {
+            for (k,v) in obj {
+                prefix.push('[');
+                prefix.push_str(&unescape(k));
+                prefix.push(']');
+                write_pairs(prefix, v, first, out)?;
+                prefix.truncate(len);
+            }
+        },
+        Json::Array(arr) => {
+            prefix.push_str("[]");
+            for e in arr {
+                write_pairs(prefix, e, first, out)?;
+            }
+            prefix.truncate(len);
+        },
+        scalar => {
+            if !*first {
+                out.write_char('&')?;
+            }
+            *first = false;
+            percent_encode(prefix, out)?;
+            out.write_char('=')?;
+            match scalar {
+                Json::String(s) => percent_encode(&unescape(s), out)?,
+                Json::Number(n) => write!(out, "{n}")?,
+                #[cfg(feature = "bigint")]
+                Json::BigInt(n) => write!(out, "{n}")?,
+                #[cfg(feature = "decimal")]
+                Json::Decimal(n) => write!(out, "{n}")?,
+                Json::True => out.write_str("true")?,
+                Json::False => out.write_str("false")?,
+                Json::Null | Json::Array(_) | Json::Object(_) => unreachable!(),
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Json {
+    /// Parses an URL-encoded query string into a [Json] object
+    ///
+    /// All values are parsed as strings. Keys can use bracket notation
+    /// to build nested objects (`a[b]=1`) and arrays (`a[]=1`).
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, Json};
+    ///
+    /// let j = Json::from_query_string("a=1&b[]=2&b[]=3&c[d]=hello+world").unwrap();
+    /// assert_eq!(j, json!({
+    ///     "a" : "1",
+    ///     "b" : ["2", "3"],
+    ///     "c" : { "d" : "hello world" }
+    /// }));
+    /// ```
+    pub fn from_query_string(query: impl AsRef<str>) -> Result<Json> {
+        let query = query.as_ref();
+        let query = query.strip_prefix('?').unwrap_or(query);
+        let mut root = Json::Object(Map::default());
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let key = percent_decode(key)?;
+            let value = percent_decode(value)?;
+            let (name, mut segments) = split_key(&key)?;
+            segments.insert(0, name);
+            insert(&mut root, &segments, Json::String(escape(&value).into()))?;
+        }
+        Ok(root)
+    }
+    /// Serializes the JSON object as an URL-encoded query string
+    /// into a `fmt::Write`.
+    ///
+    /// This is the inverse of [`from_query_string`](Self::from_query_string).
+    /// Only objects can be represented as a query string.
+    ///
+    /// Numbers and booleans are written as text, so they are read back
+    /// as strings. Null, empty arrays and empty objects can't be told
+    /// apart from an empty string, so they return an error instead.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut query = String::new();
+    /// json!({ "b" : [1, true, "c d"] }).to_query_string(&mut query).unwrap();
+    /// assert_eq!(query, "b%5B%5D=1&b%5B%5D=true&b%5B%5D=c+d");
+    ///
+    /// assert!(json!({ "a" : null }).to_query_string(&mut String::new()).is_err());
+    /// ```
+    pub fn to_query_string(&self, out: &mut dyn Write) -> fmt::Result {
+        let Json::Object(obj) = self else { return Err(fmt::Error) };
+        let mut first = true;
+        for (k,v) in obj {
+            write_pairs(&mut unescape(k).to_string(), v, &mut first, out)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+//! Random JSON generation
+//!
+//! # Example
+//! ```
+//! use json::random::GeneratorConfig;
+//!
+//! let conf = GeneratorConfig {
+//!     seed: 42,
+//!     max_depth: 3,
+//!     ..Default::default()
+//! };
+//! let a = json::generate(&conf);
+//! let b = json::generate(&conf);
+//! assert_eq!(a, b);
+//! ```
+
+use core::ops::RangeInclusive;
+
+use crate::prelude::*;
+use crate::Json;
+
+/// A small, fast and deterministic pseudo-random number generator
+///
+/// It's not suitable for anything security related.
+#[derive(Debug,Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+    /// Returns the next random u64
+    pub fn next_u64(&mut self) -> u64 {
+        /* splitmix64 */
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    /// Returns a random f64 in the range [0,1)
+    #[allow(clippy::cast_precision_loss)]
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+    /// Returns a random number in the given range.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        if end <= start { return start }
+        let len = (end - start) as u64 + 1;
+        start + (self.next_u64() % len) as usize
+    }
+    /// Returns true with the given probability
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+}
+
+/// Relative weights of each type of value.
+///
+/// A weight of 0 disables that type.
+#[derive(Debug,Clone)]
+pub struct TypeMix {
+    pub null: u32,
+    pub boolean: u32,
+    pub number: u32,
+    pub string: u32,
+    pub array: u32,
+    pub object: u32,
+}
+
+impl Default for TypeMix {
+    fn default() -> Self {
+        Self { null: 1, boolean: 2, number: 4, string: 4, array: 2, object: 2 }
+    }
+}
+
+/// Configures the random generator
+#[derive(Debug,Clone)]
+pub struct GeneratorConfig {
+    /// Seed of the generator. The same seed always
+    /// produces the same document.
+    pub seed: u64,
+    /// Max depth for nested arrays and objects
+    pub max_depth: u32,
+    /// Range for the number of elements of arrays and objects
+    pub branching: RangeInclusive<usize>,
+    /// Range for the length of strings and keys
+    pub string_len: RangeInclusive<usize>,
+    /// Range of the generated numbers
+    pub number_range: (f64, f64),
+    /// Probability of a number being an integer
+    pub integer_ratio: f64,
+    /// Types of values to generate
+    pub mix: TypeMix,
+    /// If true, the root is always an array or an object
+    pub container_root: bool,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            max_depth: 4,
+            branching: 0..=6,
+            string_len: 1..=12,
+            number_range: (-1000.0, 1000.0),
+            integer_ratio: 0.5,
+            mix: TypeMix::default(),
+            container_root: true,
+        }
+    }
+}
+
+const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 _-";
+
+fn string(conf: &GeneratorConfig, rng: &mut Rng) -> String {
+    let len = rng.range(conf.string_len.clone());
+    (0..len).map(|_| CHARSET[rng.range(0..=CHARSET.len() - 1)] as char).collect()
+}
+
+fn value(conf: &GeneratorConfig, rng: &mut Rng, depth: u32) -> Json {
+    let m = &conf.mix;
+    let containers = depth < conf.max_depth;
+    let force_container = depth == 0 && conf.container_root && containers;
+
+    let mut weights = [
+        if force_container { 0 } else { m.null },
+        if force_container { 0 } else { m.boolean },
+        if force_container { 0 } else { m.number },
+        if force_container { 0 } else { m.string },
+        if containers { m.array } else { 0 },
+        if containers { m.object } else { 0 },
+    ];
+    if weights.iter().all(|w| *w == 0) {
+        weights[0] = 1;
+    }
+    let total: u64 = weights.iter().map(|w| u64::from(*w)).sum();
+    let mut pick = rng.next_u64() % total;
+    let mut kind = 0;
+    for (i, w) in weights.iter().enumerate() {
+        let w = u64::from(*w);
+        if pick < w {
+            kind = i;
+            break
+        }
+        pick -= w;
+    }
+
+    match kind {
+        0 => Json::Null,
+        1 => rng.chance(0.5).into(),
+        2 => {
+            let (min, max) = conf.number_range;
+            let n = min + rng.next_f64() * (max - min);
+            Json::Number(from_f64(if rng.chance(conf.integer_ratio) { trunc(n) } else { n }))
+        },
+        3 => string(conf, rng).into(),
+        4 => {
+            let len = rng.range(conf.branching.clone());
+            (0..len).map(|_| value(conf, rng, depth + 1)).collect::<Vec<_>>().into()
+        },
+        _ => {
+            let len = rng.range(conf.branching.clone());
+            let mut map = Map::default();
+            for _ in 0..len {
+                let key = string(conf, rng);
+                map.insert(key.into(), value(conf, rng, depth + 1));
+            }
+            Json::Object(map)
+        },
+    }
+}
+
+/// Generates a random [Json] document, using the given [`Rng`]
+pub fn generate_with(conf: &GeneratorConfig, rng: &mut Rng) -> Json {
+    value(conf, rng, 0)
+}
+
+/// Generates a random [Json] document
+pub fn generate(conf: &GeneratorConfig) -> Json {
+    generate_with(conf, &mut Rng::new(conf.seed))
+}
@@ -0,0 +1,126 @@
+//! Parsing from readers, and compressed input
+
+use std::io::Read;
+
+use crate::events::{Event, EventParser, MAX_DEPTH};
+use crate::prelude::*;
+use crate::{Json, Result};
+
+/// Max length of a single token read by [`Json::from_reader`].
+/// The buffer is allocated zeroed, so its pages are only
+/// committed as long tokens use them.
+const MAX_TOKEN: usize = 64 * 1024 * 1024;
+
+/// Number of bytes requested to the reader at a time
+const CHUNK_SIZE: usize = 8 * 1024;
+
+enum Frame {
+    Array(Vec<Json>),
+    Object(Map<Key,Json>, Key),
+}
+
+/// Builds a [Json] from a sequence of events
+#[derive(Default)]
+struct Builder {
+    stack: Vec<Frame>,
+    root: Option<Json>,
+}
+
+impl Builder {
+    fn value(&mut self, json: Json) {
+        match self.stack.last_mut() {
+            Some(Frame::Array(elems)) => elems.push(json),
+            Some(Frame::Object(obj, key)) => { obj.insert(core::mem::take(key), json); },
+            None => self.root = Some(json),
+        }
+    }
+    fn event(&mut self, event: Event<'_>) {
+        match event {
+            Event::StartArray => self.stack.push(Frame::Array(Vec::new())),
+            Event::StartObject => self.stack.push(Frame::Object(Map::default(), "".into())),
+            Event::EndArray | Event::EndObject => {
+                let json = match self.stack.pop() {
+                    Some(Frame::Array(elems)) => elems.into(),
+                    Some(Frame::Object(obj, _)) => Json::Object(obj),
+                    None => unreachable!("the event parser checks the nesting"),
+                };
+                self.value(json);
+            },
+            Event::Key(k) => {
+                if let Some(Frame::Object(_, key)) = self.stack.last_mut() {
+                    *key = k.into();
+                }
+            },
+            Event::String(s) => self.value(Json::String(s.into())),
+            Event::Number(n) => self.value(Json::Number(from_f64(n))),
+            Event::True => self.value(Json::True),
+            Event::False => self.value(Json::False),
+            Event::Null => self.value(Json::Null),
+        }
+    }
+}
+
+impl Json {
+    /// Parses a document from `reader`, as it's read
+    ///
+    /// Unlike reading the text first and calling [`deserialize`](Self::deserialize),
+    /// the text is never held in memory whole. Numbers are always parsed as f64,
+    /// even with the `bigint` or `decimal` features. Tokens can be up to 64 MiB
+    /// long, and arrays and objects can be nested up to 4096 levels deep.
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, Json};
+    ///
+    /// let j = Json::from_reader(&br#"{ "a" : [1, "b\n"] }"#[..]).unwrap();
+    /// assert_eq!(j, json!({ "a" : [1, r"b\n"] }));
+    /// ```
+    pub fn from_reader(mut reader: impl Read) -> Result<Json> {
+        let (mut scratch, mut nesting) = (vec![0; MAX_TOKEN], vec![0; MAX_DEPTH]);
+        let mut parser = EventParser::new(&mut scratch, &mut nesting);
+        let mut builder = Builder::default();
+        let mut buf = vec![0; CHUNK_SIZE];
+        loop {
+            let mut chunk = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => &buf[..n],
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.to_string().into()),
+            };
+            while let Some(event) = parser.next_event(&mut chunk)? {
+                builder.event(event);
+            }
+        }
+        if let Some(event) = parser.finish()? {
+            builder.event(event);
+        }
+        builder.root.ok_or_else(|| "Unexpected end of input".into())
+    }
+    /// Parses a gzip compressed document from `reader`,
+    /// decompressing it as it's parsed. See [`from_reader`](Self::from_reader)
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use flate2::{write::GzEncoder, Compression};
+    /// use json::{json, Json};
+    ///
+    /// let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    /// encoder.write_all(br#"{ "archived" : true }"#).unwrap();
+    /// let compressed = encoder.finish().unwrap();
+    ///
+    /// let j = Json::from_gzip_reader(&compressed[..]).unwrap();
+    /// assert_eq!(j, json!({ "archived" : true }));
+    /// ```
+    #[cfg(feature = "gzip")]
+    pub fn from_gzip_reader(reader: impl Read) -> Result<Json> {
+        Json::from_reader(flate2::read::GzDecoder::new(reader))
+    }
+    /// Parses a zstd compressed document from `reader`,
+    /// decompressing it as it's parsed. See [`from_reader`](Self::from_reader)
+    #[cfg(feature = "zstd")]
+    pub fn from_zstd_reader(reader: impl Read) -> Result<Json> {
+        let decoder = zstd::Decoder::new(reader).map_err(|e| e.to_string())?;
+        Json::from_reader(decoder)
+    }
+}
@@ -0,0 +1,119 @@
+//! Redaction of sensitive values
+
+use crate::prelude::*;
+use crate::{Json, JsonPointer, Result};
+
+/// A set of values to redact, by key name or by JSON pointer
+///
+/// # Example
+/// ```
+/// use json::{json, Redaction, SerializeConfig};
+///
+/// let j = json!({
+///     "user" : { "name" : "Jhon", "password" : "1234" },
+///     "auth" : { "token" : "abcd" }
+/// });
+///
+/// let redaction = Redaction::new(&["password", "/auth/token"], "***").unwrap();
+/// let conf = SerializeConfig { redaction: Some(&redaction), ..Default::default() };
+///
+/// // Pointers are relative to the serialized value
+/// let mut out = String::new();
+/// j.get("auth").unwrap().serialize_with_config(&conf, &mut out).unwrap();
+/// assert_eq!(out, r#"{"token":"abcd"}"#);
+///
+/// let mut out = String::new();
+/// j.serialize_with_config(&conf, &mut out).unwrap();
+/// let redacted = json::Json::deserialize(out).unwrap();
+/// assert_eq!(redacted, json!({
+///     "user" : { "name" : "Jhon", "password" : "***" },
+///     "auth" : { "token" : "***" }
+/// }));
+/// ```
+#[derive(Debug,Clone)]
+pub struct Redaction {
+    keys: Vec<Box<str>>,
+    pointers: Vec<JsonPointer>,
+    replacement: Json,
+}
+
+impl Redaction {
+    /// Builds a redaction of the given targets.
+    ///
+    /// Targets starting with `/` are JSON pointers, relative to the root of the
+    /// document. Any other target is a key name, which matches object members
+    /// with that key at any depth.
+    ///
+    /// Fails if one of the pointers is malformed.
+    pub fn new(targets: &[&str], replacement: impl Into<Json>) -> Result<Self> {
+        let mut keys = Vec::new();
+        let mut pointers = Vec::new();
+        for t in targets {
+            if t.starts_with('/') {
+                pointers.push(JsonPointer::parse(t)?);
+            } else {
+                keys.push((*t).into());
+            }
+        }
+        Ok(Self { keys, pointers, replacement: replacement.into() })
+    }
+    /// Returns the replacement for the value at `path`, if it must be redacted
+    pub(crate) fn replacement(&self, path: &JsonPointer) -> Option<&Json> {
+        let by_key = path.last().is_some_and(|k| self.keys.iter().any(|key| &**key == k));
+        (by_key || self.pointers.contains(path)).then_some(&self.replacement)
+    }
+    fn apply(&self, json: &mut Json, path: &mut JsonPointer) {
+        if let Some(r) = self.replacement(path) {
+            *json = r.clone();
+            return
+        }
+        match json {
+            Json::Array(arr) => {
+                for (i, e) in arr.iter_mut().enumerate() {
+                    path.push(i.to_string());
+                    self.apply(e, path);
+                    path.pop();
+                }
+            },
+            Json::Object(obj) => {
+                for (k, v) in obj.iter_mut() {
+                    path.push(&**k);
+                    self.apply(v, path);
+                    path.pop();
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+impl Json {
+    /// Replaces the values matching `targets` with `replacement`.
+    /// See [`Redaction::new`] for the syntax of the targets.
+    ///
+    /// To redact values while serializing, without modifying the
+    /// document, see [`SerializeConfig::redaction`](crate::SerializeConfig::redaction)
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut j = json!({
+    ///     "password" : "1234",
+    ///     "users" : [ { "password" : "abcd" } ],
+    ///     "auth" : { "token" : "xyz" }
+    /// });
+    /// j.redact(&["password", "/auth/token"], json!(null)).unwrap();
+    ///
+    /// assert_eq!(j, json!({
+    ///     "password" : null,
+    ///     "users" : [ { "password" : null } ],
+    ///     "auth" : { "token" : null }
+    /// }));
+    /// ```
+    pub fn redact(&mut self, targets: &[&str], replacement: impl Into<Json>) -> Result<()> {
+        let redaction = Redaction::new(targets, replacement)?;
+        redaction.apply(self, &mut JsonPointer::root());
+        Ok(())
+    }
+}
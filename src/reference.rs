@@ -0,0 +1,125 @@
+//! JSON Reference (`$ref`) resolution
+//!
+//! Objects of the form `{"$ref": "<uri>#<pointer>"}` are replaced by
+//! the value they reference. Internal references (`#/definitions/x`)
+//! point inside the document being resolved. External ones are fetched
+//! through a [Loader], and the references inside them are resolved
+//! relative to the loaded document.
+//!
+//! Other members of a `$ref` object are ignored.
+//!
+//! # Example
+//! ```
+//! use json::{json, Json};
+//!
+//! let doc = json!({
+//!     "definitions" : { "port" : { "type" : "integer" } },
+//!     "properties" : { "http" : { "$ref" : "#/definitions/port" } }
+//! });
+//! let expanded = doc.resolve_refs().unwrap();
+//! assert_eq!(expanded.pointer("/properties/http/type"), Some(&json!("integer")));
+//! ```
+
+use crate::pointer::JsonPointer;
+use crate::prelude::*;
+use crate::{Json, Result};
+
+/// Key of the reference objects
+pub const REF_KEY: &str = "$ref";
+
+/// Loads the documents referenced by external `$ref`s
+pub trait Loader {
+    /// Loads the document at the given URI
+    fn load(&mut self, uri: &str) -> Result<Json>;
+}
+
+impl<F: FnMut(&str) -> Result<Json>> Loader for F {
+    fn load(&mut self, uri: &str) -> Result<Json> {
+        self(uri)
+    }
+}
+
+/// A [Loader] that rejects every external reference
+pub struct NoLoader;
+
+impl Loader for NoLoader {
+    fn load(&mut self, uri: &str) -> Result<Json> {
+        Err(format!("Can't resolve external reference to '{uri}'").into())
+    }
+}
+
+struct Resolver<'a> {
+    root: &'a Json,
+    loader: &'a mut dyn Loader,
+    documents: Map<Key,Json>,
+    /// References being expanded, to detect cycles
+    stack: Vec<String>,
+}
+
+impl Resolver<'_> {
+    fn target(&mut self, base: &str, reference: &str) -> Result<(Box<str>,Json)> {
+        let (uri, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+        let uri = if uri.is_empty() { base } else { uri };
+        let ptr = JsonPointer::parse(fragment)?;
+
+        let doc = if uri.is_empty() {
+            self.root
+        } else {
+            if !self.documents.contains_key(uri) {
+                let doc = self.loader.load(uri)?;
+                self.documents.insert(uri.into(), doc);
+            }
+            &self.documents[uri]
+        };
+        let target = ptr.resolve(doc)
+                        .ok_or_else(|| format!("Reference '{reference}' doesn't point to any value"))?;
+        Ok((uri.into(), target.clone()))
+    }
+    fn expand(&mut self, json: &Json, base: &str) -> Result<Json> {
+        match json {
+            Json::Object(obj) => {
+                if let Some(reference) = obj.get(REF_KEY) {
+                    let reference = reference.string().ok_or("'$ref' must be a string")?;
+                    let (uri, target) = self.target(base, reference)?;
+                    let id = format!("{uri}#{}", reference.split_once('#').map_or("", |(_,f)| f));
+                    if self.stack.contains(&id) {
+                        return Err(format!("Cyclic reference '{reference}'").into());
+                    }
+                    self.stack.push(id);
+                    let expanded = self.expand(&target, &uri);
+                    self.stack.pop();
+                    return expanded;
+                }
+                let mut map = Map::default();
+                for (k,v) in obj {
+                    map.insert(k.clone(), self.expand(v, base)?);
+                }
+                Ok(Json::Object(map))
+            },
+            Json::Array(arr) => {
+                arr.iter().map(|e| self.expand(e, base)).collect::<Result<Vec<_>>>().map(Json::from)
+            },
+            other => Ok(other.clone()),
+        }
+    }
+}
+
+impl Json {
+    /// Returns a copy of this document with all the internal
+    /// `$ref`s replaced by the values they reference.
+    ///
+    /// Fails if there's an external or a cyclic reference.
+    pub fn resolve_refs(&self) -> Result<Json> {
+        self.resolve_refs_with(&mut NoLoader)
+    }
+    /// Same as [`resolve_refs`](Self::resolve_refs), but loading
+    /// external references with the given [Loader]
+    pub fn resolve_refs_with(&self, loader: &mut dyn Loader) -> Result<Json> {
+        Resolver {
+            root: self,
+            loader,
+            documents: Map::default(),
+            stack: Vec::new(),
+        }.expand(self, "")
+    }
+}
@@ -0,0 +1,290 @@
+//! JSON-RPC 2.0 messages
+//!
+//! # Example
+//! ```
+//! use json::rpc::{self, Incoming, Message, Response};
+//! use json::json;
+//!
+//! let msg = rpc::parse(r#"{"jsonrpc": "2.0", "method": "sum", "params": [1, 2], "id": 1}"#);
+//! let Incoming::Single(Ok(Message::Request(req))) = msg else { panic!() };
+//! assert_eq!(&*req.method, "sum");
+//!
+//! let resp = Response::success(req.id, json!(3));
+//! assert_eq!(json::Json::from(resp), json!({ "jsonrpc" : "2.0", "result" : 3, "id" : 1 }));
+//! ```
+
+use crate::escape::{escape, unescape};
+use crate::prelude::*;
+use crate::{Json, Number};
+
+/// Invalid JSON was received by the server
+pub const PARSE_ERROR: i64 = -32700;
+/// The JSON sent is not a valid Request object
+pub const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist / is not available
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// Invalid method parameter(s)
+pub const INVALID_PARAMS: i64 = -32602;
+/// Internal JSON-RPC error
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// Identifier of a request
+#[derive(Debug,Clone,PartialEq)]
+pub enum Id {
+    Number(f64),
+    String(Box<str>),
+    Null,
+}
+
+/// A call that expects a [Response]
+#[derive(Debug,PartialEq)]
+pub struct Request {
+    pub method: Box<str>,
+    /// Either an Array or an Object, if present
+    pub params: Option<Json>,
+    pub id: Id,
+}
+
+/// A call that doesn't expect a [Response]
+#[derive(Debug,PartialEq)]
+pub struct Notification {
+    pub method: Box<str>,
+    /// Either an Array or an Object, if present
+    pub params: Option<Json>,
+}
+
+/// Error object of a failed [Response]
+#[derive(Debug,PartialEq)]
+pub struct Error {
+    pub code: i64,
+    pub message: Box<str>,
+    pub data: Option<Json>,
+}
+
+/// Reply to a [Request]
+#[derive(Debug,PartialEq)]
+pub struct Response {
+    pub id: Id,
+    pub result: Result<Json,Error>,
+}
+
+/// Any JSON-RPC message
+#[derive(Debug,PartialEq)]
+pub enum Message {
+    Request(Request),
+    Notification(Notification),
+    Response(Response),
+}
+
+/// The result of [parsing](parse) an incoming payload
+#[derive(Debug,PartialEq)]
+pub enum Incoming {
+    Single(Result<Message,Error>),
+    /// A batch. Each element is validated independently.
+    Batch(Vec<Result<Message,Error>>),
+}
+
+impl Error {
+    pub fn new(code: i64, message: impl Into<Box<str>>) -> Self {
+        Self { code, message: message.into(), data: None }
+    }
+    pub fn parse_error() -> Self { Self::new(PARSE_ERROR, "Parse error") }
+    pub fn invalid_request() -> Self { Self::new(INVALID_REQUEST, "Invalid Request") }
+    pub fn method_not_found() -> Self { Self::new(METHOD_NOT_FOUND, "Method not found") }
+    pub fn invalid_params() -> Self { Self::new(INVALID_PARAMS, "Invalid params") }
+    pub fn internal_error() -> Self { Self::new(INTERNAL_ERROR, "Internal error") }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]: {}", self.code, self.message)
+    }
+}
+
+impl core::error::Error for Error { }
+
+impl Response {
+    pub fn success(id: Id, result: Json) -> Self {
+        Self { id, result: Ok(result) }
+    }
+    pub fn error(id: Id, error: Error) -> Self {
+        Self { id, result: Err(error) }
+    }
+}
+
+impl Request {
+    /// Builds the [Response] to this request
+    pub fn respond(&self, result: Result<Json,Error>) -> Response {
+        Response { id: self.id.clone(), result }
+    }
+}
+
+fn take_id(obj: &mut Map<Key,Json>) -> Result<Option<Id>,Error> {
+    let id = match remove_key(obj, "id") {
+        None => return Ok(None),
+        Some(Json::Number(n)) => Id::Number(to_f64(n)),
+        Some(Json::Null) => Id::Null,
+        Some(json) => Id::String(json.into_string().map_err(|_| Error::invalid_request())?),
+    };
+    Ok(Some(id))
+}
+
+fn take_params(obj: &mut Map<Key,Json>) -> Result<Option<Json>,Error> {
+    match remove_key(obj, "params") {
+        None => Ok(None),
+        Some(p @ (Json::Array(_) | Json::Object(_))) => Ok(Some(p)),
+        Some(_) => Err(Error::invalid_request()),
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn take_error(json: Json) -> Result<Error,Error> {
+    let Ok(mut obj) = json.into_object() else {
+        return Err(Error::invalid_request());
+    };
+    let code = match remove_key(&mut obj, "code") {
+        Some(Json::Number(n)) if fract(to_f64(n)) == 0.0 => n as i64,
+        _ => return Err(Error::invalid_request()),
+    };
+    let Some(Ok(message)) = remove_key(&mut obj, "message").map(Json::into_string) else {
+        return Err(Error::invalid_request());
+    };
+    let message = unescape(&message).into();
+    Ok(Error { code, message, data: remove_key(&mut obj, "data") })
+}
+
+impl TryFrom<Json> for Message {
+    type Error = Error;
+
+    /// Validates the given [Json] as a JSON-RPC 2.0 message
+    fn try_from(json: Json) -> Result<Self,Error> {
+        let Ok(mut obj) = json.into_object() else {
+            return Err(Error::invalid_request());
+        };
+        if remove_key(&mut obj, "jsonrpc").as_ref().and_then(Json::string) != Some("2.0") {
+            return Err(Error::invalid_request());
+        }
+        let id = take_id(&mut obj)?;
+
+        if let Some(method) = remove_key(&mut obj, "method") {
+            let Ok(method) = method.into_string() else {
+                return Err(Error::invalid_request());
+            };
+            let method: Box<str> = unescape(&method).into();
+            let params = take_params(&mut obj)?;
+            return Ok(match id {
+                Some(id) => Message::Request(Request { method, params, id }),
+                None => Message::Notification(Notification { method, params }),
+            })
+        }
+
+        let id = id.ok_or_else(Error::invalid_request)?;
+        let result = match (remove_key(&mut obj, "result"), remove_key(&mut obj, "error")) {
+            (Some(result), None) => Ok(result),
+            (None, Some(error)) => Err(take_error(error)?),
+            _ => return Err(Error::invalid_request()),
+        };
+        Ok(Message::Response(Response { id, result }))
+    }
+}
+
+/// Parses and validates an incoming JSON-RPC payload
+///
+/// Parse errors are reported as a single [`PARSE_ERROR`],
+/// and an empty batch as a single [`INVALID_REQUEST`].
+pub fn parse(text: impl AsRef<str>) -> Incoming {
+    match Json::deserialize(text) {
+        Err(_) => Incoming::Single(Err(Error::parse_error())),
+        Ok(json) => match json.into_array() {
+            Ok(elems) if elems.is_empty() => Incoming::Single(Err(Error::invalid_request())),
+            Ok(elems) => Incoming::Batch(elems.into_iter().map(Message::try_from).collect()),
+            Err(json) => Incoming::Single(Message::try_from(json)),
+        },
+    }
+}
+
+fn envelope() -> Map<Key,Json> {
+    let mut map = Map::default();
+    map.insert("jsonrpc".into(), "2.0".into());
+    map
+}
+
+impl From<Id> for Json {
+    fn from(value: Id) -> Self {
+        match value {
+            Id::Number(n) => Json::Number(from_f64(n)),
+            Id::String(s) => Json::String(s),
+            Id::Null => Json::Null,
+        }
+    }
+}
+
+impl From<Request> for Json {
+    fn from(value: Request) -> Self {
+        let mut map = envelope();
+        map.insert("method".into(), Json::String(escape(&value.method).into()));
+        if let Some(params) = value.params {
+            map.insert("params".into(), params);
+        }
+        map.insert("id".into(), value.id.into());
+        Json::Object(map)
+    }
+}
+
+impl From<Notification> for Json {
+    fn from(value: Notification) -> Self {
+        let mut map = envelope();
+        map.insert("method".into(), Json::String(escape(&value.method).into()));
+        if let Some(params) = value.params {
+            map.insert("params".into(), params);
+        }
+        Json::Object(map)
+    }
+}
+
+impl From<Error> for Json {
+    fn from(value: Error) -> Self {
+        let mut map = Map::default();
+        #[allow(clippy::cast_precision_loss)]
+        map.insert("code".into(), Json::Number(value.code as Number));
+        map.insert("message".into(), Json::String(escape(&value.message).into()));
+        if let Some(data) = value.data {
+            map.insert("data".into(), data);
+        }
+        Json::Object(map)
+    }
+}
+
+impl From<Response> for Json {
+    fn from(value: Response) -> Self {
+        let mut map = envelope();
+        match value.result {
+            Ok(result) => map.insert("result".into(), result),
+            Err(error) => map.insert("error".into(), error.into()),
+        };
+        map.insert("id".into(), value.id.into());
+        Json::Object(map)
+    }
+}
+
+impl From<Message> for Json {
+    fn from(value: Message) -> Self {
+        match value {
+            Message::Request(r) => r.into(),
+            Message::Notification(n) => n.into(),
+            Message::Response(r) => r.into(),
+        }
+    }
+}
+
+/// Builds the reply to a batch of responses.
+///
+/// Returns None if there's nothing to reply,
+/// which happens when the batch only had notifications.
+pub fn batch_response(responses: Vec<Response>) -> Option<Json> {
+    if responses.is_empty() {
+        return None
+    }
+    let elems: Vec<Json> = responses.into_iter().map(Json::from).collect();
+    Some(elems.into())
+}
@@ -0,0 +1,700 @@
+//! JSON Schema validation
+//!
+//! Supports a subset of the draft 2020-12 vocabulary: `type`, `enum`,
+//! `const`, `minimum`, `maximum`, `exclusiveMinimum`, `exclusiveMaximum`,
+//! `multipleOf`, `minLength`, `maxLength`, `properties`, `required`,
+//! `additionalProperties`, `items`, `minItems`, `maxItems`, `allOf`,
+//! `anyOf`, `oneOf` and `not`. Unknown keywords are ignored.
+//!
+//! Big documents can be validated while they are parsed, without
+//! building them, with [`Schema::validate_reader`] or a [`StreamValidator`].
+//!
+//! # Example
+//! ```
+//! use json::{json, Json};
+//! use json::schema::Schema;
+//!
+//! let schema = Schema::compile(&json!({
+//!     "type" : "object",
+//!     "properties" : {
+//!         "port" : { "type" : "integer", "minimum" : 1, "maximum" : 65535 }
+//!     },
+//!     "required" : ["port"]
+//! })).unwrap();
+//!
+//! assert!(schema.is_valid(&json!({ "port" : 8080 })));
+//!
+//! let errors = schema.validate(&json!({ "port" : 0 })).unwrap_err();
+//! assert_eq!(&*errors[0].path, "/port");
+//! ```
+
+use crate::escape::unescape;
+use crate::events::Event;
+#[cfg(feature = "std")]
+use crate::events::EventParser;
+use crate::prelude::*;
+use crate::random::Rng;
+use crate::{Json, Result};
+
+#[derive(Debug,Clone,Copy,PartialEq)]
+enum Type {
+    Null, Boolean, Integer, Number, String, Array, Object,
+}
+
+impl Type {
+    fn parse(s: &str) -> Result<Type> {
+        Ok(match s {
+            "null" => Type::Null,
+            "boolean" => Type::Boolean,
+            "integer" => Type::Integer,
+            "number" => Type::Number,
+            "string" => Type::String,
+            "array" => Type::Array,
+            "object" => Type::Object,
+            _ => return Err(format!("Unknown type '{s}'").into()),
+        })
+    }
+    fn name(self) -> &'static str {
+        match self {
+            Type::Null => "null",
+            Type::Boolean => "boolean",
+            Type::Integer => "integer",
+            Type::Number => "number",
+            Type::String => "string",
+            Type::Array => "array",
+            Type::Object => "object",
+        }
+    }
+    fn matches(self, json: &Json) -> bool {
+        match (self, json) {
+            (Type::Null, Json::Null) |
+            (Type::Boolean, Json::True | Json::False) |
+            (Type::Number, Json::Number(_)) |
+            (Type::String, Json::String(_)) |
+            (Type::Array, Json::Array(_)) |
+            (Type::Object, Json::Object(_)) => true,
+            (Type::Integer, Json::Number(n)) => fract(to_f64(*n)) == 0.0,
+            #[cfg(feature = "decimal")]
+            (Type::Number, Json::Decimal(_)) => true,
+            #[cfg(feature = "decimal")]
+            (Type::Integer, Json::Decimal(n)) => n.is_integer(),
+            #[cfg(feature = "bigint")]
+            (Type::Number | Type::Integer, Json::BigInt(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A compiled JSON Schema
+#[derive(Debug,Clone,Default)]
+pub struct Schema {
+    /// Some for the `true` and `false` schemas
+    always: Option<bool>,
+    types: Vec<Type>,
+    enumeration: Option<Vec<Json>>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    exclusive_minimum: Option<f64>,
+    exclusive_maximum: Option<f64>,
+    multiple_of: Option<f64>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    properties: Map<Key,Schema>,
+    required: Vec<Key>,
+    additional_properties: Option<Box<Schema>>,
+    items: Option<Box<Schema>>,
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    all_of: Vec<Schema>,
+    any_of: Vec<Schema>,
+    one_of: Vec<Schema>,
+    not: Option<Box<Schema>>,
+}
+
+/// An error found while [validating](Schema::validate) a document
+#[derive(Debug,Clone,PartialEq)]
+pub struct ValidationError {
+    /// JSON pointer to the invalid value
+    pub path: Box<str>,
+    pub message: Box<str>,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "/" } else { &self.path };
+        write!(f, "{path}: {}", self.message)
+    }
+}
+
+fn number(json: &Json, keyword: &str) -> Result<f64> {
+    json.number().ok_or_else(|| format!("'{keyword}' must be a number").into())
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn count(json: &Json, keyword: &str) -> Result<usize> {
+    match json.number() {
+        Some(n) if n >= 0.0 && fract(n) == 0.0 => Ok(n as usize),
+        _ => Err(format!("'{keyword}' must be a non-negative integer").into()),
+    }
+}
+
+fn schemas(json: &Json, keyword: &str) -> Result<Vec<Schema>> {
+    let arr = json.array().ok_or_else(|| format!("'{keyword}' must be an array"))?;
+    arr.iter().map(Schema::compile).collect()
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn char_count(s: &str) -> usize {
+    unescape(s).chars().count()
+}
+
+impl Schema {
+    /// Compiles the given schema document
+    pub fn compile(json: &Json) -> Result<Schema> {
+        let obj = match json {
+            Json::True => return Ok(Schema { always: Some(true), ..Default::default() }),
+            Json::False => return Ok(Schema { always: Some(false), ..Default::default() }),
+            Json::Object(obj) => obj,
+            _ => return Err("A schema must be an object or a boolean".into()),
+        };
+
+        let mut schema = Schema::default();
+        for (k,v) in obj {
+            match &**k {
+                "type" => {
+                    schema.types = match v {
+                        Json::String(s) => vec![Type::parse(s)?],
+                        Json::Array(arr) => arr.iter().map(|t| {
+                            t.string().ok_or("'type' must contain strings".into()).and_then(Type::parse)
+                        }).collect::<Result<_>>()?,
+                        _ => return Err("'type' must be a string or an array".into()),
+                    };
+                },
+                "enum" => {
+                    let arr = v.array().ok_or("'enum' must be an array")?;
+                    schema.enumeration = Some(arr.to_vec());
+                },
+                "const" => schema.enumeration = Some(vec![v.clone()]),
+                "minimum" => schema.minimum = Some(number(v, k)?),
+                "maximum" => schema.maximum = Some(number(v, k)?),
+                "exclusiveMinimum" => schema.exclusive_minimum = Some(number(v, k)?),
+                "exclusiveMaximum" => schema.exclusive_maximum = Some(number(v, k)?),
+                "multipleOf" => {
+                    let n = number(v, k)?;
+                    if n <= 0.0 {
+                        return Err("'multipleOf' must be greater than 0".into());
+                    }
+                    schema.multiple_of = Some(n);
+                },
+                "minLength" => schema.min_length = Some(count(v, k)?),
+                "maxLength" => schema.max_length = Some(count(v, k)?),
+                "minItems" => schema.min_items = Some(count(v, k)?),
+                "maxItems" => schema.max_items = Some(count(v, k)?),
+                "properties" => {
+                    let props = v.object().ok_or("'properties' must be an object")?;
+                    for (name, prop) in props {
+                        schema.properties.insert(name.clone(), Schema::compile(prop)?);
+                    }
+                },
+                "required" => {
+                    let arr = v.array().ok_or("'required' must be an array")?;
+                    schema.required = arr.iter().map(|r| {
+                        r.string().map(Key::from).ok_or_else(|| "'required' must contain strings".into())
+                    }).collect::<Result<_>>()?;
+                },
+                "additionalProperties" => schema.additional_properties = Some(Box::new(Schema::compile(v)?)),
+                "items" => schema.items = Some(Box::new(Schema::compile(v)?)),
+                "allOf" => schema.all_of = schemas(v, k)?,
+                "anyOf" => schema.any_of = schemas(v, k)?,
+                "oneOf" => schema.one_of = schemas(v, k)?,
+                "not" => schema.not = Some(Box::new(Schema::compile(v)?)),
+                _ => {},
+            }
+        }
+        Ok(schema)
+    }
+    /// Validates the given document, returning all the errors found
+    pub fn validate(&self, json: &Json) -> core::result::Result<(),Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        self.check(json, &mut String::new(), &mut errors);
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+    /// Returns true if the given document is valid
+    pub fn is_valid(&self, json: &Json) -> bool {
+        self.validate(json).is_ok()
+    }
+    /// Validates the document read from `reader` in a single pass, without
+    /// building it. Returns all the errors found, so an empty vector means
+    /// that the document is valid. Fails if it can't be read or parsed.
+    ///
+    /// The document is parsed by an [`EventParser`] with the given buffers,
+    /// which bound the length of its tokens and its depth. See
+    /// [`StreamValidator`] for the keywords that need to build values.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    /// use json::schema::Schema;
+    ///
+    /// let schema = Schema::compile(&json!({
+    ///     "type" : "array",
+    ///     "items" : { "type" : "object", "required" : ["id"] }
+    /// })).unwrap();
+    ///
+    /// let data = r#"[{ "id" : 1 }, { "name" : "x" }]"#;
+    /// let errors = schema.validate_reader(data.as_bytes(), &mut [0; 64], &mut [0; 8]).unwrap();
+    /// assert_eq!(errors[0].to_string(), "/1: missing required property 'id'");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn validate_reader(
+        &self,
+        mut reader: impl std::io::Read,
+        scratch: &mut [u8],
+        nesting: &mut [u8]
+    ) -> Result<Vec<ValidationError>> {
+        let mut parser = EventParser::new(scratch, nesting);
+        let mut validator = StreamValidator::new(self);
+        let mut buf = [0; 8 * 1024];
+        loop {
+            let mut chunk = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => &buf[..n],
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.to_string().into()),
+            };
+            while let Some(event) = parser.next_event(&mut chunk)? {
+                validator.event(event);
+            }
+        }
+        if let Some(event) = parser.finish()? {
+            validator.event(event);
+        }
+        Ok(validator.finish())
+    }
+    #[allow(clippy::too_many_lines, clippy::cast_precision_loss)]
+    fn check(&self, json: &Json, path: &mut String, errors: &mut Vec<ValidationError>) {
+        let mut error = |msg: String| errors.push(ValidationError {
+            path: path.as_str().into(),
+            message: msg.into(),
+        });
+
+        match self.always {
+            Some(true) => return,
+            Some(false) => return error("no value is allowed".into()),
+            None => {},
+        }
+        if !self.types.is_empty() && !self.types.iter().any(|t| t.matches(json)) {
+            let names: Vec<_> = self.types.iter().map(|t| t.name()).collect();
+            return error(format!("expected {}", names.join(" or ")));
+        }
+        if let Some(values) = &self.enumeration {
+            if !values.contains(json) {
+                error("value is not one of the allowed values".into());
+            }
+        }
+
+        #[cfg(feature = "bigint")]
+        let number = json.bigint().map(big_to_f64).or_else(|| json.number());
+        #[cfg(not(feature = "bigint"))]
+        let number = json.number();
+        if let Some(n) = number {
+            if self.minimum.is_some_and(|min| n < min) {
+                error(format!("{n} is less than the minimum of {}", self.minimum.unwrap()));
+            }
+            if self.maximum.is_some_and(|max| n > max) {
+                error(format!("{n} is greater than the maximum of {}", self.maximum.unwrap()));
+            }
+            if self.exclusive_minimum.is_some_and(|min| n <= min) {
+                error(format!("{n} must be greater than {}", self.exclusive_minimum.unwrap()));
+            }
+            if self.exclusive_maximum.is_some_and(|max| n >= max) {
+                error(format!("{n} must be less than {}", self.exclusive_maximum.unwrap()));
+            }
+            if let Some(m) = self.multiple_of {
+                let q = n / m;
+                if (q - round(q)).abs() > 1e-9 {
+                    error(format!("{n} is not a multiple of {m}"));
+                }
+            }
+        }
+
+        if let Json::String(s) = json {
+            let len = char_count(s);
+            if self.min_length.is_some_and(|min| len < min) {
+                error(format!("string is shorter than {} characters", self.min_length.unwrap()));
+            }
+            if self.max_length.is_some_and(|max| len > max) {
+                error(format!("string is longer than {} characters", self.max_length.unwrap()));
+            }
+        }
+
+        if let Json::Array(arr) = json {
+            if self.min_items.is_some_and(|min| arr.len() < min) {
+                error(format!("array has less than {} items", self.min_items.unwrap()));
+            }
+            if self.max_items.is_some_and(|max| arr.len() > max) {
+                error(format!("array has more than {} items", self.max_items.unwrap()));
+            }
+            if let Some(items) = &self.items {
+                for (i, e) in arr.iter().enumerate() {
+                    let len = path.len();
+                    write!(path, "/{i}").unwrap();
+                    items.check(e, path, errors);
+                    path.truncate(len);
+                }
+            }
+        }
+
+        if let Json::Object(obj) = json {
+            for req in &self.required {
+                if !obj.contains_key(req) {
+                    errors.push(ValidationError {
+                        path: path.as_str().into(),
+                        message: format!("missing required property '{req}'").into(),
+                    });
+                }
+            }
+            for (k, v) in obj {
+                let schema = match self.properties.get(k) {
+                    Some(s) => s,
+                    None => match &self.additional_properties {
+                        Some(s) => s,
+                        None => continue,
+                    }
+                };
+                let len = path.len();
+                path.push('/');
+                path.push_str(&escape_pointer(k));
+                schema.check(v, path, errors);
+                path.truncate(len);
+            }
+        }
+
+        for s in &self.all_of {
+            s.check(json, path, errors);
+        }
+        if !self.any_of.is_empty() && !self.any_of.iter().any(|s| s.is_valid(json)) {
+            errors.push(ValidationError {
+                path: path.as_str().into(),
+                message: "value doesn't match any of the schemas in 'anyOf'".into(),
+            });
+        }
+        if !self.one_of.is_empty() {
+            let n = self.one_of.iter().filter(|s| s.is_valid(json)).count();
+            if n != 1 {
+                errors.push(ValidationError {
+                    path: path.as_str().into(),
+                    message: format!("value matches {n} schemas in 'oneOf', instead of 1").into(),
+                });
+            }
+        }
+        if self.not.as_ref().is_some_and(|s| s.is_valid(json)) {
+            errors.push(ValidationError {
+                path: path.as_str().into(),
+                message: "value matches the schema in 'not'".into(),
+            });
+        }
+    }
+}
+
+const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Attempts made by [`Schema::sample`] before giving up
+const SAMPLE_ATTEMPTS: usize = 32;
+
+impl Schema {
+    /// Generates a random document that is valid against this schema
+    ///
+    /// Keywords like `allOf`, `oneOf` and `not` are handled by generating
+    /// candidates until one is valid, so for very restrictive combinations
+    /// the result may still be invalid. Unsatisfiable schemas produce `null`.
+    pub fn sample(&self, rng: &mut Rng) -> Json {
+        let mut candidate = self.sample_once(rng);
+        for _ in 1..SAMPLE_ATTEMPTS {
+            if self.is_valid(&candidate) { break }
+            candidate = self.sample_once(rng);
+        }
+        candidate
+    }
+    fn inferred_type(&self, rng: &mut Rng) -> Type {
+        if !self.types.is_empty() {
+            return self.types[rng.range(0..=self.types.len() - 1)];
+        }
+        if !self.properties.is_empty() || !self.required.is_empty() || self.additional_properties.is_some() {
+            Type::Object
+        } else if self.items.is_some() || self.min_items.is_some() || self.max_items.is_some() {
+            Type::Array
+        } else if self.min_length.is_some() || self.max_length.is_some() {
+            Type::String
+        } else if self.minimum.is_some() || self.maximum.is_some() || self.multiple_of.is_some()
+                  || self.exclusive_minimum.is_some() || self.exclusive_maximum.is_some() {
+            Type::Number
+        } else {
+            [Type::Null, Type::Boolean, Type::Integer, Type::String][rng.range(0..=3)]
+        }
+    }
+    fn sample_once(&self, rng: &mut Rng) -> Json {
+        if self.always == Some(false) {
+            return Json::Null
+        }
+        if let Some(values) = &self.enumeration {
+            if values.is_empty() { return Json::Null }
+            return values[rng.range(0..=values.len() - 1)].clone();
+        }
+        if self.types.is_empty() {
+            let branches = if self.one_of.is_empty() { &self.any_of } else { &self.one_of };
+            if !branches.is_empty() {
+                return branches[rng.range(0..=branches.len() - 1)].sample(rng);
+            }
+            if let Some(s) = self.all_of.first() {
+                return s.sample(rng);
+            }
+        }
+
+        match self.inferred_type(rng) {
+            Type::Null => Json::Null,
+            Type::Boolean => rng.chance(0.5).into(),
+            t @ (Type::Integer | Type::Number) => Json::Number(from_f64(self.sample_number(t == Type::Integer, rng))),
+            Type::String => {
+                let min = self.min_length.unwrap_or(0);
+                let max = self.max_length.unwrap_or(min + 8).max(min);
+                let len = rng.range(min..=max);
+                let s: String = (0..len).map(|_| CHARSET[rng.range(0..=CHARSET.len() - 1)] as char).collect();
+                s.into()
+            },
+            Type::Array => {
+                let min = self.min_items.unwrap_or(0);
+                let max = self.max_items.unwrap_or(min + 3).max(min);
+                let any = Schema::default();
+                let items = self.items.as_deref().unwrap_or(&any);
+                (0..rng.range(min..=max)).map(|_| items.sample(rng)).collect::<Vec<_>>().into()
+            },
+            Type::Object => {
+                let mut map = Map::default();
+                let any = Schema::default();
+                let additional = match self.additional_properties.as_deref() {
+                    Some(Schema { always: Some(false), .. }) | None => &any,
+                    Some(s) => s,
+                };
+                for req in &self.required {
+                    let schema = self.properties.get(req).unwrap_or(additional);
+                    map.insert(req.clone(), schema.sample(rng));
+                }
+                for (k, schema) in &self.properties {
+                    if !map.contains_key(k) && rng.chance(0.5) {
+                        map.insert(k.clone(), schema.sample(rng));
+                    }
+                }
+                Json::Object(map)
+            },
+        }
+    }
+    fn sample_number(&self, integer: bool, rng: &mut Rng) -> f64 {
+        let mut lo = self.minimum.into_iter().chain(self.exclusive_minimum).reduce(f64::max);
+        let mut hi = self.maximum.into_iter().chain(self.exclusive_maximum).reduce(f64::min);
+        if integer {
+            lo = lo.map(|n| if self.exclusive_minimum == Some(n) { floor(n) + 1.0 } else { ceil(n) });
+            hi = hi.map(|n| if self.exclusive_maximum == Some(n) { ceil(n) - 1.0 } else { floor(n) });
+        }
+        let (lo, hi) = match (lo, hi) {
+            (Some(lo), Some(hi)) => (lo, hi.max(lo)),
+            (Some(lo), None) => (lo, lo + 100.0),
+            (None, Some(hi)) => (hi - 100.0, hi),
+            (None, None) => (-100.0, 100.0),
+        };
+
+        if let Some(m) = self.multiple_of {
+            let kmin = ceil(lo / m);
+            let kmax = floor(hi / m).max(kmin);
+            let k = kmin + floor(rng.next_f64() * (kmax - kmin + 1.0)).min(kmax - kmin);
+            return k * m;
+        }
+        let n = lo + rng.next_f64() * (hi - lo);
+        if integer { round(n).clamp(lo, hi) } else { n }
+    }
+}
+
+/// Partial value of a container, built for the schemas that need it whole
+enum Partial {
+    Array(Vec<Json>),
+    Object(Map<Key,Json>),
+}
+
+/// A schema checked against the elements of a container as they are parsed
+struct Active<'s> {
+    schema: &'s Schema,
+    /// For objects, which of the required properties have been found
+    found: Vec<bool>,
+}
+
+/// An array or object being validated
+struct Level<'s> {
+    array: bool,
+    active: Vec<Active<'s>>,
+    /// If some schema needs the whole container, its value being built,
+    /// and the schemas to check against it once it's complete
+    build: Option<(Partial, Vec<&'s Schema>)>,
+    /// Number of elements or members found so far
+    len: usize,
+    /// Key of the member being parsed
+    key: Key,
+    /// Length of the path to this container
+    path_len: usize,
+}
+
+/// Validates a document against a [Schema] as it's parsed,
+/// without building it. See [`Schema::validate_reader`]
+///
+/// Most keywords are checked on the fly. The values checked against an
+/// `enum`, `const`, `anyOf`, `oneOf` or `not` keyword are built in memory,
+/// since those need the whole value. So, to keep the memory bounded, those
+/// keywords should only apply to small parts of the document.
+pub struct StreamValidator<'s> {
+    root: &'s Schema,
+    stack: Vec<Level<'s>>,
+    path: String,
+    errors: Vec<ValidationError>,
+}
+
+impl<'s> StreamValidator<'s> {
+    /// Builds a validator against the given schema
+    pub fn new(schema: &'s Schema) -> Self {
+        Self { root: schema, stack: Vec::new(), path: String::new(), errors: Vec::new() }
+    }
+    /// Validates the next event of the document
+    pub fn event(&mut self, event: Event<'_>) {
+        let json = match event {
+            Event::Key(k) => {
+                if let Some(top) = self.stack.last_mut() {
+                    for a in &mut top.active {
+                        if let Some(i) = a.schema.required.iter().position(|r| &**r == k) {
+                            a.found[i] = true;
+                        }
+                    }
+                    top.key = k.into();
+                }
+                return
+            },
+            Event::StartArray | Event::StartObject => {
+                let schemas = self.child_schemas();
+                return self.open(&schemas, event == Event::StartArray)
+            },
+            Event::EndArray | Event::EndObject => return self.close(),
+            Event::String(s) => Json::String(s.into()),
+            Event::Number(n) => Json::Number(from_f64(n)),
+            Event::True => Json::True,
+            Event::False => Json::False,
+            Event::Null => Json::Null,
+        };
+        for s in self.child_schemas() {
+            s.check(&json, &mut self.path, &mut self.errors);
+        }
+        self.add(json);
+    }
+    /// Returns all the errors found
+    pub fn finish(self) -> Vec<ValidationError> { self.errors }
+    /// Returns the schemas that apply to the next value, moving the path to it
+    fn child_schemas(&mut self) -> Vec<&'s Schema> {
+        let Some(top) = self.stack.last_mut() else {
+            return vec![self.root]
+        };
+        self.path.truncate(top.path_len);
+        if top.array {
+            write!(self.path, "/{}", top.len).unwrap();
+        } else {
+            self.path.push('/');
+            self.path.push_str(&escape_pointer(&top.key));
+        }
+        top.len += 1;
+        top.active.iter().filter_map(|a| {
+            if top.array {
+                a.schema.items.as_deref()
+            } else {
+                a.schema.properties.get(&top.key).or(a.schema.additional_properties.as_deref())
+            }
+        }).collect()
+    }
+    /// Adds a complete value to the container being built, if any
+    fn add(&mut self, json: Json) {
+        let Some(top) = self.stack.last_mut() else { return };
+        match &mut top.build {
+            Some((Partial::Array(arr), _)) => arr.push(json),
+            Some((Partial::Object(obj), _)) => { obj.insert(core::mem::take(&mut top.key), json); },
+            None => {},
+        }
+    }
+    fn open(&mut self, schemas: &[&'s Schema], array: bool) {
+        fn expand<'s>(schema: &'s Schema, active: &mut Vec<&'s Schema>, deferred: &mut Vec<&'s Schema>) {
+            if schema.enumeration.is_some() || !schema.any_of.is_empty()
+               || !schema.one_of.is_empty() || schema.not.is_some() {
+                deferred.push(schema);
+            } else {
+                active.push(schema);
+                for s in &schema.all_of {
+                    expand(s, active, deferred);
+                }
+            }
+        }
+        let (mut streamed, mut deferred) = (Vec::new(), Vec::new());
+        for s in schemas {
+            expand(s, &mut streamed, &mut deferred);
+        }
+        let t = if array { Type::Array } else { Type::Object };
+        let mut active = Vec::new();
+        for schema in streamed {
+            let msg = match schema.always {
+                Some(true) => continue,
+                Some(false) => "no value is allowed".into(),
+                None if !schema.types.is_empty() && !schema.types.contains(&t) => {
+                    let names: Vec<_> = schema.types.iter().map(|t| t.name()).collect();
+                    format!("expected {}", names.join(" or "))
+                },
+                None => {
+                    active.push(Active { schema, found: vec![false; schema.required.len()] });
+                    continue
+                },
+            };
+            self.errors.push(ValidationError { path: self.path.as_str().into(), message: msg.into() });
+        }
+        let building = self.stack.last().is_some_and(|l| l.build.is_some());
+        let build = (building || !deferred.is_empty()).then(|| {
+            let partial = if array { Partial::Array(Vec::new()) } else { Partial::Object(Map::default()) };
+            (partial, deferred)
+        });
+        self.stack.push(Level { array, active, build, len: 0, key: "".into(), path_len: self.path.len() });
+    }
+    fn close(&mut self) {
+        let Some(level) = self.stack.pop() else { return };
+        self.path.truncate(level.path_len);
+        let error = |msg: String| ValidationError { path: self.path.as_str().into(), message: msg.into() };
+        for a in &level.active {
+            let s = a.schema;
+            if !level.array {
+                for (req, _) in s.required.iter().zip(&a.found).filter(|(_, found)| !**found) {
+                    self.errors.push(error(format!("missing required property '{req}'")));
+                }
+            }
+            if level.array {
+                if s.min_items.is_some_and(|min| level.len < min) {
+                    self.errors.push(error(format!("array has less than {} items", s.min_items.unwrap())));
+                }
+                if s.max_items.is_some_and(|max| level.len > max) {
+                    self.errors.push(error(format!("array has more than {} items", s.max_items.unwrap())));
+                }
+            }
+        }
+        if let Some((partial, deferred)) = level.build {
+            let json = match partial {
+                Partial::Array(arr) => arr.into(),
+                Partial::Object(obj) => Json::Object(obj),
+            };
+            for s in deferred {
+                s.check(&json, &mut self.path, &mut self.errors);
+            }
+            self.add(json);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! JSON text sequences (RFC 7464)
+//!
+//! An `application/json-seq` stream is a sequence of records, each one
+//! made of an ASCII Record Separator (0x1E), a JSON text, and a line feed.
+//!
+//! # Example
+//! ```
+//! use json::seq::{SeqReader, SeqWriter};
+//! use json::json;
+//!
+//! let mut writer = SeqWriter::new(Vec::new());
+//! writer.write(&json!({ "level" : "info" })).unwrap();
+//! writer.write(&json!([1, 2])).unwrap();
+//! let bytes = writer.into_inner();
+//! assert_eq!(bytes, b"\x1e{\"level\":\"info\"}\n\x1e[1,2]\n");
+//!
+//! let records: Vec<_> = SeqReader::new(&bytes[..]).map(Result::unwrap).collect();
+//! assert_eq!(records, [json!({ "level" : "info" }), json!([1, 2])]);
+//! ```
+
+use std::io::{Read, Write};
+
+use crate::prelude::*;
+use crate::{Json, Result};
+
+/// The ASCII Record Separator, which starts every record
+pub const RS: u8 = 0x1E;
+
+/// Number of bytes requested to the reader at a time
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Iterator over the records of a JSON text sequence
+///
+/// A corrupt record yields an error, and the iterator resynchronizes at the
+/// next record separator, so the following records can still be read. Only
+/// I/O errors end the iteration.
+///
+/// Empty records are skipped. Numbers, `true`, `false` and `null` must be
+/// followed by whitespace. Otherwise, the record may have been truncated,
+/// and it's reported as an error.
+pub struct SeqReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    /// Bytes of buf already known not to hold a separator
+    scanned: usize,
+    eof: bool,
+    done: bool,
+}
+
+/// Parses the text of a record, without its separator
+fn record(text: &[u8]) -> Result<Json> {
+    let text = core::str::from_utf8(text).map_err(|e| e.to_string())?;
+    let json = Json::deserialize(text)?;
+    let scalar = !matches!(json, Json::Array(_) | Json::Object(_) | Json::String(_));
+    if scalar && !text.ends_with(|c: char| c.is_ascii_whitespace()) {
+        return Err("Truncated record".into())
+    }
+    Ok(json)
+}
+
+impl<R: Read> SeqReader<R> {
+    /// Builds a reader of the records in `reader`
+    pub fn new(reader: R) -> Self {
+        Self { reader, buf: Vec::new(), scanned: 0, eof: false, done: false }
+    }
+    /// Reads a new chunk. Returns false if the reader has reached EOF.
+    fn fill(&mut self) -> Result<bool> {
+        let len = self.buf.len();
+        self.buf.resize(len + CHUNK_SIZE, 0);
+        let n = loop {
+            match self.reader.read(&mut self.buf[len..]) {
+                Ok(n) => break n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {},
+                Err(e) => {
+                    self.buf.truncate(len);
+                    return Err(e.to_string().into())
+                },
+            }
+        };
+        self.buf.truncate(len + n);
+        self.eof = n == 0;
+        Ok(!self.eof)
+    }
+    /// Removes the next record from the buffer, including its separator.
+    /// Returns `None` at the end of the stream.
+    fn next_record(&mut self) -> Result<Option<Vec<u8>>> {
+        loop {
+            /* The first byte is the separator of the current record */
+            let from = self.scanned.max(1);
+            if let Some(i) = self.buf.get(from..).and_then(|b| b.iter().position(|&b| b == RS)) {
+                self.scanned = 0;
+                return Ok(Some(self.buf.drain(..from + i).collect()))
+            }
+            self.scanned = self.buf.len();
+            if self.eof || !self.fill()? {
+                self.scanned = 0;
+                return Ok((!self.buf.is_empty()).then(|| core::mem::take(&mut self.buf)))
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for SeqReader<R> {
+    type Item = Result<Json>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let rec = match self.next_record() {
+                Ok(Some(rec)) => rec,
+                Ok(None) => break,
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err))
+                },
+            };
+            let text = match rec.split_first() {
+                Some((&RS, text)) => text,
+                _ => &rec[..],
+            };
+            if text.iter().all(u8::is_ascii_whitespace) {
+                continue
+            }
+            if rec[0] != RS {
+                return Some(Err("Expected a record separator".into()))
+            }
+            return Some(record(text))
+        }
+        self.done = true;
+        None
+    }
+}
+
+/// Writes records of a JSON text sequence
+pub struct SeqWriter<W> {
+    writer: W,
+    buf: String,
+}
+
+impl<W: Write> SeqWriter<W> {
+    /// Builds a writer of records into `writer`
+    pub fn new(writer: W) -> Self {
+        Self { writer, buf: String::new() }
+    }
+    /// Writes `json` as a record, in compact form
+    pub fn write(&mut self, json: &Json) -> Result<()> {
+        self.buf.clear();
+        self.buf.push(char::from(RS));
+        json.serialize(&mut self.buf)?;
+        self.buf.push('\n');
+        self.writer.write_all(self.buf.as_bytes()).map_err(|e| e.to_string().into())
+    }
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W { self.writer }
+}
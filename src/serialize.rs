@@ -0,0 +1,539 @@
+//! Serialization
+
+use core::iter::Enumerate;
+use core::slice;
+
+use crate::color::RESET;
+use crate::escape::unescape;
+use crate::prelude::*;
+use crate::{ColorScheme, Json, JsonPointer, Redaction, Result};
+
+type MapIter<'j> = <&'j Map<Key,Json> as IntoIterator>::IntoIter;
+
+/// What to do with numbers that can't be represented in JSON (NaN and ±Infinity)
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum NonFinite {
+    /// Fail the serialization
+    Error,
+    /// Write them as `null`
+    #[default]
+    AsNull,
+    /// Write them as the strings `"NaN"`, `"Infinity"` and `"-Infinity"`
+    AsString,
+}
+
+/// Syntax of the serialized output
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub enum Syntax {
+    /// Standard JSON
+    #[default]
+    Json,
+    /// [JSON5](https://json5.org), meant for human-edited files:
+    /// - Keys that are identifiers, like `name` or `_id`, are not quoted
+    /// - Strings use single quotes
+    /// - Arrays and objects have trailing commas, if the output is indented
+    /// - NaN and ±Infinity are written as `NaN`, `Infinity` and `-Infinity`,
+    ///   regardless of [`SerializeConfig::non_finite`]
+    Json5,
+}
+
+/// Returns true if `key` can be written unquoted in JSON5
+pub(crate) fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Writes a string, which is already escaped, between quotes
+pub(crate) fn write_string(s: &str, syntax: Syntax, escape_non_ascii: bool, out: &mut dyn Write) -> fmt::Result {
+    let json5 = syntax == Syntax::Json5;
+    let quote = if json5 { '\'' } else { '"' };
+    out.write_char(quote)?;
+    if json5 || (escape_non_ascii && !s.is_ascii()) {
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('"') if json5 => out.write_char('"')?,
+                    Some(c) => {
+                        out.write_char('\\')?;
+                        out.write_char(c)?;
+                    },
+                    None => out.write_char('\\')?,
+                },
+                '\'' if json5 => out.write_str("\\'")?,
+                c if c.is_ascii() || !escape_non_ascii => out.write_char(c)?,
+                c => {
+                    let mut buf = [0_u16; 2];
+                    for unit in c.encode_utf16(&mut buf) {
+                        write!(out, "\\u{unit:04x}")?;
+                    }
+                },
+            }
+        }
+    } else {
+        out.write_str(s)?;
+    }
+    out.write_char(quote)
+}
+
+/// Configures the JSON serializer
+#[derive(Debug,Clone,Default)]
+pub struct SerializeConfig<'a> {
+    /// If set, each array element and object member is written
+    /// on its own line, indented by this string for each level of
+    /// nesting. Otherwise, the output is compact.
+    pub indent: Option<&'a str>,
+    /// If set, together with `indent`, arrays and objects that fit on
+    /// the rest of their line, up to this column, are written on a
+    /// single line, like `[1, 2, 3]`. The others are expanded as usual.
+    pub max_width: Option<usize>,
+    /// Policy for NaN and ±Infinity
+    pub non_finite: NonFinite,
+    /// Write all non-ASCII characters as `\uXXXX` escapes,
+    /// using surrogate pairs outside the Basic Multilingual Plane
+    pub escape_non_ascii: bool,
+    /// Write the members of objects sorted by their key,
+    /// instead of in the order they are stored
+    pub sort_keys: bool,
+    /// If set, the values matched by the [Redaction] are
+    /// replaced on the fly, without modifying the document
+    pub redaction: Option<&'a Redaction>,
+    /// If set, keys, strings, numbers and literals are
+    /// colored with the ANSI escape sequences of the scheme
+    pub colors: Option<&'a ColorScheme<'a>>,
+    /// If set, strings holding RFC 3339 datetimes are rewritten in UTC,
+    /// with this precision for the seconds. This makes the output
+    /// consistent for datetimes that come from different sources.
+    #[cfg(feature = "chrono")]
+    pub datetime_format: Option<chrono::SecondsFormat>,
+    /// Syntax of the output
+    pub syntax: Syntax,
+}
+
+struct Serializer<'a, 'w> {
+    conf: &'a SerializeConfig<'a>,
+    out: &'w mut dyn Write,
+    /// Path to the current value. Only tracked when redacting.
+    path: JsonPointer,
+    /// Column where the current value starts. Only
+    /// tracked when [`SerializeConfig::max_width`] is set.
+    column: usize,
+    /// Nesting level of the container that is being written on
+    /// a single line, if any. See [`SerializeConfig::max_width`]
+    inline: Option<usize>,
+    /// See [`SerializeConfig::colors`]
+    colors: Option<&'a ColorScheme<'a>>,
+}
+
+/// Counts the columns written, failing when there are no more left
+struct Columns {
+    left: usize,
+}
+
+impl Write for Columns {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.left = self.left.checked_sub(s.chars().count()).ok_or(fmt::Error)?;
+        Ok(())
+    }
+}
+
+impl<'a> Serializer<'a, '_> {
+    fn new<'w>(conf: &'a SerializeConfig<'a>, out: &'w mut dyn Write) -> Serializer<'a, 'w> {
+        Serializer { conf, out, path: JsonPointer::root(), column: 0, inline: None, colors: conf.colors }
+    }
+    /// Returns the number of columns that `f` writes,
+    /// or `None` if it needs more than `max`
+    fn width(&self, max: usize, f: impl FnOnce(&mut Serializer<'a, '_>) -> Result<()>) -> Option<usize> {
+        let mut cols = Columns { left: max };
+        let mut ser = Serializer::new(self.conf, &mut cols);
+        ser.inline = Some(0);
+        ser.colors = None;
+        if self.conf.redaction.is_some() {
+            ser.path = self.path.clone();
+        }
+        f(&mut ser).ok()?;
+        Some(max - cols.left)
+    }
+    /// Returns true if `json` fits on a single line
+    fn fits(&self, json: &Json) -> bool {
+        let Some(max) = self.conf.max_width else { return false };
+        self.width(max.saturating_sub(self.column), |ser| ser.value(json)).is_some()
+    }
+    fn newline(&mut self, level: usize) -> Result<()> {
+        if self.inline.is_some() {
+            return Ok(())
+        }
+        if let Some(indent) = self.conf.indent {
+            self.out.write_char('\n')?;
+            for _ in 0..level {
+                self.out.write_str(indent)?;
+            }
+        }
+        Ok(())
+    }
+    /// Writes a token with `f`, colored with the escape sequence that
+    /// `color` selects from the [`ColorScheme`], if there's one
+    fn paint(&mut self, color: impl FnOnce(&ColorScheme<'a>) -> &'a str, f: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        let code = self.colors.map_or("", color);
+        if code.is_empty() {
+            return f(self)
+        }
+        self.out.write_str(code)?;
+        f(self)?;
+        self.out.write_str(RESET)?;
+        Ok(())
+    }
+    /// Writes a string, which is already escaped
+    fn string(&mut self, s: &str) -> Result<()> {
+        self.paint(|c| c.string, |ser| {
+            write_string(s, ser.conf.syntax, ser.conf.escape_non_ascii, ser.out)?;
+            Ok(())
+        })
+    }
+    fn key(&mut self, key: &str) -> Result<()> {
+        self.paint(|c| c.key, |ser| {
+            if ser.conf.syntax == Syntax::Json5 && is_identifier(key) {
+                ser.out.write_str(key)?;
+            } else {
+                write_string(key, ser.conf.syntax, ser.conf.escape_non_ascii, ser.out)?;
+            }
+            Ok(())
+        })
+    }
+    fn literal(&mut self, s: &str) -> Result<()> {
+        self.paint(|c| c.literal, |ser| Ok(ser.out.write_str(s)?))
+    }
+    fn number(&mut self, n: f64) -> Result<()> {
+        self.paint(|c| c.number, |ser| ser.write_number(n))
+    }
+    fn write_number(&mut self, n: f64) -> Result<()> {
+        if n.is_finite() {
+            write!(self.out, "{n}")?;
+            return Ok(())
+        }
+        if self.conf.syntax == Syntax::Json5 {
+            self.out.write_str(if n.is_nan() { "NaN" } else if n > 0.0 { "Infinity" } else { "-Infinity" })?;
+            return Ok(())
+        }
+        match self.conf.non_finite {
+            NonFinite::Error => return Err(format!("{n} can't be represented in JSON").into()),
+            NonFinite::AsNull => self.out.write_str("null")?,
+            NonFinite::AsString if n.is_nan() => self.out.write_str("\"NaN\"")?,
+            NonFinite::AsString if n > 0.0 => self.out.write_str("\"Infinity\"")?,
+            NonFinite::AsString => self.out.write_str("\"-Infinity\"")?,
+        }
+        Ok(())
+    }
+    /// Writes a string, normalizing it if it holds a datetime.
+    /// See [`SerializeConfig::datetime_format`]
+    #[cfg(feature = "chrono")]
+    fn datetime(&mut self, json: &Json) -> Result<()> {
+        match (json.as_datetime(), self.conf.datetime_format) {
+            (Some(dt), Some(precision)) => self.string(&crate::datetime::format(&dt, precision)),
+            _ => self.string(json.string().unwrap_or_default()),
+        }
+    }
+    /// Returns the value to serialize for the child of the current value at `token`
+    fn child<'j>(&mut self, token: impl FnOnce() -> Box<str>, json: &'j Json) -> &'j Json
+    where
+        'a: 'j
+    {
+        let Some(redaction) = self.conf.redaction else {
+            return json
+        };
+        self.path.push(token());
+        redaction.replacement(&self.path).unwrap_or(json)
+    }
+    /// Writes a scalar, or the opening bracket of an array or object.
+    /// Returns the [Frame] to serialize the elements of non-empty containers.
+    fn open<'j>(&mut self, json: &'j Json) -> Result<Option<Frame<'j>>> {
+        match json {
+            Json::Array(arr) if arr.is_empty() => self.out.write_str("[]")?,
+            Json::Object(obj) if obj.is_empty() => self.out.write_str("{}")?,
+            Json::Array(arr) => {
+                self.out.write_char('[')?;
+                return Ok(Some(Frame::Array(arr.iter().enumerate())))
+            },
+            Json::Object(obj) => {
+                self.out.write_char('{')?;
+                let members = if self.conf.sort_keys {
+                    let mut sorted: Vec<_> = obj.iter().map(|(k, v)| (&**k, v)).collect();
+                    sorted.sort_by_cached_key(|(k, _)| unescape(k));
+                    Members::Sorted(sorted.into_iter())
+                } else {
+                    Members::InOrder(obj.iter())
+                };
+                return Ok(Some(Frame::Object(members.enumerate())))
+            },
+            #[cfg(feature = "chrono")]
+            Json::String(_) if self.conf.datetime_format.is_some() => self.datetime(json)?,
+            Json::String(s) => self.string(s)?,
+            Json::Number(n) => self.number(to_f64(*n))?,
+            #[cfg(feature = "bigint")]
+            Json::BigInt(n) => self.paint(|c| c.number, |ser| Ok(write!(ser.out, "{n}")?))?,
+            #[cfg(feature = "decimal")]
+            Json::Decimal(n) => self.paint(|c| c.number, |ser| Ok(write!(ser.out, "{n}")?))?,
+            Json::True => self.literal("true")?,
+            Json::False => self.literal("false")?,
+            Json::Null => self.literal("null")?,
+        }
+        Ok(None)
+    }
+    /// Serializes a value. Instead of recursing for each nested array and
+    /// object, the containers being serialized are kept on an explicit stack,
+    /// so deeply nested documents can't overflow the call stack.
+    fn value<'j>(&mut self, json: &'j Json) -> Result<()>
+    where
+        'a: 'j
+    {
+        let mut stack: Vec<Frame<'j>> = Vec::new();
+        let mut next = Some(json);
+        loop {
+            if let Some(json) = next.take() {
+                if let Some(frame) = self.open(json)? {
+                    stack.push(frame);
+                    if self.inline.is_none() && self.conf.indent.is_some() && self.fits(json) {
+                        self.inline = Some(stack.len());
+                    }
+                }
+            }
+            let level = stack.len();
+            let Some(frame) = stack.last_mut() else {
+                return Ok(())
+            };
+            let child = match frame {
+                Frame::Array(iter) => iter.next().map(|(i, e)| (i, None, e)),
+                Frame::Object(iter) => iter.next().map(|(i, (k, v))| (i, Some(k), v)),
+            };
+            if self.conf.redaction.is_some() && !matches!(child, Some((0, ..))) {
+                /* Leave the previous element */
+                self.path.pop();
+            }
+            let Some((i, key, json)) = child else {
+                if self.conf.syntax == Syntax::Json5 && self.conf.indent.is_some() && self.inline.is_none() {
+                    self.out.write_char(',')?;
+                }
+                self.newline(level - 1)?;
+                self.out.write_char(if let Frame::Array(_) = frame { ']' } else { '}' })?;
+                if self.inline == Some(level) {
+                    self.inline = None;
+                }
+                stack.pop();
+                continue
+            };
+            if i > 0 {
+                self.out.write_char(',')?;
+                if self.inline.is_some() {
+                    self.out.write_char(' ')?;
+                }
+            }
+            self.newline(level)?;
+            if let (Some(indent), Some(_), None) = (self.conf.indent, self.conf.max_width, self.inline) {
+                self.column = level * indent.chars().count();
+                if let Some(key) = key {
+                    self.column += self.width(usize::MAX, |ser| ser.key(key)).unwrap_or(0) + 2;
+                }
+            }
+            if let Some(key) = key {
+                self.key(key)?;
+                self.out.write_char(':')?;
+                if self.conf.indent.is_some() {
+                    self.out.write_char(' ')?;
+                }
+            }
+            next = Some(self.child(|| key.map_or_else(|| i.to_string().into(), Box::from), json));
+        }
+    }
+}
+
+/// An array or object being serialized
+enum Frame<'j> {
+    Array(Enumerate<slice::Iter<'j, Json>>),
+    Object(Enumerate<Members<'j>>),
+}
+
+/// Members of an object, in the order they are serialized
+enum Members<'j> {
+    InOrder(MapIter<'j>),
+    /// See [`SerializeConfig::sort_keys`]
+    Sorted(alloc::vec::IntoIter<(&'j str, &'j Json)>),
+}
+
+impl<'j> Iterator for Members<'j> {
+    type Item = (&'j str, &'j Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Members::InOrder(iter) => iter.next().map(|(k, v)| (&**k, v)),
+            Members::Sorted(iter) => iter.next(),
+        }
+    }
+}
+
+/// Abbreviated [Display] of a [Json], returned by [`Json::display_truncated`]
+#[derive(Debug,Clone,Copy)]
+pub struct Truncated<'a> {
+    json: &'a Json,
+    max_depth: usize,
+    max_elems: usize,
+    max_str_len: usize,
+}
+
+impl Truncated<'_> {
+    fn fmt_value(&self, json: &Json, depth: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match json {
+            Json::Array(arr) if arr.is_empty() => f.write_str("[]"),
+            Json::Object(obj) if obj.is_empty() => f.write_str("{}"),
+            Json::Array(_) if depth >= self.max_depth => f.write_str("[…]"),
+            Json::Object(_) if depth >= self.max_depth => f.write_str("{…}"),
+            Json::Array(arr) => {
+                f.write_char('[')?;
+                for (i, e) in arr.iter().take(self.max_elems).enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    self.fmt_value(e, depth + 1, f)?;
+                }
+                self.fmt_rest(arr.len(), f)?;
+                f.write_char(']')
+            },
+            Json::Object(obj) => {
+                f.write_char('{')?;
+                for (i, (k, v)) in obj.iter().take(self.max_elems).enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    self.fmt_str(k, f)?;
+                    f.write_str(": ")?;
+                    self.fmt_value(v, depth + 1, f)?;
+                }
+                self.fmt_rest(obj.len(), f)?;
+                f.write_char('}')
+            },
+            Json::String(s) => self.fmt_str(s, f),
+            _ => json.serialize(f),
+        }
+    }
+    fn fmt_rest(&self, len: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if len > self.max_elems {
+            if self.max_elems > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "…({} more)", len - self.max_elems)?;
+        }
+        Ok(())
+    }
+    fn fmt_str(&self, s: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match s.char_indices().nth(self.max_str_len) {
+            Some((i, _)) => write!(f, "\"{}…\"", &s[..i]),
+            None => write!(f, "\"{s}\""),
+        }
+    }
+}
+
+impl Display for Truncated<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_value(self.json, 0, f)
+    }
+}
+
+impl Json {
+    /// Returns an abbreviated [Display] of this document, meant for logging.
+    ///
+    /// - Arrays and objects nested deeper than `max_depth` are elided as `[…]` and `{…}`
+    /// - Only the first `max_elems` elements of arrays and members of objects are shown,
+    ///   followed by the number of remaining ones
+    /// - Strings (and keys) longer than `max_str_len` characters are cut
+    ///
+    /// The output is not valid JSON.
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, Json};
+    ///
+    /// let j = Json::from((1..=1000).map(Json::from).collect::<Vec<_>>());
+    /// assert_eq!(j.display_truncated(2, 2, 10).to_string(), "[1, 2, …(998 more)]");
+    ///
+    /// let j = json!({ "name" : "a long name", "nested" : { "a" : 1 } });
+    /// assert_eq!(json!([j]).display_truncated(1, 5, 6).to_string(), "[{…}]");
+    /// ```
+    pub fn display_truncated(&self, max_depth: usize, max_elems: usize, max_str_len: usize) -> Truncated<'_> {
+        Truncated { json: self, max_depth, max_elems, max_str_len }
+    }
+    /// Serializes the JSON object into a `fmt::Write`
+    ///
+    /// NaN and ±Infinity are written as `null`.
+    pub fn serialize(&self, out: &mut dyn Write) -> fmt::Result {
+        self.serialize_with_config(&SerializeConfig::default(), out)
+            .map_err(|_| fmt::Error)
+    }
+    /// Serializes the JSON object into a `fmt::Write`, with each
+    /// array element and object member on its own line, indented
+    /// by `indent` for each level of nesting.
+    ///
+    /// The [Display] implementation uses this function, with two
+    /// spaces of indentation, when the alternate flag (`{:#}`) is set.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "a" : [1, {}] });
+    /// assert_eq!(format!("{j:#}"), "{\n  \"a\": [\n    1,\n    {}\n  ]\n}");
+    ///
+    /// let mut out = String::new();
+    /// json!([true]).serialize_pretty("\t", &mut out).unwrap();
+    /// assert_eq!(out, "[\n\ttrue\n]");
+    /// ```
+    pub fn serialize_pretty(&self, indent: &str, out: &mut dyn Write) -> fmt::Result {
+        let conf = SerializeConfig { indent: Some(indent), ..Default::default() };
+        self.serialize_with_config(&conf, out)
+            .map_err(|_| fmt::Error)
+    }
+    /// Serializes the JSON object into a `fmt::Write`
+    /// using the given [`SerializeConfig`]
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, NonFinite, SerializeConfig, Syntax};
+    ///
+    /// let conf = SerializeConfig { escape_non_ascii: true, ..Default::default() };
+    /// let mut out = String::new();
+    /// json!("ñ😀").serialize_with_config(&conf, &mut out).unwrap();
+    /// assert_eq!(out, r#""\u00f1\ud83d\ude00""#);
+    ///
+    /// let j = json!([f64::NAN, f64::NEG_INFINITY]);
+    ///
+    /// let conf = SerializeConfig { non_finite: NonFinite::AsString, ..Default::default() };
+    /// let mut out = String::new();
+    /// j.serialize_with_config(&conf, &mut out).unwrap();
+    /// assert_eq!(out, r#"["NaN","-Infinity"]"#);
+    ///
+    /// let conf = SerializeConfig { non_finite: NonFinite::Error, ..Default::default() };
+    /// assert!(j.serialize_with_config(&conf, &mut String::new()).is_err());
+    ///
+    /// let conf = SerializeConfig { sort_keys: true, ..Default::default() };
+    /// let mut out = String::new();
+    /// json!({ "b" : { "d" : 1, "c" : 2 }, "a" : 3 }).serialize_with_config(&conf, &mut out).unwrap();
+    /// assert_eq!(out, r#"{"a":3,"b":{"c":2,"d":1}}"#);
+    ///
+    /// let conf = SerializeConfig { syntax: Syntax::Json5, indent: Some("  "), ..Default::default() };
+    /// let mut out = String::new();
+    /// json!({ "name" : "it's", "a-b" : [1] }).serialize_with_config(&conf, &mut out).unwrap();
+    /// # #[cfg(feature = "preserve_order")]
+    /// assert_eq!(out, "{\n  name: 'it\\'s',\n  'a-b': [\n    1,\n  ],\n}");
+    ///
+    /// let conf = SerializeConfig { indent: Some("  "), max_width: Some(16), ..Default::default() };
+    /// let mut out = String::new();
+    /// json!([[1, 2, 3], [4, 5, 6]]).serialize_with_config(&conf, &mut out).unwrap();
+    /// assert_eq!(out, "[\n  [1, 2, 3],\n  [4, 5, 6]\n]");
+    /// ```
+    pub fn serialize_with_config(&self, conf: &SerializeConfig<'_>, out: &mut dyn Write) -> Result<()> {
+        let mut ser = Serializer::new(conf, out);
+        match conf.redaction.and_then(|r| r.replacement(&ser.path)) {
+            Some(r) => ser.value(r),
+            None => ser.value(self),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Set operations on arrays
+
+use alloc::collections::BTreeMap;
+
+use crate::prelude::*;
+use crate::{Json, Result};
+
+/// Set of values, compared by structural equality. Values are
+/// bucketed by their [content hash](Json::content_hash) first.
+#[derive(Default)]
+struct Set<'a>(BTreeMap<u64, Vec<&'a Json>>);
+
+impl<'a> Set<'a> {
+    fn new(values: &'a [Json]) -> Self {
+        let mut set = Set::default();
+        for v in values {
+            set.insert(v);
+        }
+        set
+    }
+    fn contains(&self, value: &Json) -> bool {
+        self.0.get(&value.content_hash()).is_some_and(|b| b.contains(&value))
+    }
+    /// Adds the value. Returns false if it was already in the set
+    fn insert(&mut self, value: &'a Json) -> bool {
+        let bucket = self.0.entry(value.content_hash()).or_default();
+        if bucket.contains(&value) {
+            return false
+        }
+        bucket.push(value);
+        true
+    }
+}
+
+/// Returns the elements of `arr` that `keep` accepts, without duplicates
+fn filter(arr: &[Json], mut keep: impl FnMut(&Json) -> bool) -> Vec<Json> {
+    let mut seen = Set::default();
+    arr.iter()
+       .filter(|e| keep(e) && seen.insert(e))
+       .cloned()
+       .collect()
+}
+
+impl Json {
+    /// Removes the duplicated elements of this array, keeping the first
+    /// occurrence of each one. Elements are compared with [`PartialEq`].
+    ///
+    /// Fails if this is not an array.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut j = json!([1, { "a" : [2] }, 1, "1", { "a" : [2] }]);
+    /// j.dedup().unwrap();
+    /// assert_eq!(j, json!([1, { "a" : [2] }, "1"]));
+    /// ```
+    pub fn dedup(&mut self) -> Result<()> {
+        let Json::Array(arr) = self else {
+            return Err(format!("expected array, found {}", self.type_name()).into())
+        };
+        let elems = filter(arr, |_| true);
+        *arr = elems.into();
+        Ok(())
+    }
+    /// Returns the elements of this array, followed by the
+    /// elements of `other` that are not in this one
+    ///
+    /// The results of the set operations don't have duplicates, and
+    /// keep the order of the elements. They return None if any of
+    /// the values is not an array.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let a = json!(["x", "y", "y"]);
+    /// let b = json!(["z", "y"]);
+    /// assert_eq!(a.union(&b).unwrap(), json!(["x", "y", "z"]));
+    /// assert_eq!(a.intersection(&b).unwrap(), json!(["y"]));
+    /// assert_eq!(a.difference(&b).unwrap(), json!(["x"]));
+    /// ```
+    pub fn union(&self, other: &Json) -> Option<Json> {
+        let (a, b) = (self.array()?, other.array()?);
+        let mut seen = Set::default();
+        let elems = a.iter().chain(b)
+                     .filter(|e| seen.insert(e))
+                     .cloned()
+                     .collect::<Vec<_>>();
+        Some(elems.into())
+    }
+    /// Returns the elements of this array that are also in `other`.
+    /// See [`union`](Self::union)
+    pub fn intersection(&self, other: &Json) -> Option<Json> {
+        let other = Set::new(other.array()?);
+        Some(filter(self.array()?, |e| other.contains(e)).into())
+    }
+    /// Returns the elements of this array that are not in `other`.
+    /// See [`union`](Self::union)
+    pub fn difference(&self, other: &Json) -> Option<Json> {
+        let other = Set::new(other.array()?);
+        Some(filter(self.array()?, |e| !other.contains(e)).into())
+    }
+}
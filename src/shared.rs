@@ -0,0 +1,222 @@
+//! Documents that can be shared between threads
+//!
+//! A [`SharedJson`] is cheap to clone, and copy-on-write: modifying a value
+//! only copies the arrays and objects in the path to it, and only if they
+//! are shared with another clone. Everything else keeps being shared.
+//!
+//! # Example
+//! ```
+//! use std::thread;
+//! use json::{json, SharedJson};
+//!
+//! let doc = SharedJson::from(json!({ "users" : [ { "name" : "Jhon" } ] }));
+//!
+//! let handles: Vec<_> = (0..4).map(|_| {
+//!     /* Cloning only increments a reference count */
+//!     let doc = doc.clone();
+//!     thread::spawn(move || doc.pointer("/users/0/name").and_then(|n| n.string()).map(String::from))
+//! }).collect();
+//!
+//! for h in handles {
+//!     assert_eq!(h.join().unwrap().as_deref(), Some("Jhon"));
+//! }
+//! ```
+
+use alloc::sync::Arc;
+use core::mem;
+
+use crate::pointer::parse_index;
+use crate::prelude::*;
+use crate::{Json, JsonPointer, JsonType, Number};
+
+/// A JSON document whose arrays, objects and strings are reference counted.
+///
+/// Cloning a `SharedJson` is O(1), and the clones share the same data.
+/// It has the same accessors as [Json]. See the [module docs](self)
+///
+/// # Example
+/// ```
+/// use json::{json, SharedJson};
+///
+/// let v1 = SharedJson::from(json!({ "db" : { "host" : "localhost" }, "features" : ["a", "b"] }));
+///
+/// let mut v2 = v1.clone();
+/// *v2.pointer_mut("/db/host").unwrap() = SharedJson::from(json!("example.com"));
+///
+/// assert_eq!(v1.pointer("/db/host").unwrap().string(), Some("localhost"));
+/// assert_eq!(v2.pointer("/db/host").unwrap().string(), Some("example.com"));
+/// assert!(v1.get("features").unwrap().ptr_eq(v2.get("features").unwrap()));
+/// ```
+#[derive(Debug,Clone,PartialEq)]
+pub enum SharedJson {
+    Array(Arc<Vec<SharedJson>>),
+    Object(Arc<Map<Key,SharedJson>>),
+    String(Arc<str>),
+    Number(Number),
+    #[cfg(feature = "bigint")]
+    BigInt(Arc<num_bigint::BigInt>),
+    #[cfg(feature = "decimal")]
+    Decimal(rust_decimal::Decimal),
+    True, False, Null,
+}
+
+impl SharedJson {
+    /// Same as [`Json::get`]
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&SharedJson> {
+        self.object()?.get(key.as_ref())
+    }
+    /// Same as [`Json::nth`]
+    pub fn nth(&self, i: usize) -> Option<&SharedJson> {
+        self.array()?.get(i)
+    }
+    /// Same as [`Json::pointer`]
+    pub fn pointer(&self, ptr: impl AsRef<str>) -> Option<&SharedJson> {
+        JsonPointer::parse(ptr.as_ref()).ok()?.tokens().try_fold(self, |j, t| match j {
+            SharedJson::Object(o) => o.get(t),
+            SharedJson::Array(a) => a.get(parse_index(t)?),
+            _ => None,
+        })
+    }
+    /// Same as [`Json::get_mut`].
+    /// If the object is shared, it's copied first.
+    pub fn get_mut(&mut self, key: impl AsRef<str>) -> Option<&mut SharedJson> {
+        self.object_mut()?.get_mut(key.as_ref())
+    }
+    /// Same as [`Json::nth_mut`].
+    /// If the array is shared, it's copied first.
+    pub fn nth_mut(&mut self, i: usize) -> Option<&mut SharedJson> {
+        self.array_mut()?.get_mut(i)
+    }
+    /// Same as [`Json::pointer_mut`].
+    /// The arrays and objects in the path that are shared are copied first.
+    pub fn pointer_mut(&mut self, ptr: impl AsRef<str>) -> Option<&mut SharedJson> {
+        JsonPointer::parse(ptr.as_ref()).ok()?.tokens().try_fold(self, |j, t| match j {
+            SharedJson::Object(o) => Arc::make_mut(o).get_mut(t),
+            SharedJson::Array(a) => Arc::make_mut(a).get_mut(parse_index(t)?),
+            _ => None,
+        })
+    }
+    /// Same as [`Json::number`]
+    pub fn number(&self) -> Option<f64> {
+        match self {
+            SharedJson::Number(n) => Some(to_f64(*n)),
+            #[cfg(feature = "decimal")]
+            SharedJson::Decimal(n) => Some(dec_to_f64(n)),
+            _ => None,
+        }
+    }
+    /// Same as [`Json::string`]
+    pub fn string(&self) -> Option<&str> {
+        if let SharedJson::String(s) = self {
+            Some(s)
+        } else { None }
+    }
+    /// Same as [`Json::object`]
+    pub fn object(&self) -> Option<&Map<Key,SharedJson>> {
+        if let SharedJson::Object(o) = self {
+            Some(o)
+        } else { None }
+    }
+    /// Same as [`Json::array`]
+    pub fn array(&self) -> Option<&[SharedJson]> {
+        if let SharedJson::Array(arr) = self {
+            Some(arr)
+        } else { None }
+    }
+    /// Returns a mutable reference to the inner Object, if this is an Object variant.
+    /// If the object is shared, it's copied first.
+    pub fn object_mut(&mut self) -> Option<&mut Map<Key,SharedJson>> {
+        if let SharedJson::Object(o) = self {
+            Some(Arc::make_mut(o))
+        } else { None }
+    }
+    /// Returns a mutable reference to the inner Array, if this is an Array variant.
+    /// If the array is shared, it's copied first.
+    pub fn array_mut(&mut self) -> Option<&mut Vec<SharedJson>> {
+        if let SharedJson::Array(arr) = self {
+            Some(Arc::make_mut(arr))
+        } else { None }
+    }
+    /// Same as [`Json::boolean`]
+    pub fn boolean(&self) -> Option<bool> {
+        match self {
+            SharedJson::True => Some(true),
+            SharedJson::False => Some(false),
+            _ => None,
+        }
+    }
+    /// Returns true if this is a Null variant
+    pub fn is_null(&self) -> bool {
+        matches!(self, SharedJson::Null)
+    }
+    /// Same as [`Json::json_type`]
+    pub fn json_type(&self) -> JsonType {
+        match self {
+            SharedJson::Array(_) => JsonType::Array,
+            SharedJson::Object(_) => JsonType::Object,
+            SharedJson::String(_) => JsonType::String,
+            SharedJson::Number(_) => JsonType::Number,
+            #[cfg(feature = "bigint")]
+            SharedJson::BigInt(_) => JsonType::Number,
+            #[cfg(feature = "decimal")]
+            SharedJson::Decimal(_) => JsonType::Number,
+            SharedJson::True | SharedJson::False => JsonType::Bool,
+            SharedJson::Null => JsonType::Null,
+        }
+    }
+    /// Returns true if `self` and `other` share the same data
+    pub fn ptr_eq(&self, other: &SharedJson) -> bool {
+        match (self, other) {
+            (SharedJson::Array(a), SharedJson::Array(b)) => Arc::ptr_eq(a, b),
+            (SharedJson::Object(a), SharedJson::Object(b)) => Arc::ptr_eq(a, b),
+            (SharedJson::String(a), SharedJson::String(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+    /// Builds a deep copy of this document, as a [Json]
+    pub fn to_json(&self) -> Json {
+        self.into()
+    }
+}
+
+impl From<Json> for SharedJson {
+    fn from(mut value: Json) -> Self {
+        match &mut value {
+            Json::Array(arr) => SharedJson::Array(Arc::new(mem::take(arr).into_vec().into_iter().map(Into::into).collect())),
+            Json::Object(obj) => SharedJson::Object(Arc::new(mem::take(obj).into_iter().map(|(k, v)| (k, v.into())).collect())),
+            Json::String(s) => SharedJson::String(mem::take(s).into()),
+            Json::Number(n) => SharedJson::Number(*n),
+            #[cfg(feature = "bigint")]
+            Json::BigInt(n) => SharedJson::BigInt(Arc::new(mem::take(n))),
+            #[cfg(feature = "decimal")]
+            Json::Decimal(n) => SharedJson::Decimal(*n),
+            Json::True => SharedJson::True,
+            Json::False => SharedJson::False,
+            Json::Null => SharedJson::Null,
+        }
+    }
+}
+
+impl From<&SharedJson> for Json {
+    fn from(value: &SharedJson) -> Self {
+        match value {
+            SharedJson::Array(arr) => Json::Array(arr.iter().map(Json::from).collect()),
+            SharedJson::Object(obj) => Json::Object(obj.iter().map(|(k, v)| (k.clone(), v.into())).collect()),
+            SharedJson::String(s) => Json::String((**s).into()),
+            SharedJson::Number(n) => Json::Number(*n),
+            #[cfg(feature = "bigint")]
+            SharedJson::BigInt(n) => Json::BigInt((**n).clone()),
+            #[cfg(feature = "decimal")]
+            SharedJson::Decimal(n) => Json::Decimal(*n),
+            SharedJson::True => Json::True,
+            SharedJson::False => Json::False,
+            SharedJson::Null => Json::Null,
+        }
+    }
+}
+
+impl Display for SharedJson {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.to_json(), f)
+    }
+}
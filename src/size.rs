@@ -0,0 +1,58 @@
+//! Memory usage estimation
+
+use core::mem::size_of;
+
+use crate::prelude::*;
+use crate::Json;
+
+type Entry = (Box<str>, Json);
+
+/// Estimated heap bytes used by the map itself, excluding the keys' and values' heap data
+fn map_overhead(map: &Map<Key,Json>) -> usize {
+    /* hashbrown stores the entries, plus a control byte per bucket */
+    #[cfg(all(any(feature = "std", feature = "hashbrown"), not(any(feature = "preserve_order", feature = "btree_map"))))]
+    return map.capacity() * (size_of::<Entry>() + 1);
+
+    /* A vector of entries (with their hashes), plus a hash table of indices */
+    #[cfg(feature = "preserve_order")]
+    return map.capacity() * (size_of::<Entry>() + size_of::<u64>() + size_of::<usize>() + 1);
+
+    /* B-Tree nodes hold up to 11 entries, and are at least half full */
+    #[cfg(all(any(feature = "btree_map", not(any(feature = "std", feature = "hashbrown"))), not(feature = "preserve_order")))]
+    return map.len() * size_of::<Entry>() * 3 / 2;
+}
+
+impl Json {
+    /// Returns an estimate of the heap memory used by this document, in bytes.
+    ///
+    /// This counts the strings, the elements of arrays and the members of
+    /// objects (including the map's spare capacity), recursively. The size of
+    /// `self` is not included, since it may live on the stack.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// assert_eq!(json!(1).deep_size_of(), 0);
+    /// assert_eq!(json!("abc").deep_size_of(), 3);
+    ///
+    /// let arr = json!(["abc", 1]);
+    /// assert_eq!(arr.deep_size_of(), 3 + 2 * size_of::<json::Json>());
+    /// ```
+    pub fn deep_size_of(&self) -> usize {
+        match self {
+            Json::Array(arr) => {
+                arr.len() * size_of::<Json>() + arr.iter().map(Json::deep_size_of).sum::<usize>()
+            },
+            Json::Object(obj) => {
+                map_overhead(obj) + obj.iter().map(|(k, v)| k.len() + v.deep_size_of()).sum::<usize>()
+            },
+            Json::String(s) => s.len(),
+            #[cfg(feature = "bigint")]
+            Json::BigInt(n) => n.iter_u64_digits().len() * size_of::<u64>(),
+            #[cfg(feature = "decimal")]
+            Json::Decimal(_) => 0,
+            Json::Number(_) | Json::True | Json::False | Json::Null => 0,
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Sorting of object members and arrays
+
+use core::cmp::Ordering;
+
+use crate::escape::unescape;
+#[cfg(any(feature = "bigint", feature = "decimal"))]
+use crate::prelude::to_f64;
+#[cfg(feature = "bigint")]
+use crate::prelude::big_to_f64;
+#[cfg(feature = "decimal")]
+use crate::prelude::dec_to_f64;
+use crate::{Json, JsonPointer, Result};
+
+/// Total order between [Json] values.
+///
+/// null < false < true < numbers < strings < arrays < objects.
+/// Arrays are compared lexicographically, strings by their unescaped text.
+pub(crate) fn compare(a: &Json, b: &Json) -> Ordering {
+    fn rank(j: &Json) -> u8 {
+        match j {
+            Json::Null => 0,
+            Json::False => 1,
+            Json::True => 2,
+            Json::Number(_) => 3,
+            #[cfg(feature = "bigint")]
+            Json::BigInt(_) => 3,
+            #[cfg(feature = "decimal")]
+            Json::Decimal(_) => 3,
+            Json::String(_) => 4,
+            Json::Array(_) => 5,
+            Json::Object(_) => 6,
+        }
+    }
+    match (a, b) {
+        (Json::Number(a), Json::Number(b)) => a.total_cmp(b),
+        #[cfg(feature = "bigint")]
+        (Json::BigInt(a), Json::BigInt(b)) => a.cmp(b),
+        #[cfg(feature = "bigint")]
+        (Json::BigInt(a), Json::Number(b)) => big_to_f64(a).total_cmp(&to_f64(*b)),
+        #[cfg(feature = "bigint")]
+        (Json::Number(a), Json::BigInt(b)) => to_f64(*a).total_cmp(&big_to_f64(b)),
+        #[cfg(feature = "decimal")]
+        (Json::Decimal(a), Json::Decimal(b)) => a.cmp(b),
+        #[cfg(feature = "decimal")]
+        (Json::Decimal(a), Json::Number(b)) => dec_to_f64(a).total_cmp(&to_f64(*b)),
+        #[cfg(feature = "decimal")]
+        (Json::Number(a), Json::Decimal(b)) => to_f64(*a).total_cmp(&dec_to_f64(b)),
+        (Json::String(a), Json::String(b)) => unescape(a).cmp(&unescape(b)),
+        (Json::Array(a), Json::Array(b)) => {
+            a.iter().zip(b.iter())
+             .map(|(a, b)| compare(a, b))
+             .find(|o| o.is_ne())
+             .unwrap_or_else(|| a.len().cmp(&b.len()))
+        },
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+impl Json {
+    /// Sorts the members of all the objects in this document by their key.
+    ///
+    /// This only has an effect with the `preserve_order` feature. Without it,
+    /// objects are either unordered, or already sorted (with `btree_map`,
+    /// or on `no_std` without `hashbrown`).
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut j = json!({ "b" : { "d" : 1, "c" : 2 }, "a" : 3 });
+    /// j.sort_keys_recursive();
+    /// # #[cfg(feature = "preserve_order")]
+    /// assert_eq!(j.to_string(), r#"{"a":3,"b":{"c":2,"d":1}}"#);
+    /// ```
+    pub fn sort_keys_recursive(&mut self) {
+        match self {
+            Json::Array(arr) => arr.iter_mut().for_each(Json::sort_keys_recursive),
+            Json::Object(obj) => {
+                obj.values_mut().for_each(Json::sort_keys_recursive);
+                #[cfg(feature = "preserve_order")]
+                obj.sort_by(|k1, _, k2, _| unescape(k1).cmp(&unescape(k2)));
+            },
+            _ => {},
+        }
+    }
+    /// Sorts the array referenced by the JSON pointer `path` with `cmp`
+    ///
+    /// Fails if the pointer doesn't reference an array.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut j = json!({ "a" : [3, 1, 2] });
+    /// j.sort_array_by("/a", |a, b| b.number().partial_cmp(&a.number()).unwrap()).unwrap();
+    /// assert_eq!(j, json!({ "a" : [3, 2, 1] }));
+    /// ```
+    pub fn sort_array_by(&mut self, path: &str, cmp: impl FnMut(&Json, &Json) -> Ordering) -> Result<()> {
+        let ptr = JsonPointer::parse(path)?;
+        match ptr.resolve_mut(self) {
+            Some(Json::Array(arr)) => {
+                arr.sort_by(cmp);
+                Ok(())
+            },
+            Some(_) => Err(format!("'{path}' is not an array").into()),
+            None => Err(format!("'{path}' doesn't reference any value").into()),
+        }
+    }
+    /// Sorts the array referenced by the JSON pointer `path` in ascending order.
+    ///
+    /// If `key` is given, the elements are compared by their `key` member.
+    /// Otherwise, the elements themselves are compared.
+    /// Values of different types are ordered as:
+    /// null < false < true < numbers < strings < arrays < objects.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut j = json!({
+    ///     "tags" : ["b", "c", "a"],
+    ///     "users" : [ { "id" : 2 }, { "id" : 1 } ]
+    /// });
+    /// j.sort_array_by_key("/tags", None).unwrap();
+    /// j.sort_array_by_key("/users", Some("id")).unwrap();
+    ///
+    /// assert_eq!(j, json!({
+    ///     "tags" : ["a", "b", "c"],
+    ///     "users" : [ { "id" : 1 }, { "id" : 2 } ]
+    /// }));
+    /// ```
+    pub fn sort_array_by_key(&mut self, path: &str, key: Option<&str>) -> Result<()> {
+        match key {
+            Some(key) => self.sort_array_by(path, |a, b| {
+                compare(a.get(key).unwrap_or(&Json::Null), b.get(key).unwrap_or(&Json::Null))
+            }),
+            None => self.sort_array_by(path, compare),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Parsing with source locations
+//!
+//! Parses a document into a tree where every value, and every
+//! object key, keeps the [Span] it had in the source text.
+//!
+//! # Example
+//! ```
+//! use json::spanned;
+//!
+//! let src = r#"{ "server" : { "port" : "80" } }"#;
+//! let doc = spanned::parse(src).unwrap();
+//!
+//! let port = doc.pointer("/server/port").unwrap();
+//! assert_eq!(port.span.slice(src), r#""80""#);
+//!
+//! let pos = port.span.file_position(src);
+//! assert_eq!(format!("port must be a number (col {})", pos.start_col),
+//!            "port must be a number (col 24)");
+//! ```
+
+use core::mem;
+
+use crate::lexer::{self, Span};
+use crate::parser::{self, Build};
+use crate::pointer::parse_index;
+use crate::prelude::*;
+use crate::{Json, JsonConfig, JsonPointer, Number, Result};
+
+/// A value, together with its location in the source
+#[derive(Debug,Clone,PartialEq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+}
+
+/// A [Json] value whose children are [Spanned].
+///
+/// Objects keep their members in source order,
+/// including duplicated keys.
+#[derive(Debug,Clone,PartialEq)]
+pub enum SpannedJson {
+    Array(Vec<Spanned<SpannedJson>>),
+    Object(Vec<(Spanned<Box<str>>, Spanned<SpannedJson>)>),
+    String(Box<str>),
+    Number(Number),
+    #[cfg(feature = "bigint")]
+    BigInt(num_bigint::BigInt),
+    #[cfg(feature = "decimal")]
+    Decimal(rust_decimal::Decimal),
+    True, False, Null,
+}
+
+impl SpannedJson {
+    fn is_container(&self) -> bool {
+        matches!(self, SpannedJson::Array(_) | SpannedJson::Object(_))
+    }
+    /// Moves the nested values of this array or object to the end of `out`
+    fn drain_into(&mut self, out: &mut Vec<SpannedJson>) {
+        match self {
+            SpannedJson::Array(arr) => out.extend(arr.drain(..).map(|e| e.value)),
+            SpannedJson::Object(obj) => out.extend(obj.drain(..).map(|(_, v)| v.value)),
+            _ => {},
+        }
+    }
+}
+
+/// Like [Json], values are dropped without recursion
+impl Drop for SpannedJson {
+    fn drop(&mut self) {
+        let nested = match self {
+            SpannedJson::Array(arr) => arr.iter().any(|e| e.value.is_container()),
+            SpannedJson::Object(obj) => obj.iter().any(|(_, v)| v.value.is_container()),
+            _ => false,
+        };
+        if !nested {
+            return
+        }
+        let mut stack = Vec::new();
+        self.drain_into(&mut stack);
+        while let Some(mut json) = stack.pop() {
+            json.drain_into(&mut stack);
+        }
+    }
+}
+
+struct SpannedBuilder;
+
+impl Build for SpannedBuilder {
+    type Value = Spanned<SpannedJson>;
+    type Object = Vec<(Spanned<Box<str>>, Spanned<SpannedJson>)>;
+
+    fn object() -> Self::Object { Vec::new() }
+    fn insert(obj: &mut Self::Object, key: Box<str>, key_span: Span, value: Self::Value) {
+        obj.push((Spanned { value: key, span: key_span }, value));
+    }
+    fn finish_object(obj: Self::Object, span: Span) -> Self::Value {
+        Spanned { value: SpannedJson::Object(obj), span }
+    }
+    fn array(elems: Vec<Self::Value>, span: Span) -> Self::Value {
+        Spanned { value: SpannedJson::Array(elems), span }
+    }
+    fn scalar(mut json: Json, span: Span) -> Self::Value {
+        let value = match &mut json {
+            Json::String(s) => SpannedJson::String(mem::take(s)),
+            Json::Number(n) => SpannedJson::Number(*n),
+            #[cfg(feature = "bigint")]
+            Json::BigInt(n) => SpannedJson::BigInt(mem::take(n)),
+            #[cfg(feature = "decimal")]
+            Json::Decimal(n) => SpannedJson::Decimal(*n),
+            Json::True => SpannedJson::True,
+            Json::False => SpannedJson::False,
+            Json::Null => SpannedJson::Null,
+            Json::Array(_) | Json::Object(_) => unreachable!("Containers are not scalars"),
+        };
+        Spanned { value, span }
+    }
+}
+
+/// Parses the given text, keeping the spans of all values
+pub fn parse(text: impl AsRef<str>) -> Result<Spanned<SpannedJson>> {
+    parse_with_config(text, JsonConfig::default())
+}
+
+/// Same as [parse], but using the given [`JsonConfig`]
+pub fn parse_with_config(text: impl AsRef<str>, conf: JsonConfig) -> Result<Spanned<SpannedJson>> {
+    let text = text.as_ref();
+    let tokens = lexer::tokenize_with(text, conf.max_bytes, None)?;
+    parser::parse_with::<SpannedBuilder>(text, &tokens, conf)
+}
+
+impl Spanned<SpannedJson> {
+    /// Gets the member with the given key. If the key
+    /// is duplicated, the last one is returned.
+    pub fn get(&self, key: impl AsRef<str>) -> Option<&Spanned<SpannedJson>> {
+        let key = key.as_ref();
+        match &self.value {
+            SpannedJson::Object(obj) => obj.iter().rev().find(|(k, _)| &*k.value == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+    /// Gets the element at the given index
+    pub fn nth(&self, i: usize) -> Option<&Spanned<SpannedJson>> {
+        match &self.value {
+            SpannedJson::Array(arr) => arr.get(i),
+            _ => None,
+        }
+    }
+    /// Gets the value referenced by the given JSON pointer
+    pub fn pointer(&self, ptr: impl AsRef<str>) -> Option<&Spanned<SpannedJson>> {
+        JsonPointer::parse(ptr.as_ref()).ok()?.tokens().try_fold(self, |j, t| match &j.value {
+            SpannedJson::Object(_) => j.get(t),
+            SpannedJson::Array(_) => j.nth(parse_index(t)?),
+            _ => None,
+        })
+    }
+    /// Builds the plain [Json] value, without spans
+    pub fn to_json(&self) -> Json {
+        match &self.value {
+            SpannedJson::Array(arr) => arr.iter().map(Self::to_json).collect::<Vec<_>>().into(),
+            SpannedJson::Object(obj) => {
+                Json::Object(obj.iter().map(|(k, v)| ((*k.value).into(), v.to_json())).collect())
+            },
+            SpannedJson::String(s) => Json::String(s.clone()),
+            SpannedJson::Number(n) => Json::Number(*n),
+            #[cfg(feature = "bigint")]
+            SpannedJson::BigInt(n) => Json::BigInt(n.clone()),
+            #[cfg(feature = "decimal")]
+            SpannedJson::Decimal(n) => Json::Decimal(*n),
+            SpannedJson::True => Json::True,
+            SpannedJson::False => Json::False,
+            SpannedJson::Null => Json::Null,
+        }
+    }
+}
+
+impl From<&Spanned<SpannedJson>> for Json {
+    fn from(value: &Spanned<SpannedJson>) -> Self {
+        value.to_json()
+    }
+}
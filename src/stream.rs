@@ -0,0 +1,189 @@
+//! Streaming of large documents
+//!
+//! # Example
+//! ```
+//! use json::{json, Json};
+//!
+//! let data = r#"[ { "id" : 1 }, { "id" : 2 }, [3] ]"#;
+//!
+//! let mut elems = Json::array_elements(data.as_bytes());
+//! assert_eq!(elems.next().unwrap().unwrap(), json!({ "id" : 1 }));
+//! assert_eq!(elems.next().unwrap().unwrap(), json!({ "id" : 2 }));
+//! assert_eq!(elems.next().unwrap().unwrap(), json!([3]));
+//! assert!(elems.next().is_none());
+//! ```
+
+use std::io::Read;
+
+use crate::prelude::*;
+use crate::{Json, Result};
+
+/// Number of bytes requested to the reader at a time
+const CHUNK_SIZE: usize = 8 * 1024;
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    /// Before the opening bracket
+    Start,
+    /// After the opening bracket
+    First,
+    /// After an element
+    Next,
+    Done,
+}
+
+/// Iterator over the elements of a top-level JSON array.
+/// See [`Json::array_elements`]
+pub struct ArrayElements<R> {
+    reader: R,
+    buf: Vec<u8>,
+    /// Start of the unconsumed bytes in buf
+    pos: usize,
+    eof: bool,
+    state: State,
+}
+
+impl<R: Read> ArrayElements<R> {
+    /// Discards the consumed bytes, and reads a new chunk.
+    /// Returns false if the reader has reached EOF.
+    fn fill(&mut self) -> Result<bool> {
+        if self.eof {
+            return Ok(false)
+        }
+        self.buf.drain(..self.pos);
+        self.pos = 0;
+        let len = self.buf.len();
+        self.buf.resize(len + CHUNK_SIZE, 0);
+        let n = loop {
+            match self.reader.read(&mut self.buf[len..]) {
+                Ok(n) => break n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {},
+                Err(e) => {
+                    self.buf.truncate(len);
+                    return Err(e.to_string().into())
+                },
+            }
+        };
+        self.buf.truncate(len + n);
+        self.eof = n == 0;
+        Ok(!self.eof)
+    }
+    /// Skips whitespace, and returns the next byte without consuming it
+    fn peek(&mut self) -> Result<Option<u8>> {
+        loop {
+            while let Some(b) = self.buf.get(self.pos) {
+                if !b.is_ascii_whitespace() {
+                    return Ok(Some(*b))
+                }
+                self.pos += 1;
+            }
+            if !self.fill()? {
+                return Ok(None)
+            }
+        }
+    }
+    /// Parses the element starting at the current position
+    fn element(&mut self) -> Result<Json> {
+        self.peek()?;
+        let mut i = self.pos;
+        let mut depth = 0_usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        loop {
+            let Some(&b) = self.buf.get(i) else {
+                let offset = i - self.pos;
+                if !self.fill()? {
+                    return Err("Unterminated array".into())
+                }
+                i = self.pos + offset;
+                continue
+            };
+            if in_string {
+                match b {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {},
+                }
+            } else {
+                match b {
+                    b'"' => in_string = true,
+                    b'[' | b'{' => depth += 1,
+                    b']' | b'}' | b',' if depth == 0 => break,
+                    _ if depth == 0 && b.is_ascii_whitespace() => break,
+                    b']' | b'}' => depth -= 1,
+                    _ => {},
+                }
+            }
+            i += 1;
+        }
+        if i == self.pos {
+            return Err("Expected a value".into())
+        }
+        let text = core::str::from_utf8(&self.buf[self.pos..i]).map_err(|e| e.to_string())?;
+        let json = Json::deserialize(text);
+        self.pos = i;
+        json
+    }
+    fn advance(&mut self) -> Result<Option<Json>> {
+        loop {
+            let next = self.peek()?;
+            match (self.state, next) {
+                (State::Start, Some(b'[')) => {
+                    self.pos += 1;
+                    self.state = State::First;
+                },
+                (State::Start, _) => return Err("Expected an array".into()),
+                (State::First | State::Next, Some(b']')) => {
+                    self.pos += 1;
+                    self.state = State::Done;
+                    if self.peek()?.is_some() {
+                        return Err("Unexpected data after the array".into())
+                    }
+                    return Ok(None)
+                },
+                (State::First, Some(_)) => {
+                    self.state = State::Next;
+                    return self.element().map(Some)
+                },
+                (State::Next, Some(b',')) => {
+                    self.pos += 1;
+                    return self.element().map(Some)
+                },
+                (State::Next, Some(_)) => return Err("Expected ',' or ']' after an element".into()),
+                (_, None) => return Err("Unterminated array".into()),
+                (State::Done, _) => return Ok(None),
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for ArrayElements<R> {
+    type Item = Result<Json>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == State::Done {
+            return None
+        }
+        let next = self.advance();
+        if next.is_err() {
+            self.state = State::Done;
+        }
+        next.transpose()
+    }
+}
+
+impl Json {
+    /// Parses the elements of a top-level JSON array lazily, one at a time.
+    ///
+    /// Only the element being parsed is kept in memory, which allows
+    /// processing arrays much bigger than the available memory.
+    ///
+    /// To iterate over the elements of a string, pass its bytes.
+    /// Comments are not allowed between elements.
+    ///
+    /// After an error, the iterator doesn't yield more elements.
+    pub fn array_elements<R: Read>(reader: R) -> ArrayElements<R> {
+        ArrayElements { reader, buf: Vec::new(), pos: 0, eof: false, state: State::Start }
+    }
+}
@@ -0,0 +1,147 @@
+//! Utilities for arrays of objects, and their columnar form
+
+use crate::prelude::*;
+use crate::sort::compare;
+use crate::{Json, Result};
+
+/// Returns the member `key` of a row, or null if it doesn't have it
+fn column<'a>(row: &'a Json, key: &str) -> &'a Json {
+    static NULL: Json = Json::Null;
+    row.get(key).unwrap_or(&NULL)
+}
+
+impl Json {
+    /// Groups the elements of this array by the value of their `key` member
+    ///
+    /// Returns an object with a member for each value, holding the
+    /// array of elements with it, in their original order. Strings are
+    /// used as keys directly, and other values in their serialized form,
+    /// so `"1"` and `1` end up in the same group. Elements without the
+    /// key are grouped under `"null"`.
+    ///
+    /// Returns None if this is not an array.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let users = json!([
+    ///     { "name" : "a", "country" : "es" },
+    ///     { "name" : "b", "country" : "fr" },
+    ///     { "name" : "c", "country" : "es" },
+    /// ]);
+    /// assert_eq!(users.group_by("country").unwrap(), json!({
+    ///     "es" : [{ "name" : "a", "country" : "es" }, { "name" : "c", "country" : "es" }],
+    ///     "fr" : [{ "name" : "b", "country" : "fr" }],
+    /// }));
+    /// ```
+    pub fn group_by(&self, key: impl AsRef<str>) -> Option<Json> {
+        let key = key.as_ref();
+        let mut groups: Map<Key,Vec<Json>> = Map::default();
+        for row in self.array()? {
+            let group: Key = match column(row, key) {
+                Json::String(s) => (**s).into(),
+                val => val.to_string().into(),
+            };
+            groups.entry(group).or_default().push(row.clone());
+        }
+        Some(Json::Object(groups.into_iter().map(|(k, v)| (k, v.into())).collect()))
+    }
+    /// Returns an array with the `key` member of each element of this array.
+    /// Elements without the key give null, so the result has the same length.
+    ///
+    /// Returns None if this is not an array.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let rows = json!([{ "id" : 1 }, { "id" : 2 }, { "name" : "x" }]);
+    /// assert_eq!(rows.pluck("id").unwrap(), json!([1, 2, null]));
+    /// ```
+    pub fn pluck(&self, key: impl AsRef<str>) -> Option<Json> {
+        let key = key.as_ref();
+        let values = self.array()?.iter().map(|row| column(row, key).clone());
+        Some(values.collect::<Vec<_>>().into())
+    }
+    /// Sorts the elements of this array by their `key` member, in ascending
+    /// order. The sort is stable, and elements without the key sort first.
+    /// See [`sort_array_by_key`](Self::sort_array_by_key) for the order
+    /// between values.
+    ///
+    /// Fails if this is not an array.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut rows = json!([
+    ///     { "id" : 1, "created_at" : "2024-03-01" },
+    ///     { "id" : 2, "created_at" : "2023-12-24" },
+    /// ]);
+    /// rows.sort_by("created_at").unwrap();
+    /// assert_eq!(rows.pluck("id").unwrap(), json!([2, 1]));
+    /// ```
+    pub fn sort_by(&mut self, key: impl AsRef<str>) -> Result<()> {
+        let key = key.as_ref();
+        let Json::Array(arr) = self else {
+            return Err(format!("expected array, found {}", self.type_name()).into())
+        };
+        arr.sort_by(|a, b| compare(column(a, key), column(b, key)));
+        Ok(())
+    }
+    /// Converts this array of objects into an object of arrays,
+    /// with a column for each key found in the objects
+    ///
+    /// All the columns have the length of the array. If an object doesn't
+    /// have a key, its column holds null for it.
+    ///
+    /// Returns None if this is not an array of objects.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let rows = json!([{ "a" : 1, "b" : 2 }, { "a" : 3 }]);
+    /// let cols = rows.to_columns().unwrap();
+    /// assert_eq!(cols, json!({ "a" : [1, 3], "b" : [2, null] }));
+    /// assert_eq!(cols.from_columns().unwrap(), json!([{ "a" : 1, "b" : 2 }, { "a" : 3, "b" : null }]));
+    /// ```
+    pub fn to_columns(&self) -> Option<Json> {
+        let rows = self.array()?;
+        let mut columns: Map<Key,Vec<Json>> = Map::default();
+        for (i, row) in rows.iter().enumerate() {
+            for (key, val) in row.object()? {
+                let column = columns.entry(key.clone()).or_insert_with(|| vec![Json::Null; i]);
+                column.resize(i, Json::Null);
+                column.push(val.clone());
+            }
+        }
+        let columns = columns.into_iter().map(|(key, mut column)| {
+            column.resize(rows.len(), Json::Null);
+            (key, column.into())
+        });
+        Some(Json::Object(columns.collect()))
+    }
+    /// Converts this object of arrays into an array of objects.
+    /// This is the inverse of [`to_columns`](Self::to_columns).
+    ///
+    /// The array has the length of the longest column. Every object has
+    /// all the keys, with null for the elements missing in shorter columns.
+    ///
+    /// Returns None if this is not an object of arrays.
+    pub fn from_columns(&self) -> Option<Json> {
+        let columns = self.object()?
+                          .iter()
+                          .map(|(k, v)| Some((k, v.array()?)))
+                          .collect::<Option<Vec<_>>>()?;
+        let len = columns.iter().map(|(_, c)| c.len()).max().unwrap_or(0);
+        let rows = (0..len).map(|i| {
+            let row = columns.iter().map(|(key, column)| {
+                ((*key).clone(), column.get(i).cloned().unwrap_or(Json::Null))
+            });
+            Json::Object(row.collect())
+        });
+        Some(rows.collect::<Vec<_>>().into())
+    }
+}
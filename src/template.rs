@@ -0,0 +1,101 @@
+//! Placeholder substitution in [Json] templates
+
+use crate::escape::escape;
+use crate::prelude::*;
+use crate::{Json, Result};
+
+/// Renders a variable inside a string
+fn interpolate(var: &Json, out: &mut String) {
+    match var {
+        /* Both the template and the variable are already escaped */
+        Json::String(s) => out.push_str(s),
+        Json::Number(n) => write!(out, "{n}").unwrap(),
+        #[cfg(feature = "bigint")]
+        Json::BigInt(n) => write!(out, "{n}").unwrap(),
+        #[cfg(feature = "decimal")]
+        Json::Decimal(n) => write!(out, "{n}").unwrap(),
+        Json::True => out.push_str("true"),
+        Json::False => out.push_str("false"),
+        Json::Null => out.push_str("null"),
+        Json::Array(_) | Json::Object(_) => out.push_str(&escape(&var.to_string())),
+    }
+}
+
+fn render_str(s: &str, vars: &Map<Key,Json>) -> Result<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| format!("Unclosed placeholder in \"{s}\""))?;
+        let name = after[..end].trim();
+        let var = vars.get(name).ok_or_else(|| format!("Missing template variable '{name}'"))?;
+        interpolate(var, &mut out);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// If the whole string is a single placeholder, returns its name
+fn whole_placeholder(s: &str) -> Option<&str> {
+    let name = s.strip_prefix("{{")?.strip_suffix("}}")?;
+    (!name.contains("{{") && !name.contains("}}")).then(|| name.trim())
+}
+
+impl Json {
+    /// Returns a copy of this document with the `{{name}}` placeholders inside
+    /// keys and string values replaced by the values in `vars`.
+    ///
+    /// A string value made of a single placeholder is replaced by the whole
+    /// variable, keeping its type. Inside a larger string, the variable is
+    /// inserted as text. Fails if a variable is missing.
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, Map};
+    ///
+    /// let template = json!({
+    ///     "greeting" : "Hello {{ name }}!",
+    ///     "user" : "{{user}}",
+    ///     "{{name}}" : true
+    /// });
+    ///
+    /// let mut vars = Map::default();
+    /// vars.insert("name".into(), json!("Jhon"));
+    /// vars.insert("user".into(), json!({ "id" : 1 }));
+    ///
+    /// assert_eq!(template.render_template(&vars).unwrap(), json!({
+    ///     "greeting" : "Hello Jhon!",
+    ///     "user" : { "id" : 1 },
+    ///     "Jhon" : true
+    /// }));
+    /// ```
+    pub fn render_template(&self, vars: &Map<Key,Json>) -> Result<Json> {
+        Ok(match self {
+            Json::String(s) => {
+                if let Some(name) = whole_placeholder(s) {
+                    vars.get(name)
+                        .cloned()
+                        .ok_or_else(|| format!("Missing template variable '{name}'"))?
+                } else if s.contains("{{") {
+                    render_str(s, vars)?.into()
+                } else {
+                    self.clone()
+                }
+            },
+            Json::Array(arr) => {
+                arr.iter().map(|e| e.render_template(vars)).collect::<Result<Vec<_>>>()?.into()
+            },
+            Json::Object(obj) => {
+                let mut map = Map::default();
+                for (k,v) in obj {
+                    let k = if k.contains("{{") { render_str(k, vars)?.into() } else { k.clone() };
+                    map.insert(k, v.render_template(vars)?);
+                }
+                Json::Object(map)
+            },
+            other => other.clone(),
+        })
+    }
+}
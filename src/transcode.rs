@@ -0,0 +1,306 @@
+//! Bridge with [serde]
+//!
+//! [Json] implements [`Serialize`], so it can be written in any format
+//! with a serde serializer. And [Deserializer] drives serde's
+//! [`Deserialize`](serde::Deserialize) implementations with the events of
+//! an [`EventParser`], so a document can be read into Rust values, or
+//! transcoded into another format, without building a [Json] first.
+//!
+//! Like with the [`EventParser`], numbers are always read as f64.
+//! Integral numbers are given to the visitors as integers.
+//!
+//! # Example
+//! ```
+//! use std::collections::BTreeMap;
+//!
+//! let ports: BTreeMap<String, Vec<u16>> = json::transcode::from_str(r#"{
+//!     "http" : [80, 8080],
+//!     "ssh!" : [22]
+//! }"#).unwrap();
+//! assert_eq!(ports["http"], [80, 8080]);
+//! assert_eq!(ports["ssh!"], [22]);
+//! ```
+
+use serde::de::value::StrDeserializer;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, Visitor};
+use serde::{forward_to_deserialize_any, ser, Serialize, Serializer};
+
+use crate::escape::unescape;
+use crate::events::EventParser;
+use crate::prelude::*;
+use crate::typed::Tok;
+use crate::{Json, Result};
+
+impl de::Error for crate::Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        msg.to_string().into()
+    }
+}
+
+impl ser::Error for crate::Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        msg.to_string().into()
+    }
+}
+
+/// Returns `n` as an integer, if it has no fraction and it's exact as an f64
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn integral(n: f64) -> Option<i64> {
+    (fract(n) == 0.0 && n.abs() < (1_u64 << 53) as f64).then_some(n as i64)
+}
+
+fn serialize_f64<S: Serializer>(n: f64, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+    match integral(n) {
+        Some(i) => serializer.serialize_i64(i),
+        None => serializer.serialize_f64(n),
+    }
+}
+
+/// Strings and keys are unescaped. Integral numbers
+/// are serialized as integers, and the rest as f64.
+impl Serialize for Json {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        match self {
+            Json::Array(elems) => serializer.collect_seq(elems.iter()),
+            Json::Object(obj) => serializer.collect_map(obj.iter().map(|(k, v)| (unescape(k), v))),
+            Json::String(s) => serializer.serialize_str(&unescape(s)),
+            Json::Number(n) => serialize_f64(to_f64(*n), serializer),
+            #[cfg(feature = "bigint")]
+            Json::BigInt(n) => serialize_f64(big_to_f64(n), serializer),
+            #[cfg(feature = "decimal")]
+            Json::Decimal(n) => serialize_f64(dec_to_f64(n), serializer),
+            Json::True => serializer.serialize_bool(true),
+            Json::False => serializer.serialize_bool(false),
+            Json::Null => serializer.serialize_unit(),
+        }
+    }
+}
+
+/// A [`serde::Deserializer`] that reads a document with an [`EventParser`]
+///
+/// Strings and keys are unescaped before giving them to the visitors,
+/// so they can't be borrowed from the input.
+pub struct Deserializer<'a> {
+    events: EventParser<'a>,
+    input: &'a [u8],
+    peeked: Option<Tok>,
+}
+
+impl<'a> Deserializer<'a> {
+    /// Builds a deserializer for `text`. See [`EventParser::new`]
+    /// for the meaning of the `scratch` and `nesting` buffers.
+    pub fn new(text: &'a str, scratch: &'a mut [u8], nesting: &'a mut [u8]) -> Self {
+        Deserializer {
+            events: EventParser::new(scratch, nesting),
+            input: text.as_bytes(),
+            peeked: None,
+        }
+    }
+    fn peek(&mut self) -> Result<Tok> {
+        if let Some(tok) = self.peeked {
+            return Ok(tok)
+        }
+        let event = match self.events.next_event(&mut self.input)? {
+            Some(event) => Some(event),
+            None => self.events.finish()?,
+        };
+        let Some(event) = event else {
+            return Err("Unexpected end of input".into())
+        };
+        let tok = Tok::from(event);
+        self.peeked = Some(tok);
+        Ok(tok)
+    }
+    fn next(&mut self) -> Result<Tok> {
+        let tok = self.peek()?;
+        self.peeked = None;
+        Ok(tok)
+    }
+    /// Text of the last key or string, unescaped
+    fn text(&self) -> Cow<'_, str> { unescape(self.events.last_token()) }
+    fn unexpected(&self, expected: &str, found: Tok) -> crate::Error {
+        format!("[byte {}]: expected {expected}, found {}", self.events.offset(), found.name()).into()
+    }
+    /// Skips the rest of the value that starts with `tok`
+    fn skip(&mut self, tok: Tok) -> Result<()> {
+        let mut depth = 0_usize;
+        let mut tok = tok;
+        loop {
+            match tok {
+                Tok::StartArray | Tok::StartObject => depth += 1,
+                Tok::EndArray | Tok::EndObject => depth -= 1,
+                _ => {},
+            }
+            if depth == 0 {
+                return Ok(())
+            }
+            tok = self.next()?;
+        }
+    }
+    /// Checks that the document has ended after the value
+    pub fn end(mut self) -> Result<()> {
+        if self.peeked.is_some() {
+            return Err("Unexpected content after the value".into())
+        }
+        if let Some(event) = self.events.next_event(&mut self.input)? {
+            return Err(format!("Unexpected {event:?} after the value").into())
+        }
+        self.events.finish()?;
+        Ok(())
+    }
+}
+
+/// Deserializes a `T` from the text
+pub fn from_str<T: DeserializeOwned>(text: &str) -> Result<T> {
+    let (mut scratch, mut nesting) = (vec![0; text.len()], vec![0; text.len()]);
+    let mut de = Deserializer::new(text, &mut scratch, &mut nesting);
+    let value = T::deserialize(&mut de)?;
+    de.end()?;
+    Ok(value)
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'_> {
+    type Error = crate::Error;
+
+    #[allow(clippy::cast_sign_loss)]
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.next()? {
+            Tok::StartArray => {
+                let value = visitor.visit_seq(Access { de: &mut *self })?;
+                match self.next()? {
+                    Tok::EndArray => Ok(value),
+                    _ => Err("Unexpected elements at the end of the array".into()),
+                }
+            },
+            Tok::StartObject => {
+                let value = visitor.visit_map(Access { de: &mut *self })?;
+                match self.next()? {
+                    Tok::EndObject => Ok(value),
+                    _ => Err("Unexpected members at the end of the object".into()),
+                }
+            },
+            Tok::String => visitor.visit_str(&self.text()),
+            Tok::Number(n) => match integral(n) {
+                Some(i) if i < 0 => visitor.visit_i64(i),
+                Some(i) => visitor.visit_u64(i as u64),
+                None => visitor.visit_f64(n),
+            },
+            Tok::True => visitor.visit_bool(true),
+            Tok::False => visitor.visit_bool(false),
+            Tok::Null => visitor.visit_unit(),
+            tok => Err(self.unexpected("value", tok)),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.peek()? == Tok::Null {
+            self.next()?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Enums are read like `serde_json` writes them: unit variants as
+    /// a string, and the rest as an object with a single member
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.next()? {
+            Tok::String => visitor.visit_enum(StrDeserializer::new(&self.text())),
+            Tok::StartObject => {
+                match self.next()? {
+                    Tok::Key => {},
+                    tok => return Err(self.unexpected("key", tok)),
+                }
+                let value = visitor.visit_enum(Access { de: &mut *self })?;
+                match self.next()? {
+                    Tok::EndObject => Ok(value),
+                    tok => Err(self.unexpected("end of object", tok)),
+                }
+            },
+            tok => Err(self.unexpected("enum", tok)),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let tok = self.next()?;
+        self.skip(tok)?;
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+/// Gives the elements of arrays, the members of objects
+/// and the variants of enums to the visitors
+struct Access<'b, 'a> {
+    de: &'b mut Deserializer<'a>,
+}
+
+impl<'de> de::SeqAccess<'de> for Access<'_, '_> {
+    type Error = crate::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if self.de.peek()? == Tok::EndArray {
+            return Ok(None)
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Access<'_, '_> {
+    type Error = crate::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if self.de.peek()? == Tok::EndObject {
+            return Ok(None)
+        }
+        self.de.next()?;
+        seed.deserialize(StrDeserializer::new(&self.de.text())).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for Access<'_, '_> {
+    type Error = crate::Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let variant = seed.deserialize(StrDeserializer::<crate::Error>::new(&self.de.text()))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Access<'_, '_> {
+    type Error = crate::Error;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self.de)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(self.de, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_map(self.de, visitor)
+    }
+}
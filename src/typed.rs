@@ -0,0 +1,461 @@
+//! Typed parsing
+//!
+//! Parses a document directly into Rust values, driven by their types,
+//! without building a [Json] first. The input is read with an
+//! [`EventParser`], so the only allocations are the values themselves.
+//!
+//! Types implement [Typed] to describe their shape. It's implemented for
+//! strings, numbers, booleans, [Option], [Vec], maps with string keys and
+//! [Json], and the [`typed_struct`](crate::typed_struct) macro implements
+//! it for structs of fields.
+//!
+//! Errors reference the path to the value and the expected type.
+//!
+//! # Example
+//! ```
+//! use json::typed_struct;
+//!
+//! typed_struct! {
+//!     #[derive(Debug, PartialEq)]
+//!     pub struct Server {
+//!         pub host: String,
+//!         pub port: u16,
+//!         pub tags: Vec<String>,
+//!         pub debug: Option<bool>,
+//!     }
+//! }
+//!
+//! let server: Server = json::typed::parse(r#"{
+//!     "host" : "localhost",
+//!     "port" : 8080,
+//!     "tags" : ["a", "b"],
+//!     "ignored" : { "x" : [1] }
+//! }"#).unwrap();
+//! assert_eq!(server, Server {
+//!     host: "localhost".into(),
+//!     port: 8080,
+//!     tags: vec!["a".into(), "b".into()],
+//!     debug: None,
+//! });
+//!
+//! let err = json::typed::parse::<Server>(r#"{ "host" : "a", "port" : 80, "tags" : [1] }"#).unwrap_err();
+//! assert_eq!(err.to_string(), "/tags/0: expected string, found number");
+//! ```
+
+use alloc::collections::BTreeMap;
+
+use crate::escape::unescape;
+use crate::events::{Event, EventParser};
+use crate::prelude::*;
+use crate::schema::{Schema, StreamValidator};
+use crate::{Json, Result};
+
+/// A type that can be parsed by a [`TypedParser`]
+pub trait Typed: Sized {
+    /// Name of the expected value, used in error messages
+    fn expected() -> &'static str;
+    /// Parses the next value
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self>;
+    /// Value used when an object doesn't have the member of a field
+    /// of this type. By default, missing members are an error.
+    fn missing() -> Option<Self> { None }
+}
+
+/// An event without its text. See [`TypedParser::text`]
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Tok {
+    StartObject, EndObject,
+    StartArray, EndArray,
+    Key, String,
+    Number(f64),
+    True, False, Null,
+}
+
+impl Tok {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Tok::StartObject => "object",
+            Tok::StartArray => "array",
+            Tok::EndObject | Tok::EndArray => "end of container",
+            Tok::Key => "key",
+            Tok::String => "string",
+            Tok::Number(_) => "number",
+            Tok::True | Tok::False => "boolean",
+            Tok::Null => "null",
+        }
+    }
+}
+
+impl From<Event<'_>> for Tok {
+    fn from(event: Event<'_>) -> Self {
+        match event {
+            Event::StartObject => Tok::StartObject,
+            Event::EndObject => Tok::EndObject,
+            Event::StartArray => Tok::StartArray,
+            Event::EndArray => Tok::EndArray,
+            Event::Key(_) => Tok::Key,
+            Event::String(_) => Tok::String,
+            Event::Number(n) => Tok::Number(n),
+            Event::True => Tok::True,
+            Event::False => Tok::False,
+            Event::Null => Tok::Null,
+        }
+    }
+}
+
+/// Pulls the values of a document for [Typed] implementations
+pub struct TypedParser<'a> {
+    events: EventParser<'a>,
+    input: &'a [u8],
+    peeked: Option<Tok>,
+    path: String,
+    /// Reused buffer for the keys of objects
+    key: String,
+    validator: Option<StreamValidator<'a>>,
+}
+
+impl<'a> TypedParser<'a> {
+    fn new(text: &'a str, scratch: &'a mut [u8], nesting: &'a mut [u8]) -> Self {
+        TypedParser {
+            events: EventParser::new(scratch, nesting),
+            input: text.as_bytes(),
+            peeked: None,
+            path: String::new(),
+            key: String::new(),
+            validator: None,
+        }
+    }
+    /// Returns the next event, without consuming it
+    fn peek(&mut self) -> Result<Tok> {
+        if let Some(tok) = self.peeked {
+            return Ok(tok)
+        }
+        let event = match self.events.next_event(&mut self.input)? {
+            Some(event) => Some(event),
+            None => self.events.finish()?,
+        };
+        let Some(event) = event else {
+            return Err("Unexpected end of input".into())
+        };
+        if let Some(validator) = &mut self.validator {
+            validator.event(event);
+        }
+        let tok = Tok::from(event);
+        self.peeked = Some(tok);
+        Ok(tok)
+    }
+    fn next(&mut self) -> Result<Tok> {
+        let tok = self.peek()?;
+        self.peeked = None;
+        Ok(tok)
+    }
+    /// Text of the last key or string, still escaped
+    fn text(&self) -> &str { self.events.last_token() }
+    /// Builds an error at the current path
+    pub fn error(&self, msg: impl Display) -> crate::Error {
+        let path = if self.path.is_empty() { "/" } else { &self.path };
+        format!("{path}: {msg}").into()
+    }
+    fn unexpected(&self, expected: &str, found: Tok) -> crate::Error {
+        self.error(format_args!("expected {expected}, found {}", found.name()))
+    }
+    /// Builds the error for a missing field of an object
+    pub fn missing_field(&self, name: &str) -> crate::Error {
+        self.error(format_args!("missing field '{name}'"))
+    }
+    /// Parses the next value as a `T`
+    pub fn parse<T: Typed>(&mut self) -> Result<T> { T::parse(self) }
+    /// Parses a string, resolving its escape sequences
+    pub fn string(&mut self) -> Result<String> {
+        match self.next()? {
+            Tok::String => Ok(unescape(self.text()).into_owned()),
+            tok => Err(self.unexpected("string", tok)),
+        }
+    }
+    /// Parses a number. `expected` is used in the error message
+    pub fn number(&mut self, expected: &str) -> Result<f64> {
+        match self.next()? {
+            Tok::Number(n) => Ok(n),
+            tok => Err(self.unexpected(expected, tok)),
+        }
+    }
+    /// Parses a boolean
+    pub fn boolean(&mut self) -> Result<bool> {
+        match self.next()? {
+            Tok::True => Ok(true),
+            Tok::False => Ok(false),
+            tok => Err(self.unexpected("boolean", tok)),
+        }
+    }
+    /// Consumes the next value if it's null. Returns true if it was
+    pub fn null(&mut self) -> Result<bool> {
+        let null = self.peek()? == Tok::Null;
+        if null {
+            self.next()?;
+        }
+        Ok(null)
+    }
+    /// Parses an array, calling `f` to parse each of its elements
+    pub fn array(&mut self, mut f: impl FnMut(&mut Self) -> Result<()>) -> Result<()> {
+        match self.next()? {
+            Tok::StartArray => {},
+            tok => return Err(self.unexpected("array", tok)),
+        }
+        let len = self.path.len();
+        let mut i = 0;
+        while self.peek()? != Tok::EndArray {
+            write!(self.path, "/{i}")?;
+            f(self)?;
+            self.path.truncate(len);
+            i += 1;
+        }
+        self.next()?;
+        Ok(())
+    }
+    /// Parses an object. `f` is called with each key, unescaped,
+    /// to parse the value of its member. If `f` returns false,
+    /// the value is skipped.
+    pub fn object(&mut self, mut f: impl FnMut(&mut Self, &str) -> Result<bool>) -> Result<()> {
+        match self.next()? {
+            Tok::StartObject => {},
+            tok => return Err(self.unexpected("object", tok)),
+        }
+        let len = self.path.len();
+        while self.next()? == Tok::Key {
+            let mut key = core::mem::take(&mut self.key);
+            key.clear();
+            key.push_str(&unescape(self.text()));
+            self.path.push('/');
+            self.path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+            if !f(self, &key)? {
+                self.skip()?;
+            }
+            self.path.truncate(len);
+            self.key = key;
+        }
+        Ok(())
+    }
+    /// Skips the next value
+    pub fn skip(&mut self) -> Result<()> {
+        let mut depth = 0_usize;
+        loop {
+            match self.next()? {
+                Tok::StartArray | Tok::StartObject => depth += 1,
+                Tok::EndArray | Tok::EndObject => depth -= 1,
+                _ => {},
+            }
+            if depth == 0 {
+                return Ok(())
+            }
+        }
+    }
+    /// Checks that the document has ended, and that it's valid
+    /// against the schema, if any
+    fn end(mut self) -> Result<()> {
+        if let Some(event) = self.events.next_event(&mut self.input)? {
+            return Err(format!("Unexpected {event:?} after the value").into())
+        }
+        self.events.finish()?;
+        match self.validator.map(StreamValidator::finish).and_then(|e| e.into_iter().next()) {
+            Some(err) => Err(err.to_string().into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses the text into a `T`
+pub fn parse<T: Typed>(text: &str) -> Result<T> {
+    let (mut scratch, mut nesting) = (vec![0; text.len()], vec![0; text.len()]);
+    let mut p = TypedParser::new(text, &mut scratch, &mut nesting);
+    let value = p.parse()?;
+    p.end()?;
+    Ok(value)
+}
+
+/// Same as [parse], but also validates the document
+/// against `schema`, in the same pass
+///
+/// Fails with the first [validation error](crate::schema::ValidationError) found.
+pub fn parse_with_schema<T: Typed>(text: &str, schema: &Schema) -> Result<T> {
+    let (mut scratch, mut nesting) = (vec![0; text.len()], vec![0; text.len()]);
+    let mut p = TypedParser::new(text, &mut scratch, &mut nesting);
+    p.validator = Some(StreamValidator::new(schema));
+    let value = p.parse()?;
+    p.end()?;
+    Ok(value)
+}
+
+impl Typed for String {
+    fn expected() -> &'static str { "string" }
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self> { p.string() }
+}
+
+impl Typed for Box<str> {
+    fn expected() -> &'static str { "string" }
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self> { p.string().map(Into::into) }
+}
+
+impl Typed for bool {
+    fn expected() -> &'static str { "boolean" }
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self> { p.boolean() }
+}
+
+impl Typed for f64 {
+    fn expected() -> &'static str { "number" }
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self> { p.number("number") }
+}
+
+impl Typed for f32 {
+    fn expected() -> &'static str { "number" }
+    #[allow(clippy::cast_possible_truncation)]
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self> { p.number("number").map(|n| n as f32) }
+}
+
+macro_rules! typed_int {
+    ( $( $ty:ty ),* ) => {
+        $(
+            impl Typed for $ty {
+                fn expected() -> &'static str { stringify!($ty) }
+                #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss, clippy::cast_sign_loss, clippy::cast_lossless)]
+                fn parse(p: &mut TypedParser<'_>) -> Result<Self> {
+                    let n = p.number(stringify!($ty))?;
+                    /* MAX + 1 is exact as a f64, unlike the MAX of the 64 bit types */
+                    if fract(n) != 0.0 || n < <$ty>::MIN as f64 || n >= <$ty>::MAX as f64 + 1.0 {
+                        return Err(p.error(format_args!("expected {}, found {n}", stringify!($ty))))
+                    }
+                    Ok(n as $ty)
+                }
+            }
+        )*
+    };
+}
+
+typed_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl<T: Typed> Typed for Option<T> {
+    fn expected() -> &'static str { T::expected() }
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self> {
+        if p.null()? {
+            return Ok(None)
+        }
+        T::parse(p).map(Some)
+    }
+    fn missing() -> Option<Self> { Some(None) }
+}
+
+impl<T: Typed> Typed for Vec<T> {
+    fn expected() -> &'static str { "array" }
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self> {
+        let mut vec = Vec::new();
+        p.array(|p| {
+            vec.push(T::parse(p)?);
+            Ok(())
+        })?;
+        Ok(vec)
+    }
+}
+
+impl<T: Typed> Typed for BTreeMap<String, T> {
+    fn expected() -> &'static str { "object" }
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self> {
+        let mut map = BTreeMap::new();
+        p.object(|p, key| {
+            map.insert(key.to_string(), T::parse(p)?);
+            Ok(true)
+        })?;
+        Ok(map)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Typed, S: core::hash::BuildHasher + Default> Typed for std::collections::HashMap<String, T, S> {
+    fn expected() -> &'static str { "object" }
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self> {
+        let mut map = std::collections::HashMap::default();
+        p.object(|p, key| {
+            map.insert(key.to_string(), T::parse(p)?);
+            Ok(true)
+        })?;
+        Ok(map)
+    }
+}
+
+/// Builds the value as a [Json], for the parts of the document without a fixed shape
+impl Typed for Json {
+    fn expected() -> &'static str { "value" }
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self> {
+        Ok(match p.peek()? {
+            Tok::StartArray => {
+                let mut elems = Vec::new();
+                p.array(|p| {
+                    elems.push(Json::parse(p)?);
+                    Ok(())
+                })?;
+                elems.into()
+            },
+            Tok::StartObject => {
+                let mut obj = Map::default();
+                p.object(|p, _| {
+                    let key = p.text().into();
+                    obj.insert(key, Json::parse(p)?);
+                    Ok(true)
+                })?;
+                Json::Object(obj)
+            },
+            Tok::String => {
+                p.next()?;
+                Json::String(p.text().into())
+            },
+            Tok::Number(n) => {
+                p.next()?;
+                Json::Number(from_f64(n))
+            },
+            Tok::True => { p.next()?; Json::True },
+            Tok::False => { p.next()?; Json::False },
+            Tok::Null => { p.next()?; Json::Null },
+            tok => return Err(p.unexpected("value", tok)),
+        })
+    }
+}
+
+/// Declares a struct, and implements [Typed](crate::typed::Typed) for it
+///
+/// The struct is parsed from an object with a member for each field,
+/// named like it. Members with other names are skipped, and [Option]
+/// fields can be missing. See the [module documentation](crate::typed).
+#[macro_export]
+macro_rules! typed_struct {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $( $(#[$fmeta:meta])* $fvis:vis $field:ident : $ty:ty ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $( $(#[$fmeta])* $fvis $field : $ty ),*
+        }
+
+        impl $crate::typed::Typed for $name {
+            fn expected() -> &'static str { "object" }
+            fn parse(p: &mut $crate::typed::TypedParser<'_>) -> ::core::result::Result<Self, $crate::Error> {
+                $( let mut $field: ::core::option::Option<$ty> = ::core::option::Option::None; )*
+                p.object(|p, key| {
+                    match key {
+                        $( stringify!($field) => $field = ::core::option::Option::Some(p.parse()?), )*
+                        _ => return ::core::result::Result::Ok(false),
+                    }
+                    ::core::result::Result::Ok(true)
+                })?;
+                ::core::result::Result::Ok(Self {
+                    $(
+                        $field: match $field.or_else(<$ty as $crate::typed::Typed>::missing) {
+                            ::core::option::Option::Some(v) => v,
+                            ::core::option::Option::None => return ::core::result::Result::Err(p.missing_field(stringify!($field))),
+                        },
+                    )*
+                })
+            }
+        }
+    };
+}
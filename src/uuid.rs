@@ -0,0 +1,45 @@
+//! Conversions between [Json] strings and [`Uuid`]s
+
+use uuid::Uuid;
+
+use crate::prelude::*;
+use crate::{Json, JsonPointer, Result};
+
+impl Json {
+    /// Parses the inner string as a [Uuid].
+    ///
+    /// Returns `None` if this is not a String variant,
+    /// or if it doesn't hold a valid UUID.
+    pub fn as_uuid(&self) -> Option<Uuid> {
+        Uuid::parse_str(self.string()?).ok()
+    }
+    /// Parses the string referenced by the given JSON pointer as a [Uuid].
+    ///
+    /// Unlike [`as_uuid`](Self::as_uuid), the error says which
+    /// value failed, and why.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "users" : [ { "id" : "67e55044-10b1-426f-9247-bb680e5fe0c8" }, { "id" : 12 } ] });
+    ///
+    /// assert_eq!(j.uuid_at("/users/0/id").unwrap().to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    ///
+    /// let err = j.uuid_at("/users/1/id").unwrap_err();
+    /// assert_eq!(err.to_string(), "/users/1/id: expected a UUID string, found number");
+    /// ```
+    pub fn uuid_at(&self, ptr: impl AsRef<str>) -> Result<Uuid> {
+        let ptr = JsonPointer::parse(ptr.as_ref())?;
+        let json = ptr.resolve(self).ok_or_else(|| format!("{ptr}: missing value"))?;
+        let s = json.string().ok_or_else(|| format!("{ptr}: expected a UUID string, found {}", json.type_name()))?;
+        Uuid::parse_str(s).map_err(|e| format!("{ptr}: invalid UUID \"{s}\": {e}").into())
+    }
+}
+
+/// Builds a String with the UUID in its hyphenated, lowercase form
+impl From<Uuid> for Json {
+    fn from(value: Uuid) -> Self {
+        Json::String(value.hyphenated().to_string().into())
+    }
+}
@@ -0,0 +1,333 @@
+//! Recursive traversal of [Json] documents
+
+use core::iter::Enumerate;
+use core::slice;
+
+use crate::escape::unescape;
+use crate::prelude::*;
+use crate::{Json, JsonPointer};
+
+/// Visits every value in pre-order, stopping when `f` returns false
+fn walk<'a>(json: &'a Json, path: &mut JsonPointer, f: &mut dyn FnMut(&JsonPointer, &'a Json) -> bool) -> bool {
+    if !f(path, json) {
+        return false
+    }
+    match json {
+        Json::Array(arr) => {
+            for (i, e) in arr.iter().enumerate() {
+                path.push(i.to_string());
+                let cont = walk(e, path, f);
+                path.pop();
+                if !cont { return false }
+            }
+        },
+        Json::Object(obj) => {
+            for (k, v) in obj {
+                path.push(&**k);
+                let cont = walk(v, path, f);
+                path.pop();
+                if !cont { return false }
+            }
+        },
+        _ => {},
+    }
+    true
+}
+
+fn retain(json: &mut Json, path: &mut JsonPointer, f: &mut dyn FnMut(&JsonPointer, &Json) -> bool) {
+    match json {
+        Json::Array(arr) => {
+            let mut elems = core::mem::take(arr).into_vec();
+            let mut i = 0;
+            elems.retain_mut(|e| {
+                path.push(i.to_string());
+                i += 1;
+                retain(e, path, f);
+                let keep = f(path, e);
+                path.pop();
+                keep
+            });
+            *arr = elems.into_boxed_slice();
+        },
+        Json::Object(obj) => {
+            obj.retain(|k, v| {
+                path.push(&**k);
+                retain(v, path, f);
+                let keep = f(path, v);
+                path.pop();
+                keep
+            });
+        },
+        _ => {},
+    }
+}
+
+fn transform(json: &mut Json, path: &mut JsonPointer, f: &mut dyn FnMut(&JsonPointer, &mut Json)) {
+    match json {
+        Json::Array(arr) => {
+            for (i, e) in arr.iter_mut().enumerate() {
+                path.push(i.to_string());
+                transform(e, path, f);
+                path.pop();
+            }
+        },
+        Json::Object(obj) => {
+            for (k, v) in obj.iter_mut() {
+                path.push(&**k);
+                transform(v, path, f);
+                path.pop();
+            }
+        },
+        _ => {},
+    }
+    f(path, json);
+}
+
+/// A container being traversed by [Leaves]
+enum Frame<'a> {
+    Array(Enumerate<slice::Iter<'a, Json>>),
+    /// Members sorted by their key
+    Object(alloc::vec::IntoIter<(&'a str, &'a Json)>),
+}
+
+/// Iterator over the scalar values of a document,
+/// returned by [`Json::leaves`]
+pub struct Leaves<'a> {
+    stack: Vec<Frame<'a>>,
+    /// Path to the value being visited
+    path: JsonPointer,
+    next: Option<&'a Json>,
+}
+
+impl<'a> Iterator for Leaves<'a> {
+    type Item = (JsonPointer, &'a Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.next.take() {
+                Some(Json::Array(arr)) => self.stack.push(Frame::Array(arr.iter().enumerate())),
+                Some(Json::Object(obj)) => {
+                    let mut members: Vec<_> = obj.iter().map(|(k, v)| (&**k, v)).collect();
+                    members.sort_by_cached_key(|(k, _)| unescape(k));
+                    self.stack.push(Frame::Object(members.into_iter()));
+                },
+                Some(leaf) => {
+                    let path = self.path.clone();
+                    if !self.stack.is_empty() {
+                        self.path.pop();
+                    }
+                    return Some((path, leaf))
+                },
+                None => {},
+            }
+            let child = match self.stack.last_mut()? {
+                Frame::Array(iter) => iter.next().map(|(i, e)| (i.to_string().into(), e)),
+                Frame::Object(iter) => iter.next().map(|(k, v)| (Box::from(k), v)),
+            };
+            if let Some((token, json)) = child {
+                self.path.push(token);
+                self.next = Some(json);
+            } else {
+                /* Leave the finished container */
+                self.stack.pop();
+                if !self.stack.is_empty() {
+                    self.path.pop();
+                }
+            }
+        }
+    }
+}
+
+impl Json {
+    /// Returns an iterator over the scalar values of this document,
+    /// with their paths. Arrays and objects, empty or not, are not
+    /// yielded themselves.
+    ///
+    /// The order is deterministic: elements are visited in order,
+    /// and members sorted by their key.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "b" : [true, []], "a" : { "c" : null } });
+    /// let leaves: Vec<_> = j.leaves().map(|(path, v)| format!("{path} = {v}")).collect();
+    /// assert_eq!(leaves, ["/a/c = null", "/b/0 = true"]);
+    /// ```
+    pub fn leaves(&self) -> Leaves<'_> {
+        Leaves { stack: Vec::new(), path: JsonPointer::root(), next: Some(self) }
+    }
+    /// Returns the pointers to all the values for which `pred` returns true.
+    ///
+    /// The document is traversed in pre-order, so parents
+    /// come before their children.
+    ///
+    /// # Example
+    /// ```
+    /// use json::{json, JsonPointer};
+    ///
+    /// let j = json!({ "a" : [1, null, { "b" : null }] });
+    /// let nulls = j.find(|_, v| v.is_null());
+    ///
+    /// assert_eq!(nulls.len(), 2);
+    /// assert!(nulls.contains(&JsonPointer::parse("/a/1").unwrap()));
+    /// assert!(nulls.contains(&JsonPointer::parse("/a/2/b").unwrap()));
+    /// ```
+    pub fn find(&self, mut pred: impl FnMut(&JsonPointer, &Json) -> bool) -> Vec<JsonPointer> {
+        let mut found = Vec::new();
+        walk(self, &mut JsonPointer::root(), &mut |path, value| {
+            if pred(path, value) {
+                found.push(path.clone());
+            }
+            true
+        });
+        found
+    }
+    /// Returns the pointer to the first value for which `pred` returns true
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "users" : [ { "id" : 1 }, { "id" : 2 } ] });
+    /// let ptr = j.find_first(|_, v| v.get("id").and_then(|id| id.number()) == Some(2.0));
+    /// assert_eq!(ptr.unwrap().to_string(), "/users/1");
+    /// ```
+    pub fn find_first(&self, mut pred: impl FnMut(&JsonPointer, &Json) -> bool) -> Option<JsonPointer> {
+        let mut found = None;
+        walk(self, &mut JsonPointer::root(), &mut |path, value| {
+            if pred(path, value) {
+                found = Some(path.clone());
+                false
+            } else {
+                true
+            }
+        });
+        found
+    }
+    /// Returns the number of values for which `pred` returns true.
+    /// Like [find](Self::find), every value is visited, including
+    /// the arrays and objects, and the root.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "a" : [1, null, { "b" : null }], "c" : null });
+    /// assert_eq!(j.count_matching(|_, v| v.is_null()), 3);
+    /// assert_eq!(j.count_matching(|path, _| path.last() == Some("b")), 1);
+    /// ```
+    pub fn count_matching(&self, mut pred: impl FnMut(&JsonPointer, &Json) -> bool) -> usize {
+        let mut count = 0;
+        walk(self, &mut JsonPointer::root(), &mut |path, value| {
+            if pred(path, value) {
+                count += 1;
+            }
+            true
+        });
+        count
+    }
+    /// Returns true if `pred` returns true for any value of this document.
+    /// The traversal stops at the first one.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "users" : [{ "email" : "a@b.c" }, { "email" : "" }] });
+    /// assert!(j.any(|path, v| path.last() == Some("email") && v.string() == Some("")));
+    /// assert!(!j.any(|_, v| v.is_null()));
+    /// ```
+    pub fn any(&self, mut pred: impl FnMut(&JsonPointer, &Json) -> bool) -> bool {
+        !walk(self, &mut JsonPointer::root(), &mut |path, value| !pred(path, value))
+    }
+    /// Returns true if `pred` returns true for every value of this
+    /// document, including the arrays and objects, and the root.
+    /// The traversal stops at the first value that fails.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "prices" : [1.5, 3, 0.25] });
+    /// assert!(j.all(|path, v| path.tokens().len() < 2 || v.number().is_some_and(|n| n > 0.0)));
+    /// assert!(!j.all(|_, v| v.is_number()));
+    /// ```
+    pub fn all(&self, mut pred: impl FnMut(&JsonPointer, &Json) -> bool) -> bool {
+        walk(self, &mut JsonPointer::root(), &mut pred)
+    }
+    /// Recursively removes the object members and array
+    /// elements for which `pred` returns false.
+    ///
+    /// Values are visited in post-order: the children of a value are
+    /// filtered before calling `pred` on it, so it sees the pruned value.
+    /// The pointers passed to `pred` refer to the original document.
+    /// The root value is never removed.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut j = json!({
+    ///     "a" : null,
+    ///     "b" : { "c" : null },
+    ///     "_internal" : 1,
+    ///     "d" : [1, null, 2]
+    /// });
+    ///
+    /// j.retain(|path, v| {
+    ///     !v.is_null()
+    ///     && !path.last().is_some_and(|k| k.starts_with('_'))
+    ///     && !v.object().is_some_and(|o| o.is_empty())
+    /// });
+    /// assert_eq!(j, json!({ "d" : [1, 2] }));
+    /// ```
+    pub fn retain(&mut self, mut pred: impl FnMut(&JsonPointer, &Json) -> bool) {
+        retain(self, &mut JsonPointer::root(), &mut pred);
+    }
+    /// Applies `f` to every value of this document, in place.
+    ///
+    /// Values are visited leaf-first: the children of a value
+    /// are transformed before calling `f` on it. The root value
+    /// is visited last.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let mut j = json!({ "name" : "  Jhon ", "tags" : [" a", "b "] });
+    /// j.transform(|_, v| {
+    ///     if let Some(s) = v.string() {
+    ///         *v = s.trim().into();
+    ///     }
+    /// });
+    /// assert_eq!(j, json!({ "name" : "Jhon", "tags" : ["a", "b"] }));
+    /// ```
+    pub fn transform(&mut self, mut f: impl FnMut(&JsonPointer, &mut Json)) {
+        transform(self, &mut JsonPointer::root(), &mut f);
+    }
+    /// Returns a copy of this document with every value replaced by
+    /// the result of `f`. Like [transform](Self::transform), values
+    /// are visited leaf-first.
+    ///
+    /// # Example
+    /// ```
+    /// use json::json;
+    ///
+    /// let j = json!({ "a" : 1.25, "b" : [2.5, "x"] });
+    /// let rounded = j.map_values(|_, v| match v {
+    ///     json::Json::Number(n) => json!(n.round()),
+    ///     v => v,
+    /// });
+    /// assert_eq!(rounded, json!({ "a" : 1, "b" : [3, "x"] }));
+    /// ```
+    #[must_use]
+    pub fn map_values(&self, mut f: impl FnMut(&JsonPointer, Json) -> Json) -> Json {
+        let mut json = self.clone();
+        json.transform(|path, v| {
+            let value = core::mem::replace(v, Json::Null);
+            *v = f(path, value);
+        });
+        json
+    }
+}
@@ -0,0 +1,346 @@
+//! XML conversion
+//!
+//! Converts simple XML documents into [Json] and back.
+//!
+//! Every element becomes a member of its parent object. Attributes
+//! are stored under `@name` keys and the text content under `#text`.
+//! An element with no attributes and no children collapses into its
+//! text (or `null` if it's empty), and repeated elements are grouped
+//! into an array.
+//!
+//! # Example
+//! ```
+//! use json::Json;
+//!
+//! let j = Json::from_xml(r#"<user id="12"><name>Jhon</name><tag>a</tag><tag>b</tag></user>"#).unwrap();
+//! let user = j.get("user").unwrap();
+//! assert_eq!(user.get("@id").unwrap().string(), Some("12"));
+//! assert_eq!(user.get("name").unwrap().string(), Some("Jhon"));
+//! assert_eq!(user.get("tag").unwrap().array().unwrap().len(), 2);
+//! ```
+
+use crate::escape::{escape, unescape};
+use crate::prelude::*;
+use crate::{Json, Result};
+
+/// Prefix used for the keys holding attributes
+pub const ATTRIBUTE_PREFIX: char = '@';
+/// Key holding the text content of an element
+pub const TEXT_KEY: &str = "#text";
+
+struct XmlParser<'a> {
+    src: &'a str,
+    curr: usize,
+}
+
+impl XmlParser<'_> {
+    fn error<T>(&self, msg: &str) -> Result<T> {
+        Err(format!("[{}]: {msg}", self.curr).into())
+    }
+    fn rest(&self) -> &str {
+        &self.src[self.curr..]
+    }
+    fn starts_with(&self, s: &str) -> bool {
+        self.rest().starts_with(s)
+    }
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.curr += rest.len() - rest.trim_start().len();
+    }
+    fn skip_past(&mut self, end: &str) -> Result<()> {
+        match self.rest().find(end) {
+            Some(i) => { self.curr += i + end.len(); Ok(()) },
+            None => self.error(&format!("Expected '{end}'")),
+        }
+    }
+    fn expect(&mut self, s: &str) -> Result<()> {
+        if self.starts_with(s) {
+            self.curr += s.len();
+            Ok(())
+        } else {
+            self.error(&format!("Expected '{s}'"))
+        }
+    }
+    /// Skips comments, processing instructions and doctypes
+    fn skip_misc(&mut self) -> Result<()> {
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.starts_with("<!DOCTYPE") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(())
+            }
+        }
+    }
+    fn name(&mut self) -> Result<&str> {
+        let start = self.curr;
+        let len = self.rest()
+                      .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+                      .unwrap_or(self.rest().len());
+        if len == 0 {
+            return self.error("Expected name");
+        }
+        self.curr += len;
+        Ok(&self.src[start..self.curr])
+    }
+    fn document(&mut self) -> Result<Json> {
+        self.skip_misc()?;
+        let (name, value) = self.element()?;
+        self.skip_misc()?;
+        if self.curr < self.src.len() {
+            return self.error("Unexpected content after root element");
+        }
+        let mut map = Map::default();
+        map.insert(name, value);
+        Ok(Json::Object(map))
+    }
+    fn element(&mut self) -> Result<(Key,Json)> {
+        self.expect("<")?;
+        let name: Key = self.name()?.into();
+        let mut map = Map::default();
+
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("/>") {
+                self.curr += 2;
+                let value = if map.is_empty() { Json::Null } else { Json::Object(map) };
+                return Ok((name, value));
+            }
+            if self.starts_with(">") {
+                self.curr += 1;
+                break
+            }
+            let attr = format!("{ATTRIBUTE_PREFIX}{}", self.name()?);
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = if self.starts_with("\"") { "\"" } else { "'" };
+            self.expect(quote)?;
+            let Some(len) = self.rest().find(quote) else {
+                return self.error("Unterminated attribute value");
+            };
+            let value = decode_entities(&self.src[self.curr..self.curr + len]);
+            self.curr += len + 1;
+            map.insert(attr.into(), Json::String(escape(&value).into()));
+        }
+
+        let mut text = String::new();
+        loop {
+            if self.starts_with("</") {
+                self.curr += 2;
+                if self.name()? != &*name {
+                    return self.error(&format!("Expected closing tag for '{name}'"));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                break
+            } else if self.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.starts_with("<![CDATA[") {
+                self.curr += 9;
+                let Some(len) = self.rest().find("]]>") else {
+                    return self.error("Unterminated CDATA section");
+                };
+                text.push_str(&self.src[self.curr..self.curr + len]);
+                self.curr += len + 3;
+            } else if self.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.starts_with("<") {
+                let (child, value) = self.element()?;
+                insert_child(&mut map, child, value);
+            } else if self.curr >= self.src.len() {
+                return self.error(&format!("Unclosed element '{name}'"));
+            } else {
+                let len = self.rest().find('<').unwrap_or(self.rest().len());
+                text.push_str(&decode_entities(&self.src[self.curr..self.curr + len]));
+                self.curr += len;
+            }
+        }
+
+        let text = text.trim();
+        let value = if map.is_empty() {
+            if text.is_empty() { Json::Null } else { Json::String(escape(text).into()) }
+        } else {
+            if !text.is_empty() {
+                map.insert(TEXT_KEY.into(), Json::String(escape(text).into()));
+            }
+            Json::Object(map)
+        };
+        Ok((name, value))
+    }
+}
+
+fn insert_child(map: &mut Map<Key,Json>, key: Key, value: Json) {
+    match map.get_mut(&key) {
+        Some(Json::Array(arr)) => {
+            let mut elems = core::mem::take(arr).into_vec();
+            elems.push(value);
+            *arr = elems.into();
+        },
+        Some(prev) => {
+            let first = core::mem::replace(prev, Json::Null);
+            *prev = Json::Array(Box::new([first, value]));
+        },
+        None => { map.insert(key, value); },
+    }
+}
+
+fn decode_entities(s: &str) -> Cow<'_,str> {
+    if !s.contains('&') {
+        return Cow::Borrowed(s);
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let Some(end) = rest.find(';') else { break };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity.strip_prefix("#x")
+                       .or_else(|| entity.strip_prefix("#X"))
+                       .map(|hex| u32::from_str_radix(hex, 16))
+                       .or_else(|| entity.strip_prefix('#').map(str::parse))
+                       .and_then(core::result::Result::ok)
+                       .and_then(char::from_u32),
+        };
+        if let Some(c) = c {
+            out.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            out.push('&');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+fn encode_entities(s: &str, out: &mut dyn Write) -> fmt::Result {
+    for c in s.chars() {
+        match c {
+            '<' => out.write_str("&lt;")?,
+            '>' => out.write_str("&gt;")?,
+            '&' => out.write_str("&amp;")?,
+            '"' => out.write_str("&quot;")?,
+            '\'' => out.write_str("&apos;")?,
+            c => out.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if `c` can start an XML name
+fn is_name_start(c: char) -> bool {
+    matches!(c, ':' | 'A'..='Z' | '_' | 'a'..='z' | '\u{C0}'..='\u{D6}' | '\u{D8}'..='\u{F6}'
+              | '\u{F8}'..='\u{2FF}' | '\u{370}'..='\u{37D}' | '\u{37F}'..='\u{1FFF}'
+              | '\u{200C}'..='\u{200D}' | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}'
+              | '\u{3001}'..='\u{D7FF}' | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}'
+              | '\u{10000}'..='\u{EFFFF}')
+}
+
+/// Returns true if `s` is a valid XML name, for elements and attributes
+fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(is_name_start) && chars.all(|c| {
+        is_name_start(c) || matches!(c, '-' | '.' | '0'..='9' | '\u{B7}'
+                                       | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}')
+    })
+}
+
+/// Unescapes a key, checking that it's a valid XML name
+fn xml_name(key: &str) -> core::result::Result<Cow<'_,str>, fmt::Error> {
+    let name = unescape(key);
+    if is_name(&name) { Ok(name) } else { Err(fmt::Error) }
+}
+
+fn write_scalar(json: &Json, out: &mut dyn Write) -> fmt::Result {
+    match json {
+        Json::String(s) => encode_entities(&unescape(s), out),
+        Json::Number(n) => write!(out, "{n}"),
+        #[cfg(feature = "bigint")]
+        Json::BigInt(n) => write!(out, "{n}"),
+        #[cfg(feature = "decimal")]
+        Json::Decimal(n) => write!(out, "{n}"),
+        Json::True => out.write_str("true"),
+        Json::False => out.write_str("false"),
+        Json::Null | Json::Array(_) | Json::Object(_) => Ok(()),
+    }
+}
+
+fn write_element(key: &str, json: &Json, out: &mut dyn Write) -> fmt::Result {
+    let name = xml_name(key)?;
+    match json {
+        Json::Array(elems) => {
+            for e in elems {
+                write_element(key, e, out)?;
+            }
+            Ok(())
+        },
+        Json::Null => write!(out, "<{name}/>"),
+        Json::Object(obj) => {
+            write!(out, "<{name}")?;
+            for (k,v) in obj {
+                if let Some(attr) = k.strip_prefix(ATTRIBUTE_PREFIX) {
+                    write!(out, " {}=\"", xml_name(attr)?)?;
+                    write_scalar(v, out)?;
+                    out.write_char('"')?;
+                }
+            }
+            out.write_char('>')?;
+            if let Some(text) = obj.get(TEXT_KEY) {
+                write_scalar(text, out)?;
+            }
+            for (k,v) in obj {
+                if !k.starts_with(ATTRIBUTE_PREFIX) && &**k != TEXT_KEY {
+                    write_element(k, v, out)?;
+                }
+            }
+            write!(out, "</{name}>")
+        },
+        scalar => {
+            write!(out, "<{name}>")?;
+            write_scalar(scalar, out)?;
+            write!(out, "</{name}>")
+        }
+    }
+}
+
+impl Json {
+    /// Converts the given XML document into a [Json] object
+    ///
+    /// The result is an object with a single member,
+    /// named after the root element.
+    pub fn from_xml(text: impl AsRef<str>) -> Result<Json> {
+        XmlParser {
+            src: text.as_ref(),
+            curr: 0,
+        }.document()
+    }
+    /// Serializes the JSON object as XML into a `fmt::Write`.
+    ///
+    /// This is the inverse of [`from_xml`](Self::from_xml). Every member of
+    /// the top-level object becomes an element, so the object should have
+    /// exactly one member to produce a well formed document.
+    ///
+    /// Returns an error if this is not an object, or if any key
+    /// (without the `@` prefix of attributes) is not a valid XML name.
+    pub fn to_xml(&self, out: &mut dyn Write) -> fmt::Result {
+        let Json::Object(obj) = self else {
+            return Err(fmt::Error);
+        };
+        for (k,v) in obj {
+            write_element(k, v, out)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::{Cell, UnsafeCell};
+use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+
+use json::allocator::{in_allocator, Allocator, Routed};
+use json::Json;
+
+/// System allocator that counts the allocations of each thread
+struct Counting;
+
+std::thread_local! {
+    static FALLBACK_ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = FALLBACK_ALLOCS.try_with(|n| n.set(n.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Routed<Counting> = Routed::new(Counting);
+
+const ARENA_SIZE: usize = 1 << 16;
+
+/// Arena that never reuses its memory
+struct Bump {
+    buf: UnsafeCell<[u8; ARENA_SIZE]>,
+    used: AtomicUsize,
+    live: AtomicIsize,
+}
+
+unsafe impl Sync for Bump {}
+
+impl Bump {
+    const fn new() -> Self {
+        Self { buf: UnsafeCell::new([0; ARENA_SIZE]), used: AtomicUsize::new(0), live: AtomicIsize::new(0) }
+    }
+    fn owns<T: ?Sized>(&self, ptr: *const T) -> bool {
+        let base = self.buf.get() as usize;
+        (base..base + ARENA_SIZE).contains(&(ptr.cast::<u8>() as usize))
+    }
+    fn live(&self) -> isize { self.live.load(Ordering::Relaxed) }
+}
+
+unsafe impl Allocator for Bump {
+    fn allocate(&self, layout: Layout) -> *mut u8 {
+        let base = self.buf.get().cast::<u8>();
+        let start = self.used.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+            let start = (base as usize + used).next_multiple_of(layout.align()) - base as usize;
+            (start + layout.size() <= ARENA_SIZE).then_some(start + layout.size())
+        });
+        let Ok(used) = start else { return std::ptr::null_mut() };
+        let start = (base as usize + used).next_multiple_of(layout.align()) - base as usize;
+        self.live.fetch_add(1, Ordering::Relaxed);
+        unsafe { base.add(start) }
+    }
+    unsafe fn deallocate(&self, ptr: *mut u8, _layout: Layout) {
+        assert!(self.owns(ptr));
+        self.live.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+static ARENA: Bump = Bump::new();
+static INNER: Bump = Bump::new();
+
+#[test]
+fn routed_allocations() {
+    let text = r#"{ "a" : [1, 2, "three", { "b" : null }], "c" : "d" }"#;
+    let outside = Box::new([0_u8; 100]);
+    let before = FALLBACK_ALLOCS.get();
+    let (json, mut grown) = in_allocator(&ARENA, || {
+        let json = Json::deserialize(text).unwrap();
+        /* Freed inside the scope */
+        drop(json.clone());
+        /* Memory from the fallback, freed inside the scope */
+        drop(outside);
+        (json, vec![1_u8])
+    });
+    assert_eq!(FALLBACK_ALLOCS.get(), before);
+    assert!(ARENA.live() > 0);
+    assert!(ARENA.owns(grown.as_ptr()));
+    assert_eq!(json, Json::deserialize(text).unwrap());
+
+    /* Grown outside of the scope, it stays in the arena */
+    grown.extend_from_slice(&[2; 100]);
+    assert!(ARENA.owns(grown.as_ptr()));
+    assert_eq!(grown.len(), 101);
+
+    /* Nested scopes, and freeing the arena's memory in another scope */
+    let inner = in_allocator(&ARENA, || {
+        in_allocator(&INNER, || {
+            drop(json);
+            Box::new([1_u8; 100])
+        })
+    });
+    assert!(INNER.owns(&*inner));
+    drop(inner);
+    drop(grown);
+    assert_eq!(INNER.live(), 0);
+    assert_eq!(ARENA.live(), 0);
+
+    /* Outside of the scopes, the fallback is used */
+    let before = FALLBACK_ALLOCS.get();
+    let v = Box::new([1_u8; 100]);
+    assert!(!ARENA.owns(&*v));
+    assert_eq!(FALLBACK_ALLOCS.get(), before + 1);
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "bigint")]
+
+use json::{Json, JsonType};
+use num_bigint::BigInt;
+
+#[test]
+fn big_integers() {
+    let text = "[123456789012345678901234567890,-98765432109876543210,9223372036854775807,18446744073709551615,1.5]";
+    let json = Json::deserialize(text).unwrap();
+    let elems = json.array().unwrap();
+
+    let big: BigInt = "123456789012345678901234567890".parse().unwrap();
+    assert_eq!(elems[0].bigint(), Some(&big));
+    assert_eq!(elems[0].number(), None);
+    assert!(elems[0].is_number());
+    assert_eq!(elems[0].json_type(), JsonType::Number);
+    assert!(matches!(elems[1], Json::BigInt(_)));
+
+    assert!(matches!(elems[2], Json::Number(_)));
+    assert!(matches!(elems[3], Json::Number(_)));
+    assert!(matches!(elems[4], Json::Number(_)));
+
+    assert_eq!(Json::from(big).to_string(), "123456789012345678901234567890");
+    assert_eq!(Json::deserialize("-98765432109876543210").unwrap().to_string(), "-98765432109876543210");
+}
+
+#[test]
+fn big_integers_as_numbers() {
+    use json::pattern::Pattern;
+    use json::schema::Schema;
+    use json::{json, json_get};
+
+    let json = Json::deserialize(r#"{ "big" : 123456789012345678901234567890, "neg" : -18446744073709551617 }"#).unwrap();
+    let big = json.get("big").unwrap();
+    assert!(big.matches(&Pattern::from(json!("<number>"))));
+
+    assert!(Schema::compile(&json!({ "type" : "integer" })).unwrap().is_valid(big));
+    let max = Schema::compile(&json!({ "type" : "number", "maximum" : 1000 })).unwrap();
+    assert_eq!(max.validate(big).unwrap_err().len(), 1);
+    assert!(Schema::compile(&json!({ "minimum" : 0 })).unwrap().validate(json.get("neg").unwrap()).is_err());
+
+    assert_eq!(u64::try_from(big).unwrap_err().to_string(), "123456789012345678901234567890 is out of range for u64");
+    assert_eq!(json_get!(json, "big" as f64).unwrap(), 1.2345678901234568e29);
+    assert_eq!(u64::try_from(&Json::from(BigInt::from(u64::MAX))).unwrap(), u64::MAX);
+    for n in [u64::MAX, (1 << 53) + 1] {
+        assert_eq!(Json::from_value(&n).to_value::<u64>().unwrap(), n);
+    }
+    assert_eq!(Json::from_value(&i64::MIN).to_value::<i64>().unwrap(), i64::MIN);
+    assert_eq!(Json::from_value(&u64::MAX).to_string(), "18446744073709551615");
+    assert_eq!(i64::try_from(&Json::from(BigInt::from(i64::MIN))).unwrap(), i64::MIN);
+}
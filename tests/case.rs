@@ -0,0 +1,41 @@
+use json::{json, Case};
+
+#[test]
+fn convert() {
+    let camel = |k| Case::SnakeToCamel.convert(k);
+    assert_eq!(camel("user_id"), "userId");
+    assert_eq!(camel("_private_key"), "_privateKey");
+    assert_eq!(camel("a__b_"), "a_B_");
+    assert_eq!(camel("line_2"), "line2");
+    assert_eq!(camel("año_ñu"), "añoÑu");
+
+    let snake = |k| Case::CamelToSnake.convert(k);
+    assert_eq!(snake("userId"), "user_id");
+    assert_eq!(snake("HTTPServer"), "http_server");
+    assert_eq!(snake("userID"), "user_id");
+    assert_eq!(snake("line2Total"), "line2_total");
+    assert_eq!(snake("Name"), "name");
+    assert_eq!(snake("already_snake"), "already_snake");
+}
+
+#[test]
+fn rename_keys() {
+    let mut j = json!({
+        "first_name" : "a_b",
+        "items" : [ { "unit_price" : 1 }, [ { "is_valid" : true } ] ],
+    });
+    j.rename_keys(Case::SnakeToCamel);
+    assert_eq!(j, json!({
+        "firstName" : "a_b",
+        "items" : [ { "unitPrice" : 1 }, [ { "isValid" : true } ] ],
+    }));
+
+    /* The closure receives unescaped keys, and its result is escaped */
+    let mut j = json!({ "a\"b" : 1 });
+    j.rename_keys_with(|k| format!("{k}\n"));
+    assert_eq!(j.to_string(), r#"{"a\"b\n":1}"#);
+
+    let mut j = json!({ "a_b" : 1, "aB" : 2 });
+    j.rename_keys(Case::SnakeToCamel);
+    assert_eq!(j.object().unwrap().len(), 1);
+}
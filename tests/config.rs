@@ -0,0 +1,26 @@
+use json::{config, json};
+
+#[test]
+fn env_substitution() {
+    std::env::set_var("JSONRS_TEST_USER", "a \"quoted\" name");
+    let j = config::from_str(r#"{
+        "user" : "${JSONRS_TEST_USER}",
+        "list" : ["${JSONRS_TEST_UNSET:-fallback}", "cost: $$5", "$plain"],
+        "${JSONRS_TEST_USER}" : 1
+    }"#).unwrap();
+    assert_eq!(j.get("user").unwrap().to_string(), r#""a \"quoted\" name""#);
+    assert_eq!(j.get("list").unwrap(), &json!(["fallback", "cost: $5", "$plain"]));
+    assert!(j.get("${JSONRS_TEST_USER}").is_some());
+
+    assert!(config::from_str(r#"["${JSONRS_TEST_UNSET}"]"#).is_err());
+    assert!(config::from_str(r#"["${JSONRS_TEST_USER"]"#).is_err());
+}
+
+#[test]
+fn load_file() {
+    let path = std::env::temp_dir().join("jsonrs_config_test.json");
+    std::fs::write(&path, r#"{ "dir" : "${JSONRS_TEST_DIR:-/tmp}" }"#).unwrap();
+    assert_eq!(config::load(&path).unwrap(), json!({ "dir" : "/tmp" }));
+    std::fs::remove_file(&path).unwrap();
+    assert!(config::load(&path).is_err());
+}
@@ -0,0 +1,81 @@
+use json::cst::{self, Cst, Node, NodeKind};
+use json::{json, Json};
+
+#[test]
+fn lossless() {
+    let src = r#"
+// Leading comment
+{
+    "a" : [ 1 , /* one, or two */ 2 ,3],
+    "b":{},   "c" : { "d" /* key */ : "x\"y" } ,
+    "e" : [ ],
+    "a" : -1.50,
+    "f": [[], [true, false, null]]
+} // Trailing, with a comma
+"#;
+    let doc = cst::parse(src).unwrap();
+    assert_eq!(doc.to_string(), src);
+    assert_eq!(doc.to_json().unwrap(), Json::deserialize(src).unwrap());
+
+    let NodeKind::Object { members, end } = &doc.value.kind else { panic!() };
+    assert_eq!(&*doc.value.before, "\n// Leading comment\n");
+    assert_eq!(&*doc.after, " // Trailing, with a comma\n");
+    assert_eq!(&**end, "\n");
+    let keys: Vec<_> = members.iter().map(|m| &*m.key).collect();
+    assert_eq!(keys, ["a", "b", "c", "e", "a", "f"]);
+    assert_eq!(members[4].value.kind, NodeKind::Number("-1.50".into()));
+    assert_eq!(members[2].comma.as_deref(), Some(" "));
+
+    let NodeKind::Array { elems, .. } = &members[0].value.kind else { panic!() };
+    assert_eq!(elems[0].comma.as_deref(), Some(" "));
+    assert_eq!(&*elems[1].value.before, " /* one, or two */ ");
+    assert_eq!(elems[2].comma, None);
+
+    let NodeKind::Object { members: inner, .. } = &members[2].value.kind else { panic!() };
+    assert_eq!(&*inner[0].colon_before, " /* key */ ");
+    assert_eq!(inner[0].value.kind, NodeKind::String(r#"x\"y"#.into()));
+
+    for src in ["1", " \"s\" ", "[]", "{}", "[1,2]"] {
+        assert_eq!(cst::parse(src).unwrap().to_string(), src);
+    }
+    assert!(cst::parse("[1,").is_err());
+}
+
+#[test]
+fn from_json() {
+    let json = json!({ "a" : [1, 2.5, "s"], "b" : null });
+    let node = Node::from(&json);
+    assert_eq!(node.to_string(), json.to_string());
+    assert_eq!(node.to_json().unwrap(), json);
+    assert_eq!(Cst::from(&json!([])).to_string(), "[]");
+
+    for invalid in ["x", "", "-", "1.2.3", "1 2", "\"1\""] {
+        let node = Node { before: "".into(), kind: NodeKind::Number(invalid.into()) };
+        assert!(node.to_json().is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn json5() {
+    let src = r#"{
+    /* "quoted" */
+    "a" : "x", // comment
+    "b c" : [
+        "it's",
+        1.50
+    ],
+    "e" : {}
+}
+"#;
+    let doc = cst::parse(src).unwrap();
+    assert_eq!(doc.to_json5(), r#"{
+    /* "quoted" */
+    a : 'x', // comment
+    'b c' : [
+        'it\'s',
+        1.50,
+    ],
+    e : {},
+}
+"#);
+}
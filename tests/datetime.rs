@@ -0,0 +1,27 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use json::{json, Json, SerializeConfig};
+
+#[test]
+fn datetimes() {
+    let j = json!({ "a" : "2024-05-01T12:30:00.5+02:00", "b" : "2024-05-01", "c" : 1 });
+    let dt = j.get("a").unwrap().as_datetime().unwrap();
+    assert_eq!(dt.timestamp_millis(), 1_714_559_400_500);
+    assert!(j.get("b").unwrap().as_datetime().is_none());
+    assert!(j.get("c").unwrap().as_datetime().is_none());
+
+    let utc: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 5, 1, 10, 30, 0).unwrap();
+    assert_eq!(Json::from(utc), json!("2024-05-01T10:30:00Z"));
+    assert_eq!(Json::from(dt), json!("2024-05-01T10:30:00.500Z"));
+
+    let conf = SerializeConfig { datetime_format: Some(SecondsFormat::Millis), ..Default::default() };
+    let mut out = String::new();
+    j.get("a").unwrap().serialize_with_config(&conf, &mut out).unwrap();
+    assert_eq!(out, r#""2024-05-01T10:30:00.500Z""#);
+
+    let conf = SerializeConfig { datetime_format: Some(SecondsFormat::Secs), ..Default::default() };
+    let mut out = String::new();
+    json!(["2024-05-01T12:30:00.5+02:00", "not a date"]).serialize_with_config(&conf, &mut out).unwrap();
+    assert_eq!(out, r#"["2024-05-01T10:30:00Z","not a date"]"#);
+}
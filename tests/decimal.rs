@@ -0,0 +1,56 @@
+#![cfg(feature = "decimal")]
+
+use json::{json, Json, JsonConfig};
+use rust_decimal::Decimal;
+
+#[test]
+fn decimals() {
+    let text = r#"{"price":19.90,"qty":3,"rate":0.0015,"tiny":0.00000000000000000000000000000001}"#;
+    let conf = || JsonConfig { decimals: true, ..Default::default() };
+    let json = Json::deserialize_with_config(text, conf()).unwrap();
+
+    let price = json.get("price").unwrap();
+    assert_eq!(price.decimal(), Some(Decimal::new(1990, 2)));
+    assert!(price.is_number());
+    assert_eq!(price.to_string(), "19.90");
+    assert_eq!(json.get("qty").unwrap().decimal(), Some(Decimal::from(3)));
+    assert_eq!(json.get("rate").unwrap().decimal(), Some(Decimal::new(15, 4)));
+    assert!(json.get("tiny").unwrap().decimal().is_none());
+    assert!(json.get("tiny").unwrap().number().is_some());
+
+    assert!(json!(0.1).decimal().is_none());
+    let a = Json::deserialize_with_config("0.1", conf()).unwrap().decimal().unwrap();
+    let b = Json::deserialize_with_config("0.2", conf()).unwrap().decimal().unwrap();
+    assert_eq!(Json::from(a + b).to_string(), "0.3");
+
+    assert!(Json::deserialize(text).unwrap().get("price").unwrap().decimal().is_none());
+}
+
+#[test]
+fn decimals_as_numbers() {
+    use json::pattern::Pattern;
+    use json::schema::Schema;
+    use json::json_get;
+
+    let conf = JsonConfig { decimals: true, ..Default::default() };
+    let json = Json::deserialize_with_config(r#"{ "a" : 2.50, "b" : 7, "big" : 9007199254740993 }"#, conf).unwrap();
+    let a = json.get("a").unwrap();
+    assert_eq!(a.number(), Some(2.5));
+    assert_eq!(json_get!(json, "a" as f64).unwrap(), 2.5);
+    assert_eq!(json_get!(json, "b" as u64).unwrap(), 7);
+    assert_eq!(u64::try_from(json.get("big").unwrap()).unwrap(), 9_007_199_254_740_993);
+    assert_eq!(u8::try_from(a).unwrap_err().to_string(), "2.50 is out of range for u8");
+    assert!(u8::try_from(json.get("big").unwrap()).is_err());
+
+    assert!(a.matches(&Pattern::from(json!("<number>"))));
+
+    let number = Schema::compile(&json!({ "type" : "number", "minimum" : 3 })).unwrap();
+    let errors = number.validate(a).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(&*errors[0].message, "2.5 is less than the minimum of 3");
+    assert!(number.is_valid(json.get("b").unwrap()));
+
+    let integer = Schema::compile(&json!({ "type" : "integer" })).unwrap();
+    assert!(integer.is_valid(json.get("b").unwrap()));
+    assert!(!integer.is_valid(a));
+}
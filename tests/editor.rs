@@ -0,0 +1,137 @@
+use json::editor::DocumentEditor;
+use json::{json, Json};
+
+const SRC: &str = r#"{
+    "name" : "json", // The name
+    /* Version of the package */
+    "version" : "1.0.0",
+    "tags" : [
+        "parser",
+        "serializer"
+    ],
+    "empty" : {}
+}
+"#;
+
+#[test]
+fn set() {
+    let mut editor = DocumentEditor::parse(SRC).unwrap();
+    editor.set("/version", &json!("1.0.1")).unwrap();
+    assert_eq!(editor.to_string(), SRC.replace("1.0.0", "1.0.1"));
+
+    editor.set("/empty/a", &json!([1, 2])).unwrap();
+    editor.set("/empty/b", &json!(null)).unwrap();
+    editor.set("/license", &json!("MIT")).unwrap();
+    assert_eq!(editor.to_string(), r#"{
+    "name" : "json", // The name
+    /* Version of the package */
+    "version" : "1.0.1",
+    "tags" : [
+        "parser",
+        "serializer"
+    ],
+    "empty" : {"a":[1,2],"b":null},
+    "license" : "MIT"
+}
+"#);
+    assert_eq!(editor.cst().to_json().unwrap(), Json::deserialize(editor.to_string()).unwrap());
+
+    editor.set("", &json!(1)).unwrap();
+    assert_eq!(editor.to_string(), "1\n");
+
+    let mut editor = DocumentEditor::parse(SRC).unwrap();
+    assert!(editor.set("/tags/2", &json!(1)).is_err());
+    assert!(editor.set("/missing/a", &json!(1)).is_err());
+    assert!(editor.set("/name/a", &json!(1)).is_err());
+    assert_eq!(editor.to_string(), SRC);
+}
+
+#[test]
+fn insert() {
+    let mut editor = DocumentEditor::parse(SRC).unwrap();
+    editor.insert("/tags/0", &json!("fast")).unwrap();
+    editor.insert("/tags/-", &json!("lossless")).unwrap();
+    editor.insert("/tags/2", &json!("small")).unwrap();
+    editor.insert("/empty/x", &json!(true)).unwrap();
+    assert_eq!(editor.to_string(), r#"{
+    "name" : "json", // The name
+    /* Version of the package */
+    "version" : "1.0.0",
+    "tags" : [
+        "fast",
+        "parser",
+        "small",
+        "serializer",
+        "lossless"
+    ],
+    "empty" : {"x":true}
+}
+"#);
+
+    let mut editor = DocumentEditor::parse("[1, 2]").unwrap();
+    editor.insert("/0", &json!(0)).unwrap();
+    editor.insert("/3", &json!(3)).unwrap();
+    assert_eq!(editor.to_string(), "[0, 1, 2, 3]");
+
+    let mut editor = DocumentEditor::parse("[ ]").unwrap();
+    editor.insert("/-", &json!(0)).unwrap();
+    assert_eq!(editor.to_string(), "[0 ]");
+
+    assert!(editor.insert("/2", &json!(0)).is_err());
+    assert!(editor.insert("/01", &json!(0)).is_err());
+    assert!(editor.insert("", &json!(0)).is_err());
+    assert!(editor.insert("/0/a", &json!(0)).is_err());
+    let mut editor = DocumentEditor::parse(SRC).unwrap();
+    assert!(editor.insert("/name", &json!(0)).is_err());
+}
+
+#[test]
+fn remove() {
+    let mut editor = DocumentEditor::parse(SRC).unwrap();
+    editor.remove("/tags/1").unwrap();
+    editor.remove("/empty").unwrap();
+    editor.remove("/name").unwrap();
+    assert_eq!(editor.to_string(), r#"{
+    /* Version of the package */
+    "version" : "1.0.0",
+    "tags" : [
+        "parser"
+    ]
+}
+"#);
+    editor.remove("/tags/0").unwrap();
+    editor.remove("/version").unwrap();
+    assert_eq!(editor.to_string(), "{\n    \"tags\" : [\n    ]\n}\n");
+
+    let mut editor = DocumentEditor::parse("[1, 2, 3]").unwrap();
+    editor.remove("/0").unwrap();
+    editor.remove("/0").unwrap();
+    assert_eq!(editor.to_string(), "[3]");
+
+    assert!(editor.remove("/1").is_err());
+    assert!(editor.remove("/x").is_err());
+    assert!(editor.remove("").is_err());
+    assert_eq!(editor.into_cst().to_string(), "[3]");
+}
+
+#[test]
+fn comments() {
+    let src = "{\n  \"a\" : 1, // a\n  // b\n  \"b\" : 2, // b\n  \"c\" : 3 // c\n}";
+
+    let mut editor = DocumentEditor::parse(src).unwrap();
+    editor.remove("/b").unwrap();
+    assert_eq!(editor.to_string(), "{\n  \"a\" : 1, // a\n  \"c\" : 3 // c\n}");
+    editor.set("/d", &json!(4)).unwrap();
+    assert_eq!(editor.to_string(), "{\n  \"a\" : 1, // a\n  \"c\" : 3, // c\n  \"d\" : 4\n}");
+    editor.remove("/d").unwrap();
+    editor.remove("/c").unwrap();
+    assert_eq!(editor.to_string(), "{\n  \"a\" : 1 // a\n}");
+
+    let mut editor = DocumentEditor::parse(src).unwrap();
+    editor.remove("/a").unwrap();
+    assert_eq!(editor.to_string(), "{\n  // b\n  \"b\" : 2, // b\n  \"c\" : 3 // c\n}");
+
+    let mut editor = DocumentEditor::parse("[\n  1, // one\n  // two\n  2\n]").unwrap();
+    editor.insert("/1", &json!(1.5)).unwrap();
+    assert_eq!(editor.to_string(), "[\n  1, // one\n  1.5,\n  // two\n  2\n]");
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "ejson")]
+
+use json::ejson::{ExtendedDate, ExtendedValue};
+use json::Json;
+
+#[test]
+fn accessors() {
+    let j = Json::deserialize(r#"{
+        "created" : { "$date" : { "$numberLong" : "1565546054692" } },
+        "updated" : { "$date" : "2019-08-11T17:54:14.692Z" },
+        "ts" : { "$timestamp" : { "t" : 1565545664, "i" : 1 } },
+        "bin" : { "$binary" : { "base64" : "AQID", "subType" : "00" } },
+        "nan" : { "$numberDouble" : "NaN" },
+        "plain" : { "$oid" : "abc", "other" : 1 }
+    }"#).unwrap();
+
+    assert_eq!(j.get("created").unwrap().date(), Some(ExtendedDate::Millis(1_565_546_054_692)));
+    assert_eq!(j.get("updated").unwrap().date(), Some(ExtendedDate::Iso("2019-08-11T17:54:14.692Z")));
+    #[cfg(not(feature = "compact_numbers"))]
+    assert_eq!(j.get("ts").unwrap().extended(), Some(ExtendedValue::Timestamp { t: 1_565_545_664, i: 1 }));
+    assert_eq!(j.get("bin").unwrap().extended(), Some(ExtendedValue::Binary { base64: "AQID", subtype: "00" }));
+    assert!(matches!(j.get("nan").unwrap().extended(), Some(ExtendedValue::Double(n)) if n.is_nan()));
+    assert_eq!(j.get("plain").unwrap().extended(), None);
+}
+
+#[test]
+fn round_trip() {
+    let values = [
+        ExtendedValue::ObjectId("5d505646cf6d4fe581014ab2"),
+        ExtendedValue::Int32(-3),
+        ExtendedValue::Int64(i64::MAX),
+        ExtendedValue::Double(1.5),
+        ExtendedValue::Decimal128("1.10"),
+        ExtendedValue::Date(ExtendedDate::Millis(0)),
+        ExtendedValue::Regex { pattern: "^a", options: "i" },
+        ExtendedValue::MinKey,
+        ExtendedValue::Undefined,
+    ];
+    for v in values {
+        let text = Json::from(v).to_string();
+        assert_eq!(Json::deserialize(&text).unwrap().extended(), Some(v));
+    }
+}
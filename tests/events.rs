@@ -0,0 +1,63 @@
+use json::events::{Event, EventError, EventParser};
+
+/// Parses the chunks, returning the events formatted with Debug
+fn events(chunks: &[&[u8]], scratch: usize, depth: usize) -> Result<Vec<String>, EventError> {
+    let mut scratch = vec![0; scratch];
+    let mut nesting = vec![0; depth];
+    let mut parser = EventParser::new(&mut scratch, &mut nesting);
+    let mut events = Vec::new();
+    for chunk in chunks {
+        let mut chunk = *chunk;
+        while let Some(event) = parser.next_event(&mut chunk)? {
+            events.push(format!("{event:?}"));
+        }
+    }
+    if let Some(event) = parser.finish()? {
+        events.push(format!("{event:?}"));
+    }
+    Ok(events)
+}
+
+#[test]
+fn event_parser() {
+    let text = br#" { "a" : [ 1.5, -2e3, "s\"]" ], "b" : { }, "c" : [true, false, null] } "#;
+    let expected: Vec<String> = [
+        Event::StartObject,
+        Event::Key("a"), Event::StartArray, Event::Number(1.5), Event::Number(-2000.0), Event::String("s\\\"]"), Event::EndArray,
+        Event::Key("b"), Event::StartObject, Event::EndObject,
+        Event::Key("c"), Event::StartArray, Event::True, Event::False, Event::Null, Event::EndArray,
+        Event::EndObject,
+    ].iter().map(|e| format!("{e:?}")).collect();
+
+    assert_eq!(events(&[text], 8, 2).unwrap(), expected);
+    let bytes: Vec<&[u8]> = text.chunks(1).collect();
+    assert_eq!(events(&bytes, 8, 2).unwrap(), expected);
+
+    assert_eq!(events(&[b"12", b"3"], 8, 0).unwrap(), vec![format!("{:?}", Event::Number(123.0))]);
+    assert_eq!(events(&[b" \"x\" "], 8, 0).unwrap(), vec![format!("{:?}", Event::String("x"))]);
+}
+
+#[test]
+fn event_parser_errors() {
+    let err = |chunks: &[&[u8]]| events(chunks, 8, 2).unwrap_err().msg;
+
+    assert_eq!(err(&[b"\"123456789\""]), "Token too long");
+    assert_eq!(err(&[b"[[[]]]"]), "Max depth reached");
+    assert_eq!(err(&[b"[1,]"]), "Trailing comma on list");
+    assert_eq!(err(&[b"{\"a\":1,}"]), "Trailing comma in object");
+    assert_eq!(err(&[b"{1:1}"]), "Expected STRING");
+    assert_eq!(err(&[b"[1 2]"]), "Expected comma after element");
+    assert_eq!(err(&[b"[1}"]), "Unclosed '['");
+    assert_eq!(err(&[b"[tru]"]), "Unknown token");
+    assert_eq!(err(&[b"[1", b"2"]), "Unexpected end of input");
+    assert_eq!(err(&[b"{} {}"]), "Unexpected data after the value");
+    assert_eq!(events(&[b"[1 2]"], 8, 2).unwrap_err().offset, 3);
+
+    let mut scratch = [0; 4];
+    let mut nesting = [0; 1];
+    let mut parser = EventParser::new(&mut scratch, &mut nesting);
+    let mut input: &[u8] = b"[:";
+    assert_eq!(parser.next_event(&mut input), Ok(Some(Event::StartArray)));
+    let err = parser.next_event(&mut input).unwrap_err();
+    assert_eq!(parser.next_event(&mut &b"]"[..]), Err(err));
+}
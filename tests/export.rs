@@ -0,0 +1,209 @@
+#![cfg(feature = "bindings")]
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::ptr;
+
+use json::export::{json_array_foreach, json_parse_events, JsonEventCallbacks, json_deserialize, json_deserialize_n, json_free, json_free_string, json_get_pointer, json_object_foreach, json_serialize, json_serialize_with_config, Json, JsonNonFinite, SerializeConfig};
+
+#[test]
+fn serialize() {
+    let text = CString::new(r#"{ "b" : [1, "ñ"], "a" : { "d" : null, "c" : true } }"#).unwrap();
+    let json = unsafe { json_deserialize(text.as_ptr()) };
+
+    let indent = CString::new("\t").unwrap();
+    let conf = SerializeConfig {
+        indent: indent.as_ptr(),
+        non_finite: JsonNonFinite::NonFiniteError,
+        escape_non_ascii: true,
+        sort_keys: true,
+    };
+    let out = unsafe { json_serialize_with_config(&json, conf) };
+    assert_eq!(unsafe { CStr::from_ptr(out) }.to_str().unwrap(),
+               "{\n\t\"a\": {\n\t\t\"c\": true,\n\t\t\"d\": null\n\t},\n\t\"b\": [\n\t\t1,\n\t\t\"\\u00f1\"\n\t]\n}");
+    unsafe { json_free_string(out) };
+
+    let out = unsafe { json_serialize(&json) };
+    assert!(!out.is_null());
+    unsafe { json_free_string(out) };
+
+    json_free(json);
+
+    let invalid = CString::new("[1,").unwrap();
+    let json = unsafe { json_deserialize(invalid.as_ptr()) };
+    assert!(unsafe { json_serialize(&json) }.is_null());
+    assert!(unsafe { json_serialize(ptr::null()) }.is_null());
+    json_free(json);
+}
+
+unsafe extern "C" fn collect_element(i: usize, val: *const Json, user_data: *mut c_void) -> bool {
+    let out = unsafe { &mut *user_data.cast::<Vec<String>>() };
+    let s = unsafe { json_serialize(val) };
+    out.push(format!("{i}={}", unsafe { CStr::from_ptr(s) }.to_str().unwrap()));
+    unsafe { json_free_string(s) };
+    i < 1
+}
+
+unsafe extern "C" fn collect_member(key: *const c_char, key_len: usize, val: *const Json, user_data: *mut c_void) -> bool {
+    let out = unsafe { &mut *user_data.cast::<Vec<(String, *const Json)>>() };
+    let key = unsafe { CStr::from_ptr(key) }.to_str().unwrap();
+    assert_eq!(key.len(), key_len);
+    out.push((key.to_owned(), val));
+    true
+}
+
+#[test]
+fn foreach() {
+    let text = CString::new(r#"{ "arr" : [1, "two", null], "obj" : { "x" : 1, "y" : 2 } }"#).unwrap();
+    let json = unsafe { json_deserialize(text.as_ptr()) };
+
+    let mut members = Vec::<(String, *const Json)>::new();
+    assert!(unsafe { json_object_foreach(&json, collect_member, (&raw mut members).cast()) });
+    members.sort_by(|a, b| a.0.cmp(&b.0));
+    let [(_, arr), (_, obj)] = members[..] else { panic!("{members:?}") };
+
+    let mut elems = Vec::<String>::new();
+    assert!(unsafe { json_array_foreach(arr, collect_element, (&raw mut elems).cast()) });
+    assert_eq!(elems, ["0=1", "1=\"two\""]);
+
+    let mut keys = Vec::<(String, *const Json)>::new();
+    assert!(unsafe { json_object_foreach(obj, collect_member, (&raw mut keys).cast()) });
+    let mut keys: Vec<_> = keys.into_iter().map(|(k, _)| k).collect();
+    keys.sort();
+    assert_eq!(keys, ["x", "y"]);
+
+    assert!(!unsafe { json_array_foreach(obj, collect_element, ptr::null_mut()) });
+    assert!(!unsafe { json_object_foreach(arr, collect_member, ptr::null_mut()) });
+    assert!(!unsafe { json_object_foreach(ptr::null(), collect_member, ptr::null_mut()) });
+
+    json_free(json);
+}
+
+#[test]
+fn get_pointer() {
+    let text = CString::new(r#"{ "a/b" : [ { "c" : 1.5 } ], "" : null, "d\"e\\f" : true, "\u00f1" : false }"#).unwrap();
+    let json = unsafe { json_deserialize(text.as_ptr()) };
+    let get = |ptr: &str| {
+        let ptr = CString::new(ptr).unwrap();
+        unsafe { json_get_pointer(&json, ptr.as_ptr()).as_ref() }
+    };
+
+    assert!(matches!(get("/a~1b/0/c"), Some(Json::Number(1.5))));
+    assert!(matches!(get("/a~1b/0"), Some(Json::Object { len: 1, .. })));
+    assert!(matches!(get("/"), Some(Json::Null)));
+    assert!(ptr::eq(get("").unwrap(), &json));
+    assert!(matches!(get(r#"/d"e\f"#), Some(Json::True)));
+    assert!(matches!(get("/ñ"), Some(Json::False)));
+
+    assert!(get("/a~1b/1").is_none());
+    assert!(get("/a~1b/00").is_none());
+    assert!(get("/a~1b/0/c/d").is_none());
+    assert!(get("/missing").is_none());
+    assert!(get("a").is_none());
+    assert!(get("/~2").is_none());
+    assert!(unsafe { json_get_pointer(&json, ptr::null()) }.is_null());
+
+    json_free(json);
+}
+
+#[test]
+fn deserialize_n() {
+    let buf = b"[1, 2] trailing \0 bytes";
+    let json = unsafe { json_deserialize_n(buf.as_ptr().cast(), 6) };
+    assert!(matches!(json, Json::Array { len: 2, .. }));
+    json_free(json);
+
+    let json = unsafe { json_deserialize_n(buf.as_ptr().cast(), 5) };
+    assert!(matches!(json, Json::Error));
+    json_free(json);
+
+    let json = unsafe { json_deserialize_n(buf.as_ptr().cast(), buf.len()) };
+    assert!(matches!(json, Json::Error));
+    json_free(json);
+
+    let invalid = [b'"', 0xff, b'"'];
+    let json = unsafe { json_deserialize_n(invalid.as_ptr().cast(), invalid.len()) };
+    assert!(matches!(json, Json::Error));
+    json_free(json);
+}
+
+#[test]
+fn panics_dont_unwind() {
+    let empty = CString::new("").unwrap();
+    let json = unsafe { json_deserialize(empty.as_ptr()) };
+    assert!(matches!(json, Json::Error));
+    json_free(json);
+
+    let json = unsafe { json_deserialize_n(ptr::null(), 0) };
+    assert!(matches!(json, Json::Error));
+    json_free(json);
+}
+
+fn events(user_data: *mut c_void) -> &'static mut Vec<String> {
+    unsafe { &mut *user_data.cast::<Vec<String>>() }
+}
+
+unsafe extern "C" fn on_start(user_data: *mut c_void) -> bool {
+    events(user_data).push("start".into());
+    true
+}
+
+unsafe extern "C" fn on_end(user_data: *mut c_void) -> bool {
+    events(user_data).push("end".into());
+    true
+}
+
+unsafe extern "C" fn on_key(key: *const c_char, len: usize, user_data: *mut c_void) -> bool {
+    let key = unsafe { std::slice::from_raw_parts(key.cast::<u8>(), len) };
+    events(user_data).push(format!("key {}", std::str::from_utf8(key).unwrap()));
+    true
+}
+
+unsafe extern "C" fn on_number(n: f64, user_data: *mut c_void) -> bool {
+    events(user_data).push(format!("{n}"));
+    n < 10.0
+}
+
+unsafe extern "C" fn on_error(offset: usize, msg: *const c_char, user_data: *mut c_void) {
+    let msg = unsafe { CStr::from_ptr(msg) }.to_str().unwrap();
+    events(user_data).push(format!("error at {offset}: {msg}"));
+}
+
+#[test]
+fn parse_events() {
+    let callbacks = JsonEventCallbacks {
+        start_object: Some(on_start),
+        end_object: Some(on_end),
+        start_array: Some(on_start),
+        end_array: Some(on_end),
+        key: Some(on_key),
+        string: None,
+        number: Some(on_number),
+        boolean: None,
+        null: None,
+        error: Some(on_error),
+    };
+    let parse = |text: &str| {
+        let text = CString::new(text).unwrap();
+        let mut out = Vec::<String>::new();
+        let res = unsafe { json_parse_events(text.as_ptr(), &callbacks, (&raw mut out).cast()) };
+        (res, out)
+    };
+
+    let (res, out) = parse(r#"{ "a" : [1, "s", true, null], "b" : {} }"#);
+    assert!(res);
+    assert_eq!(out, ["start", "key a", "start", "1", "end", "key b", "start", "end", "end"]);
+
+    assert_eq!(parse("2.5"), (true, vec!["2.5".into()]));
+    assert_eq!(parse("[1, 20, 3]"), (false, vec!["start".into(), "1".into(), "20".into()]));
+    assert_eq!(parse("[1 2]"), (false, vec!["start".into(), "1".into(), "error at 3: Expected comma after element".into()]));
+    assert_eq!(parse("[1, "), (false, vec!["start".into(), "1".into(), "error at 4: Unexpected end of input".into()]));
+    assert!(!unsafe { json_parse_events(c"1".as_ptr(), ptr::null(), ptr::null_mut()) });
+
+    let long = format!(r#"{{ "{}" : 1 }}"#, "k".repeat(100_000));
+    assert!(parse(&long).0);
+    let deep = "[".repeat(5000) + &"]".repeat(5000);
+    let (res, out) = parse(&deep);
+    assert!(!res);
+    assert_eq!(out.len(), 4097);
+    assert_eq!(out[4096], "error at 4096: Max depth reached");
+}
@@ -0,0 +1,26 @@
+use json::{extract, json};
+
+#[test]
+fn extract_pointers() {
+    let src = r#"{
+        "skipped" : { "deep" : [1, 2, { "x" : "]" }] },
+        "user" : { "id" : 7, "tags" : ["a", "b"], "id" : 8 },
+        "list" : [ [0, 1], { "k" : null } ],
+        "a/b" : true
+    }"#;
+    let values = extract(src, &["/user/id", "/user/tags", "/user/tags/1", "/list/1/k",
+                                "/list/0/5", "/a~1b", "/user/id/x", ""]).unwrap();
+    assert_eq!(values.len(), 6);
+    assert_eq!(values.get("/user/id"), Some(&json!(8)));
+    assert_eq!(values.get("/user/tags"), Some(&json!(["a", "b"])));
+    assert_eq!(values.get("/user/tags/1"), Some(&json!("b")));
+    assert_eq!(values.get("/list/1/k"), Some(&json!(null)));
+    assert_eq!(values.get("/a~1b"), Some(&json!(true)));
+    assert_eq!(values.get(""), Some(&json::Json::deserialize(src).unwrap()));
+
+    assert!(extract(src, &[]).unwrap().is_empty());
+    assert!(extract(src, &["no-slash"]).is_err());
+    assert!(extract(r#"{ "a" : [1, 2 }"#, &["/a/0"]).is_err());
+    assert!(extract(r#"{ "a" 1 }"#, &["/a"]).is_err());
+    assert!(extract("[1] 2", &["/0"]).is_err());
+}
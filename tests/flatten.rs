@@ -0,0 +1,48 @@
+use json::flatten::{ArrayStyle, FlattenConfig};
+use json::json;
+
+#[test]
+fn round_trip() {
+    let j = json!({
+        "db" : { "host" : "localhost", "ports" : [5432, 5433] },
+        "tags" : [],
+        "meta" : {},
+        "matrix" : [[1, 2], [3]]
+    });
+
+    let flat = j.flatten();
+    assert_eq!(flat, json!({
+        "db.host" : "localhost",
+        "db.ports[0]" : 5432,
+        "db.ports[1]" : 5433,
+        "tags" : [],
+        "meta" : {},
+        "matrix[0][0]" : 1,
+        "matrix[0][1]" : 2,
+        "matrix[1][0]" : 3
+    }));
+    assert_eq!(flat.unflatten().unwrap(), j);
+
+    let conf = FlattenConfig { separator: "_", arrays: ArrayStyle::Separator };
+    let flat = j.flatten_with(&conf);
+    assert_eq!(flat.get("db_ports_1"), Some(&json!(5433)));
+    assert_eq!(flat.unflatten_with(&conf).unwrap(), j);
+
+    let conf = FlattenConfig { separator: "/", arrays: ArrayStyle::Leaf };
+    let flat = j.flatten_with(&conf);
+    assert_eq!(flat.get("db/ports"), Some(&json!([5432, 5433])));
+    assert_eq!(flat.unflatten_with(&conf).unwrap(), j);
+
+    assert_eq!(json!([1, { "a" : 2 }]).flatten(), json!({ "[0]" : 1, "[1].a" : 2 }));
+    assert_eq!(json!({ "[0]" : 1, "[1].a" : 2 }).unflatten().unwrap(), json!([1, { "a" : 2 }]));
+    assert_eq!(json!(5).flatten().unflatten().unwrap(), json!(5));
+}
+
+#[test]
+fn unflatten_errors() {
+    assert!(json!({ "a" : 1, "a.b" : 2 }).unflatten().is_err());
+    assert!(json!({ "a[x]" : 1 }).unflatten().is_err());
+    assert!(json!({ "a[0]" : 1, "a.b" : 2 }).unflatten().is_err());
+    assert!(json!([1]).unflatten().is_err());
+    assert_eq!(json!({ "a[2]" : 1 }).unflatten().unwrap(), json!({ "a" : [null, null, 1] }));
+}
@@ -0,0 +1,68 @@
+use json::json;
+
+#[test]
+fn query() {
+    let j = json!({
+        "name" : { "first" : "Tom", "last" : "Anderson" },
+        "fav.movie" : "Deer Hunter",
+        "children" : ["Sara", "Alex", "Jack"],
+        "friends" : [
+            { "first" : "Dale", "last" : "Murphy", "age" : 44, "nets" : ["ig", "fb", "tw"] },
+            { "first" : "Roger", "last" : "Craig", "age" : 68, "nets" : ["fb", "tw"] },
+            { "first" : "Jane", "last" : "Murphy", "age" : 47, "nets" : ["ig", "tw"] }
+        ]
+    });
+
+    assert_eq!(j.query("name.last"), Some(json!("Anderson")));
+    assert_eq!(j.query("children.#"), Some(json!(3)));
+    assert_eq!(j.query("children.1"), Some(json!("Alex")));
+    assert_eq!(j.query("child*.2"), Some(json!("Jack")));
+    assert_eq!(j.query("c?ildren.0"), Some(json!("Sara")));
+    assert_eq!(j.query(r"fav\.movie"), Some(json!("Deer Hunter")));
+    assert_eq!(j.query("friends.#.first"), Some(json!(["Dale", "Roger", "Jane"])));
+    assert_eq!(j.query("friends.1.last"), Some(json!("Craig")));
+    assert_eq!(j.query(r#"friends.#(last=="Murphy").first"#), Some(json!("Dale")));
+    assert_eq!(j.query(r#"friends.#(last=="Murphy")#.first"#), Some(json!(["Dale", "Jane"])));
+    assert_eq!(j.query("friends.#(age>45)#.last"), Some(json!(["Craig", "Murphy"])));
+    assert_eq!(j.query("friends.#(age<=44).first"), Some(json!("Dale")));
+    assert_eq!(j.query(r#"friends.#(first!="Dale")#.age"#), Some(json!([68, 47])));
+    assert_eq!(j.query("friends.#(nets.#>2).first"), Some(json!("Dale")));
+    assert_eq!(j.query("friends.#(age>100)#"), Some(json!([])));
+
+    assert_eq!(j.query("friends.#(age>100)"), None);
+    assert_eq!(j.query("name.middle"), None);
+    assert_eq!(j.query("children.5"), None);
+    assert_eq!(j.query(""), None);
+}
+
+#[test]
+fn select_all() {
+    let mut j = json!({
+        "store" : {
+            "books" : [
+                { "title" : "a", "price" : 8.95, "tags" : ["x"] },
+                { "title" : "b", "price" : 12.99, "tags" : [] },
+                { "title" : "c", "price" : 22.5, "tags" : ["x", "y"] }
+            ],
+            "bike" : { "price" : 19.95 }
+        }
+    });
+
+    assert_eq!(j.select_all("store.*.price"), [&json!(19.95)]);
+    assert_eq!(j.select_all("store.books.*.title"), [&json!("a"), &json!("b"), &json!("c")]);
+    assert_eq!(j.select_all("store.books.#.tags.*").len(), 3);
+    assert_eq!(j.select_all("store.books.1.title"), [&json!("b")]);
+    assert_eq!(j.select_all("store.books.#(price>10).title"), [&json!("b")]);
+    assert_eq!(j.select_all("store.books.#(price>10)#.title"), [&json!("b"), &json!("c")]);
+    assert_eq!(j.select_all("store.b*").len(), 2);
+    assert!(j.select_all("store.books.9").is_empty());
+    assert!(j.select_all("").is_empty());
+
+    let n = j.set_all("store.books.#(price>10)#.price", |p| *p = json!(10));
+    assert_eq!(n, 2);
+    assert_eq!(j.select_all("store.books.*.price"), [&json!(8.95), &json!(10), &json!(10)]);
+
+    assert_eq!(j.set_all("store.books.*.tags.*", |t| *t = json!(null)), 3);
+    assert_eq!(j.query("store.books.2.tags"), Some(json!([null, null])));
+    assert_eq!(j.set_all("store.missing.*", |_| unreachable!()), 0);
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "hjson")]
+
+use json::{json, Json};
+
+#[test]
+fn from_hjson() {
+    let j = Json::from_hjson(r#"{
+        // Comments of all kinds
+        name: Hjson test  # not a comment, inside a quoteless string
+        "quoted key": 'it\'s "quoted"'
+        list: [ 1, 2.5e2, -3,
+            true
+            null, ]
+        nested: { a: 1, b: false, },
+        path: C:\Users\a
+        /* Block
+           comment */
+        text:
+            '''
+            first
+              second
+            '''
+        inline: '''one line'''
+        empty: []
+    }"#).unwrap();
+
+    assert_eq!(j, json!({
+        "name" : "Hjson test  # not a comment, inside a quoteless string",
+        "quoted key" : r#"it's \"quoted\""#,
+        "list" : [1, 250, -3, true, null],
+        "nested" : { "a" : 1, "b" : false },
+        "path" : r"C:\\Users\\a",
+        "text" : r"first\n  second",
+        "inline" : "one line",
+        "empty" : [],
+    }));
+}
+
+#[test]
+fn root() {
+    assert_eq!(Json::from_hjson("a: 1\nb: x y").unwrap(), json!({ "a" : 1, "b" : "x y" }));
+    assert_eq!(Json::from_hjson("[1 # one\n 2]").unwrap(), json!([1, 2]));
+    assert_eq!(Json::from_hjson("  12  ").unwrap(), json!(12));
+    assert_eq!(Json::from_hjson("just text").unwrap(), json!("just text"));
+    assert_eq!(Json::from_hjson("").unwrap(), json!({}));
+}
+
+#[test]
+fn numbers_and_strings() {
+    /* Quoteless strings span until the end of the line */
+    let j = Json::from_hjson("[01, 2\n1.5x, 12 // c\n-0.5 // c\n]").unwrap();
+    assert_eq!(j, json!(["01, 2", "1.5x, 12 // c", -0.5]));
+}
+
+#[test]
+fn errors() {
+    assert!(Json::from_hjson("{ a: 1").is_err());
+    assert!(Json::from_hjson("[1, 2").is_err());
+    assert!(Json::from_hjson("{ a 1 }").is_err());
+    assert!(Json::from_hjson(r#"{ a: "\x" }"#).is_err());
+    assert!(Json::from_hjson("{ a: '''text }").is_err());
+    assert!(Json::from_hjson("[1] 2").is_err());
+}
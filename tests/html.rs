@@ -0,0 +1,26 @@
+#![cfg(feature = "html")]
+
+use json::{json, Json};
+
+fn html(json: &Json) -> String {
+    let mut out = String::new();
+    json.to_html(&mut out).unwrap();
+    out
+}
+
+#[test]
+fn scalars() {
+    assert_eq!(html(&json!(1.5)), r#"<div class="json"><span class="json-number">1.5</span></div>"#);
+    assert_eq!(html(&json!(false)), r#"<div class="json"><span class="json-boolean">false</span></div>"#);
+    assert_eq!(html(&json!([])), r#"<div class="json"><span class="json-array">[]</span></div>"#);
+    assert_eq!(html(&json!({})), r#"<div class="json"><span class="json-object">{}</span></div>"#);
+}
+
+#[test]
+fn escaping() {
+    let j = Json::deserialize(r#"{ "</script><script>'x'</script>" : "a & \"b\"\n" }"#).unwrap();
+    let out = html(&j);
+    assert!(out.contains(r#"<span class="json-key">&quot;&lt;/script&gt;&lt;script&gt;&#39;x&#39;&lt;/script&gt;&quot;</span>"#));
+    assert!(out.contains(r#"<span class="json-string">&quot;a &amp; \&quot;b\&quot;\n&quot;</span>"#));
+    assert!(!out.contains("<script>"));
+}
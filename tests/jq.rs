@@ -0,0 +1,45 @@
+#![cfg(feature = "jq")]
+
+use json::{jq, json};
+
+#[test]
+fn filters() {
+    let doc = json!({
+        "a" : [
+            { "b" : 1, "x" : 5, "name" : "one" },
+            { "b" : 2, "x" : 2, "name" : "two" },
+            { "b" : 3, "x" : 7, "name" : "three" }
+        ],
+        "key with spaces" : true
+    });
+
+    assert_eq!(jq::eval(".", &doc).unwrap(), vec![doc.clone()]);
+    assert_eq!(jq::eval(".a[].b", &doc).unwrap(), vec![json!(1), json!(2), json!(3)]);
+    assert_eq!(jq::eval(".a[-1].name", &doc).unwrap(), vec![json!("three")]);
+    assert_eq!(jq::eval(r#"."key with spaces""#, &doc).unwrap(), vec![json!(true)]);
+    assert_eq!(jq::eval(".missing", &doc).unwrap(), vec![json!(null)]);
+    assert_eq!(
+        jq::eval(".a[] | select(.x > 3) | {id: .b, name}", &doc).unwrap(),
+        vec![json!({ "id" : 1, "name" : "one" }), json!({ "id" : 3, "name" : "three" })]
+    );
+    assert_eq!(jq::eval("[.a[].x] | add", &doc).unwrap(), vec![json!(14)]);
+    assert_eq!(jq::eval(".a | map(.b * 2)", &doc).unwrap(), vec![json!([2, 4, 6])]);
+    assert_eq!(jq::eval(".a | length, (keys | length)", &doc).unwrap(), vec![json!(3), json!(3)]);
+    assert_eq!(jq::eval(r#".a[0] | has("b"), (.x == 5 and .b != 1 | not)"#, &doc).unwrap(),
+               vec![json!(true), json!(true)]);
+    assert_eq!(jq::eval(r#".a[1].name + "!""#, &doc).unwrap(), vec![json!("two!")]);
+}
+
+#[test]
+fn errors() {
+    let doc = json!({ "a" : 1 });
+
+    assert!(jq::Filter::compile(".a |").is_err());
+    assert!(jq::Filter::compile("[.a").is_err());
+    assert!(jq::eval(".a[]", &doc).is_err());
+    assert!(jq::eval(".a.b", &doc).is_err());
+    assert!(jq::eval("unknown(.)", &doc).is_err());
+
+    let filter = jq::Filter::compile(".a + 1").unwrap();
+    assert_eq!(filter.run(&doc).unwrap(), vec![json!(2)]);
+}
@@ -0,0 +1,67 @@
+use std::io::Write;
+
+use json::json;
+use json::jsonl::JsonLinesWriter;
+
+/// Writer that records the bytes that were flushed
+#[derive(Default)]
+struct Flushed {
+    pending: Vec<u8>,
+    flushed: Vec<u8>,
+}
+
+impl Write for Flushed {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.flushed.append(&mut self.pending);
+        Ok(())
+    }
+}
+
+#[test]
+fn write() {
+    let mut writer = JsonLinesWriter::new(Flushed::default());
+    writer.write(&json!({ "msg" : r"a\nb" })).unwrap();
+    writer.write(&json!(null)).unwrap();
+    assert!(writer.get_ref().flushed.is_empty());
+
+    writer.flush().unwrap();
+    assert_eq!(writer.get_ref().flushed, b"{\"msg\":\"a\\nb\"}\nnull\n");
+    assert_eq!(writer.lines(), 2);
+}
+
+#[test]
+fn flush_every() {
+    let mut writer = JsonLinesWriter::new(Flushed::default()).flush_every(Some(2));
+    for i in 0..5 {
+        writer.write(&json!(i)).unwrap();
+    }
+    let out = writer.into_inner();
+    assert_eq!(out.flushed, b"0\n1\n2\n3\n");
+    assert_eq!(out.pending, b"4\n");
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn gzip() {
+    use std::io::Read;
+
+    let mut writer = JsonLinesWriter::gzip(Vec::new());
+    writer.write(&json!({ "a" : 1 })).unwrap();
+    writer.flush().unwrap();
+
+    /* Everything before a flush can be read, even without the end of the stream */
+    let mut text = String::new();
+    let partial = writer.get_ref().get_ref().clone();
+    let _ = flate2::read::GzDecoder::new(&partial[..]).read_to_string(&mut text);
+    assert_eq!(text, "{\"a\":1}\n");
+
+    writer.write(&json!([2])).unwrap();
+    let compressed = writer.finish().unwrap();
+    text.clear();
+    flate2::read::GzDecoder::new(&compressed[..]).read_to_string(&mut text).unwrap();
+    assert_eq!(text, "{\"a\":1}\n[2]\n");
+}
@@ -0,0 +1,25 @@
+use json::lexer::{self, TokenKind};
+
+#[test]
+fn tokenize() {
+    let src = r#"[ -12.5, "a\"b", null /* Comment */, { "k" : false } ]"#;
+    let tokens = lexer::tokenize(src).unwrap();
+    let lexemes: Vec<_> = tokens.iter().map(|t| (t.get_type(), t.span().slice(src))).collect();
+    assert_eq!(lexemes, [
+        (TokenKind::LSquareBracket, "["),
+        (TokenKind::Number, "-12.5"),
+        (TokenKind::Comma, ","),
+        (TokenKind::String, r#""a\"b""#),
+        (TokenKind::Comma, ","),
+        (TokenKind::Null, "null"),
+        (TokenKind::Comma, ","),
+        (TokenKind::LeftBrace, "{"),
+        (TokenKind::String, r#""k""#),
+        (TokenKind::Colon, ":"),
+        (TokenKind::False, "false"),
+        (TokenKind::RightBrace, "}"),
+        (TokenKind::RSquareBracket, "]"),
+    ]);
+
+    assert!(lexer::tokenize(r#"[ "unterminated ]"#).is_err());
+}
@@ -0,0 +1,42 @@
+use json::lint::{lint, Lint, LintConfig, Severity};
+
+#[test]
+fn lints() {
+    let src = r#"{
+  "" : 1,
+  "big" : 9007199254740993,
+  "float" : 9007199254740993.5,
+  "ok" : [1, 2, 3],
+  "mixed" : [1, null],
+  "deep" : [[[1]]],
+  "big" : 2
+}"#;
+    let conf = LintConfig { max_depth: 3, ..Default::default() };
+    let diags = lint(src, &conf);
+    let found: Vec<_> = diags.iter().map(|d| (d.lint, d.severity, d.span.slice(src))).collect();
+    assert_eq!(found, vec![
+        (Lint::EmptyKey, Severity::Warning, r#""""#),
+        (Lint::LargeInteger, Severity::Warning, "9007199254740993"),
+        (Lint::MixedArray, Severity::Warning, "[1, null]"),
+        (Lint::Depth, Severity::Warning, "1"),
+        (Lint::DuplicateKey, Severity::Error, r#""big""#),
+    ]);
+    assert_eq!(&*diags[2].message, "Array mixes number and null elements");
+    for d in &diags {
+        assert_eq!(d.position, d.span.file_position(src));
+    }
+    assert_eq!((diags[4].position.start_line, diags[4].position.start_col), (7, 3));
+
+    let conf = LintConfig { duplicate_keys: None, depth: None, ..Default::default() };
+    assert_eq!(lint(src, &conf).len(), 3);
+}
+
+#[test]
+fn syntax_error() {
+    let diags = lint("[1, 2", &LintConfig::default());
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].lint, Lint::Syntax);
+    assert_eq!(diags[0].severity, Severity::Error);
+
+    assert!(lint(r#"{ "a" : [true, false] }"#, &LintConfig::default()).is_empty());
+}
@@ -0,0 +1,52 @@
+use json::{locate, FilePosition};
+
+#[test]
+fn locate_pointers() {
+    let src = r#"{
+  "a" : [10, { "b" : [1, 2] }, "x"],
+  "c~d" : { "e/f" : null },
+  "dup" : 1, "dup" : 2
+}"#;
+    let slice = |ptr| {
+        let FilePosition { start_line, start_col, end_line, end_col } = locate(src, ptr).unwrap();
+        assert_eq!(start_line, end_line);
+        let line = src.lines().nth(start_line).unwrap();
+        /* Columns on the first line are 0-based, and 1-based on the rest */
+        let off = usize::from(start_line > 0);
+        &line[start_col - off..end_col - off]
+    };
+
+    assert_eq!(slice("/a/0"), "10");
+    assert_eq!(slice("/a/1"), r#"{ "b" : [1, 2] }"#);
+    assert_eq!(slice("/a/1/b/1"), "2");
+    assert_eq!(slice("/a/2"), r#""x""#);
+    assert_eq!(slice("/c~0d/e~1f"), "null");
+    assert_eq!(slice("/dup"), "2");
+
+    let root = locate(src, "").unwrap();
+    assert_eq!((root.start_line, root.end_line), (0, 4));
+
+    assert!(locate(src, "/a/3").is_none());
+    assert!(locate(src, "/a/01").is_none());
+    assert!(locate(src, "/missing").is_none());
+    assert!(locate(src, "/a/0/x").is_none());
+    assert!(locate(src, "a").is_none());
+}
+
+#[test]
+fn line_index() {
+    let src = "{\n\t\"ñandú\" : [1,\n\n 2], \"b\" : \"€\"\n}\n";
+    let index = json::LineIndex::new(src);
+    let offsets = src.char_indices().map(|(i, _)| i).chain([src.len()]);
+    for start in offsets.clone() {
+        for end in offsets.clone().filter(|&e| e >= start) {
+            let span = json::Span { offset: start, len: end - start };
+            assert_eq!(index.file_position(span), span.file_position(src), "{span}");
+        }
+    }
+    for ptr in ["", "/0", "/0/ñandú", "/0/ñandú/1", "/0/b"] {
+        let src = format!("[{src}]");
+        assert_eq!(json::LineIndex::new(&src).locate(ptr), locate(&src, ptr));
+    }
+    assert_eq!(json::LineIndex::new("").file_position(json::Span { offset: 0, len: 0 }), FilePosition::default());
+}
@@ -0,0 +1,141 @@
+use json::{json, Json};
+use json::merge::Conflict;
+use json::{merge3, MergeStrategy};
+
+#[test]
+fn clean_merge() {
+    let base = json!({
+        "a" : 1,
+        "removed" : true,
+        "list" : [1, 2, 3],
+        "nested" : { "x" : 1, "y" : 1 }
+    });
+    let ours = json!({
+        "a" : 2,
+        "list" : [1, 5, 3],
+        "nested" : { "x" : 2, "y" : 1 },
+        "ours" : "o"
+    });
+    let theirs = json!({
+        "a" : 2,
+        "removed" : true,
+        "list" : [1, 2, 4],
+        "nested" : { "x" : 1, "y" : 2 },
+        "theirs" : { "t" : null }
+    });
+    assert_eq!(merge3(&base, &ours, &theirs).unwrap(), json!({
+        "a" : 2,
+        "list" : [1, 5, 4],
+        "nested" : { "x" : 2, "y" : 2 },
+        "ours" : "o",
+        "theirs" : { "t" : null }
+    }));
+
+    /* Both sides add the same object with different members */
+    let merged = merge3(&json!({}), &json!({ "n" : { "a" : 1 } }), &json!({ "n" : { "b" : 2 } })).unwrap();
+    assert_eq!(merged, json!({ "n" : { "a" : 1, "b" : 2 } }));
+}
+
+#[test]
+fn conflicts() {
+    let base = json!({ "a" : 1, "b" : [1, 2], "c" : true });
+    let ours = json!({ "a" : 2, "b" : [1, 2, 3] });
+    let theirs = json!({ "a" : 3, "b" : [1], "c" : false });
+
+    let mut conflicts = merge3(&base, &ours, &theirs).unwrap_err();
+    conflicts.sort_by_key(|c| c.path.to_string());
+    assert_eq!(conflicts, vec![
+        Conflict {
+            path: "/a".parse().unwrap(),
+            base: Some(json!(1)),
+            ours: Some(json!(2)),
+            theirs: Some(json!(3)),
+        },
+        Conflict {
+            path: "/b".parse().unwrap(),
+            base: Some(json!([1, 2])),
+            ours: Some(json!([1, 2, 3])),
+            theirs: Some(json!([1])),
+        },
+        Conflict {
+            path: "/c".parse().unwrap(),
+            base: Some(json!(true)),
+            ours: None,
+            theirs: Some(json!(false)),
+        },
+    ]);
+
+    let conflicts = merge3(&json!(1), &json!(2), &json!("2")).unwrap_err();
+    assert!(conflicts[0].path.is_root());
+}
+
+#[test]
+fn merge_from_str() {
+    let mut conf = json!({
+        "name" : "app",
+        "server" : { "host" : "localhost", "port" : 80 },
+        "tags" : ["a"]
+    });
+    conf.merge_from_str(r#"{
+        "server" : { "port" : 8080, "tls" : { "cert" : null } },
+        "tags" : ["b", { "c" : null }],
+        "name" : "a\"b"
+    }"#, MergeStrategy::Deep).unwrap();
+    assert_eq!(conf, json!({
+        "name" : r#"a\"b"#,
+        "server" : { "host" : "localhost", "port" : 8080, "tls" : { "cert" : null } },
+        "tags" : ["b", { "c" : null }]
+    }));
+
+    conf.merge_from_str(r#"{ "tags" : [1], "name" : [2] }"#, MergeStrategy::Append).unwrap();
+    assert_eq!(conf.get("tags"), Some(&json!(["b", { "c" : null }, 1])));
+    assert_eq!(conf.get("name"), Some(&json!([2])));
+
+    conf.merge_from_str(r#"{
+        "server" : { "host" : null, "tls" : "on" },
+        "name" : null,
+        "tags" : [null],
+        "new" : { "a" : null, "b" : 1 }
+    }"#, MergeStrategy::Patch).unwrap();
+    assert_eq!(conf, json!({
+        "server" : { "port" : 8080, "tls" : "on" },
+        "tags" : [null],
+        "new" : { "b" : 1 }
+    }));
+
+    let mut j = json!({ "a" : 1 });
+    let long = "x".repeat(100_000);
+    j.merge_from_str(format!(r#"{{ "{long}" : "{long}" }}"#), MergeStrategy::Deep).unwrap();
+    assert_eq!(j.get(&long).and_then(Json::string), Some(&*long));
+    j.merge_from_str("[1, 2]", MergeStrategy::Deep).unwrap();
+    assert_eq!(j, json!([1, 2]));
+    j.merge_from_str(" 3 ", MergeStrategy::Append).unwrap();
+    assert_eq!(j, json!(3));
+
+    assert!(j.merge_from_str("{ \"a\" : 1 } 2", MergeStrategy::Deep).is_err());
+    assert!(j.merge_from_str("{ \"a\" : ", MergeStrategy::Deep).is_err());
+    assert!(j.merge_from_str("", MergeStrategy::Deep).is_err());
+
+    let mut j = json!({ "a" : 1, "b" : 2 });
+    assert!(j.merge_from_str(r#"{ "a" : 3, "c" : [4, "#, MergeStrategy::Deep).is_err());
+    assert_eq!(j, json!({ "a" : 3, "b" : 2, "c" : [4] }));
+}
+
+#[test]
+fn merge_from_str_deep() {
+    let depth = 4000;
+    let text = format!("{}1{}", r#"{ "a" : "#.repeat(depth), "}".repeat(depth));
+    let mut j = Json::deserialize(text.replace('1', "[2]")).unwrap();
+    j.merge_from_str(&text, MergeStrategy::Patch).unwrap();
+    let leaf = "/a".repeat(depth);
+    assert_eq!(j.pointer(&leaf), Some(&json!(1)));
+
+    j.merge_from_str("null", MergeStrategy::Deep).unwrap();
+    assert_eq!(j, json!(null));
+
+    let text = format!("{}{}", "[".repeat(20_000), "]".repeat(20_000));
+    let mut j = json!({ "a" : 1 });
+    let err = j.merge_from_str(&text, MergeStrategy::Deep).unwrap_err();
+    assert!(err.to_string().contains("Max depth reached"));
+    drop(j);
+}
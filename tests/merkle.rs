@@ -0,0 +1,39 @@
+use json::json;
+use json::merkle::MerkleTree;
+
+#[test]
+fn changes() {
+    let old = json!({
+        "a" : { "b" : 1, "c" : [1, 2, 3] },
+        "d" : [1, 2],
+        "removed" : true,
+        "same" : { "x" : [null] }
+    });
+    let new = json!({
+        "a" : { "b" : 1, "c" : [1, 5, 3] },
+        "d" : [1, 2, 3],
+        "added" : true,
+        "same" : { "x" : [null] }
+    });
+    let (t1, t2) = (MerkleTree::new(&old), MerkleTree::new(&new));
+
+    let mut changes: Vec<String> = t1.changes(&t2).iter().map(ToString::to_string).collect();
+    changes.sort();
+    assert_eq!(changes, vec!["/a/c/1", "/added", "/d", "/removed"]);
+
+    assert_ne!(t1.hash(), t2.hash());
+    assert_eq!(t1.pointer("/same").unwrap().hash(), t2.pointer("/same").unwrap().hash());
+    assert_eq!(t1.pointer("/a/b"), t2.pointer("/a/b"));
+    assert!(t1.pointer("/a/b/c").is_none());
+
+    assert!(t1.changes(&t1.clone()).is_empty());
+    assert_eq!(MerkleTree::new(&json!({ "a" : 1 })).changes(&MerkleTree::new(&json!(1))).len(), 1);
+}
+
+#[test]
+fn order_independent() {
+    let a = json::Json::deserialize(r#"{ "x" : 1, "y" : { "p" : [], "q" : "s" } }"#).unwrap();
+    let b = json::Json::deserialize(r#"{ "y" : { "q" : "s", "p" : [] }, "x" : 1 }"#).unwrap();
+    assert_eq!(MerkleTree::new(&a).hash(), MerkleTree::new(&b).hash());
+    assert_ne!(MerkleTree::new(&json!([[1], 2])).hash(), MerkleTree::new(&json!([1, [2]])).hash());
+}
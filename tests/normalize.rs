@@ -0,0 +1,21 @@
+#![cfg(feature = "unicode-normalization")]
+
+use json::{json, Json};
+
+#[test]
+fn normalize_unicode() {
+    let mut j = Json::deserialize(r#"{
+        "e\u0301" : ["Ame\u0301lie", "already \u00e9", "\"quoted\""],
+        "plain" : "ascii"
+    }"#).unwrap();
+    j.normalize_unicode();
+
+    assert_eq!(j.get("é").unwrap().nth(0).unwrap().string(), Some("Amélie"));
+    assert_eq!(j.get("é").unwrap().nth(1).unwrap().string(), Some(r"already \u00e9"));
+    assert_eq!(j.get("é").unwrap().nth(2).unwrap().string(), Some(r#"\"quoted\""#));
+    assert_eq!(j.get("plain"), Some(&json!("ascii")));
+
+    let mut dup = json!({ "e\u{301}" : 1, "\u{e9}" : 1 });
+    dup.normalize_unicode();
+    assert_eq!(dup, json!({ "\u{e9}" : 1 }));
+}
@@ -0,0 +1,84 @@
+use json::json;
+use json::patch::{Operation, Patch};
+use json::Json;
+
+fn patch(ops: Json) -> Patch {
+    Patch::try_from(ops).unwrap()
+}
+
+#[test]
+fn apply() {
+    let mut doc = json!({ "a" : { "b" : [1, 2] }, "c" : "x" });
+    patch(json!([
+        { "op" : "add", "path" : "/a/b/1", "value" : 5 },
+        { "op" : "add", "path" : "/a/b/-", "value" : 6 },
+        { "op" : "remove", "path" : "/c" },
+        { "op" : "copy", "from" : "/a/b", "path" : "/d" },
+        { "op" : "move", "from" : "/a/b/0", "path" : "/e" },
+        { "op" : "replace", "path" : "/a", "value" : null },
+        { "op" : "test", "path" : "/d/3", "value" : 6 },
+    ])).apply(&mut doc).unwrap();
+    assert_eq!(doc, json!({ "a" : null, "d" : [1, 5, 2, 6], "e" : 1 }));
+
+    /* A failed patch leaves the document unmodified */
+    let before = doc.clone();
+    let failing = patch(json!([
+        { "op" : "remove", "path" : "/a" },
+        { "op" : "test", "path" : "/e", "value" : 2 },
+    ]));
+    assert!(failing.apply(&mut doc).is_err());
+    assert_eq!(doc, before);
+
+    assert!(patch(json!([{ "op" : "remove", "path" : "/d/4" }])).apply(&mut doc).is_err());
+    assert!(patch(json!([{ "op" : "add", "path" : "/d/5", "value" : 1 }])).apply(&mut doc).is_err());
+    assert!(patch(json!([{ "op" : "move", "from" : "/d", "path" : "/d/0" }])).apply(&mut doc).is_err());
+    assert!(Patch::try_from(json!([{ "op" : "jump", "path" : "/a" }])).is_err());
+    assert!(Patch::try_from(json!([{ "op" : "add", "path" : "/a" }])).is_err());
+}
+
+#[test]
+fn diff() {
+    let a = json!({ "same" : 1, "changed" : [1, 2, 3], "removed" : true, "type" : { "x" : 1 } });
+    let b = json!({ "same" : 1, "changed" : [1, 4], "added" : "y", "type" : [1] });
+    let p = Patch::diff(&a, &b);
+
+    let mut doc = a.clone();
+    p.apply(&mut doc).unwrap();
+    assert_eq!(doc, b);
+    assert!(p.0.contains(&Operation::Remove { path: "/removed".parse().unwrap() }));
+    assert!(Patch::diff(&a, &a.clone()).0.is_empty());
+
+    /* Round trip through the JSON representation */
+    assert_eq!(patch(Json::from(p.clone())), p);
+}
+
+#[test]
+fn invert() {
+    let original = json!({ "a" : 1, "list" : [1, 2], "obj" : { "k" : "v" } });
+    let p = patch(json!([
+        { "op" : "add", "path" : "/a", "value" : 2 },
+        { "op" : "add", "path" : "/list/-", "value" : 3 },
+        { "op" : "add", "path" : "/list/0", "value" : 0 },
+        { "op" : "remove", "path" : "/obj/k" },
+        { "op" : "copy", "from" : "/list", "path" : "/copy" },
+        { "op" : "move", "from" : "/copy/0", "path" : "/a" },
+        { "op" : "move", "from" : "/list/0", "path" : "/list/-" },
+        { "op" : "replace", "path" : "/obj", "value" : [] },
+        { "op" : "test", "path" : "/a", "value" : 0 },
+    ]));
+    let undo = p.invert(&original).unwrap();
+
+    let mut doc = original.clone();
+    p.apply(&mut doc).unwrap();
+    assert_eq!(doc, json!({ "a" : 0, "list" : [1, 2, 3, 0], "obj" : [], "copy" : [1, 2, 3] }));
+    undo.apply(&mut doc).unwrap();
+    assert_eq!(doc, original);
+
+    let root = patch(json!([{ "op" : "move", "from" : "/obj", "path" : "" }]));
+    let mut doc = original.clone();
+    root.apply(&mut doc).unwrap();
+    root.invert(&original).unwrap().apply(&mut doc).unwrap();
+    assert_eq!(doc, original);
+
+    assert!(patch(json!([{ "op" : "remove", "path" : "/missing" }])).invert(&original).is_err());
+}
@@ -0,0 +1,35 @@
+use json::json;
+use json::pattern::Pattern;
+
+#[test]
+fn wildcards() {
+    let doc = json!({
+        "id" : 1,
+        "tags" : ["a", "b", "c"],
+        "meta" : { "ok" : true, "extra" : null }
+    });
+
+    let matches = |p: json::Json| doc.matches(&Pattern::from(p));
+
+    assert!(matches(json!("<any>")));
+    assert!(matches(json!("<object>")));
+    assert!(matches(json!({
+        "id" : "<number>",
+        "tags" : ["a", "<string>", "..."],
+        "meta" : { "ok" : "<boolean>", "..." : null }
+    })));
+
+    /* Missing member, extra members, extra elements, wrong type */
+    assert!(!matches(json!({ "id" : 1, "..." : null, "other" : "<any>" })));
+    assert!(!matches(json!({ "id" : 1, "tags" : "<array>" })));
+    assert!(!matches(json!({ "tags" : ["a", "b"], "..." : null })));
+    assert!(!matches(json!({ "id" : "<string>", "..." : null })));
+    assert!(!matches(json!({ "tags" : ["a", "b", "c", "d", "..."], "..." : null })));
+}
+
+#[test]
+fn exact() {
+    let literal = Pattern::Exact(json!("<any>"));
+    assert!(json!("<any>").matches(&literal));
+    assert!(!json!("x").matches(&literal));
+}
@@ -0,0 +1,73 @@
+use json::{json, Json, JsonPointer};
+
+#[test]
+fn pointers() {
+    let j = json!({
+        "foo" : ["bar", "baz"],
+        "" : 0,
+        "a/b" : 1,
+        "m~n" : 8
+    });
+    assert_eq!(j.pointer(""), Some(&j));
+    assert_eq!(j.pointer("/foo/1"), Some(&json!("baz")));
+    assert_eq!(j.pointer("/"), Some(&json!(0)));
+    assert_eq!(j.pointer("/a~1b"), Some(&json!(1)));
+    assert_eq!(j.pointer("/m~0n"), Some(&json!(8)));
+    assert_eq!(j.pointer("/foo/01"), None);
+    assert_eq!(j.pointer("/foo/-"), None);
+    assert_eq!(j.pointer("foo"), None);
+    assert!(JsonPointer::parse("/a~2").is_err());
+
+    let ptr: JsonPointer = "/m~0n/x~1y".parse().unwrap();
+    assert_eq!(ptr.tokens().collect::<Vec<_>>(), ["m~n", "x/y"]);
+    assert_eq!(ptr.to_string(), "/m~0n/x~1y");
+    assert_eq!(ptr.parent().unwrap().to_string(), "/m~0n");
+}
+
+#[test]
+fn remove_pointer() {
+    let mut j = json!({
+        "a" : { "b" : [0, { "c" : 1 }, 2] },
+        "x/y" : true
+    });
+    assert_eq!(j.remove_pointer("/a/b/1/c"), Some(json!(1)));
+    assert_eq!(j.remove_pointer("/a/b/0"), Some(json!(0)));
+    assert_eq!(j.remove_pointer("/x~1y"), Some(json!(true)));
+    assert_eq!(j, json!({ "a" : { "b" : [{}, 2] } }));
+
+    assert_eq!(j.remove_pointer("/a/b/2"), None);
+    assert_eq!(j.remove_pointer("/a/b/-"), None);
+    assert_eq!(j.remove_pointer("/a/missing/0"), None);
+    assert_eq!(j.remove_pointer("/a/b/1/x"), None);
+    assert_eq!(j.remove_pointer(""), None);
+    assert_eq!(j.remove_pointer("a"), None);
+
+    let ptr = JsonPointer::parse("/a/b").unwrap();
+    assert_eq!(ptr.remove(&mut j), Some(json!([{}, 2])));
+    assert_eq!(j, json!({ "a" : {} }));
+}
+
+#[test]
+fn references() {
+    let doc = json!({
+        "definitions" : {
+            "id" : { "type" : "integer" },
+            "user" : { "properties" : { "id" : { "$ref" : "#/definitions/id" } } }
+        },
+        "items" : [ { "$ref" : "#/definitions/user" }, { "$ref" : "common.json#/name" } ]
+    });
+
+    assert!(doc.resolve_refs().is_err());
+
+    let mut loader = |uri: &str| -> Result<Json, json::Error> {
+        assert_eq!(uri, "common.json");
+        Ok(json!({ "name" : { "$ref" : "#/defs/str" }, "defs" : { "str" : { "type" : "string" } } }))
+    };
+    let expanded = doc.resolve_refs_with(&mut loader).unwrap();
+    assert_eq!(expanded.pointer("/items/0/properties/id"), Some(&json!({ "type" : "integer" })));
+    assert_eq!(expanded.pointer("/items/1"), Some(&json!({ "type" : "string" })));
+
+    let cyclic = json!({ "a" : { "$ref" : "#/b" }, "b" : { "x" : { "$ref" : "#/a" } } });
+    assert!(cyclic.resolve_refs().is_err());
+    assert!(json!({ "a" : { "$ref" : "#/missing" } }).resolve_refs().is_err());
+}
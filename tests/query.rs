@@ -0,0 +1,53 @@
+use json::{json, Json};
+
+#[test]
+fn nested() {
+    let j = Json::from_query_string("?user[name]=Jhon%20Doe&user[tags][]=a&user[tags][]=b&empty").unwrap();
+    assert_eq!(j, json!({
+        "user" : {
+            "name" : "Jhon Doe",
+            "tags" : ["a", "b"]
+        },
+        "empty" : ""
+    }));
+}
+
+#[test]
+fn round_trip() {
+    let j = json!({
+        "q" : "a&b=c",
+        "list" : ["1", "2"],
+        "obj" : { "inner" : { "x" : "y z" } }
+    });
+    let mut query = String::new();
+    j.to_query_string(&mut query).unwrap();
+    assert_eq!(Json::from_query_string(&query).unwrap(), j);
+}
+
+#[test]
+fn errors() {
+    assert!(Json::from_query_string("a=1&a[]=2").is_err());
+    assert!(Json::from_query_string("a[b=1").is_err());
+    assert!(Json::from_query_string("a=%G1").is_err());
+}
+
+#[test]
+fn to_query_string() {
+    let mut query = String::new();
+    json!({ "a" : 1.5, "b" : [true, false] }).to_query_string(&mut query).unwrap();
+    assert_eq!(Json::from_query_string(&query).unwrap(), json!({
+        "a" : "1.5",
+        "b" : ["true", "false"]
+    }));
+
+    let mut query = String::new();
+    json!({}).to_query_string(&mut query).unwrap();
+    assert_eq!(query, "");
+
+    /* Can't be told apart from an empty string */
+    for value in [json!(null), json!([]), json!({}), json!([1, null]), json!({ "b" : {} })] {
+        let j = json!({ "a" : value });
+        assert!(j.to_query_string(&mut String::new()).is_err(), "{j}");
+    }
+    assert!(json!([1]).to_query_string(&mut String::new()).is_err());
+}
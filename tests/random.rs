@@ -0,0 +1,41 @@
+use json::random::{GeneratorConfig, TypeMix};
+use json::Json;
+
+fn depth(j: &Json) -> u32 {
+    match j {
+        Json::Array(arr) => 1 + arr.iter().map(depth).max().unwrap_or(0),
+        Json::Object(obj) => 1 + obj.values().map(depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+#[test]
+fn constraints() {
+    for seed in 0..50 {
+        let conf = GeneratorConfig { seed, max_depth: 3, branching: 1..=4, ..Default::default() };
+        let j = json::generate(&conf);
+        assert!(matches!(j, Json::Array(_) | Json::Object(_)));
+        assert!(depth(&j) <= 3);
+        assert_eq!(Json::deserialize(j.to_string()).unwrap(), j);
+    }
+}
+
+#[test]
+fn type_mix() {
+    let conf = GeneratorConfig {
+        mix: TypeMix { null: 0, boolean: 0, number: 1, string: 0, array: 1, object: 0 },
+        number_range: (0.0, 10.0),
+        integer_ratio: 1.0,
+        ..Default::default()
+    };
+    let j = json::generate(&conf);
+    fn check(j: &Json) {
+        match j {
+            Json::Array(arr) => arr.iter().for_each(check),
+            Json::Number(n) => assert!(n.fract() == 0.0 && (0.0..=10.0).contains(n)),
+            _ => panic!("unexpected value {j}"),
+        }
+    }
+    check(&j);
+    assert_ne!(json::generate(&GeneratorConfig { seed: 1, ..conf.clone() }), json::generate(&conf));
+}
@@ -0,0 +1,28 @@
+use json::{json, Json, Redaction, SerializeConfig};
+
+#[test]
+fn redact() {
+    let mut j = json!({
+        "secret" : { "nested" : 1 },
+        "list" : [ { "secret" : 2 }, "keep" ],
+        "auth" : { "token" : "t", "user" : "u" }
+    });
+    let original = j.clone();
+
+    let redaction = Redaction::new(&["secret", "/auth/token", "/list/1"], "<redacted>").unwrap();
+    let conf = SerializeConfig { redaction: Some(&redaction), indent: Some("  "), ..Default::default() };
+    let mut out = String::new();
+    j.serialize_with_config(&conf, &mut out).unwrap();
+
+    j.redact(&["secret", "/auth/token", "/list/1"], "<redacted>").unwrap();
+    let expected = json!({
+        "secret" : "<redacted>",
+        "list" : [ { "secret" : "<redacted>" }, "<redacted>" ],
+        "auth" : { "token" : "<redacted>", "user" : "u" }
+    });
+    assert_eq!(j, expected);
+    assert_eq!(Json::deserialize(out).unwrap(), expected);
+    assert_ne!(original, expected);
+
+    assert!(Redaction::new(&["/bad~pointer"], Json::Null).is_err());
+}
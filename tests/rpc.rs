@@ -0,0 +1,54 @@
+use json::rpc::{self, Error, Id, Incoming, Message, Response};
+use json::{json, Json};
+
+#[test]
+fn messages() {
+    let Incoming::Single(Ok(Message::Notification(n))) =
+        rpc::parse(r#"{"jsonrpc":"2.0","method":"update","params":{"a":1}}"#) else { panic!() };
+    assert_eq!(&*n.method, "update");
+    assert_eq!(n.params, Some(json!({ "a" : 1 })));
+
+    let Incoming::Single(Ok(Message::Response(r))) =
+        rpc::parse(r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found"},"id":"x"}"#) else { panic!() };
+    assert_eq!(r.id, Id::String("x".into()));
+    assert_eq!(r.result, Err(Error::method_not_found()));
+}
+
+#[test]
+fn validation() {
+    let invalid = [
+        r#"{"method":"a","id":1}"#,
+        r#"{"jsonrpc":"1.0","method":"a","id":1}"#,
+        r#"{"jsonrpc":"2.0","method":1,"id":1}"#,
+        r#"{"jsonrpc":"2.0","method":"a","params":"x"}"#,
+        r#"{"jsonrpc":"2.0","method":"a","id":{}}"#,
+        r#"{"jsonrpc":"2.0","result":1,"error":{"code":1,"message":""},"id":1}"#,
+        r#"[]"#,
+    ];
+    for text in invalid {
+        assert_eq!(rpc::parse(text), Incoming::Single(Err(Error::invalid_request())), "{text}");
+    }
+    assert_eq!(rpc::parse("{"), Incoming::Single(Err(Error::parse_error())));
+}
+
+#[test]
+fn batch() {
+    let Incoming::Batch(msgs) = rpc::parse(r#"[
+        {"jsonrpc":"2.0","method":"sum","params":[1,2],"id":1},
+        {"jsonrpc":"2.0","method":"notify"},
+        1
+    ]"#) else { panic!() };
+    assert_eq!(msgs.len(), 3);
+    assert!(matches!(msgs[2], Err(ref e) if e.code == rpc::INVALID_REQUEST));
+
+    let responses = msgs.into_iter().filter_map(|m| match m {
+        Ok(Message::Request(req)) => Some(req.respond(Ok(json!(3)))),
+        Ok(_) => None,
+        Err(e) => Some(Response::error(Id::Null, e)),
+    }).collect();
+    let reply = rpc::batch_response(responses).unwrap();
+    assert_eq!(reply.nth(0), Some(&json!({ "jsonrpc" : "2.0", "result" : 3, "id" : 1 })));
+    assert!(reply.nth(1).unwrap().get("error").is_some());
+    assert!(rpc::batch_response(Vec::new()).is_none());
+    let _: Json = Error::internal_error().into();
+}
@@ -0,0 +1,94 @@
+use json::random::Rng;
+use json::schema::Schema;
+use json::{json, Json};
+
+fn schema() -> Schema {
+    Schema::compile(&json!({
+        "type" : "object",
+        "properties" : {
+            "id" : { "type" : "integer", "exclusiveMinimum" : 0, "maximum" : 10 },
+            "name" : { "type" : "string", "minLength" : 3, "maxLength" : 5 },
+            "price" : { "type" : "number", "multipleOf" : 0.5, "minimum" : 1 },
+            "kind" : { "enum" : ["a", "b", 3] },
+            "tags" : { "type" : "array", "items" : { "type" : "string" }, "minItems" : 1, "maxItems" : 3 },
+            "either" : { "oneOf" : [ { "type" : "null" }, { "type" : "boolean" } ] }
+        },
+        "required" : ["id", "name", "tags"],
+        "additionalProperties" : false
+    })).unwrap()
+}
+
+#[test]
+fn validate() {
+    let s = schema();
+    assert!(s.is_valid(&json!({ "id" : 10, "name" : "abc", "tags" : ["x"] })));
+
+    let errors = s.validate(&json!({
+        "id" : 0,
+        "name" : "ab",
+        "tags" : [1],
+        "kind" : "c",
+        "extra" : 1
+    })).unwrap_err();
+    let paths: Vec<_> = errors.iter().map(|e| &*e.path).collect();
+    for p in ["/id", "/name", "/tags/0", "/kind", "/extra"] {
+        assert!(paths.contains(&p), "{p} not in {paths:?}");
+    }
+    assert_eq!(errors.len(), 5);
+
+    let missing = s.validate(&json!({})).unwrap_err();
+    assert_eq!(missing.len(), 3);
+    assert!(Schema::compile(&json!({ "type" : "bogus" })).is_err());
+    assert!(Schema::compile(&json!(1)).is_err());
+}
+
+#[test]
+fn sample() {
+    let s = schema();
+    let mut rng = Rng::new(7);
+    for _ in 0..200 {
+        let doc = s.sample(&mut rng);
+        assert!(s.is_valid(&doc), "invalid sample {doc}: {:?}", s.validate(&doc));
+        assert!(matches!(doc.get("kind"), None | Some(Json::String(_) | Json::Number(_))));
+    }
+}
+
+#[test]
+fn validate_reader() {
+    let s = schema();
+    let streamed = |text: &str| {
+        let mut errors: Vec<String> = s.validate_reader(text.as_bytes(), &mut [0; 64], &mut [0; 8])
+                                       .unwrap()
+                                       .iter().map(ToString::to_string).collect();
+        errors.sort();
+        errors
+    };
+    let built = |text: &str| {
+        let mut errors: Vec<String> = s.validate(&Json::deserialize(text).unwrap())
+                                       .err().unwrap_or_default()
+                                       .iter().map(ToString::to_string).collect();
+        errors.sort();
+        errors
+    };
+
+    for doc in [
+        r#"{ "id" : 10, "name" : "abc", "tags" : ["x"] }"#,
+        r#"{ "id" : 0, "name" : "ab", "tags" : [1], "kind" : "c", "extra" : 1 }"#,
+        r#"{ "id" : 1, "name" : "abc", "tags" : [], "kind" : 3, "either" : [] }"#,
+        r#"{ "id" : 1.5, "name" : "abcdef", "tags" : ["a", "b", "c", "d"], "either" : true }"#,
+        r#"{ "id" : 1, "name" : "abc", "tags" : {}, "price" : 1.25, "kind" : ["a"] }"#,
+        "{}",
+        "[1, 2]",
+    ] {
+        assert_eq!(streamed(doc), built(doc), "{doc}");
+    }
+
+    /* Values under "not" are built to be checked */
+    let s = Schema::compile(&json!({ "items" : { "not" : { "required" : ["x"] } }, "allOf" : [{ "maxItems" : 1 }] })).unwrap();
+    let errors = s.validate_reader(r#"[{ "y" : 1 }, { "x" : [{}] }]"#.as_bytes(), &mut [0; 64], &mut [0; 8]).unwrap();
+    let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(errors, ["/1: value matches the schema in 'not'", "/: array has more than 1 items"]);
+
+    assert!(s.validate_reader(&b"[1,"[..], &mut [0; 64], &mut [0; 8]).is_err());
+    assert!(s.validate_reader(&b"[[[1]]]"[..], &mut [0; 64], &mut [0; 2]).is_err());
+}
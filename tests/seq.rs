@@ -0,0 +1,49 @@
+use std::io::Read;
+
+use json::json;
+use json::seq::{SeqReader, SeqWriter};
+
+/// Reader that returns one byte at a time
+struct Bytes<'a>(&'a [u8]);
+
+impl Read for Bytes<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let Some((first, rest)) = self.0.split_first() else { return Ok(0) };
+        buf[0] = *first;
+        self.0 = rest;
+        Ok(1)
+    }
+}
+
+#[test]
+fn read() {
+    let data = b"\x1e{ \"a\" : 1 }\n\x1e\x1e\n\x1e[1, \x1e\"ok\"\n\x1e12\x1e13\n\x1etrue\n";
+    let results: Vec<_> = SeqReader::new(Bytes(data)).collect();
+    assert_eq!(results.len(), 6);
+    assert_eq!(results[0].as_ref().unwrap(), &json!({ "a" : 1 }));
+    assert!(results[1].is_err());
+    assert_eq!(results[2].as_ref().unwrap(), &json!("ok"));
+    assert_eq!(results[3].as_ref().unwrap_err().to_string(), "Truncated record");
+    assert_eq!(results[4].as_ref().unwrap(), &json!(13));
+    assert_eq!(results[5].as_ref().unwrap(), &json!(true));
+
+    let results: Vec<_> = SeqReader::new(&b"\n[1]\n\x1e[2]\n"[..]).collect();
+    assert!(results[0].is_err());
+    assert_eq!(results[1].as_ref().unwrap(), &json!([2]));
+
+    assert_eq!(SeqReader::new(&b""[..]).count(), 0);
+    assert_eq!(SeqReader::new(&b" \n "[..]).count(), 0);
+}
+
+#[test]
+fn write() {
+    let mut writer = SeqWriter::new(Vec::new());
+    for i in 0..3 {
+        writer.write(&json!({ "i" : i })).unwrap();
+    }
+    writer.write(&json!(null)).unwrap();
+    let bytes = writer.into_inner();
+
+    let records: Vec<_> = SeqReader::new(&bytes[..]).map(Result::unwrap).collect();
+    assert_eq!(records, [json!({ "i" : 0 }), json!({ "i" : 1 }), json!({ "i" : 2 }), json!(null)]);
+}
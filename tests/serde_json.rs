@@ -0,0 +1,55 @@
+#![cfg(feature = "serde_json")]
+
+use json::{json, Json};
+use serde_json::Value;
+
+#[test]
+fn conversions() {
+    let j = Json::deserialize(r#"{
+        "s" : "quote \" and \n newline",
+        "n" : [1, -2.5],
+        "b" : [true, false, null],
+        "o" : { "k" : {} }
+    }"#);
+    let j = j.unwrap();
+
+    let v = Value::from(Json::deserialize(j.to_string()).unwrap());
+    assert_eq!(v["s"], Value::String("quote \" and \n newline".into()));
+    assert_eq!(v["n"][0], serde_json::json!(1));
+    assert_eq!(v["n"][1], serde_json::json!(-2.5));
+    assert_eq!(v["b"], serde_json::json!([true, false, null]));
+
+    assert_eq!(Json::from(v), j);
+}
+
+#[test]
+fn non_finite() {
+    assert_eq!(Value::from(Json::from(f64::NAN)), Value::Null);
+    assert_eq!(Json::from(serde_json::json!({ "a" : [1] })), json!({ "a" : [1] }));
+}
+
+#[test]
+#[cfg(feature = "preserve_order")]
+fn key_order() {
+    let text = r#"{"z":1,"a":{"y":2,"b":3},"m":4}"#;
+    let v = Value::from(Json::deserialize(text).unwrap());
+    assert_eq!(v.to_string(), text);
+    assert_eq!(Json::from(v).to_string(), text);
+}
+
+#[test]
+fn integers() {
+    let v: Value = serde_json::from_str("[9007199254740993, 18446744073709551615, -9223372036854775808]").unwrap();
+    let j = Json::from(v.clone());
+    #[cfg(feature = "bigint")]
+    {
+        assert_eq!(j.to_string(), "[9007199254740993,18446744073709551615,-9223372036854775808]");
+        assert_eq!(Value::from(j), v);
+    }
+    /* Without big integers, they're rounded to the nearest f64 */
+    #[cfg(not(feature = "bigint"))]
+    assert_eq!(Value::from(j), serde_json::json!([9007199254740992_u64, 18446744073709551616.0, -9223372036854775808_i64]));
+
+    assert_eq!(Value::from(json!(1e15)).to_string(), "1000000000000000");
+    assert_eq!(Value::from(json!(1e20)).to_string(), "1e+20");
+}
@@ -0,0 +1,38 @@
+use json::json;
+
+#[test]
+fn dedup() {
+    let mut j = json!([
+        { "id" : 1, "tags" : ["a"] },
+        null,
+        { "tags" : ["a"], "id" : 1 },
+        [1, 2],
+        [2, 1],
+        null,
+        -0.0,
+        0,
+    ]);
+    j.dedup().unwrap();
+    assert_eq!(j, json!([{ "id" : 1, "tags" : ["a"] }, null, [1, 2], [2, 1], -0.0]));
+
+    let mut empty = json!([]);
+    empty.dedup().unwrap();
+    assert_eq!(empty, json!([]));
+    assert!(json!({}).dedup().is_err());
+}
+
+#[test]
+fn set_operations() {
+    let a = json!([1, 2, 2, { "x" : true }, "s"]);
+    let b = json!(["s", { "x" : true }, 3, 3]);
+
+    assert_eq!(a.union(&b).unwrap(), json!([1, 2, { "x" : true }, "s", 3]));
+    assert_eq!(b.union(&a).unwrap(), json!(["s", { "x" : true }, 3, 1, 2]));
+    assert_eq!(a.intersection(&b).unwrap(), json!([{ "x" : true }, "s"]));
+    assert_eq!(a.difference(&b).unwrap(), json!([1, 2]));
+    assert_eq!(b.difference(&a).unwrap(), json!([3]));
+    assert_eq!(a.difference(&json!([])).unwrap(), json!([1, 2, { "x" : true }, "s"]));
+
+    assert_eq!(a.union(&json!(1)), None);
+    assert_eq!(json!(null).intersection(&a), None);
+}
@@ -0,0 +1,70 @@
+use json::{json, Json, JsonType, SharedJson};
+
+#[test]
+fn accessors() {
+    let j = json!({
+        "arr" : [1, "two", true, null],
+        "obj" : { "k" : false }
+    });
+    let shared = SharedJson::from(j.clone());
+
+    let arr = shared.get("arr").unwrap();
+    assert_eq!(arr.array().unwrap().len(), 4);
+    assert_eq!(arr.nth(0).unwrap().number(), Some(1.0));
+    assert_eq!(arr.nth(1).unwrap().string(), Some("two"));
+    assert_eq!(arr.nth(2).unwrap().boolean(), Some(true));
+    assert!(arr.nth(3).unwrap().is_null());
+    assert!(arr.nth(4).is_none());
+    assert_eq!(shared.pointer("/obj/k").unwrap().boolean(), Some(false));
+    assert_eq!(shared.get("obj").unwrap().object().unwrap().len(), 1);
+    assert_eq!(shared.json_type(), JsonType::Object);
+    assert!(shared.get("missing").is_none());
+
+    assert_eq!(shared.to_json(), j);
+    assert_eq!(Json::from(&shared), j);
+    assert_eq!(Json::deserialize(shared.to_string()).unwrap(), j);
+}
+
+#[test]
+fn sharing() {
+    let shared = SharedJson::from(json!({ "big" : [[1, 2, 3], "s"] }));
+    let clone = shared.clone();
+    assert!(shared.ptr_eq(&clone));
+    assert!(shared.get("big").unwrap().ptr_eq(clone.get("big").unwrap()));
+    assert!(!shared.ptr_eq(&SharedJson::from(json!({ "big" : [[1, 2, 3], "s"] }))));
+    assert_eq!(shared, SharedJson::from(json!({ "big" : [[1, 2, 3], "s"] })));
+
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+    assert_send_sync(&shared);
+}
+
+#[test]
+fn copy_on_write() {
+    let v1 = SharedJson::from(json!({
+        "a" : { "b" : [1, 2], "c" : { "d" : null } },
+        "e" : ["x"]
+    }));
+
+    let mut v2 = v1.clone();
+    *v2.pointer_mut("/a/b/1").unwrap() = json!(5).into();
+    v2.object_mut().unwrap().insert("f".into(), json!(true).into());
+    v2.get_mut("e").unwrap().array_mut().unwrap().push(json!("y").into());
+
+    assert_eq!(v1.to_json(), json!({ "a" : { "b" : [1, 2], "c" : { "d" : null } }, "e" : ["x"] }));
+    assert_eq!(v2.to_json(), json!({ "a" : { "b" : [1, 5], "c" : { "d" : null } }, "e" : ["x", "y"], "f" : true }));
+
+    /* Only the modified paths were copied */
+    assert!(!v1.pointer("/a/b").unwrap().ptr_eq(v2.pointer("/a/b").unwrap()));
+    assert!(v1.pointer("/a/c").unwrap().ptr_eq(v2.pointer("/a/c").unwrap()));
+
+    /* Values that aren't shared are modified in place */
+    let mut v3 = v2.clone();
+    drop(v2);
+    let c = v3.pointer("/a/c").unwrap().clone();
+    *v3.pointer_mut("/a/b").unwrap().nth_mut(0).unwrap() = json!(0).into();
+    assert!(c.ptr_eq(v3.pointer("/a/c").unwrap()));
+    assert_eq!(v3.pointer("/a/b/0"), Some(&json!(0).into()));
+
+    assert!(v3.pointer_mut("/e/5").is_none());
+    assert!(v3.get_mut("e").unwrap().get_mut("x").is_none());
+}
@@ -0,0 +1,42 @@
+use json::json;
+
+#[test]
+fn sort_arrays() {
+    let mut j = json!({
+        "mixed" : ["b", 2, null, true, "a", 1, false],
+        "users" : [ { "name" : "c" }, { "name" : "a" }, { "id" : 0 } ],
+        "n" : 1
+    });
+
+    j.sort_array_by_key("/mixed", None).unwrap();
+    j.sort_array_by_key("/users", Some("name")).unwrap();
+    assert_eq!(j, json!({
+        "mixed" : [null, false, true, 1, 2, "a", "b"],
+        "users" : [ { "id" : 0 }, { "name" : "a" }, { "name" : "c" } ],
+        "n" : 1
+    }));
+
+    j.sort_array_by("/mixed", |a, b| a.is_null().cmp(&b.is_null())).unwrap();
+    assert!(j.pointer("/mixed/6").unwrap().is_null());
+
+    assert!(j.sort_array_by_key("/n", None).is_err());
+    assert!(j.sort_array_by_key("/missing", None).is_err());
+    assert!(j.sort_array_by_key("bad", None).is_err());
+}
+
+#[test]
+#[cfg(feature = "preserve_order")]
+fn sort_keys() {
+    let mut j = json::Json::deserialize(r#"{"z":1,"b":[{"y":1,"x":2}],"a":{"d":1,"c":2}}"#).unwrap();
+    assert_eq!(j.to_string(), r#"{"z":1,"b":[{"y":1,"x":2}],"a":{"d":1,"c":2}}"#);
+
+    j.sort_keys_recursive();
+    assert_eq!(j.to_string(), r#"{"a":{"c":2,"d":1},"b":[{"x":2,"y":1}],"z":1}"#);
+}
+
+#[test]
+#[cfg(all(feature = "btree_map", not(feature = "preserve_order")))]
+fn btree_map() {
+    let j = json::Json::deserialize(r#"{"z":1,"b":[{"y":1,"x":2}],"a":{"d":1,"c":2}}"#).unwrap();
+    assert_eq!(j.to_string(), r#"{"a":{"c":2,"d":1},"b":[{"x":2,"y":1}],"z":1}"#);
+}
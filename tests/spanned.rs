@@ -0,0 +1,31 @@
+use json::{json, spanned, Span};
+use json::spanned::SpannedJson;
+
+#[test]
+fn spans() {
+    let src = r#"{
+  "a" : [1, true, "x"],
+  "b" : { "c" : null },
+  "a" : -2
+}"#;
+    let doc = spanned::parse(src).unwrap();
+    assert_eq!(doc.span, Span { offset: 0, len: src.len() });
+    assert_eq!(doc.to_json(), json!({ "a" : -2, "b" : { "c" : null } }));
+
+    let SpannedJson::Object(members) = &doc.value else { panic!() };
+    let keys: Vec<_> = members.iter().map(|(k, _)| k.span.slice(src)).collect();
+    assert_eq!(keys, vec![r#""a""#, r#""b""#, r#""a""#]);
+
+    assert_eq!(doc.pointer("/a").unwrap().span.slice(src), "-2");
+    assert_eq!(members[0].1.span.slice(src), r#"[1, true, "x"]"#);
+    assert_eq!(members[0].1.nth(2).unwrap().span.slice(src), r#""x""#);
+    assert_eq!(doc.pointer("/b").unwrap().span.slice(src), r#"{ "c" : null }"#);
+
+    let null = doc.pointer("/b/c").unwrap();
+    assert_eq!(null.value, SpannedJson::Null);
+    let pos = null.span.file_position(src);
+    assert_eq!((pos.start_line, pos.start_col), (2, 17));
+
+    assert!(doc.pointer("/missing").is_none());
+    assert!(spanned::parse("[1,").is_err());
+}
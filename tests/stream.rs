@@ -0,0 +1,105 @@
+use std::io::Read;
+
+use json::{json, Json};
+
+/// Reader that returns a single byte on each read
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let Some((first, rest)) = self.0.split_first() else { return Ok(0) };
+        buf[0] = *first;
+        self.0 = rest;
+        Ok(1)
+    }
+}
+
+#[test]
+fn array_elements() {
+    let text = r#"
+        [
+            { "s" : "a ] \" , [ b", "n" : [1, [2]] },
+            "str",
+            12.5 ,
+            null
+        ]
+    "#;
+    let expected = vec![
+        Json::deserialize(r#"{ "s" : "a ] \" , [ b", "n" : [1, [2]] }"#).unwrap(),
+        json!("str"),
+        json!(12.5),
+        json!(null),
+    ];
+    let elems: Vec<Json> = Json::array_elements(text.as_bytes()).collect::<Result<_,_>>().unwrap();
+    assert_eq!(elems, expected);
+    let elems: Vec<Json> = Json::array_elements(Trickle(text.as_bytes())).collect::<Result<_,_>>().unwrap();
+    assert_eq!(elems, expected);
+
+    assert_eq!(Json::array_elements(" [ ] ".as_bytes()).count(), 0);
+
+    let big = format!("[{}]", vec!["[1, 2, 3]"; 10_000].join(","));
+    assert_eq!(Json::array_elements(big.as_bytes()).filter(|e| e.as_ref().unwrap() == &json!([1, 2, 3])).count(), 10_000);
+}
+
+#[test]
+fn array_elements_errors() {
+    let results: Vec<_> = Json::array_elements("[1, 2".as_bytes()).collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[1].is_err());
+
+    let mut elems = Json::array_elements("[1, {], 3]".as_bytes());
+    assert_eq!(elems.next().unwrap().unwrap(), json!(1));
+    assert!(elems.next().unwrap().is_err());
+    assert!(elems.next().is_none());
+
+    assert!(Json::array_elements("{}".as_bytes()).next().unwrap().is_err());
+    assert!(Json::array_elements("[1 2]".as_bytes()).nth(1).unwrap().is_err());
+    assert!(Json::array_elements("[1,]".as_bytes()).nth(1).unwrap().is_err());
+    assert!(Json::array_elements("[1] 2".as_bytes()).nth(1).unwrap().is_err());
+    assert!(Json::array_elements("".as_bytes()).next().unwrap().is_err());
+}
+
+#[test]
+fn from_reader() {
+    let text = r#"{
+        "users" : [
+            { "name" : "a \"b\"", "tags" : [], "age" : 30 },
+            { "name" : "c", "tags" : ["x", "y"], "admin" : true, "boss" : null }
+        ],
+        "total" : -2500.5,
+        "empty" : {}
+    }"#;
+    let expected = Json::deserialize(text).unwrap();
+    assert_eq!(Json::from_reader(text.as_bytes()).unwrap(), expected);
+    assert_eq!(Json::from_reader(Trickle(text.as_bytes())).unwrap(), expected);
+    assert_eq!(Json::from_reader(&b" 12 "[..]).unwrap(), json!(12));
+
+    assert!(Json::from_reader(&b"[1, 2"[..]).is_err());
+    assert!(Json::from_reader(&b"[1] 2"[..]).is_err());
+    assert!(Json::from_reader(&b"  "[..]).is_err());
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn from_gzip_reader() {
+    use std::io::Write;
+
+    let text = r#"[{ "a" : 1 }, "b"]"#;
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(text.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    assert_eq!(Json::from_gzip_reader(&compressed[..]).unwrap(), json!([{ "a" : 1 }, "b"]));
+    assert!(Json::from_gzip_reader(&compressed[..compressed.len() / 2]).is_err());
+    assert!(Json::from_gzip_reader(text.as_bytes()).is_err());
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn from_zstd_reader() {
+    let text = r#"{ "compressed" : [true, false] }"#;
+    let compressed = zstd::encode_all(text.as_bytes(), 0).unwrap();
+
+    assert_eq!(Json::from_zstd_reader(&compressed[..]).unwrap(), json!({ "compressed" : [true, false] }));
+    assert!(Json::from_zstd_reader(text.as_bytes()).is_err());
+}
@@ -0,0 +1,70 @@
+use json::json;
+
+fn orders() -> json::Json {
+    json!([
+        { "id" : 3, "country" : "es", "total" : 10.5 },
+        { "id" : 1, "country" : "fr", "total" : 4 },
+        { "id" : 2, "country" : "es" },
+        { "id" : 4, "total" : 7 },
+        { "id" : 5, "country" : 1 },
+    ])
+}
+
+#[test]
+fn group_by() {
+    let groups = orders().group_by("country").unwrap();
+    assert_eq!(groups.len(), Some(4));
+    assert_eq!(groups.get("es").unwrap().pluck("id").unwrap(), json!([3, 2]));
+    assert_eq!(groups.get("fr").unwrap().pluck("id").unwrap(), json!([1]));
+    assert_eq!(groups.get("null").unwrap().pluck("id").unwrap(), json!([4]));
+    assert_eq!(groups.get("1").unwrap().pluck("id").unwrap(), json!([5]));
+
+    assert_eq!(json!([]).group_by("a"), Some(json!({})));
+    assert_eq!(json!({ "a" : 1 }).group_by("a"), None);
+}
+
+#[test]
+fn pluck() {
+    assert_eq!(orders().pluck("total").unwrap(), json!([10.5, 4, null, 7, null]));
+    assert_eq!(json!([1, { "a" : true }]).pluck("a").unwrap(), json!([null, true]));
+    assert_eq!(json!("a").pluck("a"), None);
+}
+
+#[test]
+fn sort_by() {
+    let mut rows = orders();
+    rows.sort_by("id").unwrap();
+    assert_eq!(rows.pluck("id").unwrap(), json!([1, 2, 3, 4, 5]));
+
+    rows.sort_by("total").unwrap();
+    assert_eq!(rows.pluck("id").unwrap(), json!([2, 5, 1, 4, 3]));
+
+    rows.sort_by("country").unwrap();
+    assert_eq!(rows.pluck("id").unwrap(), json!([4, 5, 2, 3, 1]));
+
+    assert_eq!(json!(null).sort_by("a").unwrap_err().to_string(), "expected array, found null");
+}
+
+#[test]
+fn columns() {
+    let cols = orders().to_columns().unwrap();
+    assert_eq!(cols, json!({
+        "id" : [3, 1, 2, 4, 5],
+        "country" : ["es", "fr", "es", null, 1],
+        "total" : [10.5, 4, null, 7, null],
+    }));
+
+    let rows = cols.from_columns().unwrap();
+    assert_eq!(rows.len(), Some(5));
+    assert_eq!(rows.to_columns().unwrap(), cols);
+    assert_eq!(rows.pluck("total").unwrap(), orders().pluck("total").unwrap());
+
+    assert_eq!(json!({ "a" : [1, 2], "b" : [] }).from_columns().unwrap(),
+               json!([{ "a" : 1, "b" : null }, { "a" : 2, "b" : null }]));
+    assert_eq!(json!([]).to_columns().unwrap(), json!({}));
+    assert_eq!(json!({}).from_columns().unwrap(), json!([]));
+    assert_eq!(json!([{}, {}]).to_columns().unwrap(), json!({}));
+
+    assert_eq!(json!([{ "a" : 1 }, 2]).to_columns(), None);
+    assert_eq!(json!({ "a" : 1 }).from_columns(), None);
+}
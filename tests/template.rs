@@ -0,0 +1,24 @@
+use json::{json, Map};
+
+#[test]
+fn render() {
+    let template = json!({
+        "body" : ["{{count}}", "{{ count }} items", "{{obj}}", "{{obj}}!", "plain"],
+        "nested" : { "quote" : "said {{quote}}" }
+    });
+    let mut vars = Map::default();
+    vars.insert("count".into(), json!(3));
+    vars.insert("obj".into(), json!({ "a" : "b" }));
+    vars.insert("quote".into(), json::Json::deserialize(r#""\"hi\"""#).unwrap());
+
+    let out = template.render_template(&vars).unwrap();
+    assert_eq!(out.pointer("/body/0"), Some(&json!(3)));
+    assert_eq!(out.pointer("/body/1"), Some(&json!("3 items")));
+    assert_eq!(out.pointer("/body/2"), Some(&json!({ "a" : "b" })));
+    assert_eq!(out.pointer("/body/3").unwrap().to_string(), r#""{\"a\":\"b\"}!""#);
+    assert_eq!(out.pointer("/nested/quote").unwrap().to_string(), r#""said \"hi\"""#);
+    assert_eq!(json::Json::deserialize(out.to_string()).unwrap(), out);
+
+    assert!(json!("{{missing}}").render_template(&vars).is_err());
+    assert!(json!("a {{count").render_template(&vars).is_err());
+}
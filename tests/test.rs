@@ -1,7 +1,7 @@
 use core::panic;
 use std::ops::Deref;
 
-use json::{json, Json};
+use json::{json, Json, JsonConfig, JsonParser, LoneSurrogates};
 
 #[test]
 fn simple() {
@@ -18,7 +18,7 @@ fn simple() {
             Ok(Json::Null)
     ));
     let string = "\"string\"".to_owned();
-    match Json::deserialize(&string) {
+    match &Json::deserialize(&string) {
        Ok(Json::String(s)) => assert_eq!(s.deref(), "string"),
        _ => panic!()
     }
@@ -43,3 +43,747 @@ fn nested() {
     assert_eq!(expected, j);
 }
 
+#[test]
+fn negative_numbers() {
+    assert_eq!(Json::deserialize("-12.5").unwrap(), Json::Number(-12.5));
+    assert_eq!(Json::deserialize("[-1, 2]").unwrap(), Json::from(vec![Json::Number(-1.0), json!(2)]));
+}
+
+#[test]
+fn macro_interpolation() {
+    let name = String::from("id");
+    let existing = json!({ "inner" : [1, 2] });
+    let some = Some(12);
+    let none: Option<&str> = None;
+    let n = 4;
+
+    let j = json!({
+        name : (n * 2),
+        ["comp".to_string() + "uted"] : existing,
+        "opt" : some,
+        "null" : none,
+        "omitted" ? : none,
+        "kept" ? : some,
+        "expr" : -1,
+        "list" : [n, -n, "s", null, { "a" : none }]
+    });
+
+    assert_eq!(j.get("id"), Some(&Json::Number(8.0)));
+    assert_eq!(j.get("computed"), Some(&json!({ "inner" : [1, 2] })));
+    assert_eq!(j.get("opt"), Some(&Json::Number(12.0)));
+    assert!(j.get("null").unwrap().is_null());
+    assert!(j.get("omitted").is_none());
+    assert_eq!(j.get("kept"), Some(&Json::Number(12.0)));
+    assert_eq!(j.get("expr"), Some(&Json::Number(-1.0)));
+    assert_eq!(j.get("list").unwrap().nth(1), Some(&Json::Number(-4.0)));
+    assert!(j.get("list").unwrap().nth(4).unwrap().get("a").unwrap().is_null());
+}
+
+#[test]
+fn macro_grammar() {
+    let rest = vec![json!(2), json!(null)];
+    let j = json!([
+        null, true, false,
+        [null, true, false,],
+        { "n" : null, "t" : true, "f" : false, },
+        ..[0, 1],
+        ..rest,
+        ..Vec::<Json>::new(),
+    ]);
+    assert_eq!(j.array().unwrap().len(), 9);
+    assert_eq!(j.nth(3), Some(&json!([null, true, false])));
+    assert_eq!(j.nth(4).unwrap().get("f"), Some(&Json::False));
+    assert_eq!(j.nth(6), Some(&Json::Number(1.0)));
+    assert_eq!(j.nth(8), Some(&Json::Null));
+    assert_eq!(json!([]), Json::from(Vec::new()));
+    assert_eq!(json!({ "a" : 1, }), json!({ "a" : 1 }));
+}
+
+#[test]
+fn typed_get() {
+    use json::json_get;
+
+    let doc = Json::deserialize(r#"{
+        "servers" : [ { "port" : 8080, "name" : "a\"b", "tls" : true } ]
+    }"#).unwrap();
+
+    let i = 0;
+    assert_eq!(json_get!(doc, "servers"[i].port as u16).unwrap(), 8080);
+    assert_eq!(json_get!(doc, "servers"[i]."name" as String).unwrap(), "a\"b");
+    assert!(json_get!(doc, "servers"[0].tls as bool).unwrap());
+
+    assert_eq!(json_get!(doc, "servers"[1]).unwrap_err().to_string(), "servers[1]: index out of bounds");
+    assert_eq!(json_get!(doc, "servers"[0].host).unwrap_err().to_string(), "servers[0].host: missing key");
+    assert_eq!(json_get!(doc, "servers".port).unwrap_err().to_string(), "servers.port: expected object, found array");
+    assert_eq!(json_get!(doc, "servers"[0].port as u8).unwrap_err().to_string(), "servers[0].port: 8080 is out of range for u8");
+    assert_eq!(json_get!(doc, as bool).unwrap_err().to_string(), "<root>: expected boolean, found object");
+}
+
+#[test]
+fn int_bounds() {
+    assert_eq!(i8::try_from(&json!(127)).unwrap(), 127);
+    assert_eq!(i8::try_from(&json!(-128)).unwrap(), -128);
+    assert!(i8::try_from(&json!(128)).is_err());
+    assert!(u8::try_from(&json!(-1)).is_err());
+    assert!(u32::try_from(&json!(1.5)).is_err());
+
+    assert_eq!(i64::try_from(&json!(-9223372036854775808.0)).unwrap(), i64::MIN);
+    assert!(i64::try_from(&json!(9223372036854775808.0)).unwrap_err().to_string().ends_with("out of range for i64"));
+    assert!(u64::try_from(&json!(18446744073709551616.0)).unwrap_err().to_string().ends_with("out of range for u64"));
+
+    /* The largest f64 below 2^63 and 2^64. They can't be stored as f32 */
+    #[cfg(not(feature = "compact_numbers"))]
+    {
+        assert_eq!(i64::try_from(&json!(9223372036854774784.0)).unwrap(), 9223372036854774784);
+        assert_eq!(u64::try_from(&json!(18446744073709549568.0)).unwrap(), 18446744073709549568);
+    }
+}
+
+#[test]
+fn to_from_value() {
+    use std::collections::{BTreeMap, HashMap};
+
+    let mut servers = HashMap::new();
+    servers.insert("main".to_string(), vec![(8080_u16, Some("a\"b".to_string()))]);
+    servers.insert("backup".to_string(), vec![(8081, None)]);
+
+    let j = Json::from_value(&servers);
+    assert_eq!(j, json!({
+        "main" : [[8080, r#"a\"b"#]],
+        "backup" : [[8081, null]],
+    }));
+    assert_eq!(j.to_value::<HashMap<String, Vec<(u16, Option<String>)>>>().unwrap(), servers);
+
+    assert_eq!(Json::from_value(&[1_i64, -2]), json!([1, -2]));
+
+    /* Up to 2^53, the 64 bit integers round-trip exactly */
+    const SAFE: u64 = 1 << 53;
+    #[cfg(not(feature = "compact_numbers"))]
+    for n in [SAFE, SAFE - 1] {
+        assert_eq!(Json::from_value(&n).to_value::<u64>().unwrap(), n);
+        assert_eq!(Json::from_value(&-(n as i64)).to_value::<i64>().unwrap(), -(n as i64));
+    }
+    /* Above it, they're rounded, unless they're stored as big integers */
+    #[cfg(not(feature = "bigint"))]
+    assert_eq!(Json::from_value(&(SAFE + 1)).to_value::<u64>().unwrap(), SAFE);
+    assert_eq!(json!([1, 2, 3]).to_value::<[u8; 3]>().unwrap(), [1, 2, 3]);
+    assert_eq!(json!({ "x" : [true] }).to_value::<BTreeMap<String, Json>>().unwrap()["x"], json!([true]));
+
+    let err = |j: Json| j.to_value::<BTreeMap<String, Vec<(u16, Option<String>)>>>().unwrap_err().to_string();
+    assert_eq!(err(json!({ "main" : [[80, 1]] })), "main[0][1]: expected string, found number");
+    assert_eq!(err(json!({ "main" : [[80]] })), "main[0]: expected an array of 2 elements, found 1");
+    assert_eq!(err(json!({ "main" : [[-1, null]] })), "main[0][0]: -1 is out of range for u16");
+    assert_eq!(err(json!([])), "expected object, found array");
+    assert!(json!([1, 2]).to_value::<[u8; 3]>().is_err());
+}
+
+#[test]
+fn get_as() {
+    use json::JsonType;
+
+    let conf = json!({
+        "name" : "db",
+        "port" : 5432,
+        "replicas" : [{ "host" : "a" }, { "host" : 1 }],
+    });
+    assert_eq!(conf.get_as::<String>("name").unwrap(), "db");
+    assert_eq!(conf.get_as::<u16>("port").unwrap(), 5432);
+    assert_eq!(conf.as_type::<std::collections::BTreeMap<String, Json>>().unwrap().len(), 3);
+
+    let err = conf.get_as::<Vec<std::collections::HashMap<String, String>>>("replicas").unwrap_err();
+    assert_eq!(err.path, "replicas[1].host");
+    assert_eq!(err.found, Some(JsonType::Number));
+    assert_eq!(err.to_string(), "replicas[1].host: expected string, found number");
+
+    let err = conf.get_as::<u8>("port").unwrap_err();
+    assert_eq!(err.to_string(), "port: 5432 is out of range for u8");
+
+    let err = conf.get_as::<bool>("tls").unwrap_err();
+    assert_eq!((&*err.path, err.found), ("tls", None));
+
+    let err = json!([1]).get_as::<u8>("a").unwrap_err();
+    assert_eq!((&*err.path, err.found), ("", Some(JsonType::Array)));
+    assert_eq!(err.to_string(), "expected object, found array");
+}
+
+#[test]
+fn len() {
+    assert_eq!(json!([1, 2, 3]).len(), Some(3));
+    assert_eq!(json!({ "a" : 1 }).len(), Some(1));
+    assert_eq!(Json::deserialize(r#""aññ""#).unwrap().len(), Some(3));
+    assert_eq!(json!(1).len(), None);
+    assert_eq!(json!(null).len(), None);
+
+    assert!(json!([]).is_empty());
+    assert!(json!({}).is_empty());
+    assert!(json!("").is_empty());
+    assert!(!json!(null).is_empty());
+    assert!(!json!([null]).is_empty());
+}
+
+#[test]
+fn json_type() {
+    use json::JsonType;
+
+    assert_eq!(json!([]).json_type(), JsonType::Array);
+    assert_eq!(json!({}).json_type(), JsonType::Object);
+    assert_eq!(json!("a").json_type(), JsonType::String);
+    assert_eq!(json!(1).json_type(), JsonType::Number);
+    assert_eq!(json!(false).json_type(), JsonType::Bool);
+    assert_eq!(json!(null).json_type(), JsonType::Null);
+
+    assert_eq!(json!(true).type_name(), "boolean");
+    assert_eq!(JsonType::Object.to_string(), "object");
+}
+
+#[test]
+fn predicates() {
+    let values = [json!({}), json!([]), json!("s"), json!(1), json!(true), json!(false), json!(null)];
+    let count = |f: fn(&Json) -> bool| values.iter().filter(|v| f(v)).count();
+
+    assert_eq!(count(Json::is_object), 1);
+    assert_eq!(count(Json::is_array), 1);
+    assert_eq!(count(Json::is_string), 1);
+    assert_eq!(count(Json::is_number), 1);
+    assert_eq!(count(Json::is_boolean), 2);
+    assert_eq!(count(Json::is_true), 1);
+    assert_eq!(count(Json::is_false), 1);
+    assert_eq!(count(Json::is_null), 1);
+
+    assert!(values[0].is_object() && values[1].is_array() && values[2].is_string());
+    assert!(values[3].is_number() && values[4].is_true() && values[5].is_false());
+}
+
+#[test]
+fn into_inner() {
+    let j = json!({ "list" : [1, "two"], "n" : 3 });
+
+    let obj = j.into_object().unwrap();
+    let n = obj.get("n").unwrap().clone();
+    let list = obj.into_iter().find(|(k, _)| &**k == "list").unwrap().1;
+    let list = list.into_array().unwrap();
+    assert_eq!(list, vec![json!(1), json!("two")]);
+
+    let mut list = list.into_iter();
+    assert_eq!(list.next().unwrap().into_number(), Ok(1.0));
+    assert_eq!(&*list.next().unwrap().into_string().unwrap(), "two");
+
+    assert_eq!(n.clone().into_string(), Err(n.clone()));
+    assert_eq!(n.clone().into_array(), Err(n.clone()));
+    assert_eq!(json!(null).into_number(), Err(json!(null)));
+}
+
+#[test]
+fn empty_input() {
+    for text in ["", " ", "-", ".5"] {
+        let err = Json::deserialize(text).unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected end of input", "{text:?}");
+    }
+}
+
+#[test]
+fn std_traits() {
+    assert_eq!(Json::default(), Json::Null);
+
+    let j: Json = r#"{ "a" : [1, 2, 3] }"#.parse().unwrap();
+    assert_eq!(j, json!({ "a" : [1, 2, 3] }));
+    assert!("{".parse::<Json>().is_err());
+
+    let arr = j.get("a").unwrap();
+    let doubled: Vec<Json> = arr.into_iter().map(|(_, v)| json!(v.number().unwrap() * 2.0)).collect();
+    assert_eq!(doubled, vec![json!(2), json!(4), json!(6)]);
+    assert!(arr.into_iter().all(|(k, _)| k.is_none()));
+
+    let keys: Vec<_> = j.into_iter().map(|(k, _)| k.unwrap()).collect();
+    assert_eq!(keys, vec!["a".into()]);
+
+    assert_eq!(json!(1).into_iter().count(), 0);
+}
+
+#[test]
+fn pretty_display() {
+    let j = json!({ "list" : [1, [], { "b" : null }] });
+
+    assert_eq!(format!("{j}"), r#"{"list":[1,[],{"b":null}]}"#);
+    assert_eq!(format!("{j:#}"), "\
+{
+  \"list\": [
+    1,
+    [],
+    {
+      \"b\": null
+    }
+  ]
+}");
+    assert_eq!(format!("{:#}", json!("s")), "\"s\"");
+    assert_eq!(Json::deserialize(format!("{j:#}")).unwrap(), j);
+}
+
+#[test]
+fn non_finite() {
+    use json::{NonFinite, SerializeConfig};
+
+    let j = json!({ "a" : [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1.5] });
+    assert_eq!(j.to_string(), r#"{"a":[null,null,null,1.5]}"#);
+
+    let ser = |non_finite| {
+        let conf = SerializeConfig { non_finite, ..Default::default() };
+        let mut out = String::new();
+        j.serialize_with_config(&conf, &mut out).map(|()| out)
+    };
+    assert_eq!(ser(NonFinite::AsNull).unwrap(), r#"{"a":[null,null,null,1.5]}"#);
+    assert_eq!(ser(NonFinite::AsString).unwrap(), r#"{"a":["NaN","Infinity","-Infinity",1.5]}"#);
+    assert!(ser(NonFinite::Error).is_err());
+
+    let conf = SerializeConfig { indent: Some(" "), non_finite: NonFinite::AsString, ..Default::default() };
+    let mut out = String::new();
+    json!([f64::NAN]).serialize_with_config(&conf, &mut out).unwrap();
+    assert_eq!(out, "[\n \"NaN\"\n]");
+}
+
+#[test]
+fn escape_non_ascii() {
+    use json::SerializeConfig;
+
+    let j = Json::deserialize(r#"{ "clé" : "ñ \" 𝄞 \u00e9" }"#).unwrap();
+    let conf = SerializeConfig { escape_non_ascii: true, ..Default::default() };
+    let mut out = String::new();
+    j.serialize_with_config(&conf, &mut out).unwrap();
+
+    assert_eq!(out, r#"{"cl\u00e9":"\u00f1 \" \ud834\udd1e \u00e9"}"#);
+    assert!(out.is_ascii());
+    assert_eq!(Json::deserialize(&out).unwrap().to_string(),
+               r#"{"cl\u00e9":"\u00f1 \" \ud834\udd1e \u00e9"}"#);
+}
+
+#[test]
+fn deep_size_of() {
+    use std::mem::size_of;
+
+    assert_eq!(json!(null).deep_size_of(), 0);
+    assert_eq!(json!([]).deep_size_of(), 0);
+    assert_eq!(json!([[1, 2], "xy"]).deep_size_of(), 4 * size_of::<Json>() + 2);
+
+    let small = json!({ "a" : 1 });
+    let big = json!({ "a" : 1, "bb" : "x".repeat(100), "c" : [1, 2, 3] });
+    assert!(small.deep_size_of() > size_of::<Json>());
+    assert!(big.deep_size_of() > small.deep_size_of() + 100 + 3 * size_of::<Json>());
+}
+
+#[test]
+fn display_truncated() {
+    let j = json!({
+        "name" : "a very long name",
+        "list" : [1, 2, 3, 4],
+        "nested" : { "deeper" : { "x" : [] } }
+    });
+    let nested = j.get("nested").unwrap();
+    assert_eq!(nested.display_truncated(1, 10, 10).to_string(), r#"{"deeper": {…}}"#);
+    assert_eq!(nested.display_truncated(5, 10, 10).to_string(), r#"{"deeper": {"x": []}}"#);
+    assert_eq!(j.get("list").unwrap().display_truncated(5, 0, 0).to_string(), "[…(4 more)]");
+    assert_eq!(j.get("name").unwrap().display_truncated(5, 5, 6).to_string(), r#""a very…""#);
+    assert_eq!(json!("short").display_truncated(0, 0, 5).to_string(), r#""short""#);
+    assert_eq!(json!([]).display_truncated(0, 0, 0).to_string(), "[]");
+}
+
+#[test]
+fn content_hash() {
+    let a = json!({ "x" : [1, -0.0, "s"], "y" : { "z" : null } });
+    let b = Json::deserialize(r#"{ "y" : { "z" : null }, "x" : [1.0, 0, "s"] }"#).unwrap();
+    assert_eq!(a.content_hash(), b.content_hash());
+
+    /* The hash is stable across runs */
+    assert_eq!(json!(null).content_hash(), 0xaf63_ad4c_8601_9caf);
+    assert_eq!(Json::deserialize(r#""\u0061""#).unwrap().content_hash(), json!("a").content_hash());
+
+    assert_ne!(json!(["ab", "c"]).content_hash(), json!(["a", "bc"]).content_hash());
+    assert_ne!(json!([[]]).content_hash(), json!([]).content_hash());
+    assert_ne!(json!({ "a" : 1 }).content_hash(), json!({ "a" : "1" }).content_hash());
+
+    use std::hash::{DefaultHasher, Hasher};
+    let mut h1 = DefaultHasher::new();
+    a.canonical_hash(&mut h1);
+    let mut h2 = DefaultHasher::new();
+    b.canonical_hash(&mut h2);
+    assert_eq!(h1.finish(), h2.finish());
+}
+
+#[test]
+#[cfg(not(feature = "compact_numbers"))]
+fn approx_eq() {
+    let a = json!({ "x" : [1.0, 2.000_000_000_000_001], "y" : "s", "z" : null });
+    let b = json!({ "z" : null, "x" : [1.0 + 1e-15, 2.0], "y" : "s" });
+    assert!(a.approx_eq(&b, 1e-9));
+    assert!(!a.approx_eq(&b, 0.0));
+
+    assert!(json!(f64::INFINITY).approx_eq(&json!(f64::INFINITY), 1e-9));
+    assert!(!json!(f64::NAN).approx_eq(&json!(f64::NAN), 1e-9));
+    assert!(!json!([1.0]).approx_eq(&json!([1.0, 2.0]), 1e-9));
+    assert!(!json!({ "a" : 1 }).approx_eq(&json!({ "b" : 1 }), 1e-9));
+    assert!(!json!(1).approx_eq(&json!("1"), 1e-9));
+}
+
+#[test]
+fn deserialize_with_progress() {
+    let text = format!("[{}]", vec![r#"{ "key" : "value", "n" : 1.5 }"#; 50_000].join(","));
+    let mut calls = Vec::new();
+    let json = Json::deserialize_with_progress(&text, JsonConfig::default(), |done, total| {
+        assert_eq!(total, text.len());
+        calls.push(done);
+    }).unwrap();
+    assert_eq!(json.array().unwrap().len(), 50_000);
+    assert!(calls.len() > 10);
+    assert!(calls.windows(2).all(|w| w[0] <= w[1]));
+    assert!(calls.iter().any(|c| *c > text.len() / 2 && *c < text.len()));
+    assert_eq!(calls.last(), Some(&text.len()));
+
+    let mut calls = 0;
+    assert!(Json::deserialize_with_progress("[1, 2", JsonConfig::default(), |_, _| calls += 1).is_err());
+    assert_eq!(calls, 0);
+}
+
+#[test]
+fn deserialize_cancellable() {
+    use json::CancelToken;
+    use std::time::{Duration, Instant};
+
+    let text = format!("[{}]", vec![r#"{ "key" : [1, 2, 3] }"#; 50_000].join(","));
+
+    let token = CancelToken::new();
+    assert!(!token.is_cancelled());
+    assert!(Json::deserialize_cancellable(&text, JsonConfig::default(), &token).is_ok());
+
+    std::thread::scope(|s| {
+        s.spawn(|| token.cancel());
+    });
+    assert!(token.is_cancelled());
+    let err = Json::deserialize_cancellable(&text, JsonConfig::default(), &token).unwrap_err();
+    assert_eq!(err.to_string(), "Deserialization cancelled");
+
+    let expired = CancelToken::with_deadline(Instant::now());
+    assert!(expired.is_cancelled());
+    assert!(Json::deserialize_cancellable(&text, JsonConfig::default(), &expired).is_err());
+    assert!(!CancelToken::with_timeout(Duration::from_secs(3600)).is_cancelled());
+}
+
+#[test]
+fn max_bytes() {
+    let conf = |max_bytes| JsonConfig { max_bytes, ..Default::default() };
+
+    let long = format!(r#"["{}"]"#, "a".repeat(10_000));
+    let err = Json::deserialize_with_config(&long, conf(5_000)).unwrap_err();
+    assert_eq!(err.to_string(), "Memory budget exceeded");
+    assert!(Json::deserialize_with_config(&long, conf(20_000)).is_ok());
+
+    let many = format!("[{}]", vec!["1"; 10_000].join(","));
+    assert!(Json::deserialize_with_config(&many, conf(100_000)).is_err());
+    assert!(Json::deserialize_with_config(&many, conf(usize::MAX)).is_ok());
+
+    let obj = format!("{{{}}}", (0..1000).map(|i| format!(r#""{i}":null"#)).collect::<Vec<_>>().join(","));
+    assert!(Json::deserialize_with_config(&obj, conf(10_000)).is_err());
+    assert!(json::spanned::parse_with_config(&long, conf(5_000)).is_err());
+}
+
+#[test]
+fn deserialize_batch() {
+    let docs: Vec<String> = (0..500).map(|i| format!(r#"{{ "i" : {i}, "list" : [{i}] }}"#)).collect();
+    let mut inputs: Vec<&str> = docs.iter().map(String::as_str).collect();
+    inputs.push("[1,");
+
+    for threads in [0, 1, 3, 64, 1000] {
+        let results = json::deserialize_batch_with(&inputs, threads);
+        assert_eq!(results.len(), inputs.len());
+        for (i, r) in results[..500].iter().enumerate() {
+            assert_eq!(r.as_ref().unwrap(), &json!({ "i" : i as u16, "list" : [i as u16] }));
+        }
+        assert!(results[500].is_err());
+    }
+    assert_eq!(json::deserialize_batch(&inputs).len(), 501);
+    assert!(json::deserialize_batch(&[]).is_empty());
+}
+
+#[test]
+fn deeply_nested() {
+    const DEPTH: usize = 200_000;
+
+    let text = "[{\"a\":".repeat(DEPTH) + "null" + &"}]".repeat(DEPTH);
+    let json = Json::deserialize(&text).unwrap();
+    assert_eq!(json.to_string(), text);
+    drop(json);
+
+    /* The partially built values are dropped on errors */
+    let text = "[{\"a\":".repeat(DEPTH) + "[" + &"]}".repeat(DEPTH) + ",}";
+    assert!(Json::deserialize(&text).is_err());
+    assert!(json::spanned::parse(&text).is_err());
+
+    let conf = JsonConfig { max_depth: 10, ..Default::default() };
+    assert!(Json::deserialize_with_config("[".repeat(12) + &"]".repeat(12), conf).is_err());
+    let conf = JsonConfig { max_depth: 10, ..Default::default() };
+    assert!(Json::deserialize_with_config("[".repeat(11) + &"]".repeat(11), conf).is_ok());
+}
+
+#[test]
+#[cfg(feature = "compact_numbers")]
+fn compact_numbers() {
+    assert_eq!(size_of::<json::Number>(), 4);
+    let json = Json::deserialize("[1.5, 16777217]").unwrap();
+    assert_eq!(json.nth(0).unwrap().number(), Some(1.5));
+    assert_eq!(json.nth(1).unwrap().number(), Some(16_777_216.0));
+}
+
+#[test]
+fn reusable_parser() {
+    let mut parser = JsonParser::new();
+    for i in 0..100 {
+        let text = format!(r#"{{ "id" : {i}, "tags" : [[{i}], {{ "x" : null }}] }}"#);
+        assert_eq!(parser.parse(&text).unwrap(), Json::deserialize(&text).unwrap());
+    }
+    /* An error in the middle of a document doesn't affect the next one */
+    assert!(parser.parse("[[1, {\"a\" : [}]]").is_err());
+    assert_eq!(parser.parse("[1, [2]]").unwrap(), json!([1, [2]]));
+
+    let mut parser = JsonParser::with_config(JsonConfig { max_depth: 2, recover_from_errors: true, ..Default::default() });
+    assert_eq!(parser.parse("[[1,],]").unwrap(), json!([[1]]));
+    assert!(parser.parse("[[[1]]]").is_err());
+}
+
+#[test]
+fn escape_sequences() {
+    let json = Json::deserialize(r#"["\"\\\/\b\f\n\r\t", "é😀"]"#).unwrap();
+    assert_eq!(json.nth(1).unwrap().to_string(), r#""é😀""#);
+
+    let err = |text| Json::deserialize(text).unwrap_err().to_string();
+    assert_eq!(err(r#"["ab\x"]"#), "[0:4] Invalid escape sequence [\\x]");
+    assert_eq!(err(r#"["\8"]"#), "[0:2] Invalid escape sequence [\\8]");
+    assert_eq!(err("[\n\"a\\u12\"]"), "[1:3] Truncated \\u escape sequence");
+    assert!(Json::deserialize(r#""\"#).is_err());
+}
+
+#[test]
+fn lone_surrogates() {
+    let text = r#"{ "\uD800" : ["a\uDC00b", "\uD83D\uDE00", "\uD800A"] }"#;
+    let json = Json::deserialize(text).unwrap();
+    assert_eq!(json.to_string(), r#"{"�":["a�b","\uD83D\uDE00","�A"]}"#);
+
+    let conf = JsonConfig { lone_surrogates: LoneSurrogates::Error, ..Default::default() };
+    let err = Json::deserialize_with_config(text, conf).unwrap_err();
+    assert_eq!(err.to_string(), "[0:3]: Lone surrogate in string");
+    let err = Json::deserialize_with_config(r#"["a\uDC00b"]"#, conf).unwrap_err();
+    assert_eq!(err.to_string(), "[0:3]: Lone surrogate in string");
+    assert!(Json::deserialize_with_config(r#"["\uD83D\uDE00"]"#, conf).is_ok());
+    let err = Json::deserialize_with_config("[\n  \"ñ\",\n  \"\\uD800\"\n]", conf).unwrap_err();
+    assert_eq!(err.to_string(), "[2:4]: Lone surrogate in string");
+}
+
+#[test]
+fn json5() {
+    use json::{SerializeConfig, Syntax};
+
+    let conf = |indent| SerializeConfig { syntax: Syntax::Json5, indent, ..Default::default() };
+    let ser = |json: &Json, conf| {
+        let mut out = String::new();
+        json.serialize_with_config(&conf, &mut out).unwrap();
+        out
+    };
+
+    let j = json!([{ "$id" : r#"a \"b\" 'c'"# }, { "1x" : [] }, f64::NAN, f64::NEG_INFINITY]);
+    assert_eq!(ser(&j, conf(None)), r#"[{$id:'a "b" \'c\''},{'1x':[]},NaN,-Infinity]"#);
+    assert_eq!(ser(&j, conf(Some(" "))), r#"[
+ {
+  $id: 'a "b" \'c\'',
+ },
+ {
+  '1x': [],
+ },
+ NaN,
+ -Infinity,
+]"#);
+}
+
+#[test]
+fn try_get() {
+    use json::{AccessError, JsonType};
+
+    let conf = json!({ "servers" : [{ "port" : 80 }] });
+    assert_eq!(conf.try_get("servers").unwrap().try_nth(0).unwrap().try_get("port").unwrap(), &json!(80));
+
+    let err = conf.try_get("timeout").unwrap_err();
+    assert_eq!(err, AccessError { path: "timeout".into(), expected: JsonType::Object, found: None });
+    assert_eq!(err.to_string(), "timeout: missing key");
+
+    let servers = conf.try_get("servers").unwrap();
+    let err = servers.try_nth(1).unwrap_err().within_key("servers");
+    assert_eq!(err.to_string(), "servers[1]: index out of bounds");
+
+    let err = servers.try_get("port").unwrap_err();
+    assert_eq!(err.found, Some(JsonType::Array));
+    assert_eq!(err.within_key("servers").to_string(), "servers.port: expected object, found array");
+
+    let err = json!(1).try_nth(0).unwrap_err().within_index(2).within_key("a");
+    assert_eq!(err.to_string(), "a[2][0]: expected array, found number");
+    let err: json::Error = json!(null).try_get("x").unwrap_err().into();
+    assert_eq!(err.to_string(), "x: expected object, found null");
+}
+
+#[test]
+fn at() {
+    let conf = json!({
+        "name" : "db",
+        "tls" : false,
+        "replicas" : [{ "host" : "a", "weight" : 0.5 }, null],
+    });
+    assert_eq!(conf.at("name").as_str(), Some("db"));
+    assert_eq!(conf.at("tls").as_bool(), Some(false));
+    assert_eq!(conf.at("replicas").at(0).at("weight").as_f64(), Some(0.5));
+    assert!(conf.at("replicas").at(1).is_null());
+    assert!(conf.at("name").as_f64().is_none());
+
+    let key = String::from("host");
+    assert_eq!(conf.at("replicas").at(0).at(&key).get(), Some(&json!("a")));
+
+    let err = |at: json::At<'_>| at.required().unwrap_err().to_string();
+    assert_eq!(err(conf.at("replicas").at(2).at("host")), "replicas[2]: index out of bounds");
+    assert_eq!(err(conf.at("replicas").at(1).at("host")), "replicas[1].host: expected object, found null");
+    assert_eq!(err(conf.at("name").at(0)), "name[0]: expected array, found string");
+    assert_eq!(err(conf.at("port")), "port: missing key");
+}
+
+#[test]
+fn checked_arithmetic() {
+    assert_eq!(json!(2).checked_add(3).unwrap(), json!(5));
+    assert_eq!(json!(2).checked_sub(3.5).unwrap(), json!(-1.5));
+    assert_eq!(json!(2).checked_mul(-4).unwrap(), json!(-8));
+    assert_eq!(json!(3).checked_div(2).unwrap(), json!(1.5));
+
+    assert_eq!(json!(1).checked_div(0).unwrap_err().to_string(), "1 / 0 is not a finite number");
+    assert_eq!(json!(2).checked_mul(f64::MAX).unwrap_err().to_string(), format!("2 * {} is not a finite number", f64::MAX));
+    assert_eq!(json!("1").checked_add(1).unwrap_err().to_string(), "expected number, found string");
+    assert_eq!(json!(null).checked_sub(1).unwrap_err().to_string(), "expected number, found null");
+    assert!(json!([1]).checked_mul(1).is_err());
+}
+
+#[test]
+fn operators() {
+    let (a, b) = (json!({ "x" : [1], "y" : "a" }), json!({ "y" : "b", "z" : null }));
+    assert_eq!((&a + &b).unwrap(), json!({ "x" : [1], "y" : "b", "z" : null }));
+    assert_eq!((a + b).unwrap(), json!({ "x" : [1], "y" : "b", "z" : null }));
+
+    assert_eq!((&json!(r#"a\"b"#) + &json!("c")).unwrap(), json!(r#"a\"bc"#));
+    assert_eq!((json!([]) + json!([null])).unwrap(), json!([null]));
+    assert_eq!((&json!(7) - &json!(2)).unwrap(), json!(5));
+    assert_eq!((json!(7) * json!(2)).unwrap(), json!(14));
+    assert_eq!((json!(7) / json!(2)).unwrap(), json!(3.5));
+
+    assert_eq!((json!(1) / json!(0)).unwrap_err().to_string(), "1 / 0 is not a finite number");
+    assert_eq!((json!("a") - json!("b")).unwrap_err().to_string(), "Can't apply - to string and string");
+    assert_eq!((&json!(1) + &json!("1")).unwrap_err().to_string(), "Can't apply + to number and string");
+    assert_eq!((json!(null) + json!(null)).unwrap_err().to_string(), "Can't apply + to null and null");
+}
+
+#[test]
+#[cfg(feature = "arc_keys")]
+fn arc_keys() {
+    let a = json!({ "key" : [1] });
+    let b = a.clone();
+    let (Json::Object(a), Json::Object(b)) = (&a, &b) else { unreachable!() };
+    let (ka, kb) = (a.keys().next().unwrap(), b.keys().next().unwrap());
+    assert!(std::sync::Arc::ptr_eq(ka, kb));
+}
+
+#[test]
+fn max_width() {
+    use json::{SerializeConfig, Syntax};
+
+    let ser = |json: &Json, max_width, syntax| {
+        let conf = SerializeConfig { indent: Some("  "), max_width: Some(max_width), syntax, ..Default::default() };
+        let mut out = String::new();
+        json.serialize_with_config(&conf, &mut out).unwrap();
+        out
+    };
+
+    let j = json!({ "m" : [[1, 2, 3], [4, 5, { "a" : null }]] });
+    assert_eq!(ser(&j, 40, Syntax::Json), r#"{"m": [[1, 2, 3], [4, 5, {"a": null}]]}"#);
+    assert_eq!(ser(&j, 28, Syntax::Json), r#"{
+  "m": [
+    [1, 2, 3],
+    [4, 5, {"a": null}]
+  ]
+}"#);
+    assert_eq!(ser(&j, 20, Syntax::Json), r#"{
+  "m": [
+    [1, 2, 3],
+    [
+      4,
+      5,
+      {"a": null}
+    ]
+  ]
+}"#);
+    assert_eq!(ser(&j, 20, Syntax::Json5), r#"{
+  m: [
+    [1, 2, 3],
+    [
+      4,
+      5,
+      {a: null},
+    ],
+  ],
+}"#);
+    assert_eq!(ser(&json!([[], {}]), 0, Syntax::Json), "[\n  [],\n  {}\n]");
+}
+
+#[test]
+fn colored() {
+    use json::{ColorScheme, SerializeConfig, Syntax};
+
+    let j = json!({ "k" : ["s", 1.5, true, null, {}] });
+    let mut out = String::new();
+    j.serialize_colored(&mut out, &ColorScheme::none()).unwrap();
+    assert_eq!(out, format!("{j:#}"));
+
+    let scheme = ColorScheme { key: "<k>", string: "<s>", number: "<n>", literal: "" };
+    let mut out = String::new();
+    j.serialize_colored(&mut out, &scheme).unwrap();
+    assert_eq!(out, "{\n  <k>\"k\"\x1b[0m: [\n    <s>\"s\"\x1b[0m,\n    <n>1.5\x1b[0m,\n    true,\n    null,\n    {}\n  ]\n}");
+
+    let conf = SerializeConfig { colors: Some(&scheme), syntax: Syntax::Json5, max_width: Some(80), ..Default::default() };
+    let mut out = String::new();
+    j.serialize_with_config(&conf, &mut out).unwrap();
+    assert_eq!(out, "{<k>k\x1b[0m:[<s>'s'\x1b[0m,<n>1.5\x1b[0m,true,null,{}]}");
+
+    /* The escape sequences don't count for the width */
+    let conf = SerializeConfig { colors: Some(&scheme), indent: Some("  "), max_width: Some(33), ..Default::default() };
+    let mut out = String::new();
+    j.serialize_with_config(&conf, &mut out).unwrap();
+    assert_eq!(out, "{<k>\"k\"\x1b[0m: [<s>\"s\"\x1b[0m, <n>1.5\x1b[0m, true, null, {}]}");
+
+    std::env::set_var("NO_COLOR", "1");
+    assert_eq!(ColorScheme::default(), ColorScheme::none());
+    std::env::set_var("NO_COLOR", "");
+    assert_eq!(ColorScheme::default(), ColorScheme::ansi());
+}
+
+#[test]
+fn contains() {
+    use json::ArrayPolicy;
+
+    let j = json!({
+        "a" : { "b" : [1, { "c" : 2, "d" : 3 }], "e" : null },
+        "f" : "s"
+    });
+    assert!(j.contains(&j));
+    assert!(j.contains(&json!({})));
+    assert!(j.contains(&json!({ "a" : { "e" : null } })));
+    assert!(j.contains(&json!({ "a" : { "b" : [1, { "c" : 2, "d" : 3 }] } })));
+    assert!(!j.contains(&json!({ "a" : { "b" : [1, { "c" : 2 }] } })));
+    assert!(!j.contains(&json!({ "f" : "t" })));
+    assert!(!j.contains(&json!({ "g" : null })));
+    assert!(!j.contains(&json!({ "a" : [] })));
+    assert!(json!({ "f" : "s" }).subset_of(&j));
+    assert!(!j.subset_of(&json!({ "f" : "s" })));
+
+    assert!(j.contains_with(&json!({ "a" : { "b" : [{ "d" : 3 }, 1] } }), ArrayPolicy::Elements));
+    assert!(j.contains_with(&json!({ "a" : { "b" : [] } }), ArrayPolicy::Elements));
+    assert!(!j.contains_with(&json!({ "a" : { "b" : [{ "d" : 4 }] } }), ArrayPolicy::Elements));
+    assert!(!json!(1).contains_with(&json!([1]), ArrayPolicy::Elements));
+}
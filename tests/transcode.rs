@@ -0,0 +1,40 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use json::transcode::from_str;
+
+#[test]
+fn deserialize() {
+    let v: Vec<Option<String>> = from_str(r#"["a\nb", null, "é"]"#).unwrap();
+    assert_eq!(v, [Some("a\nb".into()), None, Some("é".into())]);
+
+    let m: BTreeMap<String, (i32, f64, bool)> = from_str(r#"{ "a\"" : [-1, 2.5, true] }"#).unwrap();
+    assert_eq!(m["a\""], (-1, 2.5, true));
+
+    assert!(from_str::<Vec<u8>>("[1, 256]").is_err());
+    assert!(from_str::<Vec<u8>>(r#"[1, "2"]"#).is_err());
+    assert!(from_str::<(u8, u8)>("[1, 2, 3]").is_err());
+    assert!(from_str::<u8>("1 2").is_err());
+    assert!(from_str::<Vec<u8>>("[1, 2").is_err());
+}
+
+#[test]
+#[cfg(feature = "serde_json")]
+fn transcode() {
+    use json::json;
+    use serde::Deserialize;
+
+    let text = r#"{ "a" : [1, -2, 0.5, "x\ty"], "b" : { "c" : null, "d" : false } }"#;
+    let (mut scratch, mut nesting) = ([0; 16], [0; 4]);
+    let mut de = json::transcode::Deserializer::new(text, &mut scratch, &mut nesting);
+    let value = serde_json::Value::deserialize(&mut de).unwrap();
+    de.end().unwrap();
+    assert_eq!(value, serde_json::json!({
+        "a" : [1, -2, 0.5, "x\ty"],
+        "b" : { "c" : null, "d" : false },
+    }));
+
+    let j = json!({ r#"k\""# : [1, -2.5, r"a\nb", true, null] });
+    assert_eq!(serde_json::to_string(&j).unwrap(), r#"{"k\"":[1,-2.5,"a\nb",true,null]}"#);
+}
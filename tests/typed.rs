@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use json::schema::Schema;
+use json::typed::{self, Typed, TypedParser};
+use json::{json, typed_struct, Json};
+
+typed_struct! {
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+}
+
+typed_struct! {
+    #[derive(Debug, PartialEq)]
+    struct Shape {
+        name: String,
+        points: Vec<Point>,
+        closed: Option<bool>,
+        extra: Option<Json>,
+    }
+}
+
+#[test]
+fn primitives() {
+    assert_eq!(typed::parse::<String>(r#""a\nb""#).unwrap(), "a\nb");
+    assert_eq!(typed::parse::<u8>("255").unwrap(), 255);
+    assert_eq!(typed::parse::<f64>("1.5").unwrap(), 1.5);
+    assert!(typed::parse::<bool>("true").unwrap());
+    assert_eq!(typed::parse::<Option<i64>>("null").unwrap(), None);
+    assert_eq!(typed::parse::<Vec<u32>>("[1, 2, 3]").unwrap(), [1, 2, 3]);
+
+    let map = typed::parse::<BTreeMap<String, Vec<bool>>>(r#"{ "a" : [true], "b" : [] }"#).unwrap();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["a"], [true]);
+}
+
+#[test]
+fn structs() {
+    let shape: Shape = typed::parse(r#"{
+        "name" : "tri",
+        "unknown" : [{ "deep" : [1, 2] }],
+        "points" : [ { "x" : 0, "y" : 0 }, { "y" : 2, "x" : 1 } ],
+        "extra" : { "color" : "red" }
+    }"#).unwrap();
+    assert_eq!(shape, Shape {
+        name: "tri".into(),
+        points: vec![Point { x: 0, y: 0 }, Point { x: 1, y: 2 }],
+        closed: None,
+        extra: Some(json!({ "color" : "red" })),
+    });
+}
+
+#[test]
+fn errors() {
+    let err = |text: &str| typed::parse::<Shape>(text).unwrap_err().to_string();
+
+    assert_eq!(err(r#"{ "name" : 1 }"#), "/name: expected string, found number");
+    assert_eq!(err(r#"{ "name" : "a", "points" : [{ "x" : 1.5, "y" : 0 }] }"#),
+               "/points/0/x: expected i32, found 1.5");
+    assert_eq!(err(r#"{ "name" : "a", "points" : [{ "x" : 1 }] }"#),
+               "/points/0: missing field 'y'");
+    assert_eq!(err(r#"{ "name" : "a" }"#), "/: missing field 'points'");
+    assert_eq!(err(r#"[]"#), "/: expected object, found array");
+
+    assert!(typed::parse::<u8>("256").is_err());
+    assert!(typed::parse::<u32>("-1").is_err());
+    assert_eq!(typed::parse::<i64>("-9223372036854775808").unwrap(), i64::MIN);
+    assert!(typed::parse::<i64>("9223372036854775808").is_err());
+    assert!(typed::parse::<u64>("18446744073709551616").is_err());
+    assert!(typed::parse::<u32>("1 2").is_err());
+    assert!(typed::parse::<Vec<u32>>("[1, 2").is_err());
+}
+
+#[derive(Debug)]
+struct Celsius(f64);
+
+impl Typed for Celsius {
+    fn expected() -> &'static str { "temperature" }
+    fn parse(p: &mut TypedParser<'_>) -> Result<Self, json::Error> {
+        let n = p.number("temperature")?;
+        if n < -273.15 {
+            return Err(p.error("temperature below absolute zero"))
+        }
+        Ok(Celsius(n))
+    }
+}
+
+#[test]
+fn custom() {
+    let temps = typed::parse::<Vec<Celsius>>("[20, -5.5]").unwrap();
+    assert_eq!(temps.iter().map(|c| c.0).collect::<Vec<_>>(), [20.0, -5.5]);
+
+    let err = typed::parse::<Vec<Celsius>>("[1, -300]").unwrap_err();
+    assert_eq!(err.to_string(), "/1: temperature below absolute zero");
+}
+
+#[test]
+fn with_schema() {
+    let schema = Schema::compile(&json!({
+        "type" : "object",
+        "properties" : { "x" : { "minimum" : 0 } },
+    })).unwrap();
+
+    let p: Point = typed::parse_with_schema(r#"{ "x" : 1, "y" : -1 }"#, &schema).unwrap();
+    assert_eq!(p, Point { x: 1, y: -1 });
+    assert!(typed::parse_with_schema::<Point>(r#"{ "x" : -1, "y" : 0 }"#, &schema).is_err());
+}
@@ -0,0 +1,19 @@
+#![cfg(feature = "uuid")]
+
+use json::{json, Json};
+use uuid::Uuid;
+
+#[test]
+fn uuids() {
+    let id = Uuid::parse_str("67E55044-10B1-426F-9247-BB680E5FE0C8").unwrap();
+    assert_eq!(Json::from(id), json!("67e55044-10b1-426f-9247-bb680e5fe0c8"));
+    assert_eq!(Json::from(id).as_uuid(), Some(id));
+    assert_eq!(json!("67e55044").as_uuid(), None);
+    assert_eq!(json!(1).as_uuid(), None);
+
+    let j = json!({ "a" : { "id" : "67e55044-10b1-426f-9247-bb680e5fe0c8", "bad" : "xyz" } });
+    assert_eq!(j.uuid_at("/a/id").unwrap(), id);
+    assert!(j.uuid_at("/a/bad").unwrap_err().to_string().starts_with("/a/bad: invalid UUID \"xyz\""));
+    assert_eq!(j.uuid_at("/a/missing").unwrap_err().to_string(), "/a/missing: missing value");
+    assert!(j.uuid_at("no slash").is_err());
+}
@@ -0,0 +1,119 @@
+use json::{json, JsonPointer};
+
+#[test]
+fn find() {
+    let j = json!({
+        "a" : { "id" : 7, "b" : [ { "id" : 7 }, 3 ] },
+        "c" : "7"
+    });
+
+    let mut found = j.find(|_, v| v.number() == Some(7.0));
+    found.sort();
+    assert_eq!(found, vec![
+        JsonPointer::parse("/a/b/0/id").unwrap(),
+        JsonPointer::parse("/a/id").unwrap(),
+    ]);
+
+    let found = j.find(|path, _| path.last() == Some("b"));
+    assert_eq!(found, vec![JsonPointer::parse("/a/b").unwrap()]);
+
+    assert_eq!(j.find_first(|path, _| path.is_root()), Some(JsonPointer::root()));
+    assert_eq!(j.find_first(|_, v| v.number() == Some(3.0)).unwrap().to_string(), "/a/b/1");
+    assert!(j.find_first(|_, v| v.is_null()).is_none());
+}
+
+#[test]
+fn retain() {
+    let mut j = json!({
+        "keep" : [ { "x" : null }, null, 1, [] ],
+        "drop" : { "y" : null },
+        "z" : null
+    });
+
+    let mut visited = Vec::new();
+    j.retain(|path, v| {
+        visited.push(path.to_string());
+        !v.is_null() && !v.object().is_some_and(|o| o.is_empty())
+    });
+    assert_eq!(j, json!({ "keep" : [1, []] }));
+    assert!(visited.contains(&"/keep/2".to_string()));
+    assert!(visited.contains(&"/drop/y".to_string()));
+    assert!(!visited.contains(&String::new()));
+
+    let mut j = json!([1, 2, 3, 4]);
+    j.retain(|_, v| v.number().is_some_and(|n| n % 2.0 == 0.0));
+    assert_eq!(j, json!([2, 4]));
+}
+
+#[test]
+fn map_values() {
+    let j = json!({ "a" : [1, 2], "b" : { "c" : 3 } });
+
+    let mut order = Vec::new();
+    let strings = j.map_values(|path, v| {
+        order.push(path.to_string());
+        match v.number() {
+            Some(n) => json!(n.to_string()),
+            None => v,
+        }
+    });
+    assert_eq!(strings, json!({ "a" : ["1", "2"], "b" : { "c" : "3" } }));
+    assert_eq!(order.last().unwrap(), "");
+    let pos = |p: &str| order.iter().position(|o| o == p).unwrap();
+    assert!(pos("/a/0") < pos("/a"));
+    assert!(pos("/b/c") < pos("/b"));
+
+    let mut j = json!([[1], [2, 3]]);
+    j.transform(|_, v| {
+        if let Some(arr) = v.array() {
+            *v = json!(arr.len() as u8);
+        }
+    });
+    assert_eq!(j, json!(2));
+}
+
+#[test]
+fn leaves() {
+    let j = json!({
+        "z" : 1,
+        "a" : [ { "y" : "s", "x" : null }, [], [false] ],
+        "m~/" : {}
+    });
+    let leaves: Vec<_> = j.leaves().map(|(path, v)| (path.to_string(), v.clone())).collect();
+    assert_eq!(leaves, [
+        ("/a/0/x".to_string(), json!(null)),
+        ("/a/0/y".to_string(), json!("s")),
+        ("/a/2/0".to_string(), json!(false)),
+        ("/z".to_string(), json!(1)),
+    ]);
+
+    let scalar = json!(3);
+    let (path, v) = scalar.leaves().next().unwrap();
+    assert!(path.is_root());
+    assert_eq!(v, &json!(3));
+    assert_eq!(json!([[], {}]).leaves().count(), 0);
+}
+
+#[test]
+fn predicates() {
+    let j = json!({
+        "users" : [
+            { "name" : "a", "email" : "a@b.c" },
+            { "name" : "b", "email" : null }
+        ]
+    });
+
+    assert_eq!(j.count_matching(|_, _| true), 8);
+    assert_eq!(j.count_matching(|path, _| path.last() == Some("email")), 2);
+    assert_eq!(j.count_matching(|_, v| v.is_number()), 0);
+
+    assert!(j.any(|path, v| path.last() == Some("email") && v.is_null()));
+    assert!(!j.any(|_, v| v.is_number()));
+
+    assert!(j.all(|_, v| !v.is_number()));
+    assert!(!j.all(|path, v| path.last() != Some("email") || v.is_string()));
+
+    let mut visited = 0;
+    assert!(!j.all(|_, v| { visited += 1; !v.is_array() }));
+    assert_eq!(visited, 2);
+}
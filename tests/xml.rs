@@ -0,0 +1,55 @@
+#![cfg(feature = "xml")]
+
+use json::{json, Json};
+
+#[test]
+fn from_xml() {
+    let j = Json::from_xml(r#"<?xml version="1.0"?>
+        <!-- feed -->
+        <feed lang="en">
+            <entry id="1">First &amp; best</entry>
+            <entry id="2"><![CDATA[<raw>]]></entry>
+            <empty/>
+        </feed>"#).unwrap();
+
+    let feed = j.get("feed").unwrap();
+    assert_eq!(feed.get("@lang").unwrap().string(), Some("en"));
+    assert!(feed.get("empty").unwrap().is_null());
+
+    let entries = feed.get("entry").unwrap().array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].get("@id").unwrap().string(), Some("1"));
+    assert_eq!(entries[0].get("#text").unwrap().string(), Some("First & best"));
+    assert_eq!(entries[1].get("#text").unwrap().string(), Some("<raw>"));
+}
+
+#[test]
+fn round_trip() {
+    let src = r#"<a x="1"><b>text &lt;</b><c/><d>1</d><d>2</d></a>"#;
+    let j = Json::from_xml(src).unwrap();
+    let mut xml = String::new();
+    j.to_xml(&mut xml).unwrap();
+    assert_eq!(Json::from_xml(&xml).unwrap(), j);
+}
+
+#[test]
+fn invalid_names() {
+    let to_xml = |j: Json| {
+        let mut xml = String::new();
+        j.to_xml(&mut xml).map(|()| xml)
+    };
+    assert_eq!(to_xml(json!({ "ñ-1" : { "@x.y" : 1 } })).unwrap(), r#"<ñ-1 x.y="1"></ñ-1>"#);
+    assert!(to_xml(json!({ "a b" : 1 })).is_err());
+    assert!(to_xml(json!({ r#"x\"y"# : 1 })).is_err());
+    assert!(to_xml(json!({ "a" : { "1b" : null } })).is_err());
+    assert!(to_xml(json!({ "a" : { "@" : 1 } })).is_err());
+    assert!(to_xml(json!({ "a" : [{ "@b c" : 1 }] })).is_err());
+    assert!(to_xml(json!({ "" : 1 })).is_err());
+}
+
+#[test]
+fn errors() {
+    assert!(Json::from_xml("<a><b></a>").is_err());
+    assert!(Json::from_xml("<a>").is_err());
+    assert!(Json::from_xml("<a/><b/>").is_err());
+}